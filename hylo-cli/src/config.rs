@@ -0,0 +1,237 @@
+//! Connection and signer configuration
+//!
+//! Flags take precedence over the Solana CLI config file, which in turn
+//! overrides built-in defaults.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::Arc;
+
+use anchor_client::solana_client::nonblocking::rpc_client::RpcClient;
+use anchor_client::solana_sdk::commitment_config::CommitmentConfig;
+use anchor_client::solana_sdk::pubkey::Pubkey;
+use anchor_client::solana_sdk::signature::read_keypair_file;
+use anchor_client::Cluster;
+use anyhow::{anyhow, Context, Result};
+use clap::Args;
+use hylo_clients::prelude::{ProgramClient, TransactionSigner};
+use serde::Deserialize;
+
+const DEFAULT_RPC_URL: &str = "https://api.mainnet-beta.solana.com";
+
+/// Connection flags shared by every subcommand.
+#[derive(Args, Debug, Default)]
+pub struct ConfigArgs {
+  /// Solana CLI config file [default: ~/.config/solana/cli/config.yml]
+  #[arg(long, short = 'C', global = true)]
+  pub config: Option<PathBuf>,
+
+  /// RPC URL or moniker: mainnet, devnet, testnet, localnet
+  #[arg(long, short = 'u', global = true)]
+  pub url: Option<String>,
+
+  /// Keypair file signing and paying for transactions
+  #[arg(long, short = 'k', global = true)]
+  pub keypair: Option<PathBuf>,
+
+  /// Commitment level: processed, confirmed or finalized
+  #[arg(long, global = true)]
+  pub commitment: Option<String>,
+}
+
+/// Subset of the Solana CLI `config.yml` used by this tool.
+#[derive(Debug, Default, Deserialize)]
+struct CliConfig {
+  json_rpc_url: Option<String>,
+  websocket_url: Option<String>,
+  keypair_path: Option<String>,
+  commitment: Option<String>,
+}
+
+impl CliConfig {
+  /// Reads `path`, or the default location when absent.
+  ///
+  /// A missing default config is not an error, an explicit one is.
+  fn read(path: Option<&Path>) -> Result<CliConfig> {
+    let (path, explicit) = match path {
+      Some(path) => (path.to_path_buf(), true),
+      None => (solana_config_dir().join("cli/config.yml"), false),
+    };
+    match fs::read_to_string(&path) {
+      Ok(yaml) => serde_yaml::from_str(&yaml)
+        .with_context(|| format!("Failed to parse {}", path.display())),
+      Err(_) if !explicit => Ok(CliConfig::default()),
+      Err(e) => Err(anyhow!("Failed to read {}: {e}", path.display())),
+    }
+  }
+}
+
+/// Resolved connection settings.
+#[derive(Debug)]
+pub struct Config {
+  pub cluster: Cluster,
+  pub commitment: CommitmentConfig,
+  pub keypair_path: PathBuf,
+}
+
+impl Config {
+  /// Resolves flags against the Solana CLI config.
+  ///
+  /// # Errors
+  /// * Unreadable or malformed config file
+  /// * Invalid URL or commitment
+  pub fn load(args: &ConfigArgs) -> Result<Config> {
+    let file = CliConfig::read(args.config.as_deref())?;
+    Config::resolve(args, file)
+  }
+
+  fn resolve(args: &ConfigArgs, file: CliConfig) -> Result<Config> {
+    let cluster = match (&args.url, file.json_rpc_url, file.websocket_url) {
+      (Some(url), _, _) => parse_cluster(url)?,
+      (None, Some(url), Some(ws)) if !ws.is_empty() => Cluster::Custom(url, ws),
+      (None, Some(url), _) => parse_cluster(&url)?,
+      (None, None, _) => parse_cluster(DEFAULT_RPC_URL)?,
+    };
+    let commitment = args
+      .commitment
+      .clone()
+      .or(file.commitment)
+      .map(|level| CommitmentConfig::from_str(&level))
+      .transpose()
+      .map_err(|_| anyhow!("Invalid commitment"))?
+      .unwrap_or_else(CommitmentConfig::confirmed);
+    let keypair_path = args
+      .keypair
+      .clone()
+      .or(file.keypair_path.map(PathBuf::from))
+      .unwrap_or_else(|| solana_config_dir().join("id.json"));
+    Ok(Config {
+      cluster,
+      commitment,
+      keypair_path,
+    })
+  }
+
+  /// RPC client at the configured URL and commitment.
+  #[must_use]
+  pub fn rpc(&self) -> Arc<RpcClient> {
+    Arc::new(RpcClient::new_with_commitment(
+      self.cluster.url().to_string(),
+      self.commitment,
+    ))
+  }
+
+  /// Signer loaded from the keypair file.
+  ///
+  /// # Errors
+  /// * Missing or malformed keypair file
+  pub fn signer(&self) -> Result<Arc<dyn TransactionSigner>> {
+    let keypair = read_keypair_file(&self.keypair_path).map_err(|e| {
+      anyhow!(
+        "Failed to read keypair {}: {e}",
+        self.keypair_path.display()
+      )
+    })?;
+    Ok(Arc::new(keypair))
+  }
+
+  /// Program client signing with the keypair, or acting as `payer` without
+  /// its key when given.
+  ///
+  /// # Errors
+  /// * Keypair cannot be loaded
+  /// * Client construction
+  pub fn client<C: ProgramClient>(&self, payer: Option<Pubkey>) -> Result<C> {
+    match payer {
+      Some(payer) => {
+        C::new_unsigned(self.cluster.clone(), payer, self.commitment)
+      }
+      None => C::new_from_signer(
+        self.cluster.clone(),
+        self.signer()?,
+        self.commitment,
+      ),
+    }
+  }
+
+  /// Program client for reads and simulations, which need no key.
+  ///
+  /// # Errors
+  /// * Client construction
+  pub fn read_only_client<C: ProgramClient>(&self) -> Result<C> {
+    C::new_random_keypair(self.cluster.clone(), self.commitment)
+  }
+
+  /// Pubkey of the configured keypair, if it can be loaded.
+  #[must_use]
+  pub fn address(&self) -> Option<Pubkey> {
+    self.signer().ok().map(|signer| signer.address())
+  }
+}
+
+/// Parses a URL or moniker, accepting the Solana CLI's `mainnet-beta` and
+/// `localhost` spellings.
+fn parse_cluster(url: &str) -> Result<Cluster> {
+  let moniker = match url {
+    "mainnet-beta" => "mainnet",
+    "localhost" => "localnet",
+    other => other,
+  };
+  Cluster::from_str(moniker)
+}
+
+fn solana_config_dir() -> PathBuf {
+  std::env::var_os("HOME")
+    .map_or_else(PathBuf::new, PathBuf::from)
+    .join(".config/solana")
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  const CLI_CONFIG: &str = "---
+json_rpc_url: https://api.devnet.solana.com
+websocket_url: ''
+keypair_path: /keys/id.json
+address_labels:
+  '11111111111111111111111111111111': System Program
+commitment: finalized
+";
+
+  #[test]
+  fn reads_solana_cli_config() -> Result<()> {
+    let file = serde_yaml::from_str(CLI_CONFIG)?;
+    let config = Config::resolve(&ConfigArgs::default(), file)?;
+    assert_eq!(config.cluster.url(), "https://api.devnet.solana.com");
+    assert_eq!(config.commitment, CommitmentConfig::finalized());
+    assert_eq!(config.keypair_path, PathBuf::from("/keys/id.json"));
+    Ok(())
+  }
+
+  #[test]
+  fn flags_override_config() -> Result<()> {
+    let file = serde_yaml::from_str(CLI_CONFIG)?;
+    let args = ConfigArgs {
+      url: Some("localhost".to_string()),
+      keypair: Some(PathBuf::from("/keys/ops.json")),
+      commitment: Some("processed".to_string()),
+      ..ConfigArgs::default()
+    };
+    let config = Config::resolve(&args, file)?;
+    assert_eq!(config.cluster, Cluster::Localnet);
+    assert_eq!(config.commitment, CommitmentConfig::processed());
+    assert_eq!(config.keypair_path, PathBuf::from("/keys/ops.json"));
+    Ok(())
+  }
+
+  #[test]
+  fn rejects_invalid_commitment() {
+    let args = ConfigArgs {
+      commitment: Some("eventually".to_string()),
+      ..ConfigArgs::default()
+    };
+    assert!(Config::resolve(&args, CliConfig::default()).is_err());
+  }
+}
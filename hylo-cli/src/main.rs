@@ -0,0 +1,139 @@
+//! `hylo` command-line tool
+//!
+//! Quotes, protocol stats, account snapshots and transactions against a
+//! Hylo deployment. Connection settings default to the Solana CLI config and
+//! every command can print JSON for scripting.
+//!
+//! ```text
+//! hylo quote jitosol hyusd 1000000000 --strategy simulation
+//! hylo stats --json
+//! hylo portfolio <WALLET> --redeem-lst hylosol
+//! hylo snapshot --out-dir snapshots
+//! hylo tx --mode send update-lst-prices
+//! hylo tx --mode build --payer <VAULT> update-lst-swap-fee 0.0025
+//! ```
+
+mod config;
+mod output;
+mod portfolio;
+mod quote;
+mod snapshot;
+mod stats;
+mod transaction;
+
+use anyhow::Result;
+use clap::{Parser, Subcommand};
+
+use crate::config::{Config, ConfigArgs};
+
+#[derive(Parser, Debug)]
+#[command(name = "hylo", version, about = "Hylo protocol command-line tool")]
+struct Cli {
+  #[command(flatten)]
+  config: ConfigArgs,
+
+  /// Print results as JSON
+  #[arg(long, global = true)]
+  json: bool,
+
+  #[command(subcommand)]
+  command: Command,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+  /// Quote a mint, redeem, swap or stability pool operation
+  Quote(quote::QuoteArgs),
+  /// Print exchange and stability pool stats
+  Stats(stats::StatsArgs),
+  /// Value a wallet's Hylo positions and what they redeem for
+  Portfolio(portfolio::PortfolioArgs),
+  /// Dump a protocol accounts snapshot as JSON
+  Snapshot(snapshot::SnapshotArgs),
+  /// Build, simulate or send user and admin transactions
+  Tx(transaction::TxArgs),
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+  let cli = Cli::parse();
+  let config = Config::load(&cli.config)?;
+  let (value, json) = match &cli.command {
+    Command::Quote(args) => (quote::run(&config, args).await?, cli.json),
+    Command::Stats(args) => (stats::run(&config, args).await?, cli.json),
+    Command::Portfolio(args) => {
+      (portfolio::run(&config, args).await?, cli.json)
+    }
+    // Raw accounts only make sense as JSON
+    Command::Snapshot(args) => (
+      snapshot::run(&config, args).await?,
+      cli.json || args.out_dir.is_none(),
+    ),
+    Command::Tx(args) => (transaction::run(&config, args).await?, cli.json),
+  };
+  output::print(&value, json)
+}
+
+#[cfg(test)]
+mod tests {
+  use clap::CommandFactory;
+
+  use super::*;
+
+  #[test]
+  fn cli_definition_is_valid() {
+    Cli::command().debug_assert();
+  }
+
+  #[test]
+  fn parses_global_flags_after_subcommand() {
+    let cli = Cli::try_parse_from([
+      "hylo",
+      "tx",
+      "update-lst-swap-fee",
+      "0.0025",
+      "--mode",
+      "build",
+      "--payer",
+      "11111111111111111111111111111111",
+      "-u",
+      "devnet",
+      "--json",
+    ])
+    .expect("valid command line");
+    assert!(cli.json);
+    assert_eq!(cli.config.url.as_deref(), Some("devnet"));
+    let Command::Tx(args) = cli.command else {
+      panic!("expected tx");
+    };
+    assert_eq!(args.mode, transaction::Mode::Build);
+    assert!(args.payer.is_some());
+  }
+
+  #[test]
+  fn parses_stablecoin_fee_flags() {
+    let cli = Cli::try_parse_from([
+      "hylo",
+      "tx",
+      "update-stablecoin-fees",
+      "--normal-mint",
+      "0.001",
+      "--normal-redeem",
+      "0.002",
+      "--mode-1-mint",
+      "0.003",
+      "--mode-1-redeem",
+      "0.004",
+    ])
+    .expect("valid command line");
+    let Command::Tx(args) = cli.command else {
+      panic!("expected tx");
+    };
+    let transaction::Action::UpdateStablecoinFees { mode_1_redeem, .. } =
+      args.action
+    else {
+      panic!("expected update-stablecoin-fees");
+    };
+    assert_eq!(mode_1_redeem, transaction::FixedPoint { bits: 4, exp: -3 });
+  }
+}
@@ -0,0 +1,100 @@
+//! Text and JSON rendering of command results
+//!
+//! Every command produces a [`Value`], printed as pretty JSON with `--json`
+//! or as indented `key: value` lines otherwise.
+
+use std::fmt::Write;
+
+use anyhow::Result;
+use fix::prelude::UFixValue64;
+use hylo_quotes::format::Fixed;
+use serde_json::Value;
+
+/// Prints a command result in the selected format.
+///
+/// # Errors
+/// * JSON serialization
+pub fn print(value: &Value, json: bool) -> Result<()> {
+  if json {
+    println!("{}", serde_json::to_string_pretty(value)?);
+  } else {
+    let mut text = String::new();
+    render(value, 0, &mut text);
+    print!("{text}");
+  }
+  Ok(())
+}
+
+/// Exact decimal string of a fixed point value.
+pub fn decimal(value: impl Into<UFixValue64>) -> Value {
+  Value::String(Fixed(value.into()).to_string())
+}
+
+fn render(value: &Value, indent: usize, out: &mut String) {
+  let pad = "  ".repeat(indent);
+  match value {
+    Value::Object(fields) => fields.iter().for_each(|(key, field)| {
+      if is_scalar(field) {
+        let _ = writeln!(out, "{pad}{key}: {}", scalar(field));
+      } else {
+        let _ = writeln!(out, "{pad}{key}:");
+        render(field, indent + 1, out);
+      }
+    }),
+    Value::Array(items) => items.iter().for_each(|item| {
+      if is_scalar(item) {
+        let _ = writeln!(out, "{pad}- {}", scalar(item));
+      } else {
+        let _ = writeln!(out, "{pad}-");
+        render(item, indent + 1, out);
+      }
+    }),
+    scalar_value => {
+      let _ = writeln!(out, "{pad}{}", scalar(scalar_value));
+    }
+  }
+}
+
+fn is_scalar(value: &Value) -> bool {
+  !matches!(value, Value::Object(_) | Value::Array(_))
+}
+
+fn scalar(value: &Value) -> String {
+  match value {
+    Value::String(s) => s.clone(),
+    Value::Null => "-".to_string(),
+    other => other.to_string(),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use serde_json::json;
+
+  use super::*;
+
+  #[test]
+  fn renders_nested_text() {
+    let value = json!({
+      "operation": "mint_stablecoin",
+      "amount_out": "12.5",
+      "error": null,
+      "logs": ["a", "b"],
+      "stats": { "nav": "1.01" },
+    });
+    let mut text = String::new();
+    render(&value, 0, &mut text);
+    assert_eq!(
+      text,
+      "amount_out: 12.5\nerror: -\nlogs:\n  - a\n  - b\noperation: \
+       mint_stablecoin\nstats:\n  nav: 1.01\n"
+    );
+  }
+
+  #[test]
+  fn formats_fixed_point_exactly() {
+    assert_eq!(decimal(UFixValue64::new(1_234_500, -6)), json!("1.2345"));
+    assert_eq!(decimal(UFixValue64::new(0, -9)), json!("0"));
+    assert_eq!(decimal(UFixValue64::new(3, 2)), json!("300"));
+  }
+}
@@ -0,0 +1,73 @@
+//! `hylo portfolio`
+
+use anchor_client::solana_sdk::pubkey::Pubkey;
+use anyhow::{anyhow, Result};
+use clap::Args;
+use fix::typenum::Integer;
+use hylo_idl::tokens::{TokenMint, JITOSOL};
+use hylo_quotes::format::token_label;
+use hylo_quotes::prelude::{
+  Portfolio, Position, RpcStateProvider, StateProvider,
+};
+use serde_json::{json, Value};
+
+use crate::config::Config;
+use crate::output::decimal;
+use crate::quote::parse_mint;
+
+#[derive(Args, Debug)]
+pub struct PortfolioArgs {
+  /// Wallet to read [default: keypair pubkey]
+  pub owner: Option<Pubkey>,
+
+  /// LST symbol or mint to value redemptions in
+  #[arg(long, value_parser = parse_mint, default_value_t = JITOSOL::MINT)]
+  pub redeem_lst: Pubkey,
+}
+
+/// Runs `hylo portfolio`.
+///
+/// # Errors
+/// * No owner given and no keypair configured
+/// * State or token account fetch failure
+/// * Valuation failure
+pub async fn run(config: &Config, args: &PortfolioArgs) -> Result<Value> {
+  let owner = args
+    .owner
+    .or_else(|| config.address())
+    .ok_or(anyhow!("No owner given and no keypair configured"))?;
+  let rpc = config.rpc();
+  let state = RpcStateProvider::new(rpc.clone()).fetch_state().await?;
+  let portfolio =
+    Portfolio::fetch(&rpc, &state, owner, args.redeem_lst).await?;
+  portfolio_json(&portfolio)
+}
+
+fn position_json<Exp: Integer>(position: &Position<Exp>) -> Value {
+  json!({
+    "amount": decimal(position.amount),
+    "usd_value": decimal(position.usd_value),
+    "redeemable": position.redeemable.map(decimal),
+  })
+}
+
+fn portfolio_json(portfolio: &Portfolio) -> Result<Value> {
+  let mut shyusd = position_json(&portfolio.shyusd.position);
+  shyusd["hyusd_share"] = decimal(portfolio.shyusd.hyusd_share);
+  shyusd["xsol_share"] = decimal(portfolio.shyusd.xsol_share);
+  let lsts = portfolio
+    .lsts
+    .iter()
+    .map(|lst| (token_label(&lst.mint), position_json(&lst.position)))
+    .collect::<serde_json::Map<_, _>>();
+  Ok(json!({
+    "owner": portfolio.owner.to_string(),
+    "redeem_lst": token_label(&portfolio.redeem_lst),
+    "hyusd": position_json(&portfolio.hyusd),
+    "xsol": position_json(&portfolio.xsol),
+    "shyusd": shyusd,
+    "lsts": lsts,
+    "total_usd_value": decimal(portfolio.total_usd_value()?),
+    "total_redeemable": decimal(portfolio.total_redeemable()?),
+  }))
+}
@@ -0,0 +1,156 @@
+//! `hylo quote`
+
+use std::str::FromStr;
+
+use anchor_client::solana_sdk::pubkey::Pubkey;
+use anyhow::{anyhow, Result};
+use clap::{Args, ValueEnum};
+use hylo_clients::prelude::{ExchangeClient, StabilityPoolClient};
+use hylo_clients::util::REFERENCE_WALLET;
+use hylo_idl::tokens::mint_from_symbol;
+use hylo_quotes::prelude::{
+  ExecutableQuoteValue, ProtocolStateStrategy, QuoteMetadata, RpcStateProvider,
+  RuntimeQuoteStrategy, SimulationStrategy,
+};
+use serde_json::{json, Value};
+
+use crate::config::Config;
+use crate::output::decimal;
+
+/// Quote strategy backing `hylo quote`.
+#[derive(Clone, Copy, Debug, Default, ValueEnum)]
+pub enum Strategy {
+  /// Protocol math over fetched accounts
+  #[default]
+  State,
+  /// Transaction simulation, checking wallet balances
+  Simulation,
+}
+
+#[derive(Args, Debug)]
+pub struct QuoteArgs {
+  /// Input token symbol (HYUSD, XSOL, SHYUSD, JITOSOL, HYLOSOL) or mint
+  #[arg(value_parser = parse_mint)]
+  pub input: Pubkey,
+
+  /// Output token symbol or mint
+  #[arg(value_parser = parse_mint)]
+  pub output: Pubkey,
+
+  /// Input amount in base units
+  pub amount: u64,
+
+  #[arg(long, value_enum, default_value_t)]
+  pub strategy: Strategy,
+
+  /// Quoting wallet [default: keypair pubkey]
+  #[arg(long)]
+  pub user: Option<Pubkey>,
+
+  /// Slippage tolerance in basis points
+  #[arg(long, default_value_t = 50)]
+  pub slippage_bps: u64,
+}
+
+/// Runs `hylo quote`.
+///
+/// # Errors
+/// * Unsupported pair
+/// * State fetch or simulation failure
+pub async fn run(config: &Config, args: &QuoteArgs) -> Result<Value> {
+  let user = args
+    .user
+    .or_else(|| config.address())
+    .unwrap_or(REFERENCE_WALLET);
+  let (quote, metadata) = fetch_quote(
+    config,
+    args.strategy,
+    args.input,
+    args.output,
+    args.amount,
+    user,
+    args.slippage_bps,
+  )
+  .await?;
+  Ok(quote_json(args.input, args.output, &quote, &metadata))
+}
+
+/// Quotes a pair through the selected strategy.
+///
+/// # Errors
+/// * Unsupported pair
+/// * State fetch or simulation failure
+pub async fn fetch_quote(
+  config: &Config,
+  strategy: Strategy,
+  input: Pubkey,
+  output: Pubkey,
+  amount: u64,
+  user: Pubkey,
+  slippage_bps: u64,
+) -> Result<(ExecutableQuoteValue, QuoteMetadata)> {
+  match strategy {
+    Strategy::State => {
+      ProtocolStateStrategy::new(RpcStateProvider::new(config.rpc()))
+        .runtime_quote_with_metadata(input, output, amount, user, slippage_bps)
+        .await
+    }
+    Strategy::Simulation => {
+      SimulationStrategy::new(
+        config.read_only_client::<ExchangeClient>()?,
+        config.read_only_client::<StabilityPoolClient>()?,
+      )
+      .runtime_quote_with_metadata(input, output, amount, user, slippage_bps)
+      .await
+    }
+  }
+}
+
+fn quote_json(
+  input: Pubkey,
+  output: Pubkey,
+  quote: &ExecutableQuoteValue,
+  metadata: &QuoteMetadata,
+) -> Value {
+  json!({
+    "operation": metadata.operation.as_str(),
+    "description": metadata.description,
+    "input_mint": input.to_string(),
+    "output_mint": output.to_string(),
+    "amount_in": decimal(quote.amount_in),
+    "amount_out": decimal(quote.amount_out),
+    "fee_amount": decimal(quote.fee_amount),
+    "fee_mint": quote.fee_mint.to_string(),
+    "compute_units": quote.compute_units,
+    "compute_unit_strategy": format!("{:?}", quote.compute_unit_strategy),
+  })
+}
+
+/// Parses a token symbol, case-insensitively, or a base58 mint.
+///
+/// # Errors
+/// * Neither a known symbol nor a valid pubkey
+pub fn parse_mint(token: &str) -> Result<Pubkey> {
+  match mint_from_symbol(token) {
+    Some(mint) => Ok(mint),
+    None => Pubkey::from_str(token)
+      .map_err(|_| anyhow!("Unknown token {token}, expected symbol or mint")),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use hylo_idl::tokens::{TokenMint, HYUSD, JITOSOL};
+
+  use super::*;
+
+  #[test]
+  fn parses_symbols_and_mints() -> Result<()> {
+    assert_eq!(parse_mint("hyusd")?, HYUSD::MINT);
+    assert_eq!(parse_mint("JitoSOL")?, JITOSOL::MINT);
+    let mint = Pubkey::new_unique();
+    assert_eq!(parse_mint(&mint.to_string())?, mint);
+    assert!(parse_mint("USDC").is_err());
+    Ok(())
+  }
+}
@@ -0,0 +1,38 @@
+//! `hylo snapshot`
+
+use std::path::PathBuf;
+
+use anyhow::Result;
+use clap::Args;
+use hylo_quotes::prelude::{ProtocolSnapshot, RpcStateProvider};
+use serde_json::{json, Value};
+
+use crate::config::Config;
+
+#[derive(Args, Debug)]
+pub struct SnapshotArgs {
+  /// Write the snapshot into this directory instead of printing it, under
+  /// the name `FileStateProvider` reads
+  #[arg(long)]
+  pub out_dir: Option<PathBuf>,
+}
+
+/// Runs `hylo snapshot`.
+///
+/// # Errors
+/// * Account fetch failure
+/// * File IO
+pub async fn run(config: &Config, args: &SnapshotArgs) -> Result<Value> {
+  let accounts = RpcStateProvider::new(config.rpc()).fetch_accounts().await?;
+  let snapshot = ProtocolSnapshot::new(accounts)?;
+  match &args.out_dir {
+    Some(dir) => {
+      let path = snapshot.write_to_dir(dir)?;
+      Ok(json!({
+        "slot": snapshot.slot,
+        "path": path.display().to_string(),
+      }))
+    }
+    None => Ok(serde_json::to_value(&snapshot)?),
+  }
+}
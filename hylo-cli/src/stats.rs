@@ -0,0 +1,76 @@
+//! `hylo stats`
+
+use anyhow::Result;
+use clap::{Args, ValueEnum};
+use hylo_clients::prelude::{ExchangeClient, StabilityPoolClient};
+use hylo_idl::exchange::events::ExchangeStats;
+use hylo_idl::stability_pool::events::StabilityPoolStats;
+use serde_json::{json, Map, Value};
+
+use crate::config::Config;
+use crate::output::decimal;
+
+/// Program whose stats to print.
+#[derive(Clone, Copy, Debug, ValueEnum)]
+pub enum Program {
+  Exchange,
+  Pool,
+}
+
+#[derive(Args, Debug)]
+pub struct StatsArgs {
+  /// Only print stats of one program [default: both]
+  #[arg(value_enum)]
+  pub program: Option<Program>,
+}
+
+/// Runs `hylo stats`.
+///
+/// # Errors
+/// * Stats simulation failure
+pub async fn run(config: &Config, args: &StatsArgs) -> Result<Value> {
+  let mut stats = Map::new();
+  if matches!(args.program, None | Some(Program::Exchange)) {
+    let client = config.read_only_client::<ExchangeClient>()?;
+    stats.insert("exchange".into(), exchange_json(&client.get_stats().await?));
+  }
+  if matches!(args.program, None | Some(Program::Pool)) {
+    let client = config.read_only_client::<StabilityPoolClient>()?;
+    stats.insert(
+      "stability_pool".into(),
+      pool_json(&client.get_stats().await?),
+    );
+  }
+  Ok(Value::Object(stats))
+}
+
+fn exchange_json(stats: &ExchangeStats) -> Value {
+  json!({
+    "total_value_locked": decimal(stats.total_value_locked),
+    "stablecoin_nav": decimal(stats.stablecoin_nav),
+    "stablecoin_supply": decimal(stats.stablecoin_supply),
+    "levercoin_nav": decimal(stats.levercoin_nav),
+    "levercoin_supply": decimal(stats.levercoin_supply),
+    "collateral_ratio": decimal(stats.collateral_ratio),
+    "stability_mode": format!("{:?}", stats.stability_mode),
+    "yield_harvest_cache": {
+      "epoch": stats.yield_harvest_cache.epoch,
+      "stability_pool_cap":
+        decimal(stats.yield_harvest_cache.stability_pool_cap),
+      "stablecoin_yield_to_pool":
+        decimal(stats.yield_harvest_cache.stablecoin_yield_to_pool),
+    },
+  })
+}
+
+fn pool_json(stats: &StabilityPoolStats) -> Value {
+  json!({
+    "lp_token_nav": decimal(stats.lp_token_nav),
+    "lp_token_supply": decimal(stats.lp_token_supply),
+    "stability_pool_cap": decimal(stats.stability_pool_cap),
+    "stablecoin_in_pool": decimal(stats.stablecoin_in_pool),
+    "stablecoin_nav": decimal(stats.stablecoin_nav),
+    "levercoin_in_pool": decimal(stats.levercoin_in_pool),
+    "levercoin_nav": decimal(stats.levercoin_nav),
+  })
+}
@@ -0,0 +1,379 @@
+//! `hylo tx`
+
+use std::str::FromStr;
+
+use anchor_client::solana_sdk::pubkey::Pubkey;
+use anyhow::{anyhow, ensure, Result};
+use base64::prelude::{Engine, BASE64_STANDARD};
+use clap::{Args, Subcommand, ValueEnum};
+use hylo_clients::prelude::{
+  ExchangeClient, ProgramClient, StabilityPoolClient, VersionedTransactionData,
+};
+use hylo_clients::program_error::HyloTransactionError;
+use hylo_clients::util::{simulation_config, LST_REGISTRY_LOOKUP_TABLE};
+use hylo_idl::exchange::client::args as exchange_args;
+use hylo_idl::exchange::types::{
+  FeePair, LevercoinFees, StablecoinFees, UFixValue64, YieldHarvestConfig,
+};
+use hylo_idl::stability_pool::client::args as pool_args;
+use hylo_idl::stability_pool::types::UFixValue64 as PoolUFixValue64;
+use rust_decimal::Decimal;
+use serde_json::{json, Value};
+
+use crate::config::Config;
+use crate::quote::{fetch_quote, parse_mint, Strategy};
+
+/// What to do with a built transaction.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum Mode {
+  /// Print the unsigned transaction as base64
+  Build,
+  /// Simulate and print logs and compute units
+  #[default]
+  Simulate,
+  /// Sign, send and confirm
+  Send,
+}
+
+#[derive(Args, Debug)]
+pub struct TxArgs {
+  #[arg(long, value_enum, default_value_t, global = true)]
+  pub mode: Mode,
+
+  /// Build for this payer without its key, e.g. a multisig vault or hardware
+  /// wallet (build and simulate only)
+  #[arg(long, global = true)]
+  pub payer: Option<Pubkey>,
+
+  #[command(subcommand)]
+  pub action: Action,
+}
+
+/// Fixed point argument, e.g. `0.0025`, kept at its written precision.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FixedPoint {
+  pub bits: u64,
+  pub exp: i8,
+}
+
+impl FromStr for FixedPoint {
+  type Err = anyhow::Error;
+
+  fn from_str(s: &str) -> Result<FixedPoint> {
+    let value = Decimal::from_str(s)?;
+    ensure!(value.is_sign_positive(), "{s} is negative");
+    Ok(FixedPoint {
+      bits: u64::try_from(value.mantissa())?,
+      exp: -i8::try_from(value.scale())?,
+    })
+  }
+}
+
+impl From<FixedPoint> for UFixValue64 {
+  fn from(FixedPoint { bits, exp }: FixedPoint) -> UFixValue64 {
+    UFixValue64 { bits, exp }
+  }
+}
+
+impl From<FixedPoint> for PoolUFixValue64 {
+  fn from(FixedPoint { bits, exp }: FixedPoint) -> PoolUFixValue64 {
+    PoolUFixValue64 { bits, exp }
+  }
+}
+
+#[derive(Subcommand, Debug)]
+pub enum Action {
+  /// Mint, redeem or swap at the protocol state quote
+  Swap {
+    /// Input token symbol or mint
+    #[arg(value_parser = parse_mint)]
+    input: Pubkey,
+    /// Output token symbol or mint
+    #[arg(value_parser = parse_mint)]
+    output: Pubkey,
+    /// Input amount in base units
+    amount: u64,
+    /// Slippage tolerance in basis points
+    #[arg(long, default_value_t = 50)]
+    slippage_bps: u64,
+  },
+  /// Crank LST prices for the current epoch
+  UpdateLstPrices,
+  /// Harvest LST yield into the stability pool
+  HarvestYield,
+  /// Set the LST to LST swap fee (admin)
+  UpdateLstSwapFee { fee: FixedPoint },
+  /// Set the oracle confidence tolerance (admin)
+  UpdateOracleConfTolerance { tolerance: FixedPoint },
+  /// Set the oracle staleness interval in seconds (admin)
+  UpdateOracleInterval { seconds: u64 },
+  /// Set the SOL/USD oracle account (admin)
+  UpdateSolUsdOracle { oracle: Pubkey },
+  /// Set the stability pool address (admin)
+  UpdateStabilityPool { stability_pool: Pubkey },
+  /// Set the treasury address (admin)
+  UpdateTreasury { treasury: Pubkey },
+  /// Set the stability pool withdrawal fee (admin)
+  UpdateWithdrawalFee { fee: FixedPoint },
+  /// Set the hyUSD mint and redeem fees per stability mode (admin)
+  UpdateStablecoinFees {
+    #[arg(long)]
+    normal_mint: FixedPoint,
+    #[arg(long)]
+    normal_redeem: FixedPoint,
+    #[arg(long)]
+    mode_1_mint: FixedPoint,
+    #[arg(long)]
+    mode_1_redeem: FixedPoint,
+  },
+  /// Set the xSOL mint and redeem fees per stability mode (admin)
+  UpdateLevercoinFees {
+    #[arg(long)]
+    normal_mint: FixedPoint,
+    #[arg(long)]
+    normal_redeem: FixedPoint,
+    #[arg(long)]
+    mode_1_mint: FixedPoint,
+    #[arg(long)]
+    mode_1_redeem: FixedPoint,
+    #[arg(long)]
+    mode_2_mint: FixedPoint,
+    #[arg(long)]
+    mode_2_redeem: FixedPoint,
+  },
+  /// Set the collateral ratios entering stability modes 1 and 2 (admin)
+  UpdateStabilityThresholds {
+    threshold_1: FixedPoint,
+    threshold_2: FixedPoint,
+  },
+  /// Set the yield harvest allocation and treasury fee (admin)
+  UpdateYieldHarvestConfig {
+    allocation: FixedPoint,
+    fee: FixedPoint,
+  },
+  /// Register an LST for mint and redeem (admin)
+  RegisterLst {
+    /// LST mint
+    lst_mint: Pubkey,
+    #[arg(long)]
+    stake_pool_state: Pubkey,
+    #[arg(long)]
+    sanctum_calculator_program: Pubkey,
+    #[arg(long)]
+    sanctum_calculator_state: Pubkey,
+    #[arg(long)]
+    stake_pool_program: Pubkey,
+    #[arg(long)]
+    stake_pool_program_data: Pubkey,
+    /// LST registry lookup table to extend
+    #[arg(long, default_value_t = LST_REGISTRY_LOOKUP_TABLE)]
+    lst_registry: Pubkey,
+  },
+}
+
+fn fee_pair(mint: FixedPoint, redeem: FixedPoint) -> FeePair {
+  FeePair {
+    mint: mint.into(),
+    redeem: redeem.into(),
+  }
+}
+
+/// Runs `hylo tx`.
+///
+/// # Errors
+/// * `--payer` combined with `--mode send`
+/// * Transaction building, simulation or submission failure
+pub async fn run(config: &Config, args: &TxArgs) -> Result<Value> {
+  ensure!(
+    args.payer.is_none() || args.mode != Mode::Send,
+    "--payer transactions cannot be sent, use --mode build"
+  );
+  let client = config.client::<ExchangeClient>(args.payer)?;
+  let vtd = match &args.action {
+    Action::Swap {
+      input,
+      output,
+      amount,
+      slippage_bps,
+    } => {
+      let (quote, _) = fetch_quote(
+        config,
+        Strategy::State,
+        *input,
+        *output,
+        *amount,
+        client.signer().address(),
+        *slippage_bps,
+      )
+      .await?;
+      quote.transaction_data(&client).await?
+    }
+    Action::UpdateLstPrices => client.update_lst_prices().await?,
+    Action::HarvestYield => client.harvest_yield().await?,
+    Action::UpdateLstSwapFee { fee } => {
+      client.update_lst_swap_fee(&exchange_args::UpdateLstSwapFee {
+        new_lst_swap_fee: (*fee).into(),
+      })?
+    }
+    Action::UpdateOracleConfTolerance { tolerance } => client
+      .update_oracle_conf_tolerance(
+        &exchange_args::UpdateOracleConfTolerance {
+          new_oracle_conf_tolerance: (*tolerance).into(),
+        },
+      )?,
+    Action::UpdateOracleInterval { seconds } => {
+      client.update_oracle_interval(&exchange_args::UpdateOracleInterval {
+        new_oracle_interval_secs: *seconds,
+      })?
+    }
+    Action::UpdateSolUsdOracle { oracle } => {
+      client.update_sol_usd_oracle(&exchange_args::UpdateSolUsdOracle {
+        new_oracle: *oracle,
+      })?
+    }
+    Action::UpdateStabilityPool { stability_pool } => client
+      .update_stability_pool(&exchange_args::UpdateStabilityPool {
+        new_stability_pool: *stability_pool,
+      })?,
+    Action::UpdateTreasury { treasury } => {
+      client.update_treasury(&exchange_args::UpdateTreasury {
+        new_treasury: *treasury,
+      })?
+    }
+    Action::UpdateStablecoinFees {
+      normal_mint,
+      normal_redeem,
+      mode_1_mint,
+      mode_1_redeem,
+    } => {
+      client.update_stablecoin_fees(&exchange_args::UpdateStablecoinFees {
+        new_stablecoin_fees: StablecoinFees {
+          normal: fee_pair(*normal_mint, *normal_redeem),
+          mode_1: fee_pair(*mode_1_mint, *mode_1_redeem),
+        },
+      })?
+    }
+    Action::UpdateLevercoinFees {
+      normal_mint,
+      normal_redeem,
+      mode_1_mint,
+      mode_1_redeem,
+      mode_2_mint,
+      mode_2_redeem,
+    } => client.update_levercoin_fees(&exchange_args::UpdateLevercoinFees {
+      new_levercoin_fees: LevercoinFees {
+        normal: fee_pair(*normal_mint, *normal_redeem),
+        mode_1: fee_pair(*mode_1_mint, *mode_1_redeem),
+        mode_2: fee_pair(*mode_2_mint, *mode_2_redeem),
+      },
+    })?,
+    Action::UpdateStabilityThresholds {
+      threshold_1,
+      threshold_2,
+    } => client.update_stability_thresholds(
+      &exchange_args::UpdateStabilityThresholds {
+        new_stability_threshold_1: (*threshold_1).into(),
+        new_stability_threshold_2: (*threshold_2).into(),
+      },
+    )?,
+    Action::UpdateYieldHarvestConfig { allocation, fee } => client
+      .update_yield_harvest_config(
+        &exchange_args::UpdateYieldHarvestConfig {
+          new_yield_harvest_config: YieldHarvestConfig {
+            allocation: (*allocation).into(),
+            fee: (*fee).into(),
+          },
+        },
+      )?,
+    Action::RegisterLst {
+      lst_mint,
+      stake_pool_state,
+      sanctum_calculator_program,
+      sanctum_calculator_state,
+      stake_pool_program,
+      stake_pool_program_data,
+      lst_registry,
+    } => client.register_lst(
+      *lst_registry,
+      *lst_mint,
+      *stake_pool_state,
+      *sanctum_calculator_program,
+      *sanctum_calculator_state,
+      *stake_pool_program,
+      *stake_pool_program_data,
+    )?,
+    Action::UpdateWithdrawalFee { fee } => {
+      let client = config.client::<StabilityPoolClient>(args.payer)?;
+      let vtd =
+        client.update_withdrawal_fee(&pool_args::UpdateWithdrawalFee {
+          new_withdrawal_fee: (*fee).into(),
+        })?;
+      return execute(&client, args.mode, &vtd).await;
+    }
+  };
+  execute(&client, args.mode, &vtd).await
+}
+
+async fn execute<C: ProgramClient + Sync>(
+  client: &C,
+  mode: Mode,
+  vtd: &VersionedTransactionData,
+) -> Result<Value> {
+  let payer = client.signer().address();
+  match mode {
+    Mode::Build => {
+      let tx = client.build_unsigned_v0_transaction(&payer, vtd).await?;
+      Ok(json!({
+        "payer": payer.to_string(),
+        "transaction": BASE64_STANDARD.encode(bincode::serialize(&tx)?),
+      }))
+    }
+    Mode::Simulate => {
+      let tx = client.build_simulation_transaction(&payer, vtd).await?;
+      let result = client
+        .program()
+        .rpc()
+        .simulate_transaction_with_config(&tx, simulation_config())
+        .await?
+        .value;
+      let failure = HyloTransactionError::from_simulation(&tx.message, &result);
+      Ok(json!({
+        "payer": payer.to_string(),
+        "error": result.err.map(|err| err.to_string()),
+        "program_error": failure
+          .and_then(|failure| failure.code)
+          .map(|code| code.to_string()),
+        "units_consumed": result.units_consumed,
+        "logs": result.logs.unwrap_or_default(),
+      }))
+    }
+    Mode::Send => {
+      let signature = client
+        .send_v0_transaction(vtd)
+        .await
+        .map_err(|e| anyhow!("Transaction failed: {e}"))?;
+      Ok(json!({ "signature": signature.to_string() }))
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn parses_fixed_point_at_written_precision() -> Result<()> {
+    assert_eq!(
+      FixedPoint::from_str("0.0025")?,
+      FixedPoint { bits: 25, exp: -4 }
+    );
+    assert_eq!(
+      FixedPoint::from_str("0.0100")?,
+      FixedPoint { bits: 100, exp: -4 }
+    );
+    assert_eq!(FixedPoint::from_str("3")?, FixedPoint { bits: 3, exp: 0 });
+    assert!(FixedPoint::from_str("-0.1").is_err());
+    assert!(FixedPoint::from_str("fee").is_err());
+    Ok(())
+  }
+}
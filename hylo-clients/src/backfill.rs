@@ -0,0 +1,543 @@
+use std::sync::Arc;
+
+use anchor_client::solana_client::nonblocking::rpc_client::RpcClient;
+use anchor_client::solana_client::rpc_client::GetConfirmedSignaturesForAddress2Config;
+use anchor_client::solana_client::rpc_config::RpcTransactionConfig;
+use anchor_client::solana_sdk::commitment_config::CommitmentConfig;
+use anchor_client::solana_sdk::pubkey::Pubkey;
+use anchor_client::solana_sdk::signature::Signature;
+use anyhow::{Context, Result};
+use futures::stream::{self, StreamExt, TryStreamExt};
+use hylo_idl::{exchange, stability_pool};
+use solana_transaction_status_client_types::UiTransactionEncoding;
+
+use crate::events::{parse_transaction_events, HyloEvent};
+
+/// Maximum page size accepted by `getSignaturesForAddress`.
+pub const MAX_SIGNATURES_PAGE: usize = 1000;
+
+/// Default number of transactions fetched concurrently.
+pub const DEFAULT_CONCURRENCY: usize = 8;
+
+/// Hylo event with the transaction it was emitted in.
+#[derive(Clone, Debug)]
+pub struct IndexedEvent {
+  pub signature: Signature,
+  pub slot: u64,
+  pub block_time: Option<i64>,
+  pub event: HyloEvent,
+}
+
+/// Position of a backfill in a program's signature history.
+///
+/// Signatures are paged from newest to oldest. `before` is the oldest
+/// signature already processed, `until` the newest signature of a previous
+/// backfill to stop at, and `newest` the first signature of this backfill.
+/// Once history is exhausted, `newest` becomes the `until` watermark of the
+/// next backfill.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct BackfillCursor {
+  pub before: Option<Signature>,
+  pub until: Option<Signature>,
+  pub newest: Option<Signature>,
+}
+
+impl BackfillCursor {
+  /// Cursor for the next backfill, stopping at the newest signature seen.
+  #[must_use]
+  pub fn next_run(&self) -> BackfillCursor {
+    BackfillCursor {
+      before: None,
+      until: self.newest.or(self.until),
+      newest: None,
+    }
+  }
+}
+
+/// Destination for backfilled events.
+#[async_trait::async_trait]
+pub trait EventSink: Send {
+  /// Persists one page of events, oldest last as returned by the RPC.
+  /// `cursor` resumes the backfill after this page, and should be stored
+  /// atomically with the events.
+  ///
+  /// # Errors
+  /// - Storage failure, which aborts the backfill
+  async fn write(
+    &mut self,
+    program_id: Pubkey,
+    events: Vec<IndexedEvent>,
+    cursor: &BackfillCursor,
+  ) -> Result<()>;
+}
+
+#[async_trait::async_trait]
+impl EventSink for Vec<IndexedEvent> {
+  async fn write(
+    &mut self,
+    _program_id: Pubkey,
+    events: Vec<IndexedEvent>,
+    _cursor: &BackfillCursor,
+  ) -> Result<()> {
+    self.extend(events);
+    Ok(())
+  }
+}
+
+/// Pages through program signature history and decodes Hylo events.
+///
+/// Each program's backfill only keeps events emitted by that program, so
+/// transactions touching both programs are not indexed twice.
+pub struct Backfill {
+  rpc: Arc<RpcClient>,
+  commitment: CommitmentConfig,
+  page_size: usize,
+  concurrency: usize,
+}
+
+impl Backfill {
+  #[must_use]
+  pub fn new(rpc: Arc<RpcClient>) -> Backfill {
+    Backfill {
+      rpc,
+      commitment: CommitmentConfig::confirmed(),
+      page_size: MAX_SIGNATURES_PAGE,
+      concurrency: DEFAULT_CONCURRENCY,
+    }
+  }
+
+  /// Sets the commitment for signature and transaction queries.
+  #[must_use]
+  pub fn with_commitment(self, commitment: CommitmentConfig) -> Backfill {
+    Backfill { commitment, ..self }
+  }
+
+  /// Sets the number of signatures per page, capped at
+  /// [`MAX_SIGNATURES_PAGE`].
+  #[must_use]
+  pub fn with_page_size(self, page_size: usize) -> Backfill {
+    Backfill {
+      page_size: page_size.clamp(1, MAX_SIGNATURES_PAGE),
+      ..self
+    }
+  }
+
+  /// Sets the number of transactions fetched concurrently.
+  #[must_use]
+  pub fn with_concurrency(self, concurrency: usize) -> Backfill {
+    Backfill {
+      concurrency: concurrency.max(1),
+      ..self
+    }
+  }
+
+  /// Backfills both the exchange and stability pool programs from their own
+  /// cursors, returning the final cursors in the same order.
+  ///
+  /// # Errors
+  /// - See [`Self::run`]
+  pub async fn run_all<S: EventSink>(
+    &self,
+    exchange_cursor: BackfillCursor,
+    stability_pool_cursor: BackfillCursor,
+    sink: &mut S,
+  ) -> Result<(BackfillCursor, BackfillCursor)> {
+    let exchange = self.run(exchange::ID, exchange_cursor, sink).await?;
+    let stability_pool = self
+      .run(stability_pool::ID, stability_pool_cursor, sink)
+      .await?;
+    Ok((exchange, stability_pool))
+  }
+
+  /// Pages backwards through `program_id`'s signatures from `cursor` until
+  /// history or `cursor.until` is exhausted, writing each page to `sink`.
+  ///
+  /// Returns the cursor of the next backfill, which is also written to
+  /// `sink` with no events.
+  ///
+  /// # Errors
+  /// - RPC failure
+  /// - Event decoding failure
+  /// - Sink failure
+  pub async fn run<S: EventSink>(
+    &self,
+    program_id: Pubkey,
+    mut cursor: BackfillCursor,
+    sink: &mut S,
+  ) -> Result<BackfillCursor> {
+    while let Some(events) = self.next_page(program_id, &mut cursor).await? {
+      sink.write(program_id, events, &cursor).await?;
+    }
+    sink.write(program_id, Vec::new(), &cursor).await?;
+    Ok(cursor)
+  }
+
+  /// Fetches and decodes the next page of signatures before `cursor`,
+  /// advancing it. Returns `None` once there are no more signatures, leaving
+  /// `cursor` at [`BackfillCursor::next_run`].
+  ///
+  /// # Errors
+  /// - RPC failure
+  /// - Event decoding failure
+  pub async fn next_page(
+    &self,
+    program_id: Pubkey,
+    cursor: &mut BackfillCursor,
+  ) -> Result<Option<Vec<IndexedEvent>>> {
+    let config = GetConfirmedSignaturesForAddress2Config {
+      before: cursor.before,
+      until: cursor.until,
+      limit: Some(self.page_size),
+      commitment: Some(self.commitment),
+    };
+    let statuses = self
+      .rpc
+      .get_signatures_for_address_with_config(&program_id, config)
+      .await?;
+    let (Some(newest), Some(oldest)) = (statuses.first(), statuses.last())
+    else {
+      *cursor = cursor.next_run();
+      return Ok(None);
+    };
+    if cursor.before.is_none() && cursor.newest.is_none() {
+      cursor.newest = Some(newest.signature.parse()?);
+    }
+    let oldest = oldest.signature.parse()?;
+    let signatures = statuses
+      .into_iter()
+      .filter(|status| status.err.is_none())
+      .map(|status| Ok(status.signature.parse::<Signature>()?))
+      .collect::<Result<Vec<_>>>()?;
+    let pages = stream::iter(signatures)
+      .map(|signature| self.fetch_events(program_id, signature))
+      .buffered(self.concurrency)
+      .try_collect::<Vec<_>>()
+      .await?;
+    cursor.before = Some(oldest);
+    Ok(Some(pages.into_iter().flatten().collect()))
+  }
+
+  async fn fetch_events(
+    &self,
+    program_id: Pubkey,
+    signature: Signature,
+  ) -> Result<Vec<IndexedEvent>> {
+    let config = RpcTransactionConfig {
+      encoding: Some(UiTransactionEncoding::Base64),
+      commitment: Some(self.commitment),
+      max_supported_transaction_version: Some(0),
+    };
+    let tx = self
+      .rpc
+      .get_transaction_with_config(&signature, config)
+      .await
+      .with_context(|| format!("Failed to fetch transaction {signature}"))?;
+    let events = parse_transaction_events(&tx)
+      .with_context(|| format!("Failed to decode events in {signature}"))?;
+    Ok(
+      events
+        .into_iter()
+        .filter(|event| event.program_id() == program_id)
+        .map(|event| IndexedEvent {
+          signature,
+          slot: tx.slot,
+          block_time: tx.block_time,
+          event,
+        })
+        .collect(),
+    )
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::sync::Mutex;
+
+  use anchor_client::solana_client::rpc_request::RpcRequest;
+  use anchor_client::solana_client::rpc_sender::{
+    RpcSender, RpcTransportStats,
+  };
+  use anchor_client::solana_sdk::bs58;
+  use anchor_lang::event::EVENT_IX_TAG_LE;
+  use anchor_lang::{AnchorSerialize, Discriminator};
+  use base64::prelude::{Engine, BASE64_STANDARD};
+  use hylo_idl::exchange::events::SwapStableToLeverEventV0;
+  use hylo_idl::exchange::types::UFixValue64;
+  use hylo_idl::stability_pool::events::RebalanceStableToLeverEvent;
+  use itertools::Itertools;
+  use serde_json::{json, Value};
+
+  use super::*;
+
+  /// Serves a signature history, newest first, where every
+  /// transaction emits one exchange CPI event and one stability pool log
+  /// event.
+  struct MockSender {
+    signatures: Arc<Mutex<Vec<Signature>>>,
+  }
+
+  impl MockSender {
+    fn transaction(slot: u64) -> Value {
+      let amount = UFixValue64 {
+        bits: slot,
+        exp: -6,
+      };
+      let swap = SwapStableToLeverEventV0 {
+        stablecoin_burned: amount,
+        levercoin_minted: amount,
+      };
+      let mut cpi = EVENT_IX_TAG_LE.to_vec();
+      cpi.extend(SwapStableToLeverEventV0::DISCRIMINATOR);
+      cpi.extend(swap.try_to_vec().expect("serialize"));
+      let rebalance = RebalanceStableToLeverEvent {
+        stablecoin_swapped: hylo_idl::stability_pool::types::UFixValue64 {
+          bits: slot,
+          exp: -6,
+        },
+      };
+      let mut log = RebalanceStableToLeverEvent::DISCRIMINATOR.to_vec();
+      log.extend(rebalance.try_to_vec().expect("serialize"));
+      let pool = stability_pool::ID.to_string();
+      json!({
+        "slot": slot,
+        "blockTime": 1_700_000_000 + slot,
+        "transaction": {
+          "signatures": [],
+          "message": {
+            "header": {
+              "numRequiredSignatures": 1,
+              "numReadonlySignedAccounts": 0,
+              "numReadonlyUnsignedAccounts": 1
+            },
+            "accountKeys": [Pubkey::new_unique().to_string()],
+            "recentBlockhash": Pubkey::default().to_string(),
+            "instructions": []
+          }
+        },
+        "meta": {
+          "err": null,
+          "status": { "Ok": null },
+          "fee": 5000,
+          "preBalances": [],
+          "postBalances": [],
+          "innerInstructions": [{
+            "index": 0,
+            "instructions": [{
+              "programIdIndex": 1,
+              "accounts": [],
+              "data": bs58::encode(&cpi).into_string(),
+              "stackHeight": 2
+            }]
+          }],
+          "logMessages": [
+            format!("Program {pool} invoke [1]"),
+            format!("Program data: {}", BASE64_STANDARD.encode(&log)),
+            format!("Program {pool} success")
+          ],
+          "loadedAddresses": {
+            "writable": [],
+            "readonly": [exchange::ID.to_string()]
+          }
+        }
+      })
+    }
+  }
+
+  #[async_trait::async_trait]
+  impl RpcSender for MockSender {
+    async fn send(
+      &self,
+      request: RpcRequest,
+      params: Value,
+    ) -> anchor_client::solana_client::client_error::Result<Value> {
+      let value = match request {
+        RpcRequest::GetSignaturesForAddress => {
+          let config = &params[1];
+          let parse = |key: &str| {
+            config[key]
+              .as_str()
+              .map(|s| s.parse::<Signature>().expect("signature"))
+          };
+          let limit = usize::try_from(config["limit"].as_u64().expect("limit"))
+            .expect("limit");
+          let signatures = self.signatures.lock().expect("signatures");
+          let start = parse("before").map_or(0, |before| {
+            signatures.iter().position(|s| *s == before).unwrap() + 1
+          });
+          let until = parse("until");
+          let page = signatures[start..]
+            .iter()
+            .take_while(|s| Some(**s) != until)
+            .take(limit)
+            .map(|s| {
+              let slot = slot_in(&signatures, s);
+              json!({
+                "signature": s.to_string(),
+                "slot": slot,
+                "err": null,
+                "memo": null,
+                "blockTime": null,
+                "confirmationStatus": "confirmed"
+              })
+            })
+            .collect::<Vec<_>>();
+          json!(page)
+        }
+        RpcRequest::GetTransaction => {
+          let signature = params[0]
+            .as_str()
+            .expect("signature")
+            .parse()
+            .expect("signature");
+          Self::transaction(self.slot(&signature))
+        }
+        _ => Value::Null,
+      };
+      Ok(value)
+    }
+
+    fn get_transport_stats(&self) -> RpcTransportStats {
+      RpcTransportStats::default()
+    }
+
+    fn url(&self) -> String {
+      "mock".to_string()
+    }
+  }
+
+  impl MockSender {
+    fn slot(&self, signature: &Signature) -> u64 {
+      slot_in(&self.signatures.lock().expect("signatures"), signature)
+    }
+  }
+
+  /// Slot of `signature`, counting up from the oldest in `signatures`.
+  fn slot_in(signatures: &[Signature], signature: &Signature) -> u64 {
+    let index = signatures
+      .iter()
+      .position(|s| s == signature)
+      .expect("known signature");
+    (signatures.len() - index) as u64
+  }
+
+  fn unique_signatures(n: usize) -> Vec<Signature> {
+    (0..n).map(|_| Signature::new_unique()).collect_vec()
+  }
+
+  fn mock_backfill(
+    n: usize,
+  ) -> (Backfill, Vec<Signature>, Arc<Mutex<Vec<Signature>>>) {
+    let signatures = unique_signatures(n);
+    let history = Arc::new(Mutex::new(signatures.clone()));
+    let sender = MockSender {
+      signatures: history.clone(),
+    };
+    let rpc = RpcClient::new_sender(sender, Default::default());
+    let backfill = Backfill::new(Arc::new(rpc))
+      .with_page_size(3)
+      .with_concurrency(2);
+    (backfill, signatures, history)
+  }
+
+  #[tokio::test]
+  async fn pages_through_history() -> Result<()> {
+    let (backfill, signatures, _) = mock_backfill(7);
+    let mut events = Vec::new();
+    let cursor = backfill
+      .run(exchange::ID, BackfillCursor::default(), &mut events)
+      .await?;
+    assert_eq!(cursor.before, None);
+    assert_eq!(cursor.until, signatures.first().copied());
+    assert_eq!(events.iter().map(|e| e.signature).collect_vec(), signatures);
+    assert!(events
+      .iter()
+      .all(|e| matches!(e.event, HyloEvent::SwapStableToLever(_))));
+    assert_eq!(events[0].slot, 7);
+    assert_eq!(events[0].block_time, Some(1_700_000_007));
+    Ok(())
+  }
+
+  #[tokio::test]
+  async fn resumes_from_cursor() -> Result<()> {
+    let (backfill, signatures, _) = mock_backfill(7);
+    let cursor = BackfillCursor {
+      before: Some(signatures[1]),
+      until: Some(signatures[5]),
+      newest: Some(signatures[0]),
+    };
+    let mut events = Vec::new();
+    let cursor = backfill
+      .run(stability_pool::ID, cursor, &mut events)
+      .await?;
+    assert_eq!(
+      events.iter().map(|e| e.signature).collect_vec(),
+      signatures[2..5]
+    );
+    assert!(events
+      .iter()
+      .all(|e| matches!(e.event, HyloEvent::RebalanceStableToLever(_))));
+    assert_eq!(
+      cursor,
+      BackfillCursor {
+        before: None,
+        until: Some(signatures[0]),
+        newest: None,
+      }
+    );
+    Ok(())
+  }
+
+  #[tokio::test]
+  async fn consecutive_runs_index_only_new_signatures() -> Result<()> {
+    let (backfill, first, history) = mock_backfill(4);
+    let mut events = Vec::new();
+    let cursor = backfill
+      .run(exchange::ID, BackfillCursor::default(), &mut events)
+      .await?;
+    assert_eq!(events.len(), 4);
+
+    let second = unique_signatures(5);
+    history
+      .lock()
+      .expect("history")
+      .splice(0..0, second.clone());
+    let mut events = Vec::new();
+    let cursor = backfill.run(exchange::ID, cursor, &mut events).await?;
+    assert_eq!(events.iter().map(|e| e.signature).collect_vec(), second);
+    assert_eq!(cursor.until, second.first().copied());
+
+    let mut events = Vec::new();
+    let cursor = backfill.run(exchange::ID, cursor, &mut events).await?;
+    assert!(events.is_empty());
+    assert_eq!(cursor.until, second.first().copied());
+    assert_ne!(cursor.until, first.first().copied());
+    Ok(())
+  }
+
+  #[tokio::test]
+  async fn sink_receives_next_run_cursor() -> Result<()> {
+    struct Cursors(Vec<BackfillCursor>);
+
+    #[async_trait::async_trait]
+    impl EventSink for Cursors {
+      async fn write(
+        &mut self,
+        _program_id: Pubkey,
+        _events: Vec<IndexedEvent>,
+        cursor: &BackfillCursor,
+      ) -> Result<()> {
+        self.0.push(cursor.clone());
+        Ok(())
+      }
+    }
+
+    let (backfill, signatures, _) = mock_backfill(4);
+    let mut sink = Cursors(Vec::new());
+    let cursor = backfill
+      .run(exchange::ID, BackfillCursor::default(), &mut sink)
+      .await?;
+    assert_eq!(sink.0.first().and_then(|c| c.newest), Some(signatures[0]));
+    assert_eq!(sink.0.last(), Some(&cursor));
+    Ok(())
+  }
+}
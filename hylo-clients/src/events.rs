@@ -0,0 +1,751 @@
+use std::str::FromStr;
+
+use anchor_client::solana_sdk::bs58;
+use anchor_lang::event::EVENT_IX_TAG_LE;
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::{AnchorDeserialize, Discriminator};
+use anyhow::{anyhow, Context, Result};
+use base64::prelude::{Engine, BASE64_STANDARD};
+use fix::prelude::UFixValue64;
+use hylo_idl::exchange::events as exchange_events;
+use hylo_idl::stability_pool::events as stability_pool_events;
+use hylo_idl::{exchange, stability_pool};
+use solana_transaction_status_client_types::option_serializer::OptionSerializer;
+use solana_transaction_status_client_types::{
+  EncodedConfirmedTransactionWithStatusMeta, EncodedTransaction, UiInstruction,
+  UiMessage, UiParsedInstruction, UiPartiallyDecodedInstruction,
+  UiTransactionStatusMeta,
+};
+
+/// Exponent of protocol token amounts in legacy events storing raw `u64`.
+const TOKEN_EXP: i8 = -6;
+
+/// Exponent of LST amounts in legacy events storing raw `u64`.
+const LST_EXP: i8 = -9;
+
+/// Log line prefix for events emitted with `emit!`.
+const PROGRAM_DATA: &str = "Program data: ";
+
+/// Mint of hyUSD or xSOL, across all event versions.
+/// Fields missing from older versions are `None`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct MintEvent {
+  pub version: u8,
+  pub minted: UFixValue64,
+  pub nav: UFixValue64,
+  pub sol_usd_price: Option<UFixValue64>,
+  pub lst_mint: Option<Pubkey>,
+  pub lst_sol_price: Option<UFixValue64>,
+  pub collateral_deposited: Option<UFixValue64>,
+  pub fees_deposited: Option<UFixValue64>,
+  pub collateral_ratio: Option<UFixValue64>,
+  pub total_supply: Option<UFixValue64>,
+}
+
+/// Redemption of hyUSD or xSOL, across all event versions.
+/// Fields missing from older versions are `None`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RedeemEvent {
+  pub version: u8,
+  pub redeemed: UFixValue64,
+  pub nav: UFixValue64,
+  pub sol_usd_price: Option<UFixValue64>,
+  pub lst_mint: Option<Pubkey>,
+  pub lst_sol_price: Option<UFixValue64>,
+  pub collateral_withdrawn: Option<UFixValue64>,
+  pub fees_deposited: Option<UFixValue64>,
+  pub collateral_ratio: Option<UFixValue64>,
+  pub total_supply: Option<UFixValue64>,
+}
+
+/// Swap between hyUSD and xSOL, across all event versions.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SwapEvent {
+  pub version: u8,
+  /// Input token burned
+  pub burned: UFixValue64,
+  /// Output token minted to the user
+  pub minted: UFixValue64,
+  /// Fees taken in stablecoin
+  pub stablecoin_fees: Option<UFixValue64>,
+  pub stablecoin_nav: Option<UFixValue64>,
+  pub levercoin_nav: Option<UFixValue64>,
+}
+
+/// Yield harvest into the stability pool, across all event versions.
+#[derive(Clone, Debug, PartialEq)]
+pub struct HarvestYieldEvent {
+  pub version: u8,
+  pub harvest_token_mint: Option<Pubkey>,
+  pub harvestable_stablecoin: Option<UFixValue64>,
+  pub total_sol_harvested: Option<UFixValue64>,
+  pub fees_extracted: UFixValue64,
+  pub token_to_pool: UFixValue64,
+  pub sol_usd_price: UFixValue64,
+}
+
+/// Stability pool withdrawal, across all event versions.
+#[derive(Clone, Debug, PartialEq)]
+pub struct UserWithdrawEvent {
+  pub version: u8,
+  pub lp_token_burned: UFixValue64,
+  pub stablecoin_withdrawn: UFixValue64,
+  pub stablecoin_fees: Option<UFixValue64>,
+  pub stablecoin_nav: Option<UFixValue64>,
+  pub levercoin_withdrawn: UFixValue64,
+  pub levercoin_nav: Option<UFixValue64>,
+}
+
+/// Every event emitted by the exchange and stability pool programs.
+/// Versioned events are normalized into a single variant.
+#[derive(Clone, Debug)]
+pub enum HyloEvent {
+  // Exchange
+  MintStablecoin(MintEvent),
+  MintLevercoin(MintEvent),
+  RedeemStablecoin(RedeemEvent),
+  RedeemLevercoin(RedeemEvent),
+  SwapStableToLever(SwapEvent),
+  SwapLeverToStable(SwapEvent),
+  SwapLst(exchange_events::SwapLstEventV0),
+  HarvestYield(HarvestYieldEvent),
+  ExchangeStats(exchange_events::ExchangeStats),
+  RegisterLst(exchange_events::RegisterLstEvent),
+  UpdateLstPrices(exchange_events::UpdateLstPricesEvent),
+  UpdateExchangeAdmin(exchange_events::UpdateAdminEvent),
+  UpdateLevercoinFees(exchange_events::UpdateLevercoinFeesEvent),
+  UpdateStablecoinFees(exchange_events::UpdateStablecoinFeesEvent),
+  UpdateLstSwapFee(exchange_events::UpdateLstSwapFeeEvent),
+  UpdateOracleAddress(exchange_events::UpdateOracleAddressEvent),
+  UpdateOracleConf(exchange_events::UpdateOracleConfEvent),
+  UpdateOracleInterval(exchange_events::UpdateOracleIntervalEvent),
+  UpdateStabilityPool(exchange_events::UpdateStabilityPoolEvent),
+  UpdateStabilityThresholds(exchange_events::UpdateStabilityThresholdsEvent),
+  UpdateTreasury(exchange_events::UpdateTreasuryEvent),
+  UpdateYieldHarvestConfig(exchange_events::UpdateYieldHarvestConfigEvent),
+  WithdrawFees(exchange_events::WithdrawFeesEvent),
+  // Stability pool
+  UserDeposit(stability_pool_events::UserDepositEvent),
+  UserWithdraw(UserWithdrawEvent),
+  RebalanceStableToLever(stability_pool_events::RebalanceStableToLeverEvent),
+  RebalanceLeverToStable(stability_pool_events::RebalanceLeverToStableEvent),
+  StabilityPoolStats(stability_pool_events::StabilityPoolStats),
+  UpdateStabilityPoolAdmin(stability_pool_events::UpdateAdminEvent),
+  UpdateWithdrawalFee(stability_pool_events::UpdateWithdrawalFeeEvent),
+}
+
+/// Tries each event type's discriminator against `$data`, deserializing the
+/// first match and mapping it into a [`HyloEvent`].
+macro_rules! decode_events {
+  ($data:expr, { $($event:ty => $variant:expr),* $(,)? }) => {{
+    let data: &[u8] = $data;
+    $(
+      if let Some(mut body) =
+        data.strip_prefix(<$event as Discriminator>::DISCRIMINATOR)
+      {
+        let event = <$event>::deserialize(&mut body).with_context(|| {
+          format!("Failed to deserialize {}", stringify!($event))
+        })?;
+        return Ok(Some($variant(event)));
+      }
+    )*
+    Ok(None)
+  }};
+}
+
+impl HyloEvent {
+  /// Program which emitted this event.
+  #[must_use]
+  pub fn program_id(&self) -> Pubkey {
+    match self {
+      HyloEvent::UserDeposit(_)
+      | HyloEvent::UserWithdraw(_)
+      | HyloEvent::RebalanceStableToLever(_)
+      | HyloEvent::RebalanceLeverToStable(_)
+      | HyloEvent::StabilityPoolStats(_)
+      | HyloEvent::UpdateStabilityPoolAdmin(_)
+      | HyloEvent::UpdateWithdrawalFee(_) => stability_pool::ID,
+      _ => exchange::ID,
+    }
+  }
+
+  /// Decodes discriminator-prefixed event data emitted by `program_id`.
+  /// Returns `None` for programs or discriminators outside of Hylo.
+  ///
+  /// # Errors
+  /// - Discriminator matches but event body fails deserialization
+  pub fn decode(program_id: &Pubkey, data: &[u8]) -> Result<Option<HyloEvent>> {
+    if *program_id == exchange::ID {
+      Self::decode_exchange(data)
+    } else if *program_id == stability_pool::ID {
+      Self::decode_stability_pool(data)
+    } else {
+      Ok(None)
+    }
+  }
+
+  fn decode_exchange(data: &[u8]) -> Result<Option<HyloEvent>> {
+    use exchange_events::*;
+    use HyloEvent as E;
+    decode_events!(data, {
+      MintStablecoinEventV0 => |e| E::MintStablecoin(MintEvent::from(e)),
+      MintStablecoinEventV1 => |e| E::MintStablecoin(MintEvent::from(e)),
+      MintStablecoinEventV2 => |e| E::MintStablecoin(MintEvent::from(e)),
+      MintLevercoinEventV0 => |e| E::MintLevercoin(MintEvent::from(e)),
+      MintLevercoinEventV1 => |e| E::MintLevercoin(MintEvent::from(e)),
+      MintLevercoinEventV2 => |e| E::MintLevercoin(MintEvent::from(e)),
+      RedeemStablecoinEventV0 => |e| E::RedeemStablecoin(RedeemEvent::from(e)),
+      RedeemStablecoinEventV1 => |e| E::RedeemStablecoin(RedeemEvent::from(e)),
+      RedeemStablecoinEventV2 => |e| E::RedeemStablecoin(RedeemEvent::from(e)),
+      RedeemLevercoinEventV0 => |e| E::RedeemLevercoin(RedeemEvent::from(e)),
+      RedeemLevercoinEventV1 => |e| E::RedeemLevercoin(RedeemEvent::from(e)),
+      RedeemLevercoinEventV2 => |e| E::RedeemLevercoin(RedeemEvent::from(e)),
+      SwapStableToLeverEventV0 => |e| E::SwapStableToLever(SwapEvent::from(e)),
+      SwapStableToLeverEventV1 => |e| E::SwapStableToLever(SwapEvent::from(e)),
+      SwapLeverToStableEventV0 => |e| E::SwapLeverToStable(SwapEvent::from(e)),
+      SwapLeverToStableEventV1 => |e| E::SwapLeverToStable(SwapEvent::from(e)),
+      SwapLstEventV0 => E::SwapLst,
+      HarvestYieldEventV1 => |e| E::HarvestYield(HarvestYieldEvent::from(e)),
+      HarvestYieldEventV2 => |e| E::HarvestYield(HarvestYieldEvent::from(e)),
+      ExchangeStats => E::ExchangeStats,
+      RegisterLstEvent => E::RegisterLst,
+      UpdateLstPricesEvent => E::UpdateLstPrices,
+      UpdateAdminEvent => E::UpdateExchangeAdmin,
+      UpdateLevercoinFeesEvent => E::UpdateLevercoinFees,
+      UpdateStablecoinFeesEvent => E::UpdateStablecoinFees,
+      UpdateLstSwapFeeEvent => E::UpdateLstSwapFee,
+      UpdateOracleAddressEvent => E::UpdateOracleAddress,
+      UpdateOracleConfEvent => E::UpdateOracleConf,
+      UpdateOracleIntervalEvent => E::UpdateOracleInterval,
+      UpdateStabilityPoolEvent => E::UpdateStabilityPool,
+      UpdateStabilityThresholdsEvent => E::UpdateStabilityThresholds,
+      UpdateTreasuryEvent => E::UpdateTreasury,
+      UpdateYieldHarvestConfigEvent => E::UpdateYieldHarvestConfig,
+      WithdrawFeesEvent => E::WithdrawFees,
+    })
+  }
+
+  fn decode_stability_pool(data: &[u8]) -> Result<Option<HyloEvent>> {
+    use stability_pool_events::*;
+    use HyloEvent as E;
+    decode_events!(data, {
+      UserDepositEvent => E::UserDeposit,
+      UserWithdrawEventV0 => |e| E::UserWithdraw(UserWithdrawEvent::from(e)),
+      UserWithdrawEventV1 => |e| E::UserWithdraw(UserWithdrawEvent::from(e)),
+      RebalanceStableToLeverEvent => E::RebalanceStableToLever,
+      RebalanceLeverToStableEvent => E::RebalanceLeverToStable,
+      StabilityPoolStats => E::StabilityPoolStats,
+      UpdateAdminEvent => E::UpdateStabilityPoolAdmin,
+      UpdateWithdrawalFeeEvent => E::UpdateWithdrawalFee,
+    })
+  }
+}
+
+/// Extracts every Hylo event emitted by a confirmed transaction, in emission
+/// order.
+///
+/// Events emitted through `emit_cpi!` are read from inner instructions and
+/// `emit!` events from program logs. Both are ordered by top-level instruction
+/// index, then by their position in the logs. An `emit_cpi!` event whose
+/// invocation is missing from truncated logs sorts last in its instruction.
+/// Failed transactions yield no events.
+///
+/// # Errors
+/// - Transaction has no status meta
+/// - Account keys or event data cannot be decoded
+pub fn parse_transaction_events(
+  tx: &EncodedConfirmedTransactionWithStatusMeta,
+) -> Result<Vec<HyloEvent>> {
+  let meta = tx
+    .transaction
+    .meta
+    .as_ref()
+    .context("Transaction has no status meta")?;
+  if meta.err.is_some() {
+    return Ok(Vec::new());
+  }
+  let account_keys = account_keys(&tx.transaction.transaction, meta)?;
+  let trace = LogTrace::new(meta)?;
+  let mut events = cpi_events(meta, &account_keys, &trace.inner_invokes)?;
+  events.extend(trace.events);
+  events.sort_by_key(|event| (event.instruction, event.position));
+  Ok(events.into_iter().map(|event| event.event).collect())
+}
+
+/// Event with the top-level instruction and log line emitting it.
+struct OrderedEvent {
+  instruction: usize,
+  position: usize,
+  event: HyloEvent,
+}
+
+/// Decodes events from self-CPI inner instructions tagged with
+/// [`EVENT_IX_TAG_LE`], positioned at the log line of their invocation.
+fn cpi_events(
+  meta: &UiTransactionStatusMeta,
+  account_keys: &[Pubkey],
+  inner_invokes: &[Vec<usize>],
+) -> Result<Vec<OrderedEvent>> {
+  let OptionSerializer::Some(inner) = &meta.inner_instructions else {
+    return Ok(Vec::new());
+  };
+  let mut events = Vec::new();
+  for ixs in inner {
+    let instruction = usize::from(ixs.index);
+    for (i, ix) in ixs.instructions.iter().enumerate() {
+      let (program_id, data) = match ix {
+        UiInstruction::Compiled(ix) => (
+          *account_keys
+            .get(usize::from(ix.program_id_index))
+            .context("Program index out of account keys")?,
+          &ix.data,
+        ),
+        UiInstruction::Parsed(UiParsedInstruction::PartiallyDecoded(
+          UiPartiallyDecodedInstruction {
+            program_id, data, ..
+          },
+        )) => (Pubkey::from_str(program_id)?, data),
+        UiInstruction::Parsed(UiParsedInstruction::Parsed(_)) => continue,
+      };
+      let bytes = bs58::decode(data).into_vec()?;
+      if let Some(event_data) = bytes.strip_prefix(EVENT_IX_TAG_LE) {
+        let position = inner_invokes
+          .get(instruction)
+          .and_then(|invokes| invokes.get(i))
+          .copied()
+          .unwrap_or(usize::MAX);
+        events.extend(HyloEvent::decode(&program_id, event_data)?.map(
+          |event| OrderedEvent {
+            instruction,
+            position,
+            event,
+          },
+        ));
+      }
+    }
+  }
+  Ok(events)
+}
+
+/// Events and inner invocations read from program logs.
+struct LogTrace {
+  /// `emit!` events, attributed to the program on top of the invocation
+  /// stack
+  events: Vec<OrderedEvent>,
+
+  /// Log line of each inner invocation, per top-level instruction, matching
+  /// the order of inner instructions in the status meta
+  inner_invokes: Vec<Vec<usize>>,
+}
+
+impl LogTrace {
+  fn new(meta: &UiTransactionStatusMeta) -> Result<LogTrace> {
+    let mut trace = LogTrace {
+      events: Vec::new(),
+      inner_invokes: Vec::new(),
+    };
+    let OptionSerializer::Some(logs) = &meta.log_messages else {
+      return Ok(trace);
+    };
+    let mut stack: Vec<Pubkey> = Vec::new();
+    for (position, log) in logs.iter().enumerate() {
+      let instruction = trace.inner_invokes.len().saturating_sub(1);
+      if let Some(data) = log.strip_prefix(PROGRAM_DATA) {
+        if let Some(program_id) = stack.last() {
+          let bytes = BASE64_STANDARD.decode(data)?;
+          trace
+            .events
+            .extend(HyloEvent::decode(program_id, &bytes)?.map(|event| {
+              OrderedEvent {
+                instruction,
+                position,
+                event,
+              }
+            }));
+        }
+      } else if let Some(rest) = log.strip_prefix("Program ") {
+        let mut words = rest.split_whitespace();
+        match (words.next(), words.next(), words.next()) {
+          (Some(program_id), Some("invoke"), Some("[1]")) => {
+            stack.push(Pubkey::from_str(program_id)?);
+            trace.inner_invokes.push(Vec::new());
+          }
+          (Some(program_id), Some("invoke"), _) => {
+            stack.push(Pubkey::from_str(program_id)?);
+            if let Some(invokes) = trace.inner_invokes.last_mut() {
+              invokes.push(position);
+            }
+          }
+          (Some(_), Some("success" | "failed:"), _) => {
+            stack.pop();
+          }
+          _ => {}
+        }
+      }
+    }
+    Ok(trace)
+  }
+}
+
+/// Full account key list of a transaction, including keys loaded from
+/// address lookup tables.
+fn account_keys(
+  tx: &EncodedTransaction,
+  meta: &UiTransactionStatusMeta,
+) -> Result<Vec<Pubkey>> {
+  let static_keys = match tx {
+    EncodedTransaction::Json(ui_tx) => match &ui_tx.message {
+      // Parsed messages already include loaded addresses
+      UiMessage::Parsed(msg) => {
+        return msg
+          .account_keys
+          .iter()
+          .map(|account| Ok(Pubkey::from_str(&account.pubkey)?))
+          .collect();
+      }
+      UiMessage::Raw(msg) => msg
+        .account_keys
+        .iter()
+        .map(|key| Ok(Pubkey::from_str(key)?))
+        .collect::<Result<Vec<_>>>()?,
+    },
+    EncodedTransaction::Accounts(list) => {
+      return list
+        .account_keys
+        .iter()
+        .map(|account| Ok(Pubkey::from_str(&account.pubkey)?))
+        .collect();
+    }
+    EncodedTransaction::LegacyBinary(_) | EncodedTransaction::Binary(..) => tx
+      .decode()
+      .ok_or(anyhow!("Failed to decode binary transaction"))?
+      .message
+      .static_account_keys()
+      .to_vec(),
+  };
+  let loaded = match &meta.loaded_addresses {
+    OptionSerializer::Some(loaded) => loaded
+      .writable
+      .iter()
+      .chain(&loaded.readonly)
+      .map(|key| Ok(Pubkey::from_str(key)?))
+      .collect::<Result<Vec<_>>>()?,
+    _ => Vec::new(),
+  };
+  Ok(static_keys.into_iter().chain(loaded).collect())
+}
+
+// ============================================================================
+// Version normalization
+// ============================================================================
+
+fn token_amount(bits: u64) -> UFixValue64 {
+  UFixValue64 {
+    bits,
+    exp: TOKEN_EXP,
+  }
+}
+
+fn lst_amount(bits: u64) -> UFixValue64 {
+  UFixValue64 { bits, exp: LST_EXP }
+}
+
+/// Implements [`From`] for the mint and redeem event families, which share
+/// field layouts between hyUSD and xSOL.
+macro_rules! impl_mint_redeem_from {
+  ($mint_v0:ty, $mint_v1:ty, $mint_v2:ty,
+   $redeem_v0:ty, $redeem_v1:ty, $redeem_v2:ty) => {
+    impl From<$mint_v0> for MintEvent {
+      fn from(e: $mint_v0) -> Self {
+        MintEvent {
+          version: 0,
+          minted: e.minted.into(),
+          nav: e.nav.into(),
+          sol_usd_price: Some(e.sol_usd_price.into()),
+          lst_mint: None,
+          lst_sol_price: None,
+          collateral_deposited: Some(lst_amount(e.collateral_deposited)),
+          fees_deposited: None,
+          collateral_ratio: Some(e.collateral_ratio.into()),
+          total_supply: Some(token_amount(e.total_supply)),
+        }
+      }
+    }
+
+    impl From<$mint_v1> for MintEvent {
+      fn from(e: $mint_v1) -> Self {
+        MintEvent {
+          version: 1,
+          minted: e.minted.into(),
+          nav: e.nav.into(),
+          sol_usd_price: None,
+          lst_mint: None,
+          lst_sol_price: None,
+          collateral_deposited: None,
+          fees_deposited: None,
+          collateral_ratio: None,
+          total_supply: None,
+        }
+      }
+    }
+
+    impl From<$mint_v2> for MintEvent {
+      fn from(e: $mint_v2) -> Self {
+        MintEvent {
+          version: 2,
+          minted: e.minted.into(),
+          nav: e.nav.into(),
+          sol_usd_price: Some(e.sol_usd_price.into()),
+          lst_mint: Some(e.lst_mint),
+          lst_sol_price: Some(e.lst_sol_price.into()),
+          collateral_deposited: Some(e.collateral_deposited.into()),
+          fees_deposited: Some(e.fees_deposited.into()),
+          collateral_ratio: None,
+          total_supply: None,
+        }
+      }
+    }
+
+    impl From<$redeem_v0> for RedeemEvent {
+      fn from(e: $redeem_v0) -> Self {
+        RedeemEvent {
+          version: 0,
+          redeemed: token_amount(e.redeemed),
+          nav: e.nav.into(),
+          sol_usd_price: Some(e.sol_usd_price.into()),
+          lst_mint: None,
+          lst_sol_price: None,
+          collateral_withdrawn: Some(e.collateral_withdrawn.into()),
+          fees_deposited: None,
+          collateral_ratio: Some(e.collateral_ratio.into()),
+          total_supply: Some(token_amount(e.total_supply)),
+        }
+      }
+    }
+
+    impl From<$redeem_v1> for RedeemEvent {
+      fn from(e: $redeem_v1) -> Self {
+        RedeemEvent {
+          version: 1,
+          redeemed: e.redeemed.into(),
+          nav: e.nav.into(),
+          sol_usd_price: None,
+          lst_mint: None,
+          lst_sol_price: None,
+          collateral_withdrawn: None,
+          fees_deposited: None,
+          collateral_ratio: None,
+          total_supply: None,
+        }
+      }
+    }
+
+    impl From<$redeem_v2> for RedeemEvent {
+      fn from(e: $redeem_v2) -> Self {
+        RedeemEvent {
+          version: 2,
+          redeemed: e.redeemed.into(),
+          nav: e.nav.into(),
+          sol_usd_price: Some(e.sol_usd_price.into()),
+          lst_mint: Some(e.lst_mint),
+          lst_sol_price: Some(e.lst_sol_price.into()),
+          collateral_withdrawn: Some(e.collateral_withdrawn.into()),
+          fees_deposited: Some(e.fees_deposited.into()),
+          collateral_ratio: None,
+          total_supply: None,
+        }
+      }
+    }
+  };
+}
+
+impl_mint_redeem_from!(
+  exchange_events::MintStablecoinEventV0,
+  exchange_events::MintStablecoinEventV1,
+  exchange_events::MintStablecoinEventV2,
+  exchange_events::RedeemStablecoinEventV0,
+  exchange_events::RedeemStablecoinEventV1,
+  exchange_events::RedeemStablecoinEventV2
+);
+
+impl_mint_redeem_from!(
+  exchange_events::MintLevercoinEventV0,
+  exchange_events::MintLevercoinEventV1,
+  exchange_events::MintLevercoinEventV2,
+  exchange_events::RedeemLevercoinEventV0,
+  exchange_events::RedeemLevercoinEventV1,
+  exchange_events::RedeemLevercoinEventV2
+);
+
+impl From<exchange_events::SwapStableToLeverEventV0> for SwapEvent {
+  fn from(e: exchange_events::SwapStableToLeverEventV0) -> Self {
+    SwapEvent {
+      version: 0,
+      burned: e.stablecoin_burned.into(),
+      minted: e.levercoin_minted.into(),
+      stablecoin_fees: None,
+      stablecoin_nav: None,
+      levercoin_nav: None,
+    }
+  }
+}
+
+impl From<exchange_events::SwapStableToLeverEventV1> for SwapEvent {
+  fn from(e: exchange_events::SwapStableToLeverEventV1) -> Self {
+    SwapEvent {
+      version: 1,
+      burned: e.stablecoin_burned.into(),
+      minted: e.levercoin_minted.into(),
+      stablecoin_fees: Some(e.stablecoin_fees.into()),
+      stablecoin_nav: Some(e.stablecoin_nav.into()),
+      levercoin_nav: Some(e.levercoin_nav.into()),
+    }
+  }
+}
+
+impl From<exchange_events::SwapLeverToStableEventV0> for SwapEvent {
+  fn from(e: exchange_events::SwapLeverToStableEventV0) -> Self {
+    SwapEvent {
+      version: 0,
+      burned: e.levercoin_burned.into(),
+      minted: e.stablecoin_minted.into(),
+      stablecoin_fees: None,
+      stablecoin_nav: None,
+      levercoin_nav: None,
+    }
+  }
+}
+
+impl From<exchange_events::SwapLeverToStableEventV1> for SwapEvent {
+  fn from(e: exchange_events::SwapLeverToStableEventV1) -> Self {
+    SwapEvent {
+      version: 1,
+      burned: e.levercoin_burned.into(),
+      minted: e.stablecoin_minted_user.into(),
+      stablecoin_fees: Some(e.stablecoin_minted_fees.into()),
+      stablecoin_nav: Some(e.stablecoin_nav.into()),
+      levercoin_nav: Some(e.levercoin_nav.into()),
+    }
+  }
+}
+
+impl From<exchange_events::HarvestYieldEventV1> for HarvestYieldEvent {
+  fn from(e: exchange_events::HarvestYieldEventV1) -> Self {
+    HarvestYieldEvent {
+      version: 1,
+      harvest_token_mint: None,
+      harvestable_stablecoin: Some(e.harvestable_stablecoin.into()),
+      total_sol_harvested: None,
+      fees_extracted: e.fees_extracted.into(),
+      token_to_pool: e.stablecoin_to_pool.into(),
+      sol_usd_price: e.sol_usd_price.into(),
+    }
+  }
+}
+
+impl From<exchange_events::HarvestYieldEventV2> for HarvestYieldEvent {
+  fn from(e: exchange_events::HarvestYieldEventV2) -> Self {
+    HarvestYieldEvent {
+      version: 2,
+      harvest_token_mint: Some(e.harvest_token_mint),
+      harvestable_stablecoin: None,
+      total_sol_harvested: Some(e.total_sol_harvested.into()),
+      fees_extracted: e.fees_extracted.into(),
+      token_to_pool: e.token_to_pool.into(),
+      sol_usd_price: e.sol_usd_price.into(),
+    }
+  }
+}
+
+impl From<stability_pool_events::UserWithdrawEventV0> for UserWithdrawEvent {
+  fn from(e: stability_pool_events::UserWithdrawEventV0) -> Self {
+    UserWithdrawEvent {
+      version: 0,
+      lp_token_burned: e.lp_token_burned.into(),
+      stablecoin_withdrawn: e.stablecoin_withdrawn.into(),
+      stablecoin_fees: None,
+      stablecoin_nav: None,
+      levercoin_withdrawn: e.levercoin_withdrawn.into(),
+      levercoin_nav: None,
+    }
+  }
+}
+
+impl From<stability_pool_events::UserWithdrawEventV1> for UserWithdrawEvent {
+  fn from(e: stability_pool_events::UserWithdrawEventV1) -> Self {
+    UserWithdrawEvent {
+      version: 1,
+      lp_token_burned: e.lp_token_burned.into(),
+      stablecoin_withdrawn: e.stablecoin_withdrawn.into(),
+      stablecoin_fees: Some(e.stablecoin_fees.into()),
+      stablecoin_nav: Some(e.stablecoin_nav.into()),
+      levercoin_withdrawn: e.levercoin_withdrawn.into(),
+      levercoin_nav: Some(e.levercoin_nav.into()),
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use hylo_idl::tokens::{TokenMint, JITOSOL};
+
+  use super::*;
+
+  fn fixture(name: &str) -> Result<EncodedConfirmedTransactionWithStatusMeta> {
+    let path = format!(
+      "{}/tests/data/events/{name}.json",
+      env!("CARGO_MANIFEST_DIR")
+    );
+    Ok(serde_json::from_str(&std::fs::read_to_string(path)?)?)
+  }
+
+  fn deposited(event: &HyloEvent) -> Option<u64> {
+    match event {
+      HyloEvent::UserDeposit(e) => Some(e.stablecoin_deposited.bits),
+      _ => None,
+    }
+  }
+
+  #[test]
+  fn legacy_binary_cpi_event() -> Result<()> {
+    let events = parse_transaction_events(&fixture("legacy_cpi")?)?;
+    let [HyloEvent::MintStablecoin(mint)] = events.as_slice() else {
+      panic!("Unexpected events {events:?}");
+    };
+    assert_eq!(mint.version, 2);
+    assert_eq!(mint.minted.bits, 1_000_000);
+    assert_eq!(mint.lst_mint, Some(JITOSOL::MINT));
+    Ok(())
+  }
+
+  #[test]
+  fn v0_cpi_event_from_lookup_table_program() -> Result<()> {
+    let events = parse_transaction_events(&fixture("v0_lookup_table")?)?;
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].program_id(), stability_pool::ID);
+    assert_eq!(deposited(&events[0]), Some(5_000_000));
+    Ok(())
+  }
+
+  #[test]
+  fn failed_inner_program_yields_no_events() -> Result<()> {
+    let tx = fixture("failed_inner_program")?;
+    assert!(parse_transaction_events(&tx)?.is_empty());
+    Ok(())
+  }
+
+  #[test]
+  fn mixed_events_in_emission_order() -> Result<()> {
+    let events = parse_transaction_events(&fixture("mixed_cpi_and_logs")?)?;
+    assert_eq!(events.len(), 4);
+    let HyloEvent::RedeemLevercoin(redeem) = &events[0] else {
+      panic!("Expected levercoin redemption first, got {events:?}");
+    };
+    assert_eq!(redeem.version, 0);
+    assert_eq!(redeem.redeemed, token_amount(2_000_000));
+    assert!(matches!(events[1], HyloEvent::MintStablecoin(_)));
+    assert_eq!(deposited(&events[2]), Some(5_000_000));
+    assert_eq!(deposited(&events[3]), Some(7_000_000));
+    Ok(())
+  }
+}
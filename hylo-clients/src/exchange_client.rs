@@ -1,18 +1,24 @@
 use std::sync::Arc;
 
 use anchor_client::solana_sdk::pubkey::Pubkey;
-use anchor_client::solana_sdk::signature::Keypair;
+use anchor_client::solana_sdk::signature::NullSigner;
 use anchor_client::Program;
 use anyhow::{anyhow, Result};
+use fix::prelude::*;
+use hylo_core::fee_controller::{FeeController, LevercoinFees, StablecoinFees};
 use hylo_core::idl::tokens::{TokenMint, HYUSD, XSOL};
 use hylo_core::idl::{exchange, pda};
 use hylo_core::pyth::SOL_USD_PYTH_FEED;
+use hylo_core::stability_mode::StabilityController;
+use hylo_core::yields::YieldHarvestConfig;
 use hylo_idl::exchange::client::{accounts, args};
 use hylo_idl::exchange::events::ExchangeStats;
 use hylo_idl::exchange::instruction_builders;
 
 use crate::instructions::ExchangeInstructionBuilder as ExchangeIB;
+use crate::priority_fee::FeePolicy;
 use crate::program_client::{ProgramClient, VersionedTransactionData};
+use crate::signer::TransactionSigner;
 use crate::syntax_helpers::InstructionBuilderExt;
 use crate::transaction::{
   BuildTransactionData, LstSwapArgs, MintArgs, RedeemArgs, SwapArgs,
@@ -76,26 +82,39 @@ use crate::util::{
 /// # }
 /// ```
 pub struct ExchangeClient {
-  program: Program<Arc<Keypair>>,
-  keypair: Arc<Keypair>,
+  program: Program<Arc<NullSigner>>,
+  signer: Arc<dyn TransactionSigner>,
+  fee_policy: FeePolicy,
 }
 
 impl ProgramClient for ExchangeClient {
   const PROGRAM_ID: Pubkey = exchange::ID;
 
   fn build_client(
-    program: Program<Arc<Keypair>>,
-    keypair: Arc<Keypair>,
+    program: Program<Arc<NullSigner>>,
+    signer: Arc<dyn TransactionSigner>,
   ) -> ExchangeClient {
-    ExchangeClient { program, keypair }
+    ExchangeClient {
+      program,
+      signer,
+      fee_policy: FeePolicy::default(),
+    }
   }
 
-  fn program(&self) -> &Program<Arc<Keypair>> {
+  fn program(&self) -> &Program<Arc<NullSigner>> {
     &self.program
   }
 
-  fn keypair(&self) -> Arc<Keypair> {
-    self.keypair.clone()
+  fn signer(&self) -> Arc<dyn TransactionSigner> {
+    self.signer.clone()
+  }
+
+  fn fee_policy(&self) -> &FeePolicy {
+    &self.fee_policy
+  }
+
+  fn with_fee_policy(self, fee_policy: FeePolicy) -> ExchangeClient {
+    ExchangeClient { fee_policy, ..self }
   }
 }
 
@@ -316,6 +335,133 @@ impl ExchangeClient {
       instruction_builders::update_lst_swap_fee(self.program.payer(), args);
     Ok(VersionedTransactionData::one(instruction))
   }
+
+  /// Transfers protocol admin authority, signed by the program upgrade
+  /// authority.
+  ///
+  /// # Errors
+  /// - Failed to build transaction instructions
+  pub fn update_admin(
+    &self,
+    upgrade_authority: Pubkey,
+    args: &args::UpdateAdmin,
+  ) -> Result<VersionedTransactionData> {
+    let instruction = instruction_builders::update_admin(
+      self.program.payer(),
+      upgrade_authority,
+      args,
+    );
+    Ok(VersionedTransactionData::one(instruction))
+  }
+
+  /// Updates the xSOL mint and redeem fee table.
+  ///
+  /// # Errors
+  /// - Fees are not valid basis points below 100%
+  /// - Failed to build transaction instructions
+  pub fn update_levercoin_fees(
+    &self,
+    args: &args::UpdateLevercoinFees,
+  ) -> Result<VersionedTransactionData> {
+    LevercoinFees::from(args.new_levercoin_fees).validate()?;
+    let instruction =
+      instruction_builders::update_levercoin_fees(self.program.payer(), args);
+    Ok(VersionedTransactionData::one(instruction))
+  }
+
+  /// Updates the hyUSD mint and redeem fee table.
+  ///
+  /// # Errors
+  /// - Fees are not valid basis points below 100%
+  /// - Failed to build transaction instructions
+  pub fn update_stablecoin_fees(
+    &self,
+    args: &args::UpdateStablecoinFees,
+  ) -> Result<VersionedTransactionData> {
+    StablecoinFees::from(args.new_stablecoin_fees).validate()?;
+    let instruction =
+      instruction_builders::update_stablecoin_fees(self.program.payer(), args);
+    Ok(VersionedTransactionData::one(instruction))
+  }
+
+  /// Updates the collateral ratio thresholds for stability modes 1 and 2.
+  ///
+  /// # Errors
+  /// - Thresholds are not `X.XX` values above 1.0 in descending order
+  /// - Failed to build transaction instructions
+  pub fn update_stability_thresholds(
+    &self,
+    args: &args::UpdateStabilityThresholds,
+  ) -> Result<VersionedTransactionData> {
+    let threshold_1: UFix64<N2> = args.new_stability_threshold_1.try_into()?;
+    let threshold_2: UFix64<N2> = args.new_stability_threshold_2.try_into()?;
+    StabilityController::new(threshold_1, threshold_2)?;
+    let instruction = instruction_builders::update_stability_thresholds(
+      self.program.payer(),
+      args,
+    );
+    Ok(VersionedTransactionData::one(instruction))
+  }
+
+  /// Updates the treasury address receiving protocol fees.
+  ///
+  /// # Errors
+  /// - Failed to build transaction instructions
+  pub fn update_treasury(
+    &self,
+    args: &args::UpdateTreasury,
+  ) -> Result<VersionedTransactionData> {
+    let instruction =
+      instruction_builders::update_treasury(self.program.payer(), args);
+    Ok(VersionedTransactionData::one(instruction))
+  }
+
+  /// Updates the yield harvest allocation and treasury fee.
+  ///
+  /// # Errors
+  /// - Allocation or fee is not in `(0%, 100%]`
+  /// - Failed to build transaction instructions
+  pub fn update_yield_harvest_config(
+    &self,
+    args: &args::UpdateYieldHarvestConfig,
+  ) -> Result<VersionedTransactionData> {
+    YieldHarvestConfig::from(args.new_yield_harvest_config).validate()?;
+    let instruction = instruction_builders::update_yield_harvest_config(
+      self.program.payer(),
+      args,
+    );
+    Ok(VersionedTransactionData::one(instruction))
+  }
+
+  /// Updates the maximum age of the SOL/USD oracle price.
+  ///
+  /// # Errors
+  /// - Failed to build transaction instructions
+  pub fn update_oracle_interval(
+    &self,
+    args: &args::UpdateOracleInterval,
+  ) -> Result<VersionedTransactionData> {
+    let instruction =
+      instruction_builders::update_oracle_interval(self.program.payer(), args);
+    Ok(VersionedTransactionData::one(instruction))
+  }
+
+  /// Withdraws accumulated fees for a token mint to the treasury's ATA.
+  ///
+  /// # Errors
+  /// - Failed to build transaction instructions
+  pub fn withdraw_fees(
+    &self,
+    treasury: Pubkey,
+    fee_token_mint: Pubkey,
+  ) -> Result<VersionedTransactionData> {
+    let instruction = instruction_builders::withdraw_fees(
+      self.program.payer(),
+      treasury,
+      fee_token_mint,
+    );
+    Ok(VersionedTransactionData::one(instruction))
+  }
 }
 
 #[async_trait::async_trait]
@@ -326,10 +472,12 @@ impl<OUT: LST> BuildTransactionData<HYUSD, OUT> for ExchangeClient {
     &self,
     inputs: RedeemArgs,
   ) -> Result<VersionedTransactionData> {
+    let user = inputs.user;
     let instructions = ExchangeIB::build_instructions::<HYUSD, OUT>(inputs)?;
     let lut_addresses = ExchangeIB::lookup_tables::<HYUSD, OUT>();
     let lookup_tables = self.load_multiple_lookup_tables(lut_addresses).await?;
-    Ok(VersionedTransactionData::new(instructions, lookup_tables))
+    let vtd = VersionedTransactionData::new(instructions, lookup_tables);
+    self.with_lst_price_update(user, vtd).await
   }
 }
 
@@ -341,10 +489,12 @@ impl<OUT: TokenMint + LST> BuildTransactionData<XSOL, OUT> for ExchangeClient {
     &self,
     inputs: RedeemArgs,
   ) -> Result<VersionedTransactionData> {
+    let user = inputs.user;
     let instructions = ExchangeIB::build_instructions::<XSOL, OUT>(inputs)?;
     let lut_addresses = ExchangeIB::lookup_tables::<XSOL, OUT>();
     let lookup_tables = self.load_multiple_lookup_tables(lut_addresses).await?;
-    Ok(VersionedTransactionData::new(instructions, lookup_tables))
+    let vtd = VersionedTransactionData::new(instructions, lookup_tables);
+    self.with_lst_price_update(user, vtd).await
   }
 }
 
@@ -353,10 +503,12 @@ impl<IN: LST> BuildTransactionData<IN, HYUSD> for ExchangeClient {
   type Inputs = MintArgs;
 
   async fn build(&self, inputs: MintArgs) -> Result<VersionedTransactionData> {
+    let user = inputs.user;
     let instructions = ExchangeIB::build_instructions::<IN, HYUSD>(inputs)?;
     let lut_addresses = ExchangeIB::lookup_tables::<IN, HYUSD>();
     let lookup_tables = self.load_multiple_lookup_tables(lut_addresses).await?;
-    Ok(VersionedTransactionData::new(instructions, lookup_tables))
+    let vtd = VersionedTransactionData::new(instructions, lookup_tables);
+    self.with_lst_price_update(user, vtd).await
   }
 }
 
@@ -365,10 +517,12 @@ impl<IN: LST> BuildTransactionData<IN, XSOL> for ExchangeClient {
   type Inputs = MintArgs;
 
   async fn build(&self, inputs: MintArgs) -> Result<VersionedTransactionData> {
+    let user = inputs.user;
     let instructions = ExchangeIB::build_instructions::<IN, XSOL>(inputs)?;
     let lut_addresses = ExchangeIB::lookup_tables::<IN, XSOL>();
     let lookup_tables = self.load_multiple_lookup_tables(lut_addresses).await?;
-    Ok(VersionedTransactionData::new(instructions, lookup_tables))
+    let vtd = VersionedTransactionData::new(instructions, lookup_tables);
+    self.with_lst_price_update(user, vtd).await
   }
 }
 
@@ -377,10 +531,12 @@ impl BuildTransactionData<HYUSD, XSOL> for ExchangeClient {
   type Inputs = SwapArgs;
 
   async fn build(&self, inputs: SwapArgs) -> Result<VersionedTransactionData> {
+    let user = inputs.user;
     let instructions = ExchangeIB::build_instructions::<HYUSD, XSOL>(inputs)?;
     let lut_addresses = ExchangeIB::lookup_tables::<HYUSD, XSOL>();
     let lookup_tables = self.load_multiple_lookup_tables(lut_addresses).await?;
-    Ok(VersionedTransactionData::new(instructions, lookup_tables))
+    let vtd = VersionedTransactionData::new(instructions, lookup_tables);
+    self.with_lst_price_update(user, vtd).await
   }
 }
 
@@ -389,10 +545,12 @@ impl BuildTransactionData<XSOL, HYUSD> for ExchangeClient {
   type Inputs = SwapArgs;
 
   async fn build(&self, inputs: SwapArgs) -> Result<VersionedTransactionData> {
+    let user = inputs.user;
     let instructions = ExchangeIB::build_instructions::<XSOL, HYUSD>(inputs)?;
     let lut_addresses = ExchangeIB::lookup_tables::<XSOL, HYUSD>();
     let lookup_tables = self.load_multiple_lookup_tables(lut_addresses).await?;
-    Ok(VersionedTransactionData::new(instructions, lookup_tables))
+    let vtd = VersionedTransactionData::new(instructions, lookup_tables);
+    self.with_lst_price_update(user, vtd).await
   }
 }
 
@@ -404,10 +562,12 @@ impl<L1: LST, L2: LST> BuildTransactionData<L1, L2> for ExchangeClient {
     &self,
     inputs: LstSwapArgs,
   ) -> Result<VersionedTransactionData> {
+    let user = inputs.user;
     let instructions = ExchangeIB::build_instructions::<L1, L2>(inputs)?;
     let lut_addresses = ExchangeIB::lookup_tables::<L1, L2>();
     let lookup_tables = self.load_multiple_lookup_tables(lut_addresses).await?;
-    Ok(VersionedTransactionData::new(instructions, lookup_tables))
+    let vtd = VersionedTransactionData::new(instructions, lookup_tables);
+    self.with_lst_price_update(user, vtd).await
   }
 }
 
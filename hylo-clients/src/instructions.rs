@@ -28,7 +28,7 @@
 
 use anchor_client::solana_sdk::instruction::Instruction;
 use anchor_client::solana_sdk::pubkey::Pubkey;
-use anyhow::Result;
+use anyhow::{bail, Result};
 use hylo_idl::exchange::client::args as exchange_args;
 use hylo_idl::exchange::instruction_builders::{
   mint_levercoin, mint_stablecoin, redeem_levercoin, redeem_stablecoin,
@@ -69,30 +69,121 @@ pub trait InstructionBuilder<IN: TokenMint, OUT: TokenMint> {
 pub struct ExchangeInstructionBuilder;
 
 // ============================================================================
-// LST → HYUSD (mint stablecoin)
+// Runtime LST operations
 // ============================================================================
 
-impl<L: LST> InstructionBuilder<L, HYUSD> for ExchangeInstructionBuilder {
-  type Inputs = MintArgs;
-
-  const REQUIRED_LOOKUP_TABLES: &'static [Pubkey] =
+/// Mint-keyed builders for any LST in the on-chain LST registry, including
+/// those without a [`LST`] type.
+impl ExchangeInstructionBuilder {
+  /// Lookup tables required by every operation involving an LST.
+  pub const LST_LOOKUP_TABLES: &'static [Pubkey] =
     &[EXCHANGE_LOOKUP_TABLE, LST_REGISTRY_LOOKUP_TABLE];
 
-  fn build(
+  /// Builds a mint of hyUSD or xSOL from an LST deposit.
+  ///
+  /// # Errors
+  /// - `output_mint` is neither hyUSD nor xSOL
+  pub fn mint_with_lst(
+    lst_mint: Pubkey,
+    output_mint: Pubkey,
     MintArgs {
       amount,
       user,
       slippage_config,
     }: MintArgs,
   ) -> Result<Vec<Instruction>> {
-    let ata = user_ata_instruction(&user, &HYUSD::MINT);
-    let args = exchange_args::MintStablecoin {
-      amount_lst_to_deposit: amount.bits,
-      slippage_config: slippage_config.map(Into::into),
+    let ata = user_ata_instruction(&user, &output_mint);
+    let instruction = match output_mint {
+      HYUSD::MINT => mint_stablecoin(
+        user,
+        lst_mint,
+        &exchange_args::MintStablecoin {
+          amount_lst_to_deposit: amount.bits,
+          slippage_config: slippage_config.map(Into::into),
+        },
+      ),
+      XSOL::MINT => mint_levercoin(
+        user,
+        lst_mint,
+        &exchange_args::MintLevercoin {
+          amount_lst_to_deposit: amount.bits,
+          slippage_config: slippage_config.map(Into::into),
+        },
+      ),
+      _ => bail!("Cannot mint {output_mint} with LST"),
     };
-    let instruction = mint_stablecoin(user, L::MINT, &args);
     Ok(vec![ata, instruction])
   }
+
+  /// Builds a redemption of hyUSD or xSOL for an LST.
+  ///
+  /// # Errors
+  /// - `input_mint` is neither hyUSD nor xSOL
+  pub fn redeem_for_lst(
+    input_mint: Pubkey,
+    lst_mint: Pubkey,
+    RedeemArgs {
+      amount,
+      user,
+      slippage_config,
+    }: RedeemArgs,
+  ) -> Result<Vec<Instruction>> {
+    let ata = user_ata_instruction(&user, &lst_mint);
+    let instruction = match input_mint {
+      HYUSD::MINT => redeem_stablecoin(
+        user,
+        lst_mint,
+        &exchange_args::RedeemStablecoin {
+          amount_to_redeem: amount.bits,
+          slippage_config: slippage_config.map(Into::into),
+        },
+      ),
+      XSOL::MINT => redeem_levercoin(
+        user,
+        lst_mint,
+        &exchange_args::RedeemLevercoin {
+          amount_to_redeem: amount.bits,
+          slippage_config: slippage_config.map(Into::into),
+        },
+      ),
+      _ => bail!("Cannot redeem {input_mint} for LST"),
+    };
+    Ok(vec![ata, instruction])
+  }
+
+  /// Builds a swap between the two LSTs named in `args`.
+  #[must_use]
+  pub fn swap_lsts(
+    LstSwapArgs {
+      amount_lst_a,
+      lst_a_mint,
+      lst_b_mint,
+      user,
+      slippage_config,
+    }: LstSwapArgs,
+  ) -> Vec<Instruction> {
+    let user_lst_b_ata = user_ata_instruction(&user, &lst_b_mint);
+    let args = exchange_args::SwapLst {
+      amount_lst_a: amount_lst_a.bits,
+      slippage_config: slippage_config.map(Into::into),
+    };
+    let instruction = swap_lst(user, lst_a_mint, lst_b_mint, &args);
+    vec![user_lst_b_ata, instruction]
+  }
+}
+
+// ============================================================================
+// LST → HYUSD (mint stablecoin)
+// ============================================================================
+
+impl<L: LST> InstructionBuilder<L, HYUSD> for ExchangeInstructionBuilder {
+  type Inputs = MintArgs;
+
+  const REQUIRED_LOOKUP_TABLES: &'static [Pubkey] = Self::LST_LOOKUP_TABLES;
+
+  fn build(args: MintArgs) -> Result<Vec<Instruction>> {
+    Self::mint_with_lst(L::MINT, HYUSD::MINT, args)
+  }
 }
 
 // ============================================================================
@@ -102,23 +193,10 @@ impl<L: LST> InstructionBuilder<L, HYUSD> for ExchangeInstructionBuilder {
 impl<L: LST> InstructionBuilder<HYUSD, L> for ExchangeInstructionBuilder {
   type Inputs = RedeemArgs;
 
-  const REQUIRED_LOOKUP_TABLES: &'static [Pubkey] =
-    &[EXCHANGE_LOOKUP_TABLE, LST_REGISTRY_LOOKUP_TABLE];
+  const REQUIRED_LOOKUP_TABLES: &'static [Pubkey] = Self::LST_LOOKUP_TABLES;
 
-  fn build(
-    RedeemArgs {
-      amount,
-      user,
-      slippage_config,
-    }: RedeemArgs,
-  ) -> Result<Vec<Instruction>> {
-    let ata = user_ata_instruction(&user, &L::MINT);
-    let args = exchange_args::RedeemStablecoin {
-      amount_to_redeem: amount.bits,
-      slippage_config: slippage_config.map(Into::into),
-    };
-    let instruction = redeem_stablecoin(user, L::MINT, &args);
-    Ok(vec![ata, instruction])
+  fn build(args: RedeemArgs) -> Result<Vec<Instruction>> {
+    Self::redeem_for_lst(HYUSD::MINT, L::MINT, args)
   }
 }
 
@@ -129,23 +207,10 @@ impl<L: LST> InstructionBuilder<HYUSD, L> for ExchangeInstructionBuilder {
 impl<L: LST> InstructionBuilder<L, XSOL> for ExchangeInstructionBuilder {
   type Inputs = MintArgs;
 
-  const REQUIRED_LOOKUP_TABLES: &'static [Pubkey] =
-    &[EXCHANGE_LOOKUP_TABLE, LST_REGISTRY_LOOKUP_TABLE];
+  const REQUIRED_LOOKUP_TABLES: &'static [Pubkey] = Self::LST_LOOKUP_TABLES;
 
-  fn build(
-    MintArgs {
-      amount,
-      user,
-      slippage_config,
-    }: MintArgs,
-  ) -> Result<Vec<Instruction>> {
-    let ata = user_ata_instruction(&user, &XSOL::MINT);
-    let args = exchange_args::MintLevercoin {
-      amount_lst_to_deposit: amount.bits,
-      slippage_config: slippage_config.map(Into::into),
-    };
-    let instruction = mint_levercoin(user, L::MINT, &args);
-    Ok(vec![ata, instruction])
+  fn build(args: MintArgs) -> Result<Vec<Instruction>> {
+    Self::mint_with_lst(L::MINT, XSOL::MINT, args)
   }
 }
 
@@ -156,23 +221,10 @@ impl<L: LST> InstructionBuilder<L, XSOL> for ExchangeInstructionBuilder {
 impl<L: LST> InstructionBuilder<XSOL, L> for ExchangeInstructionBuilder {
   type Inputs = RedeemArgs;
 
-  const REQUIRED_LOOKUP_TABLES: &'static [Pubkey] =
-    &[EXCHANGE_LOOKUP_TABLE, LST_REGISTRY_LOOKUP_TABLE];
+  const REQUIRED_LOOKUP_TABLES: &'static [Pubkey] = Self::LST_LOOKUP_TABLES;
 
-  fn build(
-    RedeemArgs {
-      amount,
-      user,
-      slippage_config,
-    }: RedeemArgs,
-  ) -> Result<Vec<Instruction>> {
-    let ata = user_ata_instruction(&user, &L::MINT);
-    let args = exchange_args::RedeemLevercoin {
-      amount_to_redeem: amount.bits,
-      slippage_config: slippage_config.map(Into::into),
-    };
-    let instruction = redeem_levercoin(user, L::MINT, &args);
-    Ok(vec![ata, instruction])
+  fn build(args: RedeemArgs) -> Result<Vec<Instruction>> {
+    Self::redeem_for_lst(XSOL::MINT, L::MINT, args)
   }
 }
 
@@ -287,24 +339,9 @@ impl<L1: LST, L2: LST> InstructionBuilder<L1, L2>
 {
   type Inputs = LstSwapArgs;
 
-  const REQUIRED_LOOKUP_TABLES: &'static [Pubkey] =
-    &[EXCHANGE_LOOKUP_TABLE, LST_REGISTRY_LOOKUP_TABLE];
+  const REQUIRED_LOOKUP_TABLES: &'static [Pubkey] = Self::LST_LOOKUP_TABLES;
 
-  fn build(
-    LstSwapArgs {
-      amount_lst_a,
-      lst_a_mint,
-      lst_b_mint,
-      user,
-      slippage_config,
-    }: LstSwapArgs,
-  ) -> Result<Vec<Instruction>> {
-    let user_lst_b_ata = user_ata_instruction(&user, &L2::MINT);
-    let args = exchange_args::SwapLst {
-      amount_lst_a: amount_lst_a.bits,
-      slippage_config: slippage_config.map(Into::into),
-    };
-    let instruction = swap_lst(user, lst_a_mint, lst_b_mint, &args);
-    Ok(vec![user_lst_b_ata, instruction])
+  fn build(args: LstSwapArgs) -> Result<Vec<Instruction>> {
+    Ok(Self::swap_lsts(args))
   }
 }
@@ -31,12 +31,66 @@
 //!   hyUSD and xSOL
 //! - [`stability_pool_client::StabilityPoolClient`] - Deposit/withdraw
 //!   operations for sHYUSD
+//!
+//! ## Signing
+//!
+//! - [`signer::TransactionSigner`] - Local or remote (KMS, hardware wallet)
+//!   signer behind [`program_client::ProgramClient::new_from_signer`]
+//! - [`program_client::ProgramClient::build_unsigned_v0_transaction`] -
+//!   Unsigned transactions for signing elsewhere
+//!
+//! ## Multisig
+//!
+//! - [`multisig::Multisig`] - Wraps admin transactions from a client acting as
+//!   the Squads vault into [`multisig::MultisigProposal`]s, with a diff against
+//!   on-chain config
+//!
+//! ## Fees
+//!
+//! - [`priority_fee::FeePolicy`] - Compute unit limit and priority fee
+//!   instructions prepended by [`program_client::ProgramClient`] when sending
+//!
+//! ## Oracle Updates
+//!
+//! - [`pyth_update::PythPriceUpdate`] - Posts a fresh SOL/USD price from a
+//!   [`pyth_update::PriceUpdateSource`], such as Hermes, for transactions to
+//!   read when the sponsored feed lags
+//!
+//! ## Submission
+//!
+//! - [`submission::Submitter`] - Rebroadcasts until confirmation, rebuilding on
+//!   blockhash expiry and decoding program errors via
+//!   [`program_error::decode_transaction_error`]
+//! - [`program_error::HyloTransactionError`] - Failed simulation or send with
+//!   its decoded IDL, [`hylo_core::error::CoreError`] or Anchor error and logs
+//!
+//! ## Events
+//!
+//! - [`events::parse_transaction_events`] - Decodes every Hylo event from a
+//!   confirmed transaction into [`events::HyloEvent`]
+//! - [`backfill::Backfill`] - Pages program signature history into an
+//!   [`backfill::EventSink`]
+//!
+//! ## Testing
+//!
+//! - `testing::LocalCluster` - Offline JSON-RPC endpoint over in-memory
+//!   accounts with pluggable program execution, behind the `testing` feature
 
+pub mod backfill;
+pub mod events;
 pub mod exchange_client;
 pub mod instructions;
+pub mod multisig;
 pub mod prelude;
+pub mod priority_fee;
 pub mod program_client;
+pub mod program_error;
+pub mod pyth_update;
+pub mod signer;
 pub mod stability_pool_client;
+pub mod submission;
 pub mod syntax_helpers;
+#[cfg(feature = "testing")]
+pub mod testing;
 pub mod transaction;
 pub mod util;
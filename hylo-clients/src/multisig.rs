@@ -0,0 +1,686 @@
+use std::fmt;
+
+use anchor_client::solana_client::nonblocking::rpc_client::RpcClient;
+use anchor_client::solana_sdk::hash::{hash, Hash};
+use anchor_client::solana_sdk::instruction::{AccountMeta, Instruction};
+use anchor_client::solana_sdk::message::v0;
+use anchor_client::solana_sdk::pubkey;
+use anchor_client::solana_sdk::pubkey::Pubkey;
+use anchor_lang::{
+  system_program, AccountDeserialize, AnchorDeserialize, AnchorSerialize,
+  Discriminator,
+};
+use anyhow::{anyhow, ensure, Context, Result};
+use hylo_core::util::decimal_string;
+use hylo_idl::exchange::accounts::Hylo;
+use hylo_idl::exchange::types::FeePair;
+use hylo_idl::stability_pool::accounts::PoolConfig;
+use hylo_idl::{exchange, pda, stability_pool};
+use itertools::Itertools;
+
+use crate::program_client::VersionedTransactionData;
+
+/// Squads v4 multisig program.
+pub const SQUADS_PROGRAM_ID: Pubkey =
+  pubkey!("SQDS4ep65T869zMMBKyuUq6aD6EgTu8psMjkvj52pCf");
+
+/// Byte offset of `transaction_index` in a Squads multisig account.
+const TRANSACTION_INDEX_OFFSET: usize = 78;
+
+/// Index of the LST mint in `register_lst` accounts.
+const REGISTER_LST_MINT_INDEX: usize = 8;
+
+/// Squads v4 multisig whose vault holds Hylo admin authority.
+///
+/// Admin transaction data is built by a client acting as the vault, e.g.
+/// `ExchangeClient::new_unsigned(cluster, multisig.vault(), config)`, then
+/// wrapped with [`Multisig::propose`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Multisig {
+  pub address: Pubkey,
+  pub vault_index: u8,
+}
+
+impl Multisig {
+  #[must_use]
+  pub fn new(address: Pubkey, vault_index: u8) -> Multisig {
+    Multisig {
+      address,
+      vault_index,
+    }
+  }
+
+  /// Vault PDA signing executed proposals.
+  #[must_use]
+  pub fn vault(&self) -> Pubkey {
+    Pubkey::find_program_address(
+      &[
+        b"multisig",
+        self.address.as_ref(),
+        b"vault",
+        &[self.vault_index],
+      ],
+      &SQUADS_PROGRAM_ID,
+    )
+    .0
+  }
+
+  /// Vault transaction account at `index`.
+  #[must_use]
+  pub fn transaction(&self, index: u64) -> Pubkey {
+    Pubkey::find_program_address(
+      &[
+        b"multisig",
+        self.address.as_ref(),
+        b"transaction",
+        &index.to_le_bytes(),
+      ],
+      &SQUADS_PROGRAM_ID,
+    )
+    .0
+  }
+
+  /// Proposal account for the vault transaction at `index`.
+  #[must_use]
+  pub fn proposal(&self, index: u64) -> Pubkey {
+    Pubkey::find_program_address(
+      &[
+        b"multisig",
+        self.address.as_ref(),
+        b"transaction",
+        &index.to_le_bytes(),
+        b"proposal",
+      ],
+      &SQUADS_PROGRAM_ID,
+    )
+    .0
+  }
+
+  /// Index the next vault transaction will be created at.
+  ///
+  /// # Errors
+  /// - Failed to fetch the multisig account
+  /// - Account too short to be a Squads multisig
+  pub async fn next_transaction_index(&self, rpc: &RpcClient) -> Result<u64> {
+    let account = rpc.get_account(&self.address).await?;
+    let bytes = account
+      .data
+      .get(TRANSACTION_INDEX_OFFSET..TRANSACTION_INDEX_OFFSET + 8)
+      .ok_or_else(|| anyhow!("{} is not a Squads multisig", self.address))?;
+    let current = u64::from_le_bytes(bytes.try_into()?);
+    Ok(current + 1)
+  }
+
+  /// Wraps admin transaction data into a vault transaction proposal, with a
+  /// diff of the changes against current on-chain config.
+  ///
+  /// # Errors
+  /// - Failed to fetch the multisig, `Hylo` or `PoolConfig` accounts
+  /// - Invalid admin instruction data
+  /// - See [`Multisig::build_proposal`]
+  pub async fn propose(
+    &self,
+    rpc: &RpcClient,
+    creator: Pubkey,
+    vtd: &VersionedTransactionData,
+    memo: Option<String>,
+  ) -> Result<MultisigProposal> {
+    let transaction_index = self.next_transaction_index(rpc).await?;
+    let (hylo, pool_config) = fetch_config(rpc).await?;
+    let changes = config_changes(&vtd.instructions, &hylo, &pool_config)?;
+    self.build_proposal(transaction_index, creator, vtd, memo, changes)
+  }
+
+  /// Builds the proposal for a known transaction index without RPC.
+  ///
+  /// # Errors
+  /// - Instructions require signers other than the vault
+  /// - Failed to compile the vault message
+  pub fn build_proposal(
+    &self,
+    transaction_index: u64,
+    creator: Pubkey,
+    vtd: &VersionedTransactionData,
+    memo: Option<String>,
+    changes: Vec<ConfigChange>,
+  ) -> Result<MultisigProposal> {
+    let vault = self.vault();
+    let foreign_signers = vtd
+      .instructions
+      .iter()
+      .flat_map(|ix| &ix.accounts)
+      .filter(|meta| meta.is_signer && meta.pubkey != vault)
+      .map(|meta| meta.pubkey)
+      .unique()
+      .collect_vec();
+    ensure!(
+      foreign_signers.is_empty(),
+      "Vault {vault} cannot sign for {foreign_signers:?}"
+    );
+    let compiled = v0::Message::try_compile(
+      &vault,
+      &vtd.instructions,
+      &vtd.lookup_tables,
+      Hash::default(),
+    )?;
+    let message = encode_vault_message(&compiled)?;
+    let transaction = self.transaction(transaction_index);
+    let proposal = self.proposal(transaction_index);
+    let mut vault_transaction_args = Vec::new();
+    (self.vault_index, 0u8, message.clone(), memo)
+      .serialize(&mut vault_transaction_args)?;
+    let mut proposal_args = Vec::new();
+    (transaction_index, false).serialize(&mut proposal_args)?;
+    let create = VersionedTransactionData::new(
+      vec![
+        squads_instruction(
+          "vault_transaction_create",
+          vec![
+            AccountMeta::new(self.address, false),
+            AccountMeta::new(transaction, false),
+            AccountMeta::new_readonly(creator, true),
+            AccountMeta::new(creator, true),
+            AccountMeta::new_readonly(system_program::ID, false),
+          ],
+          &vault_transaction_args,
+        ),
+        squads_instruction(
+          "proposal_create",
+          vec![
+            AccountMeta::new_readonly(self.address, false),
+            AccountMeta::new(proposal, false),
+            AccountMeta::new_readonly(creator, true),
+            AccountMeta::new(creator, true),
+            AccountMeta::new_readonly(system_program::ID, false),
+          ],
+          &proposal_args,
+        ),
+      ],
+      vec![],
+    );
+    Ok(MultisigProposal {
+      multisig: self.address,
+      vault,
+      transaction_index,
+      transaction,
+      proposal,
+      message,
+      changes,
+      create,
+    })
+  }
+}
+
+/// Admin transaction wrapped as a Squads vault transaction proposal.
+#[derive(Clone, Debug)]
+pub struct MultisigProposal {
+  pub multisig: Pubkey,
+  /// Authority signing the wrapped instructions once executed.
+  pub vault: Pubkey,
+  pub transaction_index: u64,
+  pub transaction: Pubkey,
+  pub proposal: Pubkey,
+  /// Vault transaction message in Squads' encoding.
+  pub message: Vec<u8>,
+  /// Config changes made by the wrapped instructions.
+  pub changes: Vec<ConfigChange>,
+  /// Creates the vault transaction and proposal, signed and paid for by a
+  /// multisig member.
+  pub create: VersionedTransactionData,
+}
+
+impl fmt::Display for MultisigProposal {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    writeln!(
+      f,
+      "Proposal #{} on {} (vault {})",
+      self.transaction_index, self.multisig, self.vault
+    )?;
+    self
+      .changes
+      .iter()
+      .try_for_each(|change| writeln!(f, "  {change}"))
+  }
+}
+
+/// Single config field changed by an admin instruction.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ConfigChange {
+  /// Instruction making the change, e.g. `update_lst_swap_fee`.
+  pub instruction: &'static str,
+  /// Changed account field, e.g. `hylo.lst_swap_fee`.
+  pub field: String,
+  pub current: String,
+  pub proposed: String,
+}
+
+impl ConfigChange {
+  fn new(
+    instruction: &'static str,
+    field: impl Into<String>,
+    current: impl ToString,
+    proposed: impl ToString,
+  ) -> ConfigChange {
+    ConfigChange {
+      instruction,
+      field: field.into(),
+      current: current.to_string(),
+      proposed: proposed.to_string(),
+    }
+  }
+
+  /// Whether the proposed value equals the current one.
+  #[must_use]
+  pub fn is_noop(&self) -> bool {
+    self.current == self.proposed
+  }
+}
+
+impl fmt::Display for ConfigChange {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "{}: {} -> {}", self.field, self.current, self.proposed)?;
+    if self.is_noop() {
+      write!(f, " (unchanged)")?;
+    }
+    Ok(())
+  }
+}
+
+/// Fetches current exchange and stability pool config.
+///
+/// # Errors
+/// - Failed to fetch or deserialize either account
+pub async fn fetch_config(rpc: &RpcClient) -> Result<(Hylo, PoolConfig)> {
+  let accounts = rpc
+    .get_multiple_accounts(&[*pda::HYLO, *pda::POOL_CONFIG])
+    .await?;
+  let [Some(hylo), Some(pool_config)] = accounts.as_slice() else {
+    return Err(anyhow!("Hylo or PoolConfig account not found"));
+  };
+  Ok((
+    Hylo::try_deserialize(&mut hylo.data.as_slice())?,
+    PoolConfig::try_deserialize(&mut pool_config.data.as_slice())?,
+  ))
+}
+
+/// Diffs admin instructions against current config.
+///
+/// Instructions of other programs, and Hylo instructions that change no
+/// config, yield no changes.
+///
+/// # Errors
+/// - Admin instruction args fail to deserialize
+pub fn config_changes(
+  instructions: &[Instruction],
+  hylo: &Hylo,
+  pool_config: &PoolConfig,
+) -> Result<Vec<ConfigChange>> {
+  instructions.iter().try_fold(Vec::new(), |mut changes, ix| {
+    if ix.program_id == exchange::ID {
+      changes.extend(exchange_changes(ix, hylo)?);
+    } else if ix.program_id == stability_pool::ID {
+      changes.extend(pool_changes(ix, pool_config)?);
+    }
+    Ok(changes)
+  })
+}
+
+fn exchange_changes(
+  ix: &Instruction,
+  hylo: &Hylo,
+) -> Result<Vec<ConfigChange>> {
+  use hylo_idl::exchange::client::args;
+  let data = ix.data.as_slice();
+  let changes = if let Some(args) =
+    decode::<args::UpdateOracleConfTolerance>(data)?
+  {
+    vec![ConfigChange::new(
+      "update_oracle_conf_tolerance",
+      "hylo.oracle_conf_tolerance",
+      decimal_string(hylo.oracle_conf_tolerance),
+      decimal_string(args.new_oracle_conf_tolerance),
+    )]
+  } else if let Some(args) = decode::<args::UpdateSolUsdOracle>(data)? {
+    vec![ConfigChange::new(
+      "update_sol_usd_oracle",
+      "hylo.sol_usd_oracle",
+      hylo.sol_usd_oracle,
+      args.new_oracle,
+    )]
+  } else if let Some(args) = decode::<args::UpdateStabilityPool>(data)? {
+    vec![ConfigChange::new(
+      "update_stability_pool",
+      "hylo.stability_pool",
+      hylo.stability_pool,
+      args.new_stability_pool,
+    )]
+  } else if let Some(args) = decode::<args::UpdateLstSwapFee>(data)? {
+    vec![ConfigChange::new(
+      "update_lst_swap_fee",
+      "hylo.lst_swap_fee",
+      decimal_string(hylo.lst_swap_fee),
+      decimal_string(args.new_lst_swap_fee),
+    )]
+  } else if let Some(args) = decode::<args::UpdateAdmin>(data)? {
+    vec![ConfigChange::new(
+      "update_admin",
+      "hylo.admin",
+      hylo.admin,
+      args.new_admin,
+    )]
+  } else if let Some(args) = decode::<args::UpdateTreasury>(data)? {
+    vec![ConfigChange::new(
+      "update_treasury",
+      "hylo.treasury",
+      hylo.treasury,
+      args.new_treasury,
+    )]
+  } else if let Some(args) = decode::<args::UpdateOracleInterval>(data)? {
+    vec![ConfigChange::new(
+      "update_oracle_interval",
+      "hylo.oracle_interval_secs",
+      hylo.oracle_interval_secs,
+      args.new_oracle_interval_secs,
+    )]
+  } else if let Some(args) = decode::<args::UpdateStabilityThresholds>(data)? {
+    vec![
+      ConfigChange::new(
+        "update_stability_thresholds",
+        "hylo.stability_threshold_1",
+        decimal_string(hylo.stability_threshold_1),
+        decimal_string(args.new_stability_threshold_1),
+      ),
+      ConfigChange::new(
+        "update_stability_thresholds",
+        "hylo.stability_threshold_2",
+        decimal_string(hylo.stability_threshold_2),
+        decimal_string(args.new_stability_threshold_2),
+      ),
+    ]
+  } else if let Some(args) = decode::<args::UpdateStablecoinFees>(data)? {
+    let (current, proposed) = (&hylo.stablecoin_fees, args.new_stablecoin_fees);
+    [
+      ("normal", &current.normal, proposed.normal),
+      ("mode_1", &current.mode_1, proposed.mode_1),
+    ]
+    .into_iter()
+    .flat_map(|(mode, current, proposed)| {
+      fee_pair_changes(
+        "update_stablecoin_fees",
+        "stablecoin_fees",
+        mode,
+        current,
+        &proposed,
+      )
+    })
+    .collect()
+  } else if let Some(args) = decode::<args::UpdateLevercoinFees>(data)? {
+    let (current, proposed) = (&hylo.levercoin_fees, args.new_levercoin_fees);
+    [
+      ("normal", &current.normal, proposed.normal),
+      ("mode_1", &current.mode_1, proposed.mode_1),
+      ("mode_2", &current.mode_2, proposed.mode_2),
+    ]
+    .into_iter()
+    .flat_map(|(mode, current, proposed)| {
+      fee_pair_changes(
+        "update_levercoin_fees",
+        "levercoin_fees",
+        mode,
+        current,
+        &proposed,
+      )
+    })
+    .collect()
+  } else if let Some(args) = decode::<args::UpdateYieldHarvestConfig>(data)? {
+    let (current, proposed) =
+      (&hylo.yield_harvest_config, args.new_yield_harvest_config);
+    vec![
+      ConfigChange::new(
+        "update_yield_harvest_config",
+        "hylo.yield_harvest_config.allocation",
+        decimal_string(current.allocation),
+        decimal_string(proposed.allocation),
+      ),
+      ConfigChange::new(
+        "update_yield_harvest_config",
+        "hylo.yield_harvest_config.fee",
+        decimal_string(current.fee),
+        decimal_string(proposed.fee),
+      ),
+    ]
+  } else if decode::<args::RegisterLst>(data)?.is_some() {
+    let mint = ix
+      .accounts
+      .get(REGISTER_LST_MINT_INDEX)
+      .context("register_lst is missing the LST mint")?;
+    vec![ConfigChange::new(
+      "register_lst",
+      format!("lst_registry[{}]", mint.pubkey),
+      "unregistered",
+      "registered",
+    )]
+  } else {
+    vec![]
+  };
+  Ok(changes)
+}
+
+fn pool_changes(
+  ix: &Instruction,
+  pool_config: &PoolConfig,
+) -> Result<Vec<ConfigChange>> {
+  use hylo_idl::stability_pool::client::args;
+  let data = ix.data.as_slice();
+  let changes = if let Some(args) = decode::<args::UpdateWithdrawalFee>(data)? {
+    vec![ConfigChange::new(
+      "update_withdrawal_fee",
+      "pool_config.withdrawal_fee",
+      decimal_string(pool_config.withdrawal_fee),
+      decimal_string(args.new_withdrawal_fee),
+    )]
+  } else if let Some(args) = decode::<args::UpdateAdmin>(data)? {
+    vec![ConfigChange::new(
+      "update_admin",
+      "pool_config.admin",
+      pool_config.admin,
+      args.new_admin,
+    )]
+  } else {
+    vec![]
+  };
+  Ok(changes)
+}
+
+fn fee_pair_changes(
+  instruction: &'static str,
+  fees: &str,
+  mode: &str,
+  current: &FeePair,
+  proposed: &FeePair,
+) -> [ConfigChange; 2] {
+  [
+    ConfigChange::new(
+      instruction,
+      format!("hylo.{fees}.{mode}.mint"),
+      decimal_string(current.mint),
+      decimal_string(proposed.mint),
+    ),
+    ConfigChange::new(
+      instruction,
+      format!("hylo.{fees}.{mode}.redeem"),
+      decimal_string(current.redeem),
+      decimal_string(proposed.redeem),
+    ),
+  ]
+}
+
+/// Instruction args, if `data` carries the args' discriminator.
+fn decode<T: AnchorDeserialize + Discriminator>(
+  data: &[u8],
+) -> Result<Option<T>> {
+  data
+    .strip_prefix(T::DISCRIMINATOR)
+    .map(|mut args| T::deserialize(&mut args))
+    .transpose()
+    .map_err(Into::into)
+}
+
+/// Encodes a compiled message as a Squads `TransactionMessage`, with `u8`
+/// length prefixes except for `u16` instruction data.
+///
+/// # Errors
+/// - Account, instruction or lookup counts overflow their length prefix
+pub fn encode_vault_message(message: &v0::Message) -> Result<Vec<u8>> {
+  let header = &message.header;
+  let num_signers = header.num_required_signatures;
+  let num_keys = u8::try_from(message.account_keys.len())
+    .context("Too many vault transaction accounts")?;
+  let mut bytes = vec![
+    num_signers,
+    num_signers - header.num_readonly_signed_accounts,
+    num_keys - num_signers - header.num_readonly_unsigned_accounts,
+    num_keys,
+  ];
+  message
+    .account_keys
+    .iter()
+    .for_each(|key| bytes.extend_from_slice(key.as_ref()));
+  bytes.push(u8::try_from(message.instructions.len())?);
+  for ix in &message.instructions {
+    bytes.push(ix.program_id_index);
+    bytes.push(u8::try_from(ix.accounts.len())?);
+    bytes.extend_from_slice(&ix.accounts);
+    let data_len =
+      u16::try_from(ix.data.len()).context("Instruction data too long")?;
+    bytes.extend_from_slice(&data_len.to_le_bytes());
+    bytes.extend_from_slice(&ix.data);
+  }
+  bytes.push(u8::try_from(message.address_table_lookups.len())?);
+  for lookup in &message.address_table_lookups {
+    bytes.extend_from_slice(lookup.account_key.as_ref());
+    bytes.push(u8::try_from(lookup.writable_indexes.len())?);
+    bytes.extend_from_slice(&lookup.writable_indexes);
+    bytes.push(u8::try_from(lookup.readonly_indexes.len())?);
+    bytes.extend_from_slice(&lookup.readonly_indexes);
+  }
+  Ok(bytes)
+}
+
+/// Squads instruction with Anchor's global namespace discriminator.
+fn squads_instruction(
+  name: &str,
+  accounts: Vec<AccountMeta>,
+  args: &[u8],
+) -> Instruction {
+  let discriminator = hash(format!("global:{name}").as_bytes());
+  let data = [&discriminator.to_bytes()[..8], args].concat();
+  Instruction::new_with_bytes(SQUADS_PROGRAM_ID, &data, accounts)
+}
+
+#[cfg(test)]
+mod tests {
+  use hylo_idl::exchange::client::args;
+  use hylo_idl::exchange::{instruction_builders, types};
+
+  use super::*;
+
+  fn zeroed<T: AnchorDeserialize>() -> T {
+    T::deserialize(&mut [0u8; 1024].as_slice()).expect("zeroed account")
+  }
+
+  #[test]
+  fn diffs_admin_instructions() -> Result<()> {
+    let admin = Pubkey::new_unique();
+    let mut hylo = zeroed::<Hylo>();
+    hylo.lst_swap_fee = types::UFixValue64 { bits: 10, exp: -4 };
+    let pool_config = zeroed::<PoolConfig>();
+    let new_pool = Pubkey::new_unique();
+    let instructions = [
+      instruction_builders::update_lst_swap_fee(
+        admin,
+        &args::UpdateLstSwapFee {
+          new_lst_swap_fee: types::UFixValue64 { bits: 25, exp: -4 },
+        },
+      ),
+      instruction_builders::update_stability_pool(
+        admin,
+        &args::UpdateStabilityPool {
+          new_stability_pool: new_pool,
+        },
+      ),
+      instruction_builders::update_oracle_interval(
+        admin,
+        &args::UpdateOracleInterval {
+          new_oracle_interval_secs: 0,
+        },
+      ),
+    ];
+    let changes = config_changes(&instructions, &hylo, &pool_config)?;
+    assert_eq!(
+      changes.iter().map(ToString::to_string).collect_vec(),
+      vec![
+        "hylo.lst_swap_fee: 0.001 -> 0.0025".to_string(),
+        format!("hylo.stability_pool: {} -> {new_pool}", Pubkey::default()),
+        "hylo.oracle_interval_secs: 0 -> 0 (unchanged)".to_string(),
+      ]
+    );
+    Ok(())
+  }
+
+  #[test]
+  fn encodes_vault_transaction() -> Result<()> {
+    let multisig = Multisig::new(Pubkey::new_unique(), 0);
+    let vault = multisig.vault();
+    let vtd =
+      VersionedTransactionData::one(instruction_builders::update_lst_swap_fee(
+        vault,
+        &args::UpdateLstSwapFee {
+          new_lst_swap_fee: types::UFixValue64 { bits: 25, exp: -4 },
+        },
+      ));
+    let creator = Pubkey::new_unique();
+    let proposal = multisig.build_proposal(7, creator, &vtd, None, vec![])?;
+    let compiled = v0::Message::try_compile(
+      &vault,
+      &vtd.instructions,
+      &[],
+      Hash::default(),
+    )?;
+    let keys = compiled.account_keys.len();
+    let message = &proposal.message;
+    assert_eq!(message[..4], [1, 1, message[2], u8::try_from(keys)?]);
+    assert_eq!(&message[4..36], vault.as_ref());
+    let ix = &compiled.instructions[0];
+    let ix_start = 4 + 32 * keys + 1;
+    assert_eq!(message[ix_start], ix.program_id_index);
+    let data_start = ix_start + 2 + ix.accounts.len();
+    let data_len =
+      u16::from_le_bytes([message[data_start], message[data_start + 1]]);
+    assert_eq!(usize::from(data_len), ix.data.len());
+    assert_eq!(message.len(), data_start + 2 + ix.data.len() + 1);
+    assert_eq!(proposal.transaction, multisig.transaction(7));
+    assert_eq!(proposal.create.instructions.len(), 2);
+    assert!(proposal
+      .create
+      .instructions
+      .iter()
+      .all(|ix| ix.program_id == SQUADS_PROGRAM_ID));
+    Ok(())
+  }
+
+  #[test]
+  fn rejects_signers_other_than_vault() {
+    let multisig = Multisig::new(Pubkey::new_unique(), 0);
+    let vtd =
+      VersionedTransactionData::one(instruction_builders::update_lst_swap_fee(
+        Pubkey::new_unique(),
+        &args::UpdateLstSwapFee {
+          new_lst_swap_fee: types::UFixValue64 { bits: 25, exp: -4 },
+        },
+      ));
+    let proposal =
+      multisig.build_proposal(1, Pubkey::new_unique(), &vtd, None, vec![]);
+    assert!(proposal.is_err());
+  }
+}
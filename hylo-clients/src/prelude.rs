@@ -6,13 +6,27 @@ pub use anyhow::Result;
 pub use fix::prelude::*;
 pub use hylo_core::idl::tokens::{HYUSD, JITOSOL, SHYUSD, XSOL};
 
+pub use crate::backfill::{Backfill, BackfillCursor, EventSink, IndexedEvent};
+pub use crate::events::{parse_transaction_events, HyloEvent};
 pub use crate::exchange_client::ExchangeClient;
 pub use crate::instructions::{
   ExchangeInstructionBuilder, InstructionBuilder,
   StabilityPoolInstructionBuilder,
 };
+pub use crate::multisig::{ConfigChange, Multisig, MultisigProposal};
+pub use crate::priority_fee::{
+  FeePolicy, FixedPriorityFee, PercentilePriorityFee, PriorityFeeSource,
+};
 pub use crate::program_client::{ProgramClient, VersionedTransactionData};
+pub use crate::program_error::{HyloTransactionError, ProgramErrorCode};
+pub use crate::pyth_update::{
+  HermesSource, PriceUpdateSource, PythPriceUpdate,
+};
+pub use crate::signer::{TransactionSigner, UnsignedPayer};
 pub use crate::stability_pool_client::StabilityPoolClient;
+pub use crate::submission::{
+  SubmissionConfig, SubmissionError, Submitted, TransactionSource,
+};
 pub use crate::syntax_helpers::InstructionBuilderExt;
 pub use crate::transaction::{
   BuildTransactionData, MintArgs, RedeemArgs, StabilityPoolArgs, SwapArgs,
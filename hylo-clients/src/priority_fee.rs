@@ -0,0 +1,361 @@
+use std::sync::Arc;
+
+use anchor_client::solana_client::nonblocking::rpc_client::RpcClient;
+use anchor_client::solana_sdk::instruction::Instruction;
+use anchor_client::solana_sdk::pubkey::Pubkey;
+use anyhow::{ensure, Result};
+use itertools::Itertools;
+use solana_compute_budget_interface::ComputeBudgetInstruction;
+
+use crate::program_client::VersionedTransactionData;
+
+/// Maximum compute unit limit a transaction may request.
+pub const MAX_COMPUTE_UNIT_LIMIT: u32 = 1_400_000;
+
+/// Maximum number of accounts accepted by `getRecentPrioritizationFees`.
+pub const MAX_PRIORITIZATION_FEE_ACCOUNTS: usize = 128;
+
+/// Default headroom added on top of simulated or estimated compute units.
+pub const DEFAULT_COMPUTE_UNIT_BUFFER_PCT: u64 = 10;
+
+/// Default percentile of recent prioritization fees to pay.
+pub const DEFAULT_PRIORITY_FEE_PERCENTILE: u8 = 50;
+
+/// Source of the compute unit price, in micro-lamports, for a transaction.
+#[async_trait::async_trait]
+pub trait PriorityFeeSource: Send + Sync {
+  /// Prices a transaction writing to `writable_accounts`.
+  ///
+  /// # Errors
+  /// - Source specific, e.g. RPC failures
+  async fn compute_unit_price(
+    &self,
+    rpc: &RpcClient,
+    writable_accounts: &[Pubkey],
+  ) -> Result<u64>;
+}
+
+/// Pays the same compute unit price for every transaction.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FixedPriorityFee(pub u64);
+
+#[async_trait::async_trait]
+impl PriorityFeeSource for FixedPriorityFee {
+  async fn compute_unit_price(
+    &self,
+    _rpc: &RpcClient,
+    _writable_accounts: &[Pubkey],
+  ) -> Result<u64> {
+    Ok(self.0)
+  }
+}
+
+/// Pays a percentile of `getRecentPrioritizationFees` over the accounts a
+/// transaction writes to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PercentilePriorityFee {
+  percentile: u8,
+}
+
+impl PercentilePriorityFee {
+  /// Creates a percentile fee source.
+  ///
+  /// # Errors
+  /// - Percentile above 100
+  pub fn new(percentile: u8) -> Result<PercentilePriorityFee> {
+    ensure!(percentile <= 100, "Percentile {percentile} exceeds 100");
+    Ok(PercentilePriorityFee { percentile })
+  }
+
+  #[must_use]
+  pub fn percentile(&self) -> u8 {
+    self.percentile
+  }
+}
+
+#[async_trait::async_trait]
+impl PriorityFeeSource for PercentilePriorityFee {
+  async fn compute_unit_price(
+    &self,
+    rpc: &RpcClient,
+    writable_accounts: &[Pubkey],
+  ) -> Result<u64> {
+    let accounts = &writable_accounts
+      [..writable_accounts.len().min(MAX_PRIORITIZATION_FEE_ACCOUNTS)];
+    let fees = rpc
+      .get_recent_prioritization_fees(accounts)
+      .await?
+      .into_iter()
+      .map(|fee| fee.prioritization_fee)
+      .collect_vec();
+    Ok(percentile_of(fees, self.percentile))
+  }
+}
+
+/// Nearest-rank percentile, zero when there are no samples.
+fn percentile_of(mut samples: Vec<u64>, percentile: u8) -> u64 {
+  samples.sort_unstable();
+  samples
+    .len()
+    .checked_sub(1)
+    .map_or(0, |last| samples[last * usize::from(percentile) / 100])
+}
+
+/// Decides the compute budget instructions prepended to transactions.
+///
+/// Compute unit limits come from the estimate attached to
+/// [`VersionedTransactionData`], padded by `compute_unit_buffer_pct`. Prices
+/// come from an optional [`PriorityFeeSource`], optionally capped.
+///
+/// The default policy has no price source: it only sets a compute unit limit
+/// when an estimate is attached, and never simulates or queries fees. With a
+/// price source, transactions without an estimate are simulated first.
+#[derive(Clone)]
+pub struct FeePolicy {
+  pub price_source: Option<Arc<dyn PriorityFeeSource>>,
+  pub compute_unit_buffer_pct: u64,
+  pub max_compute_unit_price: Option<u64>,
+}
+
+impl Default for FeePolicy {
+  fn default() -> FeePolicy {
+    FeePolicy::UNPRICED
+  }
+}
+
+impl FeePolicy {
+  /// Policy without priority fees, see [`FeePolicy::default`].
+  pub const UNPRICED: FeePolicy = FeePolicy {
+    price_source: None,
+    compute_unit_buffer_pct: DEFAULT_COMPUTE_UNIT_BUFFER_PCT,
+    max_compute_unit_price: None,
+  };
+
+  /// Policy paying a fixed compute unit price.
+  #[must_use]
+  pub fn fixed(micro_lamports: u64) -> FeePolicy {
+    FeePolicy::with_source(FixedPriorityFee(micro_lamports))
+  }
+
+  /// Policy paying a percentile of recent prioritization fees.
+  ///
+  /// # Errors
+  /// - Percentile above 100
+  pub fn percentile(percentile: u8) -> Result<FeePolicy> {
+    Ok(FeePolicy::with_source(PercentilePriorityFee::new(
+      percentile,
+    )?))
+  }
+
+  /// Policy with a custom price source and default buffer.
+  #[must_use]
+  pub fn with_source(source: impl PriorityFeeSource + 'static) -> FeePolicy {
+    FeePolicy {
+      price_source: Some(Arc::new(source)),
+      ..FeePolicy::default()
+    }
+  }
+
+  /// Whether transactions pay a priority fee.
+  #[must_use]
+  pub fn is_priced(&self) -> bool {
+    self.price_source.is_some()
+  }
+
+  #[must_use]
+  pub fn with_compute_unit_buffer_pct(self, pct: u64) -> FeePolicy {
+    FeePolicy {
+      compute_unit_buffer_pct: pct,
+      ..self
+    }
+  }
+
+  #[must_use]
+  pub fn with_max_compute_unit_price(self, micro_lamports: u64) -> FeePolicy {
+    FeePolicy {
+      max_compute_unit_price: Some(micro_lamports),
+      ..self
+    }
+  }
+
+  /// Pads `units` by the buffer, clamped to [`MAX_COMPUTE_UNIT_LIMIT`].
+  ///
+  /// # Errors
+  /// - Zero `units`, which would make every instruction fail
+  pub fn compute_unit_limit(&self, units: u64) -> Result<u32> {
+    ensure!(units > 0, "Compute unit estimate must be positive");
+    let padded = units
+      .saturating_mul(100 + self.compute_unit_buffer_pct)
+      .div_ceil(100);
+    Ok(
+      u32::try_from(padded)
+        .unwrap_or(MAX_COMPUTE_UNIT_LIMIT)
+        .min(MAX_COMPUTE_UNIT_LIMIT),
+    )
+  }
+
+  /// Prices the transaction through the source, applying the cap. `None`
+  /// without a price source.
+  ///
+  /// # Errors
+  /// - Price source failure
+  pub async fn compute_unit_price(
+    &self,
+    rpc: &RpcClient,
+    instructions: &[Instruction],
+  ) -> Result<Option<u64>> {
+    let Some(source) = &self.price_source else {
+      return Ok(None);
+    };
+    let price = source
+      .compute_unit_price(rpc, &writable_accounts(instructions))
+      .await?;
+    Ok(Some(
+      self
+        .max_compute_unit_price
+        .map_or(price, |max| price.min(max)),
+    ))
+  }
+
+  /// Prepends a compute unit limit for `units`, and a compute unit price when
+  /// the policy is priced.
+  ///
+  /// Transactions already carrying compute budget instructions are returned
+  /// unchanged, as duplicates would fail.
+  ///
+  /// # Errors
+  /// - Zero `units`
+  /// - Price source failure
+  pub async fn apply(
+    &self,
+    rpc: &RpcClient,
+    units: u64,
+    vtd: VersionedTransactionData,
+  ) -> Result<VersionedTransactionData> {
+    if has_compute_budget(&vtd.instructions) {
+      return Ok(vtd);
+    }
+    let limit = ComputeBudgetInstruction::set_compute_unit_limit(
+      self.compute_unit_limit(units)?,
+    );
+    let price = self
+      .compute_unit_price(rpc, &vtd.instructions)
+      .await?
+      .map(ComputeBudgetInstruction::set_compute_unit_price);
+    Ok(VersionedTransactionData {
+      instructions: std::iter::once(limit)
+        .chain(price)
+        .chain(vtd.instructions)
+        .collect(),
+      ..vtd
+    })
+  }
+}
+
+/// Whether any instruction targets the compute budget program.
+#[must_use]
+pub fn has_compute_budget(instructions: &[Instruction]) -> bool {
+  instructions
+    .iter()
+    .any(|ix| solana_compute_budget_interface::check_id(&ix.program_id))
+}
+
+/// Distinct writable accounts across `instructions`, in first-seen order.
+#[must_use]
+pub fn writable_accounts(instructions: &[Instruction]) -> Vec<Pubkey> {
+  instructions
+    .iter()
+    .flat_map(|ix| &ix.accounts)
+    .filter(|meta| meta.is_writable)
+    .map(|meta| meta.pubkey)
+    .unique()
+    .collect()
+}
+
+#[cfg(test)]
+mod tests {
+  use anchor_lang::prelude::AccountMeta;
+
+  use super::*;
+
+  fn instruction(accounts: Vec<AccountMeta>) -> Instruction {
+    Instruction::new_with_bytes(Pubkey::new_unique(), &[], accounts)
+  }
+
+  #[test]
+  fn percentile_nearest_rank() {
+    let samples = vec![50, 10, 40, 20, 30];
+    assert_eq!(percentile_of(samples.clone(), 0), 10);
+    assert_eq!(percentile_of(samples.clone(), 50), 30);
+    assert_eq!(percentile_of(samples.clone(), 75), 40);
+    assert_eq!(percentile_of(samples, 100), 50);
+    assert_eq!(percentile_of(vec![], 50), 0);
+  }
+
+  #[test]
+  fn compute_unit_limit_buffers_and_clamps() {
+    let policy = FeePolicy::fixed(0).with_compute_unit_buffer_pct(20);
+    assert_eq!(policy.compute_unit_limit(100_000).ok(), Some(120_000));
+    assert_eq!(policy.compute_unit_limit(1).ok(), Some(2));
+    assert_eq!(
+      policy.compute_unit_limit(u64::MAX).ok(),
+      Some(MAX_COMPUTE_UNIT_LIMIT)
+    );
+  }
+
+  #[test]
+  fn rejects_zero_compute_units() {
+    assert!(FeePolicy::default().compute_unit_limit(0).is_err());
+  }
+
+  #[test]
+  fn rejects_percentile_above_100() {
+    assert!(PercentilePriorityFee::new(101).is_err());
+  }
+
+  #[test]
+  fn collects_distinct_writable_accounts() {
+    let a = Pubkey::new_unique();
+    let b = Pubkey::new_unique();
+    let instructions = [
+      instruction(vec![
+        AccountMeta::new(a, false),
+        AccountMeta::new_readonly(b, false),
+      ]),
+      instruction(vec![AccountMeta::new(b, false), AccountMeta::new(a, true)]),
+    ];
+    assert_eq!(writable_accounts(&instructions), vec![a, b]);
+  }
+
+  #[tokio::test]
+  async fn prepends_budget_once() -> Result<()> {
+    let rpc = RpcClient::new_mock("succeeds".to_string());
+    let policy = FeePolicy::fixed(5_000).with_max_compute_unit_price(1_000);
+    let vtd = VersionedTransactionData::one(instruction(vec![]));
+    let applied = policy.apply(&rpc, 100_000, vtd).await?;
+    assert_eq!(
+      applied.instructions[..2],
+      [
+        ComputeBudgetInstruction::set_compute_unit_limit(110_000),
+        ComputeBudgetInstruction::set_compute_unit_price(1_000),
+      ]
+    );
+    assert_eq!(applied.instructions.len(), 3);
+    let reapplied = policy.apply(&rpc, 100_000, applied.clone()).await?;
+    assert_eq!(reapplied.instructions, applied.instructions);
+    Ok(())
+  }
+
+  #[tokio::test]
+  async fn unpriced_policy_sets_limit_only() -> Result<()> {
+    let rpc = RpcClient::new_mock("fails".to_string());
+    let vtd = VersionedTransactionData::one(instruction(vec![]));
+    let applied = FeePolicy::default().apply(&rpc, 100_000, vtd).await?;
+    assert_eq!(
+      applied.instructions[0],
+      ComputeBudgetInstruction::set_compute_unit_limit(110_000)
+    );
+    assert_eq!(applied.instructions.len(), 2);
+    Ok(())
+  }
+}
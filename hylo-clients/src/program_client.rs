@@ -3,26 +3,48 @@ use std::sync::Arc;
 use anchor_client::solana_sdk::address_lookup_table::AddressLookupTableAccount;
 use anchor_client::solana_sdk::commitment_config::CommitmentConfig;
 use anchor_client::solana_sdk::instruction::Instruction;
-use anchor_client::solana_sdk::message::{v0, VersionedMessage};
 use anchor_client::solana_sdk::pubkey::Pubkey;
-use anchor_client::solana_sdk::signature::{Keypair, Signature};
+use anchor_client::solana_sdk::signature::{Keypair, NullSigner, Signature};
 use anchor_client::solana_sdk::transaction::VersionedTransaction;
 use anchor_client::{Client, Cluster, Program};
 use anchor_lang::prelude::AccountMeta;
-use anchor_lang::{AnchorDeserialize, Discriminator};
-use anyhow::{anyhow, Result};
+use anchor_lang::{AccountDeserialize, AnchorDeserialize, Discriminator};
+use anyhow::{anyhow, ensure, Context, Result};
 use base64::prelude::{Engine, BASE64_STANDARD};
+use hylo_core::pyth::SOL_USD;
+use hylo_core::total_sol_cache::prices_outdated;
+use hylo_idl::exchange::accounts::{Hylo, LstHeader};
+use hylo_idl::exchange::instruction_builders;
+use hylo_idl::pda;
 use itertools::Itertools;
+use solana_compute_budget_interface::ComputeBudgetInstruction;
 
+use crate::multisig::{Multisig, MultisigProposal};
+use crate::priority_fee::{
+  has_compute_budget, FeePolicy, MAX_COMPUTE_UNIT_LIMIT,
+};
+use crate::program_error::HyloTransactionError;
+use crate::pyth_update::PythPriceUpdate;
+use crate::signer::{sign_transaction, TransactionSigner, UnsignedPayer};
+use crate::submission::{
+  SubmissionConfig, SubmissionError, Submitted, Submitter, TransactionSource,
+};
 use crate::util::{
-  build_lst_registry, build_v0_transaction, deserialize_lookup_table,
-  parse_event, simulation_config, LST_REGISTRY_LOOKUP_TABLE,
+  build_lst_registry, build_unsigned_v0_transaction, deserialize_lookup_table,
+  is_lst_price_update, parse_event, parse_lst_registry, simulation_config,
+  LST_REGISTRY_LOOKUP_TABLE,
 };
 
 /// Components from which a [`VersionedTransaction`] can be built.
+///
+/// `compute_units` is an optional estimate used by [`FeePolicy`] to size the
+/// compute unit limit, e.g. from an executable quote. Without it, priced fee
+/// policies simulate compute units before sending.
+#[derive(Clone, Debug)]
 pub struct VersionedTransactionData {
   pub instructions: Vec<Instruction>,
   pub lookup_tables: Vec<AddressLookupTableAccount>,
+  pub compute_units: Option<u64>,
 }
 
 impl VersionedTransactionData {
@@ -31,6 +53,7 @@ impl VersionedTransactionData {
     VersionedTransactionData {
       instructions: vec![instruction],
       lookup_tables: vec![],
+      compute_units: None,
     }
   }
 
@@ -42,23 +65,67 @@ impl VersionedTransactionData {
     VersionedTransactionData {
       instructions,
       lookup_tables,
+      compute_units: None,
+    }
+  }
+
+  /// Attaches a compute unit estimate, setting the compute unit limit and
+  /// skipping simulation when sending.
+  #[must_use]
+  pub fn with_compute_units(self, units: u64) -> VersionedTransactionData {
+    VersionedTransactionData {
+      compute_units: Some(units),
+      ..self
     }
   }
 }
 
+static UNPRICED_FEE_POLICY: FeePolicy = FeePolicy::UNPRICED;
+
 /// Abstracts the construction of client structs with `anchor_client::Program`.
 #[async_trait::async_trait]
 pub trait ProgramClient: Sized {
   const PROGRAM_ID: Pubkey;
 
   fn build_client(
-    program: Program<Arc<Keypair>>,
-    keypair: Arc<Keypair>,
+    program: Program<Arc<NullSigner>>,
+    signer: Arc<dyn TransactionSigner>,
   ) -> Self;
 
-  fn program(&self) -> &Program<Arc<Keypair>>;
+  /// Anchor program for RPC and instruction building, whose payer is a
+  /// keyless stand-in for [`Self::signer`].
+  fn program(&self) -> &Program<Arc<NullSigner>>;
+
+  fn signer(&self) -> Arc<dyn TransactionSigner>;
 
-  fn keypair(&self) -> Arc<Keypair>;
+  /// Fee policy applied by [`Self::send_v0_transaction`], by default
+  /// [`FeePolicy::UNPRICED`].
+  fn fee_policy(&self) -> &FeePolicy {
+    &UNPRICED_FEE_POLICY
+  }
+
+  /// Replaces the fee policy applied by [`Self::send_v0_transaction`].
+  ///
+  /// Clients that do not store a policy return `self` unchanged.
+  #[must_use]
+  fn with_fee_policy(self, _fee_policy: FeePolicy) -> Self {
+    self
+  }
+
+  /// Constructs the program client with any signer, local or remote.
+  ///
+  /// # Errors
+  /// - Underlying Anchor program creation
+  fn new_from_signer(
+    cluster: Cluster,
+    signer: Arc<dyn TransactionSigner>,
+    config: CommitmentConfig,
+  ) -> Result<Self> {
+    let payer = Arc::new(NullSigner::new(&signer.address()));
+    let client = Client::new_with_options(cluster, payer, config);
+    let program = client.program(Self::PROGRAM_ID)?;
+    Ok(Self::build_client(program, signer))
+  }
 
   /// Constructs the program client with a given keypair and associated program
   /// ID.
@@ -70,10 +137,7 @@ pub trait ProgramClient: Sized {
     keypair: Keypair,
     config: CommitmentConfig,
   ) -> Result<Self> {
-    let keypair = Arc::new(keypair);
-    let client = Client::new_with_options(cluster, keypair.clone(), config);
-    let program = client.program(Self::PROGRAM_ID)?;
-    Ok(Self::build_client(program, keypair))
+    Self::new_from_signer(cluster, Arc::new(keypair), config)
   }
 
   /// Constructs the program client with a random keypair.
@@ -88,18 +152,54 @@ pub trait ProgramClient: Sized {
     Self::new_from_keypair(cluster, keypair, config)
   }
 
-  /// Builds a versioned transaction from instructions and lookup tables.
+  /// Constructs a build-only client acting as `payer` without its key.
+  ///
+  /// Use [`Self::build_unsigned_v0_transaction`] to hand transactions to the
+  /// key holder, e.g. a browser wallet. Sending fails.
+  ///
+  /// # Errors
+  /// - Underlying Anchor program creation
+  fn new_unsigned(
+    cluster: Cluster,
+    payer: Pubkey,
+    config: CommitmentConfig,
+  ) -> Result<Self> {
+    Self::new_from_signer(cluster, Arc::new(UnsignedPayer(payer)), config)
+  }
+
+  /// Builds a versioned transaction from instructions and lookup tables,
+  /// signed by [`Self::signer`].
   ///
   /// # Errors
   /// - Failed to get latest blockhash
   /// - Failed to compile message
-  /// - Failed to create transaction
+  /// - Failed to sign transaction
   async fn build_v0_transaction(
     &self,
     vtd: &VersionedTransactionData,
+  ) -> Result<VersionedTransaction> {
+    let signer = self.signer();
+    let mut tx = self
+      .build_unsigned_v0_transaction(&signer.address(), vtd)
+      .await?;
+    sign_transaction(&mut tx, signer.as_ref()).await?;
+    Ok(tx)
+  }
+
+  /// Builds an unsigned versioned transaction paid by `payer` with the latest
+  /// blockhash, for signing elsewhere or by several parties via
+  /// [`sign_transaction`].
+  ///
+  /// # Errors
+  /// - Failed to get latest blockhash
+  /// - Failed to compile message
+  async fn build_unsigned_v0_transaction(
+    &self,
+    payer: &Pubkey,
+    vtd: &VersionedTransactionData,
   ) -> Result<VersionedTransaction> {
     let recent_blockhash = self.program().rpc().get_latest_blockhash().await?;
-    build_v0_transaction(vtd, &self.keypair(), &[], recent_blockhash)
+    build_unsigned_v0_transaction(vtd, payer, recent_blockhash)
   }
 
   /// Builds versioned transaction with dummy signatures for simulation.
@@ -111,43 +211,293 @@ pub trait ProgramClient: Sized {
   async fn build_simulation_transaction(
     &self,
     for_user: &Pubkey,
-    VersionedTransactionData {
-      instructions,
-      lookup_tables,
-    }: &VersionedTransactionData,
+    vtd: &VersionedTransactionData,
   ) -> Result<VersionedTransaction> {
-    let recent_blockhash = self.program().rpc().get_latest_blockhash().await?;
-    let message = v0::Message::try_compile(
-      for_user,
-      instructions,
-      lookup_tables,
-      recent_blockhash,
-    )?;
-    let num_sigs = message.header.num_required_signatures.into();
-    let dummy_signatures = vec![Signature::default(); num_sigs];
-    let tx = VersionedTransaction {
-      message: VersionedMessage::V0(message),
-      signatures: dummy_signatures,
+    self.build_unsigned_v0_transaction(for_user, vtd).await
+  }
+
+  /// Simulates compute units consumed by a transaction, measured under the
+  /// maximum compute unit limit.
+  ///
+  /// # Errors
+  /// - Failed to build or simulate transaction
+  /// - [`HyloTransactionError`] when the simulation fails
+  /// - Simulation did not report compute units
+  async fn simulate_compute_units(
+    &self,
+    vtd: &VersionedTransactionData,
+  ) -> Result<u64> {
+    let mut instructions = vtd.instructions.clone();
+    if !has_compute_budget(&instructions) {
+      instructions.insert(
+        0,
+        ComputeBudgetInstruction::set_compute_unit_limit(
+          MAX_COMPUTE_UNIT_LIMIT,
+        ),
+      );
+    }
+    let tx = self
+      .build_simulation_transaction(
+        &self.signer().address(),
+        &VersionedTransactionData::new(instructions, vtd.lookup_tables.clone()),
+      )
+      .await?;
+    let result = self
+      .program()
+      .rpc()
+      .simulate_transaction_with_config(&tx, simulation_config())
+      .await?
+      .value;
+    if let Some(err) =
+      HyloTransactionError::from_simulation(&tx.message, &result)
+    {
+      return Err(err.into());
+    }
+    result
+      .units_consumed
+      .ok_or(anyhow!("Simulation did not report compute units"))
+  }
+
+  /// Prepends compute budget instructions according to [`Self::fee_policy`].
+  ///
+  /// Unpriced policies leave transactions without a compute unit estimate
+  /// untouched; priced policies simulate them first.
+  ///
+  /// # Errors
+  /// - Compute unit simulation, when priced and no estimate is attached
+  /// - Zero compute unit estimate
+  /// - Priority fee source
+  async fn apply_fee_policy(
+    &self,
+    vtd: &VersionedTransactionData,
+  ) -> Result<VersionedTransactionData> {
+    if has_compute_budget(&vtd.instructions) {
+      return Ok(vtd.clone());
+    }
+    let units = match vtd.compute_units {
+      Some(units) => units,
+      None if self.fee_policy().is_priced() => {
+        self.simulate_compute_units(vtd).await?
+      }
+      None => return Ok(vtd.clone()),
     };
-    Ok(tx)
+    self
+      .fee_policy()
+      .apply(&self.program().rpc(), units, vtd.clone())
+      .await
+  }
+
+  /// Submits transactions from `source` with [`Self::fee_policy`] applied to
+  /// every build, rebroadcasting and rebuilding until confirmed.
+  ///
+  /// # Errors
+  /// - See [`Submitter::submit`]
+  async fn submit(
+    &self,
+    source: &dyn TransactionSource,
+    config: SubmissionConfig,
+  ) -> Result<Submitted, SubmissionError> {
+    self.submit_with_cosigners(source, config, &[]).await
   }
 
-  /// Sends a versioned transaction from instructions and lookup tables.
+  /// Like [`Self::submit`], also signing every build with each of
+  /// `cosigners` it requires.
   ///
   /// # Errors
+  /// - See [`Submitter::submit`]
+  async fn submit_with_cosigners(
+    &self,
+    source: &dyn TransactionSource,
+    config: SubmissionConfig,
+    cosigners: &[&dyn TransactionSigner],
+  ) -> Result<Submitted, SubmissionError> {
+    let rpc = self.program().rpc();
+    let signer = self.signer();
+    let source = FeePolicySource {
+      client: self,
+      source,
+    };
+    Submitter::new(&rpc, signer.as_ref(), config)
+      .with_cosigners(cosigners)
+      .submit(&source)
+      .await
+  }
+
+  /// Sends a versioned transaction from instructions and lookup tables,
+  /// with compute budget instructions from [`Self::fee_policy`].
+  ///
+  /// Submission follows [`Self::submit`] at the RPC client's commitment.
+  ///
+  /// # Errors
+  /// - Failed to apply fee policy
   /// - Failed to build transaction
-  /// - Failed to send and confirm transaction
+  /// - [`HyloTransactionError`] when a program rejects the transaction
+  /// - Transaction expired or could not be sent
   async fn send_v0_transaction(
     &self,
     args: &VersionedTransactionData,
   ) -> Result<Signature> {
-    let tx = self.build_v0_transaction(args).await?;
-    let sig = self
-      .program()
-      .rpc()
-      .send_and_confirm_transaction(&tx)
-      .await?;
-    Ok(sig)
+    let config = SubmissionConfig {
+      commitment: self.program().rpc().commitment(),
+      ..SubmissionConfig::default()
+    };
+    let submitted =
+      self.submit(args, config).await.map_err(|err| match err {
+        SubmissionError::Program(failure) => anyhow::Error::from(*failure),
+        err => err.into(),
+      })?;
+    Ok(submitted.signature)
+  }
+
+  /// Posts `update`, sends `vtd` reading the posted price in place of the
+  /// sponsored SOL/USD feed, then reclaims the rent of the update accounts.
+  ///
+  /// Rent of every account created is reclaimed even when a post
+  /// transaction or `vtd` fails, whose error is returned first. See
+  /// [`crate::pyth_update`] for how Hylo validates the posted account.
+  ///
+  /// # Errors
+  /// - `update` is not for the SOL/USD feed
+  /// - Posting the update failed, e.g. VAA rejected by Wormhole
+  /// - Same as [`Self::send_v0_transaction`] for `vtd`
+  /// - Reclaiming rent failed
+  async fn send_with_pyth_update(
+    &self,
+    update: &PythPriceUpdate,
+    vtd: &VersionedTransactionData,
+  ) -> Result<Signature> {
+    ensure!(
+      update.feed_id() == SOL_USD,
+      "Pyth update for feed {} is not SOL/USD",
+      hex::encode(update.feed_id())
+    );
+    let config = SubmissionConfig {
+      commitment: self.program().rpc().commitment(),
+      ..SubmissionConfig::default()
+    };
+    let cosigners = update.signers();
+    let send = |source: VersionedTransactionData| async move {
+      self
+        .submit_with_cosigners(&source, config, &cosigners)
+        .await
+        .map(|submitted| submitted.signature)
+        .map_err(|err| match err {
+          SubmissionError::Program(failure) => anyhow::Error::from(*failure),
+          err => err.into(),
+        })
+    };
+    let mut posted = 0;
+    let mut failed = None;
+    for post in &update.post {
+      match send(post.clone()).await {
+        Ok(_) => posted += 1,
+        Err(err) => {
+          failed = Some(err.context("Failed to post Pyth price update"));
+          break;
+        }
+      }
+    }
+    let sent = match failed {
+      None => send(update.wire(vtd.clone())).await,
+      Some(err) => Err(err),
+    };
+    let closed = match update.close(posted) {
+      Some(close) => send(close).await.map(drop),
+      None => Ok(()),
+    };
+    let signature = sent?;
+    closed.context("Failed to reclaim Pyth price update rent")?;
+    Ok(signature)
+  }
+
+  /// Wraps admin transaction data built by this client into a proposal on
+  /// `multisig`, for clients constructed with the multisig vault as payer.
+  ///
+  /// # Errors
+  /// - See [`Multisig::propose`]
+  async fn propose_to_multisig(
+    &self,
+    multisig: &Multisig,
+    creator: Pubkey,
+    vtd: &VersionedTransactionData,
+    memo: Option<String>,
+  ) -> Result<MultisigProposal> {
+    multisig
+      .propose(&self.program().rpc(), creator, vtd, memo)
+      .await
+  }
+
+  /// Whether total SOL or any registered LST price was last updated in a
+  /// previous epoch. Exchange instructions reading prices fail until
+  /// `update_lst_prices` runs in the current epoch.
+  ///
+  /// # Errors
+  /// - Failed to load the LST registry
+  /// - Failed to fetch the Hylo account, an LST header or current epoch
+  async fn lst_prices_outdated(&self) -> Result<bool> {
+    let registry = self.load_lookup_table(&LST_REGISTRY_LOOKUP_TABLE).await?;
+    let headers = parse_lst_registry(&registry)?
+      .into_iter()
+      .map(|entry| entry.header);
+    let pubkeys = std::iter::once(*pda::HYLO).chain(headers).collect_vec();
+    let rpc = self.program().rpc();
+    let accounts = rpc.get_multiple_accounts(&pubkeys).await?;
+    let (hylo, headers) = accounts
+      .split_first()
+      .ok_or_else(|| anyhow!("No accounts returned"))?;
+    let hylo = hylo.as_ref().context("Hylo account not found")?;
+    let hylo = Hylo::try_deserialize(&mut hylo.data.as_slice())?;
+    let lst_price_epochs = headers
+      .iter()
+      .zip(&pubkeys[1..])
+      .map(|(header, key)| {
+        let header = header
+          .as_ref()
+          .with_context(|| format!("No LST header {key}"))?;
+        let header = LstHeader::try_deserialize(&mut header.data.as_slice())?;
+        Ok(header.price_sol.epoch)
+      })
+      .collect::<Result<Vec<_>>>()?;
+    let epoch = rpc.get_epoch_info().await?.epoch;
+    Ok(prices_outdated(
+      hylo.total_sol_cache.current_update_epoch,
+      lst_price_epochs,
+      epoch,
+    ))
+  }
+
+  /// Prepends `update_lst_prices`, paid by `payer`, while
+  /// [`Self::lst_prices_outdated`], so that `vtd` goes through across an
+  /// epoch rollover instead of failing on outdated prices.
+  ///
+  /// # Errors
+  /// - Failed to check price epochs
+  /// - Failed to load the LST registry
+  async fn with_lst_price_update(
+    &self,
+    payer: Pubkey,
+    mut vtd: VersionedTransactionData,
+  ) -> Result<VersionedTransactionData> {
+    if vtd.instructions.iter().any(is_lst_price_update)
+      || !self.lst_prices_outdated().await?
+    {
+      return Ok(vtd);
+    }
+    let (remaining_accounts, registry_lut) = self.load_lst_registry().await?;
+    let update = instruction_builders::update_lst_prices(
+      payer,
+      LST_REGISTRY_LOOKUP_TABLE,
+      remaining_accounts,
+    );
+    vtd.instructions.insert(0, update);
+    if !vtd
+      .lookup_tables
+      .iter()
+      .any(|lut| lut.key == registry_lut.key)
+    {
+      vtd.lookup_tables.push(registry_lut);
+    }
+    Ok(vtd)
   }
 
   /// Loads LST registry lookup table and parses it into `remaining_accounts`.
@@ -202,7 +552,7 @@ pub trait ProgramClient: Sized {
   /// Simulates transaction and returns deserialized return data.
   ///
   /// # Errors
-  /// * Transaction simulation fails
+  /// * [`HyloTransactionError`] when the simulation fails
   /// * No return data found in simulation result
   /// * Base64 decoding of return data fails
   /// * Deserialization of return data fails
@@ -214,6 +564,11 @@ pub trait ProgramClient: Sized {
     let result = rpc
       .simulate_transaction_with_config(&tx, simulation_config())
       .await?;
+    if let Some(err) =
+      HyloTransactionError::from_simulation(&tx.message, &result.value)
+    {
+      return Err(err.into());
+    }
     let (data, _) = result
       .value
       .return_data
@@ -227,7 +582,7 @@ pub trait ProgramClient: Sized {
   /// Simulates transaction and extracts event from CPI instructions.
   ///
   /// # Errors
-  /// * Transaction simulation fails
+  /// * [`HyloTransactionError`] when the simulation fails
   /// * Event parsing from CPI instructions fails
   /// * Event deserialization fails
   async fn simulate_transaction_event<E: AnchorDeserialize + Discriminator>(
@@ -248,7 +603,7 @@ pub trait ProgramClient: Sized {
   /// otherwise.
   ///
   /// # Errors
-  /// * Transaction simulation fails
+  /// * [`HyloTransactionError`] when the simulation fails
   /// * Event parsing from CPI instructions fails
   /// * Event deserialization fails
   async fn simulate_transaction_event_with_cus<
@@ -261,8 +616,27 @@ pub trait ProgramClient: Sized {
     let result = rpc
       .simulate_transaction_with_config(tx, simulation_config())
       .await?;
+    if let Some(err) =
+      HyloTransactionError::from_simulation(&tx.message, &result.value)
+    {
+      return Err(err.into());
+    }
     let event = parse_event(&result)?;
     let compute_units = result.value.units_consumed;
     Ok((event, compute_units))
   }
 }
+
+/// Applies a client's fee policy to each build of an inner source.
+struct FeePolicySource<'a, C> {
+  client: &'a C,
+  source: &'a dyn TransactionSource,
+}
+
+#[async_trait::async_trait]
+impl<C: ProgramClient + Sync> TransactionSource for FeePolicySource<'_, C> {
+  async fn build(&self) -> Result<VersionedTransactionData> {
+    let vtd = self.source.build().await?;
+    self.client.apply_fee_policy(&vtd).await
+  }
+}
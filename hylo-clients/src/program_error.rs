@@ -0,0 +1,433 @@
+//! Decoding of Hylo program errors from failed simulations and transactions
+//!
+//! Custom error codes resolve against, in order, the failing program's IDL
+//! error table, the shared [`CoreError`] codes and Anchor's framework
+//! [`AnchorErrorCode`]s. [`HyloTransactionError`] carries the decoded code
+//! with the program logs and is the error behind every failed simulation or
+//! send in this crate's clients:
+//!
+//! ```rust,ignore
+//! match client.send_v0_transaction(&vtd).await {
+//!   Err(err) => match HyloTransactionError::find(&err) {
+//!     // e.g. "Oracle did not yield a price within the configured age window."
+//!     Some(failure) => println!("{}", failure.reason()),
+//!     None => println!("{err:#}"),
+//!   },
+//!   Ok(signature) => println!("{signature}"),
+//! }
+//! ```
+
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::LazyLock;
+
+use anchor_client::solana_client::rpc_response::RpcSimulateTransactionResult;
+use anchor_client::solana_sdk::instruction::InstructionError;
+use anchor_client::solana_sdk::message::VersionedMessage;
+use anchor_client::solana_sdk::pubkey::Pubkey;
+use anchor_client::solana_sdk::signature::Signature;
+use anchor_client::solana_sdk::transaction::TransactionError;
+use anchor_lang::error::ErrorCode as AnchorErrorCode;
+use hylo_core::error::CoreError;
+use hylo_idl::{exchange, stability_pool};
+use serde_json::Value;
+
+use crate::submission::SubmissionError;
+
+/// Custom error code resolved to its name and message.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ProgramErrorCode {
+  pub program_id: Pubkey,
+  pub code: u32,
+  pub name: String,
+  pub message: String,
+}
+
+impl ProgramErrorCode {
+  /// Shared protocol math error behind this code, if any.
+  #[must_use]
+  pub fn core_error(&self) -> Option<CoreError> {
+    CoreError::from_code(self.code)
+  }
+
+  /// Anchor framework error behind this code, if any.
+  #[must_use]
+  pub fn anchor_error(&self) -> Option<AnchorErrorCode> {
+    anchor_error(self.code)
+  }
+}
+
+impl fmt::Display for ProgramErrorCode {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "{} ({}): {}", self.name, self.code, self.message)
+  }
+}
+
+/// Error tables of both Hylo programs, keyed by program ID and code.
+static IDL_ERRORS: LazyLock<HashMap<(Pubkey, u32), (String, String)>> =
+  LazyLock::new(|| {
+    [
+      (exchange::ID, exchange::IDL_JSON),
+      (stability_pool::ID, stability_pool::IDL_JSON),
+    ]
+    .into_iter()
+    .flat_map(|(program_id, idl)| {
+      let idl = serde_json::from_str::<Value>(idl).unwrap_or_default();
+      idl["errors"]
+        .as_array()
+        .cloned()
+        .unwrap_or_default()
+        .into_iter()
+        .filter_map(move |error| {
+          let code = u32::try_from(error["code"].as_u64()?).ok()?;
+          let name = error["name"].as_str()?.to_string();
+          let message = error["msg"].as_str().unwrap_or_default().to_string();
+          Some(((program_id, code), (name, message)))
+        })
+    })
+    .collect()
+  });
+
+/// Every Anchor framework error, raised by account validation and the
+/// instruction dispatcher before program code runs.
+const ANCHOR_ERRORS: [AnchorErrorCode; 77] = [
+  AnchorErrorCode::InstructionMissing,
+  AnchorErrorCode::InstructionFallbackNotFound,
+  AnchorErrorCode::InstructionDidNotDeserialize,
+  AnchorErrorCode::InstructionDidNotSerialize,
+  AnchorErrorCode::IdlInstructionStub,
+  AnchorErrorCode::IdlInstructionInvalidProgram,
+  AnchorErrorCode::IdlAccountNotEmpty,
+  AnchorErrorCode::EventInstructionStub,
+  AnchorErrorCode::ConstraintMut,
+  AnchorErrorCode::ConstraintHasOne,
+  AnchorErrorCode::ConstraintSigner,
+  AnchorErrorCode::ConstraintRaw,
+  AnchorErrorCode::ConstraintOwner,
+  AnchorErrorCode::ConstraintRentExempt,
+  AnchorErrorCode::ConstraintSeeds,
+  AnchorErrorCode::ConstraintExecutable,
+  AnchorErrorCode::ConstraintState,
+  AnchorErrorCode::ConstraintAssociated,
+  AnchorErrorCode::ConstraintAssociatedInit,
+  AnchorErrorCode::ConstraintClose,
+  AnchorErrorCode::ConstraintAddress,
+  AnchorErrorCode::ConstraintZero,
+  AnchorErrorCode::ConstraintTokenMint,
+  AnchorErrorCode::ConstraintTokenOwner,
+  AnchorErrorCode::ConstraintMintMintAuthority,
+  AnchorErrorCode::ConstraintMintFreezeAuthority,
+  AnchorErrorCode::ConstraintMintDecimals,
+  AnchorErrorCode::ConstraintSpace,
+  AnchorErrorCode::ConstraintAccountIsNone,
+  AnchorErrorCode::ConstraintTokenTokenProgram,
+  AnchorErrorCode::ConstraintMintTokenProgram,
+  AnchorErrorCode::ConstraintAssociatedTokenTokenProgram,
+  AnchorErrorCode::ConstraintMintGroupPointerExtension,
+  AnchorErrorCode::ConstraintMintGroupPointerExtensionAuthority,
+  AnchorErrorCode::ConstraintMintGroupPointerExtensionGroupAddress,
+  AnchorErrorCode::ConstraintMintGroupMemberPointerExtension,
+  AnchorErrorCode::ConstraintMintGroupMemberPointerExtensionAuthority,
+  AnchorErrorCode::ConstraintMintGroupMemberPointerExtensionMemberAddress,
+  AnchorErrorCode::ConstraintMintMetadataPointerExtension,
+  AnchorErrorCode::ConstraintMintMetadataPointerExtensionAuthority,
+  AnchorErrorCode::ConstraintMintMetadataPointerExtensionMetadataAddress,
+  AnchorErrorCode::ConstraintMintCloseAuthorityExtension,
+  AnchorErrorCode::ConstraintMintCloseAuthorityExtensionAuthority,
+  AnchorErrorCode::ConstraintMintPermanentDelegateExtension,
+  AnchorErrorCode::ConstraintMintPermanentDelegateExtensionDelegate,
+  AnchorErrorCode::ConstraintMintTransferHookExtension,
+  AnchorErrorCode::ConstraintMintTransferHookExtensionAuthority,
+  AnchorErrorCode::ConstraintMintTransferHookExtensionProgramId,
+  AnchorErrorCode::RequireViolated,
+  AnchorErrorCode::RequireEqViolated,
+  AnchorErrorCode::RequireKeysEqViolated,
+  AnchorErrorCode::RequireNeqViolated,
+  AnchorErrorCode::RequireKeysNeqViolated,
+  AnchorErrorCode::RequireGtViolated,
+  AnchorErrorCode::RequireGteViolated,
+  AnchorErrorCode::AccountDiscriminatorAlreadySet,
+  AnchorErrorCode::AccountDiscriminatorNotFound,
+  AnchorErrorCode::AccountDiscriminatorMismatch,
+  AnchorErrorCode::AccountDidNotDeserialize,
+  AnchorErrorCode::AccountDidNotSerialize,
+  AnchorErrorCode::AccountNotEnoughKeys,
+  AnchorErrorCode::AccountNotMutable,
+  AnchorErrorCode::AccountOwnedByWrongProgram,
+  AnchorErrorCode::InvalidProgramId,
+  AnchorErrorCode::InvalidProgramExecutable,
+  AnchorErrorCode::AccountNotSigner,
+  AnchorErrorCode::AccountNotSystemOwned,
+  AnchorErrorCode::AccountNotInitialized,
+  AnchorErrorCode::AccountNotProgramData,
+  AnchorErrorCode::AccountNotAssociatedTokenAccount,
+  AnchorErrorCode::AccountSysvarMismatch,
+  AnchorErrorCode::AccountReallocExceedsLimit,
+  AnchorErrorCode::AccountDuplicateReallocs,
+  AnchorErrorCode::DeclaredProgramIdMismatch,
+  AnchorErrorCode::TryingToInitPayerAsProgramAccount,
+  AnchorErrorCode::InvalidNumericConversion,
+  AnchorErrorCode::Deprecated,
+];
+
+fn anchor_error(code: u32) -> Option<AnchorErrorCode> {
+  ANCHOR_ERRORS
+    .into_iter()
+    .find(|error| u32::from(*error) == code)
+}
+
+/// Resolves a custom error code raised by a Hylo program, from its IDL error
+/// table, the shared [`CoreError`] codes or Anchor's framework errors.
+#[must_use]
+pub fn decode_error_code(
+  program_id: &Pubkey,
+  code: u32,
+) -> Option<ProgramErrorCode> {
+  if *program_id != exchange::ID && *program_id != stability_pool::ID {
+    return None;
+  }
+  let (name, message) = IDL_ERRORS
+    .get(&(*program_id, code))
+    .cloned()
+    .or_else(|| {
+      CoreError::from_code(code).map(|error| (error.name(), error.to_string()))
+    })
+    .or_else(|| {
+      anchor_error(code).map(|error| (error.name(), error.to_string()))
+    })?;
+  Some(ProgramErrorCode {
+    program_id: *program_id,
+    code,
+    name,
+    message,
+  })
+}
+
+/// Program reported as failing first in transaction logs, i.e. the innermost
+/// program of a failed CPI chain.
+#[must_use]
+pub fn failing_program(logs: &[String]) -> Option<Pubkey> {
+  logs.iter().find_map(|line| {
+    let rest = line.strip_prefix("Program ")?;
+    let (program_id, status) = rest.split_once(' ')?;
+    status
+      .starts_with("failed")
+      .then(|| program_id.parse().ok())
+      .flatten()
+  })
+}
+
+/// Decodes the custom error behind a failed transaction.
+///
+/// The failing program is taken from the logs when available, falling back
+/// to the program of the failed top-level instruction.
+#[must_use]
+pub fn decode_transaction_error(
+  error: &TransactionError,
+  message: &VersionedMessage,
+  logs: &[String],
+) -> Option<ProgramErrorCode> {
+  let TransactionError::InstructionError(index, InstructionError::Custom(code)) =
+    error
+  else {
+    return None;
+  };
+  let program_id = failing_program(logs).or_else(|| {
+    let instruction = message.instructions().get(usize::from(*index))?;
+    message
+      .static_account_keys()
+      .get(usize::from(instruction.program_id_index))
+      .copied()
+  })?;
+  decode_error_code(&program_id, *code)
+}
+
+/// Failed Hylo simulation or transaction with its decoded program error and
+/// logs.
+#[derive(Clone, Debug)]
+pub struct HyloTransactionError {
+  /// Signature of the sent transaction, `None` for simulations
+  pub signature: Option<Signature>,
+  pub error: TransactionError,
+  /// Decoded custom error, `None` for runtime errors or foreign programs
+  pub code: Option<ProgramErrorCode>,
+  pub logs: Vec<String>,
+}
+
+impl HyloTransactionError {
+  /// Decodes `error` raised by a transaction with `message`.
+  #[must_use]
+  pub fn new(
+    signature: Option<Signature>,
+    error: TransactionError,
+    message: &VersionedMessage,
+    logs: Vec<String>,
+  ) -> HyloTransactionError {
+    HyloTransactionError {
+      signature,
+      code: decode_transaction_error(&error, message, &logs),
+      error,
+      logs,
+    }
+  }
+
+  /// Error of a failed simulation of a transaction with `message`, `None`
+  /// if the simulation succeeded.
+  #[must_use]
+  pub fn from_simulation(
+    message: &VersionedMessage,
+    result: &RpcSimulateTransactionResult,
+  ) -> Option<HyloTransactionError> {
+    let error = result.err.clone()?;
+    let logs = result.logs.clone().unwrap_or_default();
+    Some(HyloTransactionError::new(None, error, message, logs))
+  }
+
+  /// Short reason for display, the program's message when decoded and the
+  /// runtime error otherwise.
+  #[must_use]
+  pub fn reason(&self) -> String {
+    match &self.code {
+      Some(code) => code.message.clone(),
+      None => self.error.to_string(),
+    }
+  }
+
+  /// Finds the transaction error behind `err`, searching its context chain
+  /// and program failures reported by [`SubmissionError`].
+  #[must_use]
+  pub fn find(err: &anyhow::Error) -> Option<&HyloTransactionError> {
+    err.chain().find_map(|cause| {
+      cause.downcast_ref::<HyloTransactionError>().or_else(|| {
+        match cause.downcast_ref::<SubmissionError>() {
+          Some(SubmissionError::Program(failure)) => Some(failure.as_ref()),
+          _ => None,
+        }
+      })
+    })
+  }
+}
+
+impl fmt::Display for HyloTransactionError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self.signature {
+      Some(signature) => write!(f, "Transaction {signature} failed: ")?,
+      None => f.write_str("Simulation failed: ")?,
+    }
+    match &self.code {
+      Some(code) => code.fmt(f),
+      None => self.error.fmt(f),
+    }
+  }
+}
+
+impl std::error::Error for HyloTransactionError {}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn decodes_idl_and_core_errors() {
+    let idl = decode_error_code(&exchange::ID, 6000).expect("idl error");
+    assert_eq!(idl.name, "LevercoinRedeemDisabled");
+    let pool =
+      decode_error_code(&stability_pool::ID, 6001).expect("pool error");
+    assert_eq!(pool.name, "DepositDisabled");
+    let code = u32::from(CoreError::LstSolPriceOutdated);
+    let core = decode_error_code(&stability_pool::ID, code).expect("core");
+    assert_eq!(core.name, "LstSolPriceOutdated");
+    assert_eq!(
+      core.message,
+      "Cached LstSolPrice is not from current epoch."
+    );
+    assert!(decode_error_code(&Pubkey::new_unique(), 6000).is_none());
+  }
+
+  #[test]
+  fn decodes_anchor_errors() {
+    let raw = decode_error_code(&exchange::ID, 2003).expect("anchor error");
+    assert_eq!(raw.name, "ConstraintRaw");
+    assert_eq!(raw.anchor_error().map(u32::from), Some(2003));
+    assert!(raw.core_error().is_none());
+    let init =
+      decode_error_code(&stability_pool::ID, 3012).expect("anchor error");
+    assert_eq!(init.name, "AccountNotInitialized");
+    assert!(ANCHOR_ERRORS.into_iter().all(|error| {
+      anchor_error(error.into()).map(|found| found.name()) == Some(error.name())
+    }));
+  }
+
+  fn simulation(
+    err: Option<TransactionError>,
+    logs: Vec<String>,
+  ) -> RpcSimulateTransactionResult {
+    RpcSimulateTransactionResult {
+      err,
+      logs: Some(logs),
+      accounts: None,
+      units_consumed: None,
+      loaded_accounts_data_size: None,
+      return_data: None,
+      inner_instructions: None,
+      replacement_blockhash: None,
+    }
+  }
+
+  #[test]
+  fn decodes_failed_simulation() {
+    let message = VersionedMessage::default();
+    let code = u32::from(CoreError::LstSolPriceOutdated);
+    let result = simulation(
+      Some(TransactionError::InstructionError(
+        0,
+        InstructionError::Custom(code),
+      )),
+      vec![format!(
+        "Program {} failed: custom program error: {code:#x}",
+        exchange::ID
+      )],
+    );
+    let failure = HyloTransactionError::from_simulation(&message, &result)
+      .expect("failed simulation");
+    assert_eq!(
+      failure
+        .code
+        .as_ref()
+        .and_then(ProgramErrorCode::core_error)
+        .map(u32::from),
+      Some(code)
+    );
+    assert_eq!(
+      failure.reason(),
+      "Cached LstSolPrice is not from current epoch."
+    );
+    assert!(failure.to_string().starts_with("Simulation failed: "));
+
+    let err = anyhow::Error::from(failure).context("Quote failed");
+    let found = HyloTransactionError::find(&err).expect("transaction error");
+    assert_eq!(found.logs.len(), 1);
+    assert!(HyloTransactionError::find(&anyhow::anyhow!("other")).is_none());
+
+    let ok = simulation(None, Vec::new());
+    assert!(HyloTransactionError::from_simulation(&message, &ok).is_none());
+  }
+
+  #[test]
+  fn finds_innermost_failing_program() {
+    let logs = [
+      format!("Program {} invoke [1]", stability_pool::ID),
+      format!("Program {} invoke [2]", exchange::ID),
+      format!(
+        "Program {} failed: custom program error: 0x1770",
+        exchange::ID
+      ),
+      format!(
+        "Program {} failed: custom program error: 0x1770",
+        stability_pool::ID
+      ),
+    ];
+    assert_eq!(failing_program(&logs), Some(exchange::ID));
+    assert_eq!(failing_program(&logs[..2]), None);
+  }
+}
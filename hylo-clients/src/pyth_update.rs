@@ -0,0 +1,696 @@
+//! Pyth pull-oracle updates bundled with Hylo transactions.
+//!
+//! Exchange instructions read SOL/USD from the sponsored feed at
+//! [`hylo_core::pyth::SOL_USD_PYTH_FEED`] and fail once it lags behind the
+//! oracle interval or its confidence widens. [`PythPriceUpdate`] posts a fresh,
+//! fully verified price from any [`PriceUpdateSource`] into an ephemeral
+//! `PriceUpdateV2` account and points a transaction at it instead:
+//!
+//! 1. [`PythPriceUpdate::post`] writes the Wormhole VAA into an encoded VAA
+//!    account, verifies it against the guardian set and posts the price
+//! 2. [`PythPriceUpdate::wire`] swaps the feed account of exchange and
+//!    stability pool instructions for the posted one
+//! 3. [`PythPriceUpdate::close`] reclaims the rent of the accounts the post
+//!    transactions created
+//!
+//! [`ProgramClient::send_with_pyth_update`] runs all three in order, closing
+//! whatever was posted even when a later step fails.
+//!
+//! Hylo reads the price from whichever `PriceUpdateV2` account it is given,
+//! validating verification level, staleness and confidence but not the
+//! account address. A program version pinning the feed to
+//! `hylo.sol_usd_oracle` rejects wired transactions with a constraint error.
+//!
+//! [`ProgramClient::send_with_pyth_update`]: crate::program_client::ProgramClient::send_with_pyth_update
+
+use anchor_client::solana_sdk::instruction::{AccountMeta, Instruction};
+use anchor_client::solana_sdk::pubkey::Pubkey;
+use anchor_client::solana_sdk::signature::{Keypair, Signer};
+use anchor_lang::prelude::{borsh, Rent};
+use anchor_lang::{system_program, AnchorSerialize};
+use anyhow::{anyhow, ensure, Context, Result};
+use base64::prelude::{Engine, BASE64_STANDARD};
+use hylo_core::pyth::SOL_USD;
+use hylo_idl::exchange::instruction_builders::with_sol_usd_feed;
+use pyth_solana_receiver_sdk::pda::{get_config_address, get_treasury_address};
+use pyth_solana_receiver_sdk::price_update::FeedId;
+use pyth_solana_receiver_sdk::PostUpdateParams;
+use pythnet_sdk::messages::Message;
+use pythnet_sdk::wire::from_slice;
+use pythnet_sdk::wire::v1::{AccumulatorUpdateData, MerklePriceUpdate, Proof};
+use serde::Deserialize;
+use solana_system_interface::instruction as system_instruction;
+
+use crate::program_client::VersionedTransactionData;
+use crate::signer::TransactionSigner;
+
+/// Wormhole core bridge verifying VAAs for the Pyth receiver.
+pub const WORMHOLE_PROGRAM_ID: Pubkey = anchor_lang::solana_program::pubkey!(
+  "HDwcJBJXjL9FpJ7UBsYBtaDjsBUhuLCUYoz3zr8SWWaQ"
+);
+
+/// Public Hermes endpoint serving Pyth price updates.
+pub const DEFAULT_HERMES_ENDPOINT: &str = "https://hermes.pyth.network";
+
+/// Encoded VAA account header: discriminator, status, write authority,
+/// version and VAA length.
+const ENCODED_VAA_HEADER_LEN: usize = 8 + 1 + 32 + 1 + 4;
+
+/// VAA bytes written in the first post transaction, the rest follow in the
+/// second alongside verification.
+const VAA_SPLIT_INDEX: usize = 721;
+
+/// Compute units of the first post transaction, creating and writing the
+/// encoded VAA.
+const WRITE_VAA_CUS: u64 = 10_000;
+
+/// Compute units of the second post transaction, dominated by guardian
+/// signature recovery.
+const VERIFY_AND_POST_CUS: u64 = 400_000;
+
+/// Compute units of closing the encoded VAA account.
+const CLOSE_ENCODED_VAA_CUS: u64 = 30_000;
+
+/// Compute units of reclaiming the `PriceUpdateV2` account's rent.
+const RECLAIM_RENT_CUS: u64 = 30_000;
+
+const INIT_ENCODED_VAA: [u8; 8] = [209, 193, 173, 25, 91, 202, 181, 218];
+const WRITE_ENCODED_VAA: [u8; 8] = [199, 208, 110, 177, 150, 76, 118, 42];
+const VERIFY_ENCODED_VAA_V1: [u8; 8] = [103, 56, 177, 229, 240, 103, 68, 73];
+const CLOSE_ENCODED_VAA: [u8; 8] = [48, 221, 174, 198, 231, 7, 152, 38];
+const POST_UPDATE: [u8; 8] = [133, 95, 207, 175, 11, 79, 118, 44];
+const RECLAIM_RENT: [u8; 8] = [218, 200, 19, 197, 227, 89, 192, 22];
+
+/// Supplies Pyth accumulator update data, e.g. from Hermes or a local mock.
+#[async_trait::async_trait]
+pub trait PriceUpdateSource: Send + Sync {
+  /// Latest accumulator update for `feed_id`, in Pyth wire format.
+  ///
+  /// # Errors
+  /// - Source specific, e.g. HTTP failures
+  async fn latest_update(&self, feed_id: &FeedId) -> Result<Vec<u8>>;
+}
+
+/// Fixed update data, e.g. relayed from elsewhere or in tests.
+#[async_trait::async_trait]
+impl PriceUpdateSource for Vec<u8> {
+  async fn latest_update(&self, _feed_id: &FeedId) -> Result<Vec<u8>> {
+    Ok(self.clone())
+  }
+}
+
+/// Fetches updates from a Hermes-compatible HTTP endpoint.
+#[derive(Clone, Debug)]
+pub struct HermesSource {
+  endpoint: String,
+  http: reqwest::Client,
+}
+
+impl HermesSource {
+  /// Source querying `endpoint`, e.g. [`DEFAULT_HERMES_ENDPOINT`].
+  #[must_use]
+  pub fn new(endpoint: impl Into<String>) -> HermesSource {
+    HermesSource {
+      endpoint: endpoint.into().trim_end_matches('/').to_string(),
+      http: reqwest::Client::new(),
+    }
+  }
+}
+
+impl Default for HermesSource {
+  fn default() -> HermesSource {
+    HermesSource::new(DEFAULT_HERMES_ENDPOINT)
+  }
+}
+
+#[derive(Deserialize)]
+struct HermesResponse {
+  binary: HermesBinary,
+}
+
+#[derive(Deserialize)]
+struct HermesBinary {
+  encoding: String,
+  data: Vec<String>,
+}
+
+#[async_trait::async_trait]
+impl PriceUpdateSource for HermesSource {
+  async fn latest_update(&self, feed_id: &FeedId) -> Result<Vec<u8>> {
+    let url = format!("{}/v2/updates/price/latest", self.endpoint);
+    let response = self
+      .http
+      .get(url)
+      .query(&[
+        ("ids[]", hex::encode(feed_id).as_str()),
+        ("encoding", "base64"),
+        ("parsed", "false"),
+      ])
+      .send()
+      .await?
+      .error_for_status()?
+      .json::<HermesResponse>()
+      .await?;
+    ensure!(
+      response.binary.encoding == "base64",
+      "Unexpected Hermes encoding {}",
+      response.binary.encoding
+    );
+    let [data] = response.binary.data.as_slice() else {
+      return Err(anyhow!("Expected one Hermes update"));
+    };
+    Ok(BASE64_STANDARD.decode(data)?)
+  }
+}
+
+#[derive(AnchorSerialize)]
+struct WriteEncodedVaaArgs {
+  index: u32,
+  data: Vec<u8>,
+}
+
+/// Transactions posting one price update, to run around a Hylo transaction.
+///
+/// The encoded VAA and `PriceUpdateV2` accounts are fresh keypairs, so every
+/// post transaction needs [`Self::signers`] besides the payer's signature.
+pub struct PythPriceUpdate {
+  feed_id: FeedId,
+  encoded_vaa: Keypair,
+  price_update: Keypair,
+  /// Post transactions, to confirm in order before the Hylo transaction.
+  /// The first creates the encoded VAA account, the last the
+  /// `PriceUpdateV2` account.
+  pub post: Vec<VersionedTransactionData>,
+  /// Closes the encoded VAA account, refunding rent to the payer
+  pub close_encoded_vaa: Instruction,
+  /// Closes the `PriceUpdateV2` account, refunding rent to the payer
+  pub reclaim_rent: Instruction,
+}
+
+impl PythPriceUpdate {
+  /// Fetches the latest SOL/USD update from `source` and builds its post
+  /// transactions, paid by `payer`.
+  ///
+  /// # Errors
+  /// - Source failure
+  /// - See [`Self::build`]
+  pub async fn fetch_sol_usd(
+    source: &dyn PriceUpdateSource,
+    payer: Pubkey,
+  ) -> Result<PythPriceUpdate> {
+    let data = source
+      .latest_update(&SOL_USD)
+      .await
+      .context("Failed to fetch Pyth price update")?;
+    PythPriceUpdate::build(payer, &data, &SOL_USD)
+  }
+
+  /// Builds post transactions for the `feed_id` price in accumulator update
+  /// `data`, paid by `payer`.
+  ///
+  /// # Errors
+  /// - Malformed update data or VAA
+  /// - No price message for `feed_id` in the update
+  pub fn build(
+    payer: Pubkey,
+    data: &[u8],
+    feed_id: &FeedId,
+  ) -> Result<PythPriceUpdate> {
+    let Proof::WormholeMerkle { vaa, updates } =
+      AccumulatorUpdateData::try_from_slice(data)
+        .map_err(|err| anyhow!("Malformed Pyth update data: {err}"))?
+        .proof;
+    let vaa: Vec<u8> = vaa.into();
+    let merkle_price_update = updates
+      .into_iter()
+      .find(|update| message_feed_id(update) == Some(*feed_id))
+      .ok_or_else(|| {
+        anyhow!("No price for feed {} in update", hex::encode(feed_id))
+      })?;
+    let guardian_set_index = vaa
+      .get(1..5)
+      .and_then(|bytes| bytes.try_into().ok())
+      .map(u32::from_be_bytes)
+      .ok_or_else(|| anyhow!("Malformed VAA"))?;
+
+    let encoded_vaa = Keypair::new();
+    let price_update = Keypair::new();
+    let (first, rest) = vaa.split_at(VAA_SPLIT_INDEX.min(vaa.len()));
+    let encoded_vaa_len = ENCODED_VAA_HEADER_LEN + vaa.len();
+    let write = VersionedTransactionData::new(
+      vec![
+        system_instruction::create_account(
+          &payer,
+          &encoded_vaa.pubkey(),
+          Rent::default().minimum_balance(encoded_vaa_len),
+          u64::try_from(encoded_vaa_len)?,
+          &WORMHOLE_PROGRAM_ID,
+        ),
+        init_encoded_vaa(payer, encoded_vaa.pubkey()),
+        write_encoded_vaa(payer, encoded_vaa.pubkey(), 0, first)?,
+      ],
+      vec![],
+    )
+    .with_compute_units(WRITE_VAA_CUS);
+    let verify_and_post = VersionedTransactionData::new(
+      vec![
+        write_encoded_vaa(
+          payer,
+          encoded_vaa.pubkey(),
+          u32::try_from(first.len())?,
+          rest,
+        )?,
+        verify_encoded_vaa_v1(payer, encoded_vaa.pubkey(), guardian_set_index),
+        post_update(
+          payer,
+          encoded_vaa.pubkey(),
+          price_update.pubkey(),
+          merkle_price_update,
+        )?,
+      ],
+      vec![],
+    )
+    .with_compute_units(VERIFY_AND_POST_CUS);
+    Ok(PythPriceUpdate {
+      feed_id: *feed_id,
+      close_encoded_vaa: close_encoded_vaa(payer, encoded_vaa.pubkey()),
+      reclaim_rent: reclaim_rent(payer, price_update.pubkey()),
+      encoded_vaa,
+      price_update,
+      post: vec![write, verify_and_post],
+    })
+  }
+
+  /// Feed of the posted price.
+  #[must_use]
+  pub fn feed_id(&self) -> FeedId {
+    self.feed_id
+  }
+
+  /// Closes the accounts created once the first `posted` post transactions
+  /// landed, `None` if there are none.
+  #[must_use]
+  pub fn close(&self, posted: usize) -> Option<VersionedTransactionData> {
+    let (instructions, compute_units) = if posted == 0 {
+      return None;
+    } else if posted < self.post.len() {
+      (vec![self.close_encoded_vaa.clone()], CLOSE_ENCODED_VAA_CUS)
+    } else {
+      (
+        vec![self.close_encoded_vaa.clone(), self.reclaim_rent.clone()],
+        CLOSE_ENCODED_VAA_CUS + RECLAIM_RENT_CUS,
+      )
+    };
+    Some(
+      VersionedTransactionData::new(instructions, vec![])
+        .with_compute_units(compute_units),
+    )
+  }
+
+  /// Address of the posted `PriceUpdateV2` account.
+  #[must_use]
+  pub fn price_update(&self) -> Pubkey {
+    self.price_update.pubkey()
+  }
+
+  /// Ephemeral keypairs signing the post transactions.
+  #[must_use]
+  pub fn signers(&self) -> [&dyn TransactionSigner; 2] {
+    [&self.encoded_vaa, &self.price_update]
+  }
+
+  /// Points every exchange and stability pool instruction in `vtd` at the
+  /// posted price instead of [`hylo_core::pyth::SOL_USD_PYTH_FEED`].
+  #[must_use]
+  pub fn wire(
+    &self,
+    vtd: VersionedTransactionData,
+  ) -> VersionedTransactionData {
+    let feed = self.price_update();
+    VersionedTransactionData {
+      instructions: vtd
+        .instructions
+        .into_iter()
+        .map(|instruction| with_sol_usd_feed(instruction, feed))
+        .collect(),
+      ..vtd
+    }
+  }
+}
+
+/// Feed ID of a price feed message, `None` for other messages.
+fn message_feed_id(update: &MerklePriceUpdate) -> Option<FeedId> {
+  match from_slice::<byteorder::BE, Message>(update.message.as_ref()) {
+    Ok(Message::PriceFeedMessage(message)) => Some(message.feed_id),
+    _ => None,
+  }
+}
+
+fn guardian_set(index: u32) -> Pubkey {
+  Pubkey::find_program_address(
+    &[b"GuardianSet", &index.to_be_bytes()],
+    &WORMHOLE_PROGRAM_ID,
+  )
+  .0
+}
+
+fn init_encoded_vaa(
+  write_authority: Pubkey,
+  encoded_vaa: Pubkey,
+) -> Instruction {
+  Instruction {
+    program_id: WORMHOLE_PROGRAM_ID,
+    accounts: vec![
+      AccountMeta::new_readonly(write_authority, true),
+      AccountMeta::new(encoded_vaa, false),
+    ],
+    data: INIT_ENCODED_VAA.to_vec(),
+  }
+}
+
+fn write_encoded_vaa(
+  write_authority: Pubkey,
+  encoded_vaa: Pubkey,
+  index: u32,
+  data: &[u8],
+) -> Result<Instruction> {
+  let mut ix_data = WRITE_ENCODED_VAA.to_vec();
+  WriteEncodedVaaArgs {
+    index,
+    data: data.to_vec(),
+  }
+  .serialize(&mut ix_data)?;
+  Ok(Instruction {
+    program_id: WORMHOLE_PROGRAM_ID,
+    accounts: vec![
+      AccountMeta::new_readonly(write_authority, true),
+      AccountMeta::new(encoded_vaa, false),
+    ],
+    data: ix_data,
+  })
+}
+
+fn verify_encoded_vaa_v1(
+  write_authority: Pubkey,
+  encoded_vaa: Pubkey,
+  guardian_set_index: u32,
+) -> Instruction {
+  Instruction {
+    program_id: WORMHOLE_PROGRAM_ID,
+    accounts: vec![
+      AccountMeta::new_readonly(write_authority, true),
+      AccountMeta::new(encoded_vaa, false),
+      AccountMeta::new_readonly(guardian_set(guardian_set_index), false),
+    ],
+    data: VERIFY_ENCODED_VAA_V1.to_vec(),
+  }
+}
+
+fn close_encoded_vaa(
+  write_authority: Pubkey,
+  encoded_vaa: Pubkey,
+) -> Instruction {
+  Instruction {
+    program_id: WORMHOLE_PROGRAM_ID,
+    accounts: vec![
+      AccountMeta::new(write_authority, true),
+      AccountMeta::new(encoded_vaa, false),
+    ],
+    data: CLOSE_ENCODED_VAA.to_vec(),
+  }
+}
+
+/// Posts a price verified by `encoded_vaa` into `price_update`, spreading
+/// writes over the receiver's treasuries by the account address.
+fn post_update(
+  payer: Pubkey,
+  encoded_vaa: Pubkey,
+  price_update: Pubkey,
+  merkle_price_update: MerklePriceUpdate,
+) -> Result<Instruction> {
+  let treasury_id = price_update.to_bytes()[0];
+  let mut data = POST_UPDATE.to_vec();
+  PostUpdateParams {
+    merkle_price_update,
+    treasury_id,
+  }
+  .serialize(&mut data)?;
+  Ok(Instruction {
+    program_id: pyth_solana_receiver_sdk::ID,
+    accounts: vec![
+      AccountMeta::new(payer, true),
+      AccountMeta::new_readonly(encoded_vaa, false),
+      AccountMeta::new_readonly(get_config_address(), false),
+      AccountMeta::new(get_treasury_address(treasury_id), false),
+      AccountMeta::new(price_update, true),
+      AccountMeta::new_readonly(system_program::ID, false),
+      AccountMeta::new_readonly(payer, true),
+    ],
+    data,
+  })
+}
+
+fn reclaim_rent(payer: Pubkey, price_update: Pubkey) -> Instruction {
+  Instruction {
+    program_id: pyth_solana_receiver_sdk::ID,
+    accounts: vec![
+      AccountMeta::new(payer, true),
+      AccountMeta::new(price_update, false),
+    ],
+    data: RECLAIM_RENT.to_vec(),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use anchor_client::solana_sdk::hash::Hash;
+  use anchor_client::solana_sdk::packet::PACKET_DATA_SIZE;
+  use anchor_lang::AnchorDeserialize;
+  use hylo_core::pyth::SOL_USD_PYTH_FEED;
+  use hylo_idl::exchange::client::args::MintStablecoin;
+  use hylo_idl::exchange::instruction_builders::mint_stablecoin;
+  use hylo_idl::tokens::{TokenMint, JITOSOL};
+  use pythnet_sdk::accumulators::merkle::MerklePath;
+  use pythnet_sdk::messages::PriceFeedMessage;
+  use pythnet_sdk::wire::to_vec;
+  use solana_compute_budget_interface::ComputeBudgetInstruction;
+  use tokio::io::{AsyncReadExt, AsyncWriteExt};
+  use tokio::net::TcpListener;
+
+  use super::*;
+  use crate::util::build_unsigned_v0_transaction;
+
+  const GUARDIAN_SET_INDEX: u32 = 4;
+
+  /// VAA with 13 guardian signatures over a Merkle root, the size Hermes
+  /// serves.
+  fn vaa() -> Vec<u8> {
+    let mut vaa = vec![1];
+    vaa.extend(GUARDIAN_SET_INDEX.to_be_bytes());
+    vaa.push(13);
+    vaa.extend([0; 13 * 66]);
+    vaa.extend([0; 51]);
+    vaa.extend(b"AUWV");
+    vaa.extend([0; 33]);
+    vaa
+  }
+
+  fn price_message(feed_id: FeedId) -> Result<Vec<u8>> {
+    let message = Message::PriceFeedMessage(PriceFeedMessage {
+      feed_id,
+      price: 15_000_000_000,
+      conf: 5_000_000,
+      exponent: -8,
+      publish_time: 1_700_000_000,
+      prev_publish_time: 1_699_999_999,
+      ema_price: 15_000_000_000,
+      ema_conf: 5_000_000,
+    });
+    Ok(to_vec::<_, byteorder::BE>(&message)?)
+  }
+
+  fn update_data(feed_ids: &[FeedId]) -> Result<Vec<u8>> {
+    let updates = feed_ids
+      .iter()
+      .map(|feed_id| {
+        Ok(MerklePriceUpdate {
+          message: price_message(*feed_id)?.into(),
+          proof: MerklePath::new(vec![[7; 20]; 12]),
+        })
+      })
+      .collect::<Result<Vec<_>>>()?;
+    let data = AccumulatorUpdateData::new(Proof::WormholeMerkle {
+      vaa: vaa().into(),
+      updates,
+    });
+    Ok(to_vec::<_, byteorder::BE>(&data)?)
+  }
+
+  #[test]
+  fn posts_requested_feed() -> Result<()> {
+    let payer = Pubkey::new_unique();
+    let other = [3; 32];
+    let update = PythPriceUpdate::build(
+      payer,
+      &update_data(&[other, SOL_USD])?,
+      &SOL_USD,
+    )?;
+    let [write, verify_and_post] = update.post.as_slice() else {
+      return Err(anyhow!("Expected two post transactions"));
+    };
+    let post = &verify_and_post.instructions[2];
+    assert_eq!(post.program_id, pyth_solana_receiver_sdk::ID);
+    assert_eq!(post.accounts[4].pubkey, update.price_update());
+    let params = PostUpdateParams::deserialize(&mut &post.data[8..])?;
+    assert_eq!(message_feed_id(&params.merkle_price_update), Some(SOL_USD));
+    assert_eq!(
+      verify_and_post.instructions[1].accounts[2].pubkey,
+      guardian_set(GUARDIAN_SET_INDEX)
+    );
+    let encoded_vaa = write.instructions[1].accounts[1].pubkey;
+    assert_eq!(
+      update.signers().map(TransactionSigner::address),
+      [encoded_vaa, update.price_update()]
+    );
+    Ok(())
+  }
+
+  #[test]
+  fn post_transactions_fit_in_packets() -> Result<()> {
+    let payer = Pubkey::new_unique();
+    let update =
+      PythPriceUpdate::build(payer, &update_data(&[SOL_USD])?, &SOL_USD)?;
+    let close = update.close(update.post.len());
+    for vtd in update.post.iter().chain(close.as_ref()) {
+      let mut instructions = vec![
+        ComputeBudgetInstruction::set_compute_unit_limit(1_400_000),
+        ComputeBudgetInstruction::set_compute_unit_price(1),
+      ];
+      instructions.extend(vtd.instructions.clone());
+      let tx = build_unsigned_v0_transaction(
+        &VersionedTransactionData::new(instructions, vec![]),
+        &payer,
+        Hash::default(),
+      )?;
+      assert!(bincode::serialize(&tx)?.len() <= PACKET_DATA_SIZE);
+    }
+    Ok(())
+  }
+
+  #[test]
+  fn closes_only_posted_accounts() -> Result<()> {
+    let update = PythPriceUpdate::build(
+      Pubkey::new_unique(),
+      &update_data(&[SOL_USD])?,
+      &SOL_USD,
+    )?;
+    assert!(update.close(0).is_none());
+    let vaa_only = update.close(1).context("No VAA close")?;
+    assert_eq!(
+      vaa_only.instructions,
+      vec![update.close_encoded_vaa.clone()]
+    );
+    let both = update.close(2).context("No close")?;
+    assert_eq!(
+      both.instructions,
+      vec![
+        update.close_encoded_vaa.clone(),
+        update.reclaim_rent.clone()
+      ]
+    );
+    assert_eq!(
+      both.instructions[1].accounts[1].pubkey,
+      update.price_update()
+    );
+    Ok(())
+  }
+
+  #[test]
+  fn rejects_update_without_feed() -> Result<()> {
+    let data = update_data(&[[3; 32]])?;
+    assert!(
+      PythPriceUpdate::build(Pubkey::new_unique(), &data, &SOL_USD).is_err()
+    );
+    assert!(
+      PythPriceUpdate::build(Pubkey::new_unique(), &[1, 2], &SOL_USD).is_err()
+    );
+    Ok(())
+  }
+
+  #[test]
+  fn wires_posted_price_into_hylo_instructions() -> Result<()> {
+    let payer = Pubkey::new_unique();
+    let update =
+      PythPriceUpdate::build(payer, &update_data(&[SOL_USD])?, &SOL_USD)?;
+    let mint = mint_stablecoin(
+      payer,
+      JITOSOL::MINT,
+      &MintStablecoin {
+        amount_lst_to_deposit: 1,
+        slippage_config: None,
+      },
+    );
+    let other = Instruction {
+      program_id: Pubkey::new_unique(),
+      accounts: vec![AccountMeta::new_readonly(SOL_USD_PYTH_FEED, false)],
+      data: vec![],
+    };
+    let wired = update.wire(VersionedTransactionData::new(
+      vec![mint.clone(), other.clone()],
+      vec![],
+    ));
+    let feed_index = mint
+      .accounts
+      .iter()
+      .position(|meta| meta.pubkey == SOL_USD_PYTH_FEED)
+      .ok_or_else(|| anyhow!("No feed account"))?;
+    assert_eq!(
+      wired.instructions[0].accounts[feed_index].pubkey,
+      update.price_update()
+    );
+    assert!(!wired.instructions[0]
+      .accounts
+      .iter()
+      .any(|meta| meta.pubkey == SOL_USD_PYTH_FEED));
+    assert_eq!(wired.instructions[1], other);
+    Ok(())
+  }
+
+  #[tokio::test]
+  async fn fetches_from_hermes() -> Result<()> {
+    let data = update_data(&[SOL_USD])?;
+    let listener = TcpListener::bind("127.0.0.1:0").await?;
+    let endpoint = format!("http://{}/", listener.local_addr()?);
+    let body = serde_json::json!({
+      "binary": {
+        "encoding": "base64",
+        "data": [BASE64_STANDARD.encode(&data)],
+      },
+      "parsed": null,
+    })
+    .to_string();
+    let server = tokio::spawn(async move {
+      let (mut stream, _) = listener.accept().await?;
+      let mut request = vec![0; 4096];
+      let len = stream.read(&mut request).await?;
+      let response = format!(
+        "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: \
+         {}\r\nconnection: close\r\n\r\n{body}",
+        body.len()
+      );
+      stream.write_all(response.as_bytes()).await?;
+      anyhow::Ok(String::from_utf8_lossy(&request[..len]).into_owned())
+    });
+    let payer = Pubkey::new_unique();
+    let update =
+      PythPriceUpdate::fetch_sol_usd(&HermesSource::new(endpoint), payer)
+        .await?;
+    let request = server.await??;
+    assert!(request.starts_with(&format!(
+      "GET /v2/updates/price/latest?ids%5B%5D={}&encoding=base64",
+      hex::encode(SOL_USD)
+    )));
+    assert_eq!(update.post.len(), 2);
+    Ok(())
+  }
+}
@@ -0,0 +1,167 @@
+use anchor_client::solana_sdk::message::VersionedMessage;
+use anchor_client::solana_sdk::pubkey::Pubkey;
+use anchor_client::solana_sdk::signature::{Signature, Signer};
+use anchor_client::solana_sdk::transaction::VersionedTransaction;
+use anyhow::{anyhow, bail, Result};
+
+/// Signs transaction messages on behalf of one pubkey.
+///
+/// Unlike [`Signer`], signing is async so keys can live outside the process,
+/// e.g. in a KMS or behind a hardware wallet bridge. Every [`Signer`],
+/// including `Keypair`, is a `TransactionSigner`.
+#[async_trait::async_trait]
+pub trait TransactionSigner: Send + Sync {
+  /// Pubkey whose signature this signer produces.
+  fn address(&self) -> Pubkey;
+
+  /// Signs serialized message bytes.
+  ///
+  /// # Errors
+  /// - Signer specific, e.g. remote signer unavailable or request rejected
+  async fn sign(&self, message: &[u8]) -> Result<Signature>;
+}
+
+#[async_trait::async_trait]
+impl<S: Signer + Send + Sync + ?Sized> TransactionSigner for S {
+  fn address(&self) -> Pubkey {
+    self.pubkey()
+  }
+
+  async fn sign(&self, message: &[u8]) -> Result<Signature> {
+    Ok(self.try_sign_message(message)?)
+  }
+}
+
+/// Pubkey without a key, for clients that only build transactions.
+///
+/// Transactions built for it are returned unsigned, to be signed elsewhere,
+/// e.g. in a browser wallet. Signing always fails.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct UnsignedPayer(pub Pubkey);
+
+#[async_trait::async_trait]
+impl TransactionSigner for UnsignedPayer {
+  fn address(&self) -> Pubkey {
+    self.0
+  }
+
+  async fn sign(&self, _message: &[u8]) -> Result<Signature> {
+    Err(anyhow!(
+      "{} has no signer, build unsigned transactions instead",
+      self.0
+    ))
+  }
+}
+
+/// Adds `signer`'s signature to a partially signed transaction.
+///
+/// # Errors
+/// - `signer` is not a required signer of the message
+/// - Signing fails
+pub async fn sign_transaction(
+  tx: &mut VersionedTransaction,
+  signer: &dyn TransactionSigner,
+) -> Result<()> {
+  let index = signer_index(&tx.message, &signer.address())?;
+  let signature = signer.sign(&tx.message.serialize()).await?;
+  tx.signatures[index] = signature;
+  Ok(())
+}
+
+/// Position of `pubkey` among the required signers of `message`.
+///
+/// # Errors
+/// - `pubkey` is not a required signer
+pub fn signer_index(
+  message: &VersionedMessage,
+  pubkey: &Pubkey,
+) -> Result<usize> {
+  let num_signers = usize::from(message.header().num_required_signatures);
+  match message.static_account_keys()[..num_signers]
+    .iter()
+    .position(|key| key == pubkey)
+  {
+    Some(index) => Ok(index),
+    None => bail!("{pubkey} is not a required signer"),
+  }
+}
+
+/// Required signers that have not signed yet.
+#[must_use]
+pub fn missing_signers(tx: &VersionedTransaction) -> Vec<Pubkey> {
+  tx.message
+    .static_account_keys()
+    .iter()
+    .zip(&tx.signatures)
+    .filter(|(_, signature)| **signature == Signature::default())
+    .map(|(key, _)| *key)
+    .collect()
+}
+
+#[cfg(test)]
+mod tests {
+  use anchor_client::solana_sdk::hash::Hash;
+  use anchor_client::solana_sdk::instruction::Instruction;
+  use anchor_client::solana_sdk::signature::Keypair;
+  use anchor_lang::prelude::AccountMeta;
+
+  use super::*;
+  use crate::program_client::VersionedTransactionData;
+  use crate::util::build_unsigned_v0_transaction;
+
+  /// Signer holding its key behind an async boundary, like a KMS.
+  struct RemoteSigner(Keypair);
+
+  #[async_trait::async_trait]
+  impl TransactionSigner for RemoteSigner {
+    fn address(&self) -> Pubkey {
+      self.0.pubkey()
+    }
+
+    async fn sign(&self, message: &[u8]) -> Result<Signature> {
+      tokio::task::yield_now().await;
+      Ok(self.0.sign_message(message))
+    }
+  }
+
+  #[tokio::test]
+  async fn partially_signs_multi_party_transaction() -> Result<()> {
+    let payer = RemoteSigner(Keypair::new());
+    let cosigner = Keypair::new();
+    let vtd = VersionedTransactionData::one(Instruction::new_with_bytes(
+      Pubkey::new_unique(),
+      &[],
+      vec![AccountMeta::new_readonly(cosigner.pubkey(), true)],
+    ));
+    let mut tx = build_unsigned_v0_transaction(
+      &vtd,
+      &payer.address(),
+      Hash::new_unique(),
+    )?;
+    assert_eq!(tx.signatures.len(), 2);
+    assert_eq!(missing_signers(&tx).len(), 2);
+
+    sign_transaction(&mut tx, &payer).await?;
+    assert_eq!(missing_signers(&tx), vec![cosigner.pubkey()]);
+    sign_transaction(&mut tx, &cosigner).await?;
+    assert!(missing_signers(&tx).is_empty());
+    assert!(tx.verify_with_results().into_iter().all(|ok| ok));
+    Ok(())
+  }
+
+  #[tokio::test]
+  async fn rejects_foreign_and_keyless_signers() -> Result<()> {
+    let payer = UnsignedPayer(Pubkey::new_unique());
+    let vtd = VersionedTransactionData::one(Instruction::new_with_bytes(
+      Pubkey::new_unique(),
+      &[],
+      vec![],
+    ));
+    let mut tx =
+      build_unsigned_v0_transaction(&vtd, &payer.0, Hash::default())?;
+    assert!(sign_transaction(&mut tx, &Keypair::new()).await.is_err());
+    assert!(sign_transaction(&mut tx, &payer).await.is_err());
+    assert_eq!(missing_signers(&tx), vec![payer.0]);
+    Ok(())
+  }
+}
@@ -1,6 +1,6 @@
 use std::sync::Arc;
 
-use anchor_client::solana_sdk::signature::{Keypair, Signature};
+use anchor_client::solana_sdk::signature::{NullSigner, Signature};
 use anchor_client::Program;
 use anchor_lang::prelude::Pubkey;
 use anyhow::Result;
@@ -13,14 +13,16 @@ use hylo_idl::tokens::{TokenMint, HYUSD, SHYUSD, XSOL};
 
 use crate::exchange_client::ExchangeClient;
 use crate::instructions::StabilityPoolInstructionBuilder as StabilityPoolIB;
+use crate::priority_fee::FeePolicy;
 use crate::program_client::{ProgramClient, VersionedTransactionData};
+use crate::signer::TransactionSigner;
 use crate::syntax_helpers::InstructionBuilderExt;
 use crate::transaction::{
   BuildTransactionData, RedeemArgs, StabilityPoolArgs, TransactionSyntax,
 };
 use crate::util::{
-  user_ata_instruction, EXCHANGE_LOOKUP_TABLE, LST, LST_REGISTRY_LOOKUP_TABLE,
-  REFERENCE_WALLET, STABILITY_POOL_LOOKUP_TABLE,
+  is_lst_price_update, user_ata_instruction, EXCHANGE_LOOKUP_TABLE, LST,
+  LST_REGISTRY_LOOKUP_TABLE, REFERENCE_WALLET, STABILITY_POOL_LOOKUP_TABLE,
 };
 
 /// Client for interacting with the Hylo Stability Pool program.
@@ -59,26 +61,39 @@ use crate::util::{
 /// # }
 /// ```
 pub struct StabilityPoolClient {
-  program: Program<Arc<Keypair>>,
-  keypair: Arc<Keypair>,
+  program: Program<Arc<NullSigner>>,
+  signer: Arc<dyn TransactionSigner>,
+  fee_policy: FeePolicy,
 }
 
 impl ProgramClient for StabilityPoolClient {
   const PROGRAM_ID: Pubkey = hylo_idl::stability_pool::ID;
 
   fn build_client(
-    program: Program<Arc<Keypair>>,
-    keypair: Arc<Keypair>,
+    program: Program<Arc<NullSigner>>,
+    signer: Arc<dyn TransactionSigner>,
   ) -> StabilityPoolClient {
-    StabilityPoolClient { program, keypair }
+    StabilityPoolClient {
+      program,
+      signer,
+      fee_policy: FeePolicy::default(),
+    }
   }
 
-  fn program(&self) -> &Program<Arc<Keypair>> {
+  fn program(&self) -> &Program<Arc<NullSigner>> {
     &self.program
   }
 
-  fn keypair(&self) -> Arc<Keypair> {
-    self.keypair.clone()
+  fn signer(&self) -> Arc<dyn TransactionSigner> {
+    self.signer.clone()
+  }
+
+  fn fee_policy(&self) -> &FeePolicy {
+    &self.fee_policy
+  }
+
+  fn with_fee_policy(self, fee_policy: FeePolicy) -> StabilityPoolClient {
+    StabilityPoolClient { fee_policy, ..self }
   }
 }
 
@@ -185,11 +200,13 @@ impl BuildTransactionData<HYUSD, SHYUSD> for StabilityPoolClient {
     &self,
     inputs: StabilityPoolArgs,
   ) -> Result<VersionedTransactionData> {
+    let user = inputs.user;
     let instructions =
       StabilityPoolIB::build_instructions::<HYUSD, SHYUSD>(inputs)?;
     let lut_addresses = StabilityPoolIB::lookup_tables::<HYUSD, SHYUSD>();
     let lookup_tables = self.load_multiple_lookup_tables(lut_addresses).await?;
-    Ok(VersionedTransactionData::new(instructions, lookup_tables))
+    let vtd = VersionedTransactionData::new(instructions, lookup_tables);
+    self.with_lst_price_update(user, vtd).await
   }
 }
 
@@ -201,11 +218,13 @@ impl BuildTransactionData<SHYUSD, HYUSD> for StabilityPoolClient {
     &self,
     inputs: StabilityPoolArgs,
   ) -> Result<VersionedTransactionData> {
+    let user = inputs.user;
     let instructions =
       StabilityPoolIB::build_instructions::<SHYUSD, HYUSD>(inputs)?;
     let lut_addresses = StabilityPoolIB::lookup_tables::<SHYUSD, HYUSD>();
     let lookup_tables = self.load_multiple_lookup_tables(lut_addresses).await?;
-    Ok(VersionedTransactionData::new(instructions, lookup_tables))
+    let vtd = VersionedTransactionData::new(instructions, lookup_tables);
+    self.with_lst_price_update(user, vtd).await
   }
 }
 
@@ -245,7 +264,12 @@ impl<OUT: LST> BuildTransactionData<SHYUSD, OUT> for StabilityPoolClient {
         })
         .await?;
       instructions.extend(vec![user_ata_instruction(&user, &HYUSD::MINT)]);
-      instructions.extend(redeem_hyusd_args.instructions);
+      instructions.extend(
+        redeem_hyusd_args
+          .instructions
+          .into_iter()
+          .filter(|ix| !is_lst_price_update(ix)),
+      );
     }
 
     // If simulated transaction yields xSOL, redeem it to jitoSOL
@@ -258,7 +282,12 @@ impl<OUT: LST> BuildTransactionData<SHYUSD, OUT> for StabilityPoolClient {
         })
         .await?;
       instructions.extend(vec![user_ata_instruction(&user, &XSOL::MINT)]);
-      instructions.extend(redeem_xsol_args.instructions);
+      instructions.extend(
+        redeem_xsol_args
+          .instructions
+          .into_iter()
+          .filter(|ix| !is_lst_price_update(ix)),
+      );
     }
     let lookup_tables = self
       .load_multiple_lookup_tables(&[
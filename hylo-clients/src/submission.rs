@@ -0,0 +1,716 @@
+use std::fmt;
+use std::future::Future;
+use std::time::Duration;
+
+use anchor_client::solana_client::client_error::{
+  ClientError, ClientErrorKind,
+};
+use anchor_client::solana_client::nonblocking::rpc_client::RpcClient;
+use anchor_client::solana_client::rpc_config::{
+  RpcSendTransactionConfig, RpcTransactionConfig,
+};
+use anchor_client::solana_client::rpc_request::{
+  RpcError, RpcResponseErrorData,
+};
+use anchor_client::solana_sdk::commitment_config::CommitmentConfig;
+use anchor_client::solana_sdk::signature::Signature;
+use anchor_client::solana_sdk::transaction::{
+  TransactionError, VersionedTransaction,
+};
+use anyhow::{anyhow, Result};
+use solana_transaction_status_client_types::{
+  EncodedConfirmedTransactionWithStatusMeta, TransactionStatus,
+  UiTransactionEncoding,
+};
+
+use crate::events::{parse_transaction_events, HyloEvent};
+use crate::program_client::VersionedTransactionData;
+use crate::program_error::HyloTransactionError;
+use crate::signer::{sign_transaction, signer_index, TransactionSigner};
+use crate::util::build_unsigned_v0_transaction;
+
+/// Default delay between rebroadcasts and status polls.
+pub const DEFAULT_REBROADCAST_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Default number of rebuilds after the first blockhash expires.
+pub const DEFAULT_MAX_REBUILDS: usize = 3;
+
+/// Default number of consecutive failed status polls tolerated.
+pub const DEFAULT_MAX_RPC_FAILURES: usize = 10;
+
+/// Tuning for [`Submitter`].
+#[derive(Clone, Copy, Debug)]
+pub struct SubmissionConfig {
+  pub commitment: CommitmentConfig,
+  pub rebroadcast_interval: Duration,
+  pub max_rebuilds: usize,
+  /// Consecutive failed signature status or block height polls tolerated
+  /// while awaiting confirmation.
+  pub max_rpc_failures: usize,
+}
+
+impl Default for SubmissionConfig {
+  fn default() -> SubmissionConfig {
+    SubmissionConfig {
+      commitment: CommitmentConfig::confirmed(),
+      rebroadcast_interval: DEFAULT_REBROADCAST_INTERVAL,
+      max_rebuilds: DEFAULT_MAX_REBUILDS,
+      max_rpc_failures: DEFAULT_MAX_RPC_FAILURES,
+    }
+  }
+}
+
+/// Produces the transaction to submit, once per blockhash.
+///
+/// Sources that re-quote on every build keep slippage protection current
+/// across rebuilds. Async closures returning [`VersionedTransactionData`]
+/// are sources, as is a fixed [`VersionedTransactionData`].
+#[async_trait::async_trait]
+pub trait TransactionSource: Send + Sync {
+  /// Builds fresh transaction data.
+  ///
+  /// # Errors
+  /// - Source specific, e.g. quoting failures
+  async fn build(&self) -> Result<VersionedTransactionData>;
+}
+
+#[async_trait::async_trait]
+impl TransactionSource for VersionedTransactionData {
+  async fn build(&self) -> Result<VersionedTransactionData> {
+    Ok(self.clone())
+  }
+}
+
+#[async_trait::async_trait]
+impl<F, Fut> TransactionSource for F
+where
+  F: Fn() -> Fut + Send + Sync,
+  Fut: Future<Output = Result<VersionedTransactionData>> + Send,
+{
+  async fn build(&self) -> Result<VersionedTransactionData> {
+    self().await
+  }
+}
+
+/// Confirmed transaction with the Hylo events it emitted.
+#[derive(Clone, Debug)]
+pub struct Submitted {
+  pub signature: Signature,
+  pub slot: u64,
+  /// Empty if the confirmed transaction could not be fetched.
+  pub events: Vec<HyloEvent>,
+  /// Number of rebuilds after blockhash expiry.
+  pub rebuilds: usize,
+}
+
+/// Why [`Submitter::submit`] gave up.
+#[derive(Debug)]
+pub enum SubmissionError {
+  /// A program rejected the transaction during preflight or execution,
+  /// resubmitting would fail again.
+  Program(Box<HyloTransactionError>),
+  /// The runtime rejected the transaction before execution, e.g. for
+  /// insufficient fee balance.
+  Rejected {
+    signature: Signature,
+    error: TransactionError,
+  },
+  /// Every blockhash expired before the transaction confirmed.
+  Expired {
+    signature: Signature,
+    attempts: usize,
+  },
+  /// Building the transaction or talking to RPC failed.
+  Rpc(anyhow::Error),
+}
+
+impl fmt::Display for SubmissionError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      SubmissionError::Program(failure) => failure.fmt(f),
+      SubmissionError::Rejected { signature, error } => {
+        write!(f, "Transaction {signature} rejected: {error}")
+      }
+      SubmissionError::Expired {
+        signature,
+        attempts,
+      } => write!(
+        f,
+        "Transaction {signature} not confirmed after {attempts} blockhashes"
+      ),
+      SubmissionError::Rpc(err) => write!(f, "Submission failed: {err:#}"),
+    }
+  }
+}
+
+impl std::error::Error for SubmissionError {
+  fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+    match self {
+      SubmissionError::Rpc(err) => Some(err.as_ref()),
+      _ => None,
+    }
+  }
+}
+
+/// Outcome of broadcasting one signed transaction.
+enum Broadcast {
+  Confirmed { slot: u64 },
+  Expired,
+}
+
+/// Submits transactions until they confirm or fail for good.
+///
+/// Each signed transaction is rebroadcast until its blockhash's last valid
+/// block height. On expiry the source is rebuilt and re-signed with a fresh
+/// blockhash, up to [`SubmissionConfig::max_rebuilds`] times. Program errors
+/// end submission immediately, while RPC errors are retried up to
+/// [`SubmissionConfig::max_rpc_failures`] times in a row.
+pub struct Submitter<'a> {
+  rpc: &'a RpcClient,
+  payer: &'a dyn TransactionSigner,
+  cosigners: &'a [&'a dyn TransactionSigner],
+  config: SubmissionConfig,
+}
+
+impl<'a> Submitter<'a> {
+  #[must_use]
+  pub fn new(
+    rpc: &'a RpcClient,
+    payer: &'a dyn TransactionSigner,
+    config: SubmissionConfig,
+  ) -> Submitter<'a> {
+    Submitter {
+      rpc,
+      payer,
+      cosigners: &[],
+      config,
+    }
+  }
+
+  /// Also signs every build with each of `cosigners` the message requires,
+  /// e.g. ephemeral keypairs of accounts created in the transaction.
+  #[must_use]
+  pub fn with_cosigners(
+    self,
+    cosigners: &'a [&'a dyn TransactionSigner],
+  ) -> Submitter<'a> {
+    Submitter { cosigners, ..self }
+  }
+
+  /// Builds, signs and submits transactions from `source`.
+  ///
+  /// # Errors
+  /// - [`SubmissionError::Program`] or [`SubmissionError::Rejected`] when the
+  ///   transaction fails deterministically
+  /// - [`SubmissionError::Expired`] when no blockhash confirmed
+  /// - [`SubmissionError::Rpc`] when building, signing or fetching a blockhash
+  ///   fails, or when status polls keep failing
+  pub async fn submit(
+    &self,
+    source: &dyn TransactionSource,
+  ) -> Result<Submitted, SubmissionError> {
+    let mut signature = Signature::default();
+    for rebuilds in 0..=self.config.max_rebuilds {
+      let vtd = source.build().await.map_err(SubmissionError::Rpc)?;
+      let (blockhash, last_valid_block_height) = self
+        .rpc
+        .get_latest_blockhash_with_commitment(self.config.commitment)
+        .await
+        .map_err(|err| SubmissionError::Rpc(err.into()))?;
+      let mut tx =
+        build_unsigned_v0_transaction(&vtd, &self.payer.address(), blockhash)
+          .map_err(SubmissionError::Rpc)?;
+      sign_transaction(&mut tx, self.payer)
+        .await
+        .map_err(SubmissionError::Rpc)?;
+      for cosigner in self.cosigners {
+        if signer_index(&tx.message, &cosigner.address()).is_ok() {
+          sign_transaction(&mut tx, *cosigner)
+            .await
+            .map_err(SubmissionError::Rpc)?;
+        }
+      }
+      signature = tx.signatures[0];
+      if let Broadcast::Confirmed { slot } =
+        self.broadcast(&tx, last_valid_block_height).await?
+      {
+        let events = self
+          .fetch_transaction(&signature)
+          .await
+          .and_then(|tx| parse_transaction_events(&tx))
+          .unwrap_or_default();
+        return Ok(Submitted {
+          signature,
+          slot,
+          events,
+          rebuilds,
+        });
+      }
+    }
+    Err(SubmissionError::Expired {
+      signature,
+      attempts: self.config.max_rebuilds + 1,
+    })
+  }
+
+  /// Sends `tx` repeatedly until it confirms, fails or expires.
+  async fn broadcast(
+    &self,
+    tx: &VersionedTransaction,
+    last_valid_block_height: u64,
+  ) -> Result<Broadcast, SubmissionError> {
+    let signature = tx.signatures[0];
+    let mut preflight = true;
+    let mut rpc_failures = 0;
+    loop {
+      let config = RpcSendTransactionConfig {
+        skip_preflight: !preflight,
+        preflight_commitment: Some(self.config.commitment.commitment),
+        max_retries: Some(0),
+        ..Default::default()
+      };
+      match self.rpc.send_transaction_with_config(tx, config).await {
+        // Preflight only runs once, rebroadcasts would see AlreadyProcessed
+        Ok(_) => preflight = false,
+        Err(err) => self.classify_send_error(tx, &err)?,
+      }
+      tokio::time::sleep(self.config.rebroadcast_interval).await;
+      let statuses = self.rpc.get_signature_statuses(&[signature]).await;
+      if let Some(statuses) = self.tolerate(statuses, &mut rpc_failures)? {
+        if let Some(Some(status)) = statuses.value.first() {
+          match self.landed(tx, status).await? {
+            Some(broadcast) => return Ok(broadcast),
+            // Landed but not yet at the target commitment, cannot expire
+            None => continue,
+          }
+        }
+      }
+      let height = self
+        .rpc
+        .get_block_height_with_commitment(self.config.commitment)
+        .await;
+      if let Some(height) = self.tolerate(height, &mut rpc_failures)? {
+        if height > last_valid_block_height {
+          // Recent statuses may have missed a landing, so search the full
+          // history once before rebuilding and risking a double execution
+          let statuses = self
+            .rpc
+            .get_signature_statuses_with_history(&[signature])
+            .await
+            .map_err(|err| SubmissionError::Rpc(err.into()))?;
+          let Some(Some(status)) = statuses.value.first() else {
+            return Ok(Broadcast::Expired);
+          };
+          if let Some(broadcast) = self.landed(tx, status).await? {
+            return Ok(broadcast);
+          }
+        }
+      }
+    }
+  }
+
+  /// Value of a successful poll, `None` for a tolerated failure.
+  fn tolerate<T>(
+    &self,
+    result: Result<T, ClientError>,
+    failures: &mut usize,
+  ) -> Result<Option<T>, SubmissionError> {
+    match result {
+      Ok(value) => {
+        *failures = 0;
+        Ok(Some(value))
+      }
+      Err(_) if *failures < self.config.max_rpc_failures => {
+        *failures += 1;
+        Ok(None)
+      }
+      Err(err) => Err(SubmissionError::Rpc(anyhow!(
+        "RPC failed {} times in a row: {err}",
+        *failures + 1
+      ))),
+    }
+  }
+
+  /// Outcome of a landed transaction, `None` until it reaches the target
+  /// commitment.
+  async fn landed(
+    &self,
+    tx: &VersionedTransaction,
+    status: &TransactionStatus,
+  ) -> Result<Option<Broadcast>, SubmissionError> {
+    if let Some(error) = &status.err {
+      let logs = self.fetch_logs(&tx.signatures[0]).await;
+      return Err(self.failure(tx, error.clone(), logs));
+    }
+    Ok(
+      status
+        .satisfies_commitment(self.config.commitment)
+        .then_some(Broadcast::Confirmed { slot: status.slot }),
+    )
+  }
+
+  /// Surfaces deterministic preflight failures, tolerating transient ones.
+  fn classify_send_error(
+    &self,
+    tx: &VersionedTransaction,
+    err: &ClientError,
+  ) -> Result<(), SubmissionError> {
+    let logs = match err.kind() {
+      ClientErrorKind::RpcError(RpcError::RpcResponseError {
+        data: RpcResponseErrorData::SendTransactionPreflightFailure(result),
+        ..
+      }) => result.logs.clone().unwrap_or_default(),
+      _ => Vec::new(),
+    };
+    match err.get_transaction_error() {
+      None
+      | Some(
+        TransactionError::BlockhashNotFound
+        | TransactionError::AlreadyProcessed,
+      ) => Ok(()),
+      Some(error) => Err(self.failure(tx, error, logs)),
+    }
+  }
+
+  fn failure(
+    &self,
+    tx: &VersionedTransaction,
+    error: TransactionError,
+    logs: Vec<String>,
+  ) -> SubmissionError {
+    let signature = tx.signatures[0];
+    match error {
+      TransactionError::InstructionError(..) => {
+        SubmissionError::Program(Box::new(HyloTransactionError::new(
+          Some(signature),
+          error,
+          &tx.message,
+          logs,
+        )))
+      }
+      error => SubmissionError::Rejected { signature, error },
+    }
+  }
+
+  async fn fetch_transaction(
+    &self,
+    signature: &Signature,
+  ) -> Result<EncodedConfirmedTransactionWithStatusMeta> {
+    let config = RpcTransactionConfig {
+      encoding: Some(UiTransactionEncoding::Base64),
+      commitment: Some(self.config.commitment),
+      max_supported_transaction_version: Some(0),
+    };
+    self
+      .rpc
+      .get_transaction_with_config(signature, config)
+      .await
+      .map_err(|err| anyhow!("Failed to fetch transaction {signature}: {err}"))
+  }
+
+  async fn fetch_logs(&self, signature: &Signature) -> Vec<String> {
+    self
+      .fetch_transaction(signature)
+      .await
+      .ok()
+      .and_then(|tx| tx.transaction.meta)
+      .and_then(|meta| Option::from(meta.log_messages))
+      .unwrap_or_default()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+  use std::sync::{Arc, Mutex};
+
+  use anchor_client::solana_client::client_error;
+  use anchor_client::solana_client::rpc_request::RpcRequest;
+  use anchor_client::solana_client::rpc_response::RpcSimulateTransactionResult;
+  use anchor_client::solana_client::rpc_sender::{
+    RpcSender, RpcTransportStats,
+  };
+  use anchor_client::solana_sdk::hash::Hash;
+  use anchor_client::solana_sdk::instruction::{
+    AccountMeta, Instruction, InstructionError,
+  };
+  use anchor_client::solana_sdk::signature::{Keypair, Signer};
+  use base64::prelude::{Engine, BASE64_STANDARD};
+  use hylo_idl::exchange;
+  use serde_json::{json, Value};
+
+  use super::*;
+
+  /// Mock cluster where blockhashes expire at height 100 and the block
+  /// height jumps past it on the first status poll of every blockhash until
+  /// `expire_first` blockhashes have expired. With `landed_after_expiry`,
+  /// only history searches see the transaction. With `polls_fail`, status
+  /// and block height requests error.
+  #[derive(Default)]
+  struct MockCluster {
+    expire_first: u64,
+    landed_after_expiry: bool,
+    polls_fail: bool,
+    polls: Arc<AtomicUsize>,
+    expired: AtomicU64,
+    preflight_error: Option<TransactionError>,
+    sends: AtomicUsize,
+    last_sent: Arc<Mutex<Option<VersionedTransaction>>>,
+  }
+
+  #[async_trait::async_trait]
+  impl RpcSender for MockCluster {
+    async fn send(
+      &self,
+      request: RpcRequest,
+      params: Value,
+    ) -> client_error::Result<Value> {
+      let context = json!({ "slot": 42 });
+      if self.polls_fail
+        && matches!(
+          request,
+          RpcRequest::GetSignatureStatuses | RpcRequest::GetBlockHeight
+        )
+      {
+        self.polls.fetch_add(1, Ordering::SeqCst);
+        return Err(RpcError::ForUser("connection refused".to_string()).into());
+      }
+      match request {
+        RpcRequest::GetLatestBlockhash => Ok(json!({
+          "context": context,
+          "value": {
+            "blockhash": Hash::new_unique().to_string(),
+            "lastValidBlockHeight": 100,
+          },
+        })),
+        RpcRequest::SendTransaction => {
+          self.sends.fetch_add(1, Ordering::SeqCst);
+          if let Some(err) = &self.preflight_error {
+            return Err(
+              RpcError::RpcResponseError {
+                code: -32002,
+                message: "Transaction simulation failed".to_string(),
+                data: RpcResponseErrorData::SendTransactionPreflightFailure(
+                  RpcSimulateTransactionResult {
+                    err: Some(err.clone()),
+                    logs: Some(vec![format!(
+                      "Program {} failed: custom program error: 0x1770",
+                      exchange::ID
+                    )]),
+                    accounts: None,
+                    units_consumed: None,
+                    loaded_accounts_data_size: None,
+                    return_data: None,
+                    inner_instructions: None,
+                    replacement_blockhash: None,
+                  },
+                ),
+              }
+              .into(),
+            );
+          }
+          let bytes = BASE64_STANDARD
+            .decode(params[0].as_str().expect("tx"))
+            .expect("base64");
+          let tx =
+            bincode::deserialize::<VersionedTransaction>(&bytes).expect("tx");
+          let signature = tx.signatures[0].to_string();
+          *self.last_sent.lock().expect("lock") = Some(tx);
+          Ok(json!(signature))
+        }
+        RpcRequest::GetSignatureStatuses => {
+          let history = params[1]["searchTransactionHistory"] == json!(true);
+          let landed = if history {
+            self.landed_after_expiry
+          } else {
+            self.expired.load(Ordering::SeqCst) >= self.expire_first
+          };
+          let status = landed.then(|| {
+            json!({
+              "slot": 42,
+              "confirmations": 1,
+              "status": { "Ok": null },
+              "err": null,
+              "confirmationStatus": "confirmed",
+            })
+          });
+          Ok(json!({ "context": context, "value": [status] }))
+        }
+        RpcRequest::GetBlockHeight => {
+          self.expired.fetch_add(1, Ordering::SeqCst);
+          Ok(json!(101))
+        }
+        _ => Ok(Value::Null),
+      }
+    }
+
+    fn get_transport_stats(&self) -> RpcTransportStats {
+      RpcTransportStats::default()
+    }
+
+    fn url(&self) -> String {
+      "mock".to_string()
+    }
+  }
+
+  fn config() -> SubmissionConfig {
+    SubmissionConfig {
+      rebroadcast_interval: Duration::from_millis(1),
+      max_rebuilds: 2,
+      ..SubmissionConfig::default()
+    }
+  }
+
+  fn vtd() -> VersionedTransactionData {
+    VersionedTransactionData::one(Instruction::new_with_bytes(
+      exchange::ID,
+      &[],
+      vec![],
+    ))
+  }
+
+  async fn submit(
+    cluster: MockCluster,
+    builds: &AtomicUsize,
+  ) -> Result<Submitted, SubmissionError> {
+    let rpc = RpcClient::new_sender(cluster, Default::default());
+    let payer = Keypair::new();
+    let source = || async {
+      builds.fetch_add(1, Ordering::SeqCst);
+      Ok(vtd())
+    };
+    Submitter::new(&rpc, &payer, config()).submit(&source).await
+  }
+
+  #[tokio::test]
+  async fn confirms_first_blockhash() -> Result<()> {
+    let builds = AtomicUsize::new(0);
+    let submitted = submit(MockCluster::default(), &builds).await?;
+    assert_eq!(submitted.slot, 42);
+    assert_eq!(submitted.rebuilds, 0);
+    assert!(submitted.events.is_empty());
+    assert_eq!(builds.load(Ordering::SeqCst), 1);
+    Ok(())
+  }
+
+  #[tokio::test]
+  async fn rebuilds_on_expiry() -> Result<()> {
+    let builds = AtomicUsize::new(0);
+    let cluster = MockCluster {
+      expire_first: 2,
+      ..MockCluster::default()
+    };
+    let submitted = submit(cluster, &builds).await?;
+    assert_eq!(submitted.rebuilds, 2);
+    assert_eq!(builds.load(Ordering::SeqCst), 3);
+    Ok(())
+  }
+
+  #[tokio::test]
+  async fn gives_up_after_max_rebuilds() {
+    let builds = AtomicUsize::new(0);
+    let cluster = MockCluster {
+      expire_first: u64::MAX,
+      ..MockCluster::default()
+    };
+    let result = submit(cluster, &builds).await;
+    assert!(matches!(
+      result,
+      Err(SubmissionError::Expired { attempts: 3, .. })
+    ));
+    assert_eq!(builds.load(Ordering::SeqCst), 3);
+  }
+
+  #[tokio::test]
+  async fn confirms_landing_seen_after_expiry() -> Result<()> {
+    let builds = AtomicUsize::new(0);
+    let cluster = MockCluster {
+      expire_first: u64::MAX,
+      landed_after_expiry: true,
+      ..MockCluster::default()
+    };
+    let submitted = submit(cluster, &builds).await?;
+    assert_eq!(submitted.rebuilds, 0);
+    assert_eq!(builds.load(Ordering::SeqCst), 1);
+    Ok(())
+  }
+
+  #[tokio::test]
+  async fn gives_up_when_polls_keep_failing() {
+    let cluster = MockCluster {
+      polls_fail: true,
+      ..MockCluster::default()
+    };
+    let polls = cluster.polls.clone();
+    let rpc = RpcClient::new_sender(cluster, Default::default());
+    let payer = Keypair::new();
+    let config = SubmissionConfig {
+      max_rpc_failures: 4,
+      ..config()
+    };
+    let result = Submitter::new(&rpc, &payer, config).submit(&vtd()).await;
+    assert!(matches!(result, Err(SubmissionError::Rpc(_))));
+    assert_eq!(polls.load(Ordering::SeqCst), 5);
+  }
+
+  #[tokio::test]
+  async fn decodes_preflight_program_error() {
+    let builds = AtomicUsize::new(0);
+    let cluster = MockCluster {
+      preflight_error: Some(TransactionError::InstructionError(
+        0,
+        InstructionError::Custom(6000),
+      )),
+      ..MockCluster::default()
+    };
+    let Err(SubmissionError::Program(failure)) = submit(cluster, &builds).await
+    else {
+      panic!("expected program failure");
+    };
+    let code = failure.code.expect("decoded error");
+    assert_eq!(code.program_id, exchange::ID);
+    assert_eq!(code.name, "LevercoinRedeemDisabled");
+    assert_eq!(failure.logs.len(), 1);
+    assert_eq!(builds.load(Ordering::SeqCst), 1);
+  }
+
+  #[tokio::test]
+  async fn tolerates_transient_preflight_errors() -> Result<()> {
+    let builds = AtomicUsize::new(0);
+    let cluster = MockCluster {
+      preflight_error: Some(TransactionError::BlockhashNotFound),
+      ..MockCluster::default()
+    };
+    let submitted = submit(cluster, &builds).await?;
+    assert_eq!(submitted.rebuilds, 0);
+    Ok(())
+  }
+
+  #[tokio::test]
+  async fn signs_with_required_cosigners() -> Result<()> {
+    let cluster = MockCluster::default();
+    let last_sent = cluster.last_sent.clone();
+    let rpc = RpcClient::new_sender(cluster, Default::default());
+    let payer = Keypair::new();
+    let required = Keypair::new();
+    let unused = Keypair::new();
+    let vtd = VersionedTransactionData::one(Instruction::new_with_bytes(
+      exchange::ID,
+      &[],
+      vec![AccountMeta::new(required.pubkey(), true)],
+    ));
+    let cosigners: [&dyn TransactionSigner; 2] = [&unused, &required];
+    Submitter::new(&rpc, &payer, config())
+      .with_cosigners(&cosigners)
+      .submit(&vtd)
+      .await?;
+    let tx = last_sent
+      .lock()
+      .expect("lock")
+      .take()
+      .ok_or_else(|| anyhow!("Nothing sent"))?;
+    assert_eq!(tx.signatures.len(), 2);
+    assert!(tx.verify_with_results().into_iter().all(|valid| valid));
+    Ok(())
+  }
+}
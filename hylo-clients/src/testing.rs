@@ -0,0 +1,516 @@
+//! Offline cluster for running clients end to end without network access.
+//!
+//! [`LocalCluster`] serves the JSON-RPC methods used by the clients and
+//! quote strategies from an in-memory account set, e.g. a protocol state
+//! snapshot. Program execution is delegated to a [`ProgramExecutor`], since
+//! the Hylo program binaries are not distributed with the SDK:
+//!
+//! - [`NoPrograms`] rejects every transaction, for flows that only read
+//!   accounts or build transactions
+//! - Custom executors return an [`Execution`] per transaction, e.g. the event
+//!   an instruction is expected to emit, to drive simulation based quoting and
+//!   submission offline
+//!
+//! Executions are scripted, so the cluster exercises RPC handling, event
+//! decoding and transaction building, not what the programs compute.
+//!
+//! ```rust,no_run
+//! use hylo_clients::prelude::*;
+//! use hylo_clients::testing::{LocalCluster, NoPrograms};
+//!
+//! # async fn example() -> Result<()> {
+//! let cluster = LocalCluster::start(Vec::new(), NoPrograms).await?;
+//! let client = ExchangeClient::new_random_keypair(
+//!   cluster.cluster(),
+//!   CommitmentConfig::confirmed(),
+//! )?;
+//! # Ok(())
+//! # }
+//! ```
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use anchor_client::solana_account_decoder::{
+  encode_ui_account, UiAccountEncoding,
+};
+use anchor_client::solana_client::rpc_response::{
+  Response, RpcBlockhash, RpcResponseContext, RpcSimulateTransactionResult,
+  RpcVersionInfo,
+};
+use anchor_client::solana_sdk::account::Account;
+use anchor_client::solana_sdk::clock::Clock;
+use anchor_client::solana_sdk::epoch_info::EpochInfo;
+use anchor_client::solana_sdk::hash::Hash;
+use anchor_client::solana_sdk::instruction::{AccountMeta, Instruction};
+use anchor_client::solana_sdk::pubkey::Pubkey;
+use anchor_client::solana_sdk::rent::Rent;
+use anchor_client::solana_sdk::signature::Signature;
+use anchor_client::solana_sdk::transaction::{
+  TransactionError, VersionedTransaction,
+};
+use anchor_client::solana_sdk::{bs58, sysvar};
+use anchor_client::Cluster;
+use anchor_lang::event::EVENT_IX_TAG_LE;
+use anchor_lang::Event;
+use anyhow::{anyhow, Context, Result};
+use axum::extract::State;
+use axum::routing::post;
+use axum::{Json, Router};
+use base64::prelude::{Engine, BASE64_STANDARD};
+use serde_json::{json, Value};
+use solana_transaction_status_client_types::{
+  TransactionConfirmationStatus, TransactionStatus, UiInnerInstructions,
+  UiInstruction, UiParsedInstruction, UiPartiallyDecodedInstruction,
+  UiReturnDataEncoding, UiTransactionReturnData,
+};
+use tokio::net::TcpListener;
+use tokio::task::JoinHandle;
+
+/// Blocks a blockhash stays valid for, as on mainnet.
+const BLOCKHASH_VALIDITY: u64 = 150;
+
+/// JSON-RPC error code of failed preflight simulations.
+const PREFLIGHT_FAILURE: i64 = -32002;
+
+/// JSON-RPC error code of malformed parameters.
+const INVALID_PARAMS: i64 = -32602;
+
+/// JSON-RPC error code of unsupported methods.
+const METHOD_NOT_FOUND: i64 = -32601;
+
+/// Outcome of executing one transaction.
+#[derive(Clone, Debug, Default)]
+pub struct Execution {
+  pub err: Option<TransactionError>,
+  pub logs: Vec<String>,
+  pub units_consumed: u64,
+  /// Program and data set via `set_return_data`
+  pub return_data: Option<(Pubkey, Vec<u8>)>,
+  /// Inner instructions by top level instruction index
+  pub inner_instructions: Vec<(u8, Vec<Instruction>)>,
+  /// Accounts written when the transaction lands
+  pub writes: Vec<(Pubkey, Account)>,
+}
+
+impl Execution {
+  /// Successful execution consuming `units_consumed`.
+  #[must_use]
+  pub fn success(units_consumed: u64) -> Execution {
+    Execution {
+      units_consumed,
+      ..Execution::default()
+    }
+  }
+
+  /// Failed execution with `logs`.
+  #[must_use]
+  pub fn failure(err: TransactionError, logs: Vec<String>) -> Execution {
+    Execution {
+      err: Some(err),
+      logs,
+      ..Execution::default()
+    }
+  }
+
+  /// Adds `event` as emitted by `program_id` through `emit_cpi!` from the top
+  /// level instruction at `index`.
+  #[must_use]
+  pub fn with_event<E: Event>(
+    mut self,
+    index: u8,
+    program_id: Pubkey,
+    event: &E,
+  ) -> Execution {
+    let (event_authority, _) =
+      Pubkey::find_program_address(&[b"__event_authority"], &program_id);
+    let mut data = EVENT_IX_TAG_LE.to_vec();
+    data.extend(event.data());
+    let instruction = Instruction {
+      program_id,
+      accounts: vec![AccountMeta::new_readonly(event_authority, true)],
+      data,
+    };
+    match self
+      .inner_instructions
+      .iter_mut()
+      .find(|(i, _)| *i == index)
+    {
+      Some((_, instructions)) => instructions.push(instruction),
+      None => self.inner_instructions.push((index, vec![instruction])),
+    }
+    self
+  }
+
+  /// Sets the return data of `program_id`.
+  #[must_use]
+  pub fn with_return_data(
+    self,
+    program_id: Pubkey,
+    data: Vec<u8>,
+  ) -> Execution {
+    Execution {
+      return_data: Some((program_id, data)),
+      ..self
+    }
+  }
+}
+
+/// Executes transactions against the accounts of a [`LocalCluster`].
+pub trait ProgramExecutor: Send + Sync {
+  /// Executes `tx`, which is not applied unless the returned execution
+  /// succeeds.
+  fn execute(
+    &self,
+    tx: &VersionedTransaction,
+    accounts: &HashMap<Pubkey, Account>,
+  ) -> Execution;
+}
+
+impl<F> ProgramExecutor for F
+where
+  F: Fn(&VersionedTransaction, &HashMap<Pubkey, Account>) -> Execution
+    + Send
+    + Sync,
+{
+  fn execute(
+    &self,
+    tx: &VersionedTransaction,
+    accounts: &HashMap<Pubkey, Account>,
+  ) -> Execution {
+    self(tx, accounts)
+  }
+}
+
+/// Executor without any programs loaded, rejecting every transaction.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct NoPrograms;
+
+impl ProgramExecutor for NoPrograms {
+  fn execute(
+    &self,
+    _tx: &VersionedTransaction,
+    _accounts: &HashMap<Pubkey, Account>,
+  ) -> Execution {
+    Execution::failure(
+      TransactionError::ProgramAccountNotFound,
+      vec!["Program binaries are not loaded in LocalCluster".to_string()],
+    )
+  }
+}
+
+struct Bank {
+  accounts: HashMap<Pubkey, Account>,
+  executor: Box<dyn ProgramExecutor>,
+  statuses: HashMap<Signature, TransactionStatus>,
+  sent: Vec<VersionedTransaction>,
+  slot: u64,
+}
+
+/// In-process JSON-RPC endpoint backed by in-memory accounts.
+///
+/// Every landed transaction is finalized in its own slot. Subscriptions and
+/// transaction history beyond signature statuses are not served.
+pub struct LocalCluster {
+  url: String,
+  bank: Arc<Mutex<Bank>>,
+  server: JoinHandle<()>,
+}
+
+impl LocalCluster {
+  /// Starts serving `accounts` on a local port, executing transactions with
+  /// `executor`.
+  ///
+  /// The epoch and slot follow the clock sysvar among `accounts`, if any.
+  ///
+  /// # Errors
+  /// - Failed to bind a local port
+  pub async fn start(
+    accounts: impl IntoIterator<Item = (Pubkey, Account)>,
+    executor: impl ProgramExecutor + 'static,
+  ) -> Result<LocalCluster> {
+    let accounts: HashMap<Pubkey, Account> = accounts.into_iter().collect();
+    let slot = accounts
+      .get(&sysvar::clock::ID)
+      .and_then(|clock| bincode::deserialize::<Clock>(&clock.data).ok())
+      .map_or(0, |clock| clock.slot);
+    let bank = Arc::new(Mutex::new(Bank {
+      accounts,
+      executor: Box::new(executor),
+      statuses: HashMap::new(),
+      sent: Vec::new(),
+      slot,
+    }));
+    let listener = TcpListener::bind("127.0.0.1:0").await?;
+    let url = format!("http://{}", listener.local_addr()?);
+    let app = Router::new()
+      .route("/", post(handle))
+      .with_state(bank.clone());
+    let server = tokio::spawn(async move {
+      axum::serve(listener, app).await.ok();
+    });
+    Ok(LocalCluster { url, bank, server })
+  }
+
+  /// Cluster pointing clients at this endpoint.
+  #[must_use]
+  pub fn cluster(&self) -> Cluster {
+    Cluster::Custom(self.url.clone(), self.url.replacen("http", "ws", 1))
+  }
+
+  /// Current state of the account at `key`.
+  #[must_use]
+  pub fn account(&self, key: &Pubkey) -> Option<Account> {
+    lock(&self.bank).accounts.get(key).cloned()
+  }
+
+  /// Creates or replaces the account at `key`.
+  pub fn set_account(&self, key: Pubkey, account: Account) {
+    lock(&self.bank).accounts.insert(key, account);
+  }
+
+  /// Transactions received through `sendTransaction`, in order.
+  #[must_use]
+  pub fn sent(&self) -> Vec<VersionedTransaction> {
+    lock(&self.bank).sent.clone()
+  }
+}
+
+impl Drop for LocalCluster {
+  fn drop(&mut self) {
+    self.server.abort();
+  }
+}
+
+fn lock(bank: &Mutex<Bank>) -> MutexGuard<'_, Bank> {
+  bank
+    .lock()
+    .unwrap_or_else(std::sync::PoisonError::into_inner)
+}
+
+async fn handle(
+  State(bank): State<Arc<Mutex<Bank>>>,
+  Json(request): Json<Value>,
+) -> Json<Value> {
+  let id = request["id"].clone();
+  let method = request["method"].as_str().unwrap_or_default();
+  let params = &request["params"];
+  let response = match dispatch(&mut lock(&bank), method, params) {
+    Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+    Err(error) => json!({ "jsonrpc": "2.0", "id": id, "error": error }),
+  };
+  Json(response)
+}
+
+/// Serves one JSON-RPC call, or returns its error object.
+fn dispatch(
+  bank: &mut Bank,
+  method: &str,
+  params: &Value,
+) -> Result<Value, Value> {
+  let context = RpcResponseContext {
+    slot: bank.slot,
+    api_version: None,
+  };
+  let result = match method {
+    "getVersion" => to_value(RpcVersionInfo {
+      solana_core: "2.3.13".to_string(),
+      feature_set: None,
+    }),
+    "getSlot" | "getBlockHeight" => Ok(json!(bank.slot)),
+    "getEpochInfo" => epoch_info(bank),
+    "getLatestBlockhash" => to_value(Response {
+      context,
+      value: RpcBlockhash {
+        blockhash: Hash::new_unique().to_string(),
+        last_valid_block_height: bank.slot + BLOCKHASH_VALIDITY,
+      },
+    }),
+    "isBlockhashValid" => to_value(Response {
+      context,
+      value: true,
+    }),
+    "getMinimumBalanceForRentExemption" => {
+      let len = params[0].as_u64().unwrap_or_default();
+      Ok(json!(
+        Rent::default().minimum_balance(len.try_into().unwrap_or(0))
+      ))
+    }
+    "getRecentPrioritizationFees" => Ok(json!([])),
+    "getAccountInfo" => pubkey(&params[0]).map(
+      |key| json!({ "context": context, "value": ui_account(bank, &key) }),
+    ),
+    "getMultipleAccounts" => params[0]
+      .as_array()
+      .context("Expected account list")
+      .and_then(|keys| keys.iter().map(pubkey).collect::<Result<Vec<_>>>())
+      .map(|keys| {
+        let value: Vec<_> =
+          keys.iter().map(|key| ui_account(bank, key)).collect();
+        json!({ "context": context, "value": value })
+      }),
+    "getBalance" => pubkey(&params[0]).map(|key| {
+      let lamports = bank.accounts.get(&key).map_or(0, |a| a.lamports);
+      json!({ "context": context, "value": lamports })
+    }),
+    "simulateTransaction" => transaction(params).and_then(|tx| {
+      let execution = bank.executor.execute(&tx, &bank.accounts);
+      to_value(Response {
+        context,
+        value: simulation_result(execution),
+      })
+    }),
+    "sendTransaction" => return send_transaction(bank, params),
+    "getSignatureStatuses" => params[0]
+      .as_array()
+      .context("Expected signature list")
+      .and_then(|signatures| {
+        signatures
+          .iter()
+          .map(|signature| {
+            let signature: Signature =
+              signature.as_str().context("Expected signature")?.parse()?;
+            Ok(bank.statuses.get(&signature).cloned())
+          })
+          .collect::<Result<Vec<_>>>()
+      })
+      .and_then(|value| to_value(Response { context, value })),
+    "getTransaction" => Ok(Value::Null),
+    _ => {
+      return Err(json!({
+        "code": METHOD_NOT_FOUND,
+        "message": format!("Method {method} not served by LocalCluster"),
+      }))
+    }
+  };
+  result.map_err(invalid_params)
+}
+
+fn send_transaction(bank: &mut Bank, params: &Value) -> Result<Value, Value> {
+  let tx = transaction(params).map_err(invalid_params)?;
+  let signature = tx.signatures.first().copied().unwrap_or_default();
+  if bank.statuses.contains_key(&signature) {
+    return Ok(json!(signature.to_string()));
+  }
+  let execution = bank.executor.execute(&tx, &bank.accounts);
+  let skip_preflight = params[1]["skipPreflight"].as_bool().unwrap_or(false);
+  if let (Some(err), false) = (execution.err.clone(), skip_preflight) {
+    let data =
+      to_value(simulation_result(execution)).map_err(invalid_params)?;
+    return Err(json!({
+      "code": PREFLIGHT_FAILURE,
+      "message": format!("Transaction simulation failed: {err}"),
+      "data": data,
+    }));
+  }
+  bank.sent.push(tx);
+  bank.slot += 1;
+  if execution.err.is_none() {
+    bank.accounts.extend(execution.writes);
+  }
+  bank.statuses.insert(
+    signature,
+    TransactionStatus {
+      slot: bank.slot,
+      confirmations: None,
+      status: execution.err.clone().map_or(Ok(()), Err),
+      err: execution.err,
+      confirmation_status: Some(TransactionConfirmationStatus::Finalized),
+    },
+  );
+  Ok(json!(signature.to_string()))
+}
+
+fn invalid_params(err: anyhow::Error) -> Value {
+  json!({ "code": INVALID_PARAMS, "message": format!("{err:#}") })
+}
+
+fn to_value(value: impl serde::Serialize) -> Result<Value> {
+  Ok(serde_json::to_value(value)?)
+}
+
+fn pubkey(value: &Value) -> Result<Pubkey> {
+  Ok(value.as_str().context("Expected pubkey")?.parse()?)
+}
+
+fn ui_account(bank: &Bank, key: &Pubkey) -> Value {
+  bank.accounts.get(key).map_or(Value::Null, |account| {
+    json!(encode_ui_account(
+      key,
+      account,
+      UiAccountEncoding::Base64,
+      None,
+      None
+    ))
+  })
+}
+
+fn epoch_info(bank: &Bank) -> Result<Value> {
+  let clock = bank
+    .accounts
+    .get(&sysvar::clock::ID)
+    .map(|clock| bincode::deserialize::<Clock>(&clock.data))
+    .transpose()?
+    .unwrap_or_default();
+  to_value(EpochInfo {
+    epoch: clock.epoch,
+    slot_index: 0,
+    slots_in_epoch: 432_000,
+    absolute_slot: bank.slot,
+    block_height: bank.slot,
+    transaction_count: None,
+  })
+}
+
+/// Decodes the transaction in the first parameter, in the encoding given by
+/// the config in the second.
+fn transaction(params: &Value) -> Result<VersionedTransaction> {
+  let encoded = params[0].as_str().context("Expected transaction")?;
+  let bytes = match params[1]["encoding"].as_str() {
+    Some("base58") => bs58::decode(encoded).into_vec()?,
+    _ => BASE64_STANDARD.decode(encoded)?,
+  };
+  bincode::deserialize(&bytes).map_err(|err| anyhow!("{err}"))
+}
+
+fn simulation_result(execution: Execution) -> RpcSimulateTransactionResult {
+  let inner_instructions = execution
+    .inner_instructions
+    .into_iter()
+    .map(|(index, instructions)| UiInnerInstructions {
+      index,
+      instructions: instructions
+        .into_iter()
+        .map(|ix| {
+          UiInstruction::Parsed(UiParsedInstruction::PartiallyDecoded(
+            UiPartiallyDecodedInstruction {
+              program_id: ix.program_id.to_string(),
+              accounts: ix
+                .accounts
+                .iter()
+                .map(|meta| meta.pubkey.to_string())
+                .collect(),
+              data: bs58::encode(ix.data).into_string(),
+              stack_height: Some(2),
+            },
+          ))
+        })
+        .collect(),
+    })
+    .collect();
+  RpcSimulateTransactionResult {
+    err: execution.err,
+    logs: Some(execution.logs),
+    accounts: None,
+    units_consumed: Some(execution.units_consumed),
+    loaded_accounts_data_size: None,
+    return_data: execution.return_data.map(|(program_id, data)| {
+      UiTransactionReturnData {
+        program_id: program_id.to_string(),
+        data: (BASE64_STANDARD.encode(data), UiReturnDataEncoding::Base64),
+      }
+    }),
+    inner_instructions: Some(inner_instructions),
+    replacement_blockhash: None,
+  }
+}
@@ -12,7 +12,7 @@ use anchor_client::solana_sdk::hash::Hash;
 use anchor_client::solana_sdk::instruction::Instruction;
 use anchor_client::solana_sdk::message::{v0, VersionedMessage};
 use anchor_client::solana_sdk::pubkey::Pubkey;
-use anchor_client::solana_sdk::signature::Keypair;
+use anchor_client::solana_sdk::signature::{Keypair, Signature};
 use anchor_client::solana_sdk::signer::Signer;
 use anchor_client::solana_sdk::transaction::VersionedTransaction;
 use anchor_client::solana_sdk::{bs58, pubkey};
@@ -23,6 +23,7 @@ use anchor_spl::associated_token::spl_associated_token_account::instruction::cre
 use anchor_spl::token;
 use anyhow::{anyhow, bail, Context, Result};
 use fix::typenum::N9;
+use hylo_core::idl::exchange;
 use hylo_core::idl::tokens::{TokenMint, HYLOSOL, JITOSOL};
 use itertools::Itertools;
 use solana_transaction_status_client_types::{
@@ -79,6 +80,33 @@ pub fn deserialize_lookup_table(
   })
 }
 
+/// Builds a versioned transaction with placeholder signatures for every
+/// required signer, to be filled by [`crate::signer::sign_transaction`].
+///
+/// # Errors
+/// - Failed to compile message
+pub fn build_unsigned_v0_transaction(
+  VersionedTransactionData {
+    instructions,
+    lookup_tables,
+    ..
+  }: &VersionedTransactionData,
+  payer: &Pubkey,
+  recent_blockhash: Hash,
+) -> Result<VersionedTransaction> {
+  let message = v0::Message::try_compile(
+    payer,
+    instructions,
+    lookup_tables,
+    recent_blockhash,
+  )?;
+  let num_sigs = message.header.num_required_signatures.into();
+  Ok(VersionedTransaction {
+    message: VersionedMessage::V0(message),
+    signatures: vec![Signature::default(); num_sigs],
+  })
+}
+
 /// Builds a signed versioned transaction.
 ///
 /// # Errors
@@ -88,6 +116,7 @@ pub fn build_v0_transaction(
   VersionedTransactionData {
     instructions,
     lookup_tables,
+    ..
   }: &VersionedTransactionData,
   payer: &Keypair,
   additional_signers: &[&Keypair],
@@ -110,6 +139,59 @@ pub fn build_v0_transaction(
   Ok(tx)
 }
 
+/// Block of accounts for one LST registered in the LST registry table.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LstRegistryEntry {
+  pub header: Pubkey,
+  pub mint: Pubkey,
+  pub vault: Pubkey,
+  pub pool_state: Pubkey,
+}
+
+/// Splits LST registry table into its preamble and per-LST blocks.
+///
+/// # Errors
+/// - Malformed structure (preamble cannot be split at 16)
+fn split_lst_registry(
+  table: &AddressLookupTableAccount,
+) -> Result<(&[Pubkey], impl Iterator<Item = LstRegistryEntry> + '_)> {
+  let (preamble, blocks) = table
+    .addresses
+    .split_at_checked(16)
+    .ok_or_else(|| anyhow!("Malformed LST registry preamble."))?;
+  let entries =
+    blocks
+      .iter()
+      .tuples()
+      .map(|(header, mint, vault, pool_state)| LstRegistryEntry {
+        header: *header,
+        mint: *mint,
+        vault: *vault,
+        pool_state: *pool_state,
+      });
+  Ok((preamble, entries))
+}
+
+/// Whether `instruction` is the exchange's `update_lst_prices`.
+#[must_use]
+pub fn is_lst_price_update(instruction: &Instruction) -> bool {
+  instruction.program_id == exchange::ID
+    && instruction
+      .data
+      .starts_with(exchange::client::args::UpdateLstPrices::DISCRIMINATOR)
+}
+
+/// Lists every LST registered in the LST registry table.
+///
+/// # Errors
+/// - Malformed structure (preamble cannot be split at 16)
+pub fn parse_lst_registry(
+  table: &AddressLookupTableAccount,
+) -> Result<Vec<LstRegistryEntry>> {
+  let (_, entries) = split_lst_registry(table)?;
+  Ok(entries.collect())
+}
+
 /// Creates `remaining_accounts` array from LST registry table with all
 /// headers writable.
 ///
@@ -119,27 +201,20 @@ pub fn build_v0_transaction(
 pub fn build_lst_registry(
   table: AddressLookupTableAccount,
 ) -> Result<(Vec<AccountMeta>, AddressLookupTableAccount)> {
-  if let Some((preamble, blocks)) = table.addresses.split_at_checked(16) {
-    let preamble = preamble
-      .iter()
-      .map(|key| AccountMeta::new_readonly(*key, false));
-    let blocks =
-      blocks
-        .iter()
-        .tuples()
-        .flat_map(|(header, mint, vault, pool_state)| {
-          [
-            AccountMeta::new(*header, false),
-            AccountMeta::new_readonly(*mint, false),
-            AccountMeta::new_readonly(*vault, false),
-            AccountMeta::new_readonly(*pool_state, false),
-          ]
-        });
-    let remaining_accounts = preamble.chain(blocks).collect_vec();
-    Ok((remaining_accounts, table))
-  } else {
-    Err(anyhow!("Malformed LST registry preamble."))
-  }
+  let (preamble, entries) = split_lst_registry(&table)?;
+  let preamble = preamble
+    .iter()
+    .map(|key| AccountMeta::new_readonly(*key, false));
+  let blocks = entries.flat_map(|entry| {
+    [
+      AccountMeta::new(entry.header, false),
+      AccountMeta::new_readonly(entry.mint, false),
+      AccountMeta::new_readonly(entry.vault, false),
+      AccountMeta::new_readonly(entry.pool_state, false),
+    ]
+  });
+  let remaining_accounts = preamble.chain(blocks).collect_vec();
+  Ok((remaining_accounts, table))
 }
 
 /// Parses event type `E` from a simulated RPC call.
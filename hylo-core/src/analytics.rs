@@ -0,0 +1,358 @@
+use anchor_lang::prelude::*;
+use fix::prelude::*;
+
+use crate::error::AnalyticsError::{
+  EffectiveLeverage, SolPriceChange, StabilityPoolCoverage, StablecoinToRedeem,
+  ThresholdSolPrice,
+};
+use crate::error::CoreError::TargetCollateralRatioTooLow;
+use crate::exchange_context::ExchangeContext;
+use crate::exchange_math::max_swappable_stablecoin;
+use crate::solana_clock::SolanaClock;
+use crate::stability_mode::StabilityMode::{self, Depeg, Mode1, Mode2, Normal};
+
+/// SOL/USD price at which the collateral ratio equals the given threshold.
+///
+/// ```txt
+///                   threshold * stablecoin_supply
+/// threshold_price = -----------------------------
+///                            total_sol
+/// ```
+///
+/// Rounds up, so the result is the lowest price still at or above threshold.
+pub fn threshold_sol_price(
+  threshold: UFix64<N2>,
+  total_sol: UFix64<N9>,
+  stablecoin_supply: UFix64<N6>,
+) -> Result<UFix64<N8>> {
+  stablecoin_supply
+    .convert::<N8>()
+    .mul_div_ceil(threshold.convert::<N9>(), total_sol)
+    .ok_or(ThresholdSolPrice.into())
+}
+
+/// Fractional move from `current_price` to `target_price`, in either
+/// direction.
+///
+/// ```txt
+///          |target_price - current_price|
+/// change = ------------------------------
+///                  current_price
+/// ```
+pub fn sol_price_change(
+  current_price: UFix64<N8>,
+  target_price: UFix64<N8>,
+) -> Result<UFix64<N9>> {
+  current_price
+    .abs_diff(&target_price)
+    .convert::<N9>()
+    .mul_div_floor(UFix64::<N8>::one(), current_price)
+    .ok_or(SolPriceChange.into())
+}
+
+/// Amount of stablecoin to redeem at $1 NAV for the collateral ratio to reach
+/// a higher target threshold. Zero if the target is already met.
+///
+/// ```txt
+///              target_cr * stablecoin_supply - tvl
+/// to_redeem = -------------------------------------
+///                         target_cr - 1
+/// ```
+pub fn stablecoin_to_redeem(
+  target_collateral_ratio: UFix64<N2>,
+  total_value_locked: UFix64<N9>,
+  stablecoin_supply: UFix64<N6>,
+) -> Result<UFix64<N6>> {
+  if target_collateral_ratio > UFix64::one() {
+    // Rounds against the protocol, TVL down and target cap up
+    let tvl = total_value_locked.convert::<N6>();
+    let target_cap = stablecoin_supply
+      .mul_div_ceil(target_collateral_ratio, UFix64::one())
+      .ok_or(StablecoinToRedeem)?;
+    if target_cap <= tvl {
+      Ok(UFix64::zero())
+    } else {
+      let denominator =
+        target_collateral_ratio.checked_sub(&UFix64::<N2>::one());
+      target_cap
+        .checked_sub(&tvl)
+        .zip(denominator)
+        .and_then(|(n, d)| n.mul_div_ceil(UFix64::one(), d))
+        .ok_or(StablecoinToRedeem.into())
+    }
+  } else {
+    Err(TargetCollateralRatioTooLow.into())
+  }
+}
+
+/// Effective SOL exposure per dollar of levercoin market cap.
+///
+/// ```txt
+///                    total_value_locked
+/// effective_lever = --------------------
+///                     levercoin_cap
+/// ```
+pub fn levercoin_effective_leverage(
+  total_value_locked: UFix64<N9>,
+  levercoin_supply: UFix64<N6>,
+  levercoin_nav: UFix64<N9>,
+) -> Result<UFix64<N9>> {
+  levercoin_supply
+    .convert::<N9>()
+    .mul_div_floor(levercoin_nav, UFix64::one())
+    .filter(|cap| *cap > UFix64::zero())
+    .and_then(|cap| total_value_locked.mul_div_floor(UFix64::one(), cap))
+    .ok_or(EffectiveLeverage.into())
+}
+
+/// Ratio of stability pool capitalization to outstanding stablecoin.
+///
+/// ```txt
+///            stability_pool_cap
+/// coverage = -----------------
+///            stablecoin_supply
+/// ```
+pub fn stability_pool_coverage(
+  stability_pool_cap: UFix64<N6>,
+  stablecoin_supply: UFix64<N6>,
+) -> Result<UFix64<N9>> {
+  if stablecoin_supply == UFix64::zero() {
+    Ok(UFix64::new(u64::MAX))
+  } else {
+    stability_pool_cap
+      .convert::<N9>()
+      .mul_div_floor(UFix64::<N6>::one(), stablecoin_supply)
+      .ok_or(StabilityPoolCoverage.into())
+  }
+}
+
+/// Position of the protocol relative to one stability threshold.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct ThresholdDistance {
+  /// Mode entered when crossing the threshold.
+  pub stability_mode: StabilityMode,
+  /// Collateral ratio at the threshold.
+  pub collateral_ratio: UFix64<N2>,
+  /// SOL/USD price at which the threshold is crossed.
+  pub sol_usd_price: UFix64<N8>,
+  /// Fractional SOL/USD price change needed to cross the threshold.
+  pub sol_price_change: UFix64<N9>,
+  /// Stablecoin needed to cross the threshold at the current price.
+  /// For the lower threshold, the amount swappable in from levercoin.
+  /// For the higher threshold, the amount to redeem at $1, `None` when
+  /// redemption cannot restore it (`Depeg`).
+  pub stablecoin: Option<UFix64<N6>>,
+}
+
+/// Snapshot of protocol health and risk metrics.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct ProtocolHealth {
+  pub collateral_ratio: UFix64<N9>,
+  pub stability_mode: StabilityMode,
+  /// SOL/USD price at which the protocol enters `Mode1`.
+  pub mode1_sol_price: UFix64<N8>,
+  /// SOL/USD price at which the protocol enters `Mode2`.
+  pub mode2_sol_price: UFix64<N8>,
+  /// SOL/USD price at which the stablecoin depegs.
+  pub depeg_sol_price: UFix64<N8>,
+  /// Next lower threshold, `None` in `Depeg`.
+  pub next_threshold: Option<ThresholdDistance>,
+  /// Previous higher threshold, `None` in `Normal`.
+  pub prev_threshold: Option<ThresholdDistance>,
+  /// Levercoin effective leverage, `None` if levercoin has no value.
+  pub levercoin_leverage: Option<UFix64<N9>>,
+  /// Stability pool cap over stablecoin supply.
+  pub stability_pool_coverage: UFix64<N9>,
+}
+
+impl ProtocolHealth {
+  /// Computes health metrics from the exchange context and stability pool
+  /// balances.
+  pub fn new<C: SolanaClock>(
+    ctx: &ExchangeContext<C>,
+    stablecoin_in_pool: UFix64<N6>,
+    levercoin_in_pool: UFix64<N6>,
+  ) -> Result<ProtocolHealth> {
+    let controller = ctx.stability_controller;
+    let price = ctx.sol_usd_price.lower;
+    let tvl = ctx.total_value_locked()?;
+    let sol_price =
+      |t| threshold_sol_price(t, ctx.total_sol, ctx.stablecoin_supply);
+
+    let next_threshold = controller
+      .next_stability_threshold(ctx.stability_mode)
+      .map(|threshold| -> Result<ThresholdDistance> {
+        let sol_usd_price = sol_price(threshold)?;
+        Ok(ThresholdDistance {
+          stability_mode: mode_below(ctx.stability_mode),
+          collateral_ratio: threshold,
+          sol_usd_price,
+          sol_price_change: sol_price_change(price, sol_usd_price)?,
+          stablecoin: Some(max_swappable_stablecoin(
+            threshold,
+            tvl,
+            ctx.stablecoin_supply,
+          )?),
+        })
+      })
+      .transpose()?;
+
+    let prev_threshold = controller
+      .prev_stability_threshold(ctx.stability_mode)
+      .map(|threshold| -> Result<ThresholdDistance> {
+        let sol_usd_price = sol_price(threshold)?;
+        Ok(ThresholdDistance {
+          stability_mode: mode_above(ctx.stability_mode),
+          collateral_ratio: threshold,
+          sol_usd_price,
+          sol_price_change: sol_price_change(price, sol_usd_price)?,
+          stablecoin: stablecoin_to_redeem(
+            threshold,
+            tvl,
+            ctx.stablecoin_supply,
+          )
+          .ok(),
+        })
+      })
+      .transpose()?;
+
+    let levercoin_leverage = ctx
+      .levercoin_supply()
+      .and_then(|supply| {
+        levercoin_effective_leverage(tvl, supply, ctx.levercoin_redeem_nav()?)
+      })
+      .ok();
+
+    let stability_pool_coverage = stability_pool_coverage(
+      ctx.stability_pool_cap(stablecoin_in_pool, levercoin_in_pool)?,
+      ctx.stablecoin_supply,
+    )?;
+
+    Ok(ProtocolHealth {
+      collateral_ratio: ctx.collateral_ratio,
+      stability_mode: ctx.stability_mode,
+      mode1_sol_price: sol_price(controller.stability_threshold_1)?,
+      mode2_sol_price: sol_price(controller.stability_threshold_2)?,
+      depeg_sol_price: sol_price(UFix64::one())?,
+      next_threshold,
+      prev_threshold,
+      levercoin_leverage,
+      stability_pool_coverage,
+    })
+  }
+}
+
+/// Mode entered when crossing the next lower threshold.
+fn mode_below(mode: StabilityMode) -> StabilityMode {
+  match mode {
+    Normal => Mode1,
+    Mode1 => Mode2,
+    Mode2 | Depeg => Depeg,
+  }
+}
+
+/// Mode entered when crossing the previous higher threshold.
+fn mode_above(mode: StabilityMode) -> StabilityMode {
+  match mode {
+    Normal | Mode1 => Normal,
+    Mode2 => Mode1,
+    Depeg => Mode2,
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use fix::typenum::N2;
+  use proptest::prelude::*;
+
+  use super::*;
+  use crate::eq_tolerance;
+  use crate::exchange_math::{collateral_ratio, total_value_locked};
+  use crate::util::proptest::*;
+
+  fn thresholds() -> BoxedStrategy<UFix64<N2>> {
+    (101u64..300u64).prop_map(UFix64::new).boxed()
+  }
+
+  proptest! {
+    #[test]
+    fn threshold_price_hits_threshold(
+      state in protocol_state(()),
+      threshold in thresholds(),
+    ) {
+      let total_sol = state.total_sol().expect("total_sol");
+      let price =
+        threshold_sol_price(threshold, total_sol, state.stablecoin_amount)?;
+      let cr = collateral_ratio(total_sol, price, state.stablecoin_amount)?;
+      prop_assert!(cr >= threshold.convert());
+      prop_assert!(eq_tolerance!(threshold, cr, N2, UFix64::new(1)));
+    }
+
+    #[test]
+    fn redeem_restores_threshold(
+      state in protocol_state(()),
+      threshold in thresholds(),
+    ) {
+      let total_sol = state.total_sol().expect("total_sol");
+      let tvl = total_value_locked(total_sol, state.usd_sol_price)?;
+      let redeem =
+        stablecoin_to_redeem(threshold, tvl, state.stablecoin_amount);
+      if let Ok(redeem) = redeem {
+        let new_tvl = tvl.checked_sub(&redeem.convert());
+        let new_supply = state.stablecoin_amount.checked_sub(&redeem);
+        if let Some((new_tvl, new_supply)) = new_tvl.zip(new_supply) {
+          if new_supply > UFix64::zero() {
+            let new_cr = new_tvl
+              .mul_div_floor(UFix64::one(), new_supply.convert::<N9>())
+              .expect("cr");
+            prop_assert!(new_cr >= threshold.convert());
+            if redeem > UFix64::zero() {
+              prop_assert!(
+                eq_tolerance!(threshold, new_cr, N2, UFix64::new(1))
+              );
+            }
+          }
+        }
+      }
+    }
+
+    #[test]
+    fn leverage_at_least_one(state in protocol_state(())) {
+      let total_sol = state.total_sol().expect("total_sol");
+      let tvl = total_value_locked(total_sol, state.usd_sol_price)?;
+      if let Ok(leverage) = levercoin_effective_leverage(
+        tvl,
+        state.levercoin_amount,
+        state.levercoin_nav,
+      ) {
+        prop_assert!(leverage >= UFix64::one());
+      }
+    }
+  }
+
+  #[test]
+  fn price_change_simple() -> Result<()> {
+    let current = UFix64::<N8>::new(20_000_000_000);
+    let target = UFix64::<N8>::new(15_000_000_000);
+    assert_eq!(
+      UFix64::<N9>::new(250_000_000),
+      sol_price_change(current, target)?
+    );
+    assert_eq!(
+      UFix64::<N9>::new(333_333_333),
+      sol_price_change(target, current)?
+    );
+    Ok(())
+  }
+
+  #[test]
+  fn coverage_simple() -> Result<()> {
+    let cap = UFix64::<N6>::new(25_000_000);
+    let supply = UFix64::<N6>::new(100_000_000);
+    assert_eq!(
+      UFix64::<N9>::new(250_000_000),
+      stability_pool_coverage(cap, supply)?
+    );
+    Ok(())
+  }
+}
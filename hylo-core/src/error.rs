@@ -1,6 +1,9 @@
+use anchor_lang::error::ERROR_CODE_OFFSET;
 use anchor_lang::prelude::error_code;
+use num_enum::TryFromPrimitive;
 
 #[error_code]
+#[derive(TryFromPrimitive)]
 pub enum CoreError {
   // `total_sol_cache`
   #[msg("Cannot decrement TotalSolCache due to outdated epoch.")]
@@ -114,3 +117,54 @@ pub enum CoreError {
   #[msg("Arithmetic error while computing yield harvest allocation.")]
   YieldHarvestAllocation,
 }
+
+/// Errors raised only by off-chain analytics, kept out of the code range
+/// programs return [`CoreError`] from.
+#[error_code]
+pub enum AnalyticsError {
+  #[msg("Arithmetic error while computing SOL price at stability threshold.")]
+  ThresholdSolPrice = 8000,
+  #[msg("Arithmetic error while computing SOL price change.")]
+  SolPriceChange,
+  #[msg("Arithmetic error while computing stablecoin to redeem.")]
+  StablecoinToRedeem,
+  #[msg("Arithmetic error or zero cap while computing levercoin leverage.")]
+  EffectiveLeverage,
+  #[msg("Arithmetic error while computing stability pool coverage.")]
+  StabilityPoolCoverage,
+}
+
+impl CoreError {
+  /// Looks up the variant behind an on-chain custom error code, which Anchor
+  /// offsets from the discriminant by [`ERROR_CODE_OFFSET`].
+  #[must_use]
+  pub fn from_code(code: u32) -> Option<CoreError> {
+    code
+      .checked_sub(ERROR_CODE_OFFSET)
+      .and_then(|discriminant| CoreError::try_from(discriminant).ok())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn codes_round_trip() {
+    let first = u32::from(CoreError::TotalSolCacheDecrement);
+    let last = u32::from(CoreError::YieldHarvestAllocation);
+    assert!(CoreError::from_code(first - 1).is_none());
+    assert!(CoreError::from_code(last + 1).is_none());
+    for code in first..=last {
+      let error = CoreError::from_code(code).expect("contiguous codes");
+      assert_eq!(u32::from(error), code);
+    }
+  }
+
+  #[test]
+  fn analytics_errors_outside_core_range() {
+    let code = u32::from(AnalyticsError::ThresholdSolPrice);
+    assert!(CoreError::from_code(code).is_none());
+    assert!(u32::from(CoreError::YieldHarvestAllocation) < code);
+  }
+}
@@ -24,13 +24,31 @@ use crate::stability_pool_math::stability_pool_cap;
 use crate::total_sol_cache::TotalSolCache;
 
 /// Container for common values needed in an exchange transaction.
+///
+/// Deserialization goes through [`ExchangeContext::new`], so the derived
+/// collateral ratio and stability mode are recomputed rather than trusted.
 #[derive(Clone)]
+#[cfg_attr(
+  feature = "serde",
+  derive(serde::Serialize, serde::Deserialize),
+  serde(
+    try_from = "ExchangeContextInputs<C>",
+    bound(deserialize = "C: SolanaClock + serde::Deserialize<'de>")
+  )
+)]
 pub struct ExchangeContext<C> {
   pub clock: C,
+  #[cfg_attr(feature = "serde", serde(with = "crate::serde_schema::ufix64"))]
   pub total_sol: UFix64<N9>,
   pub sol_usd_price: PriceRange<N8>,
+  #[cfg_attr(feature = "serde", serde(with = "crate::serde_schema::ufix64"))]
   pub stablecoin_supply: UFix64<N6>,
+  #[cfg_attr(
+    feature = "serde",
+    serde(with = "crate::serde_schema::option_ufix64")
+  )]
   levercoin_supply: Option<UFix64<N6>>,
+  #[cfg_attr(feature = "serde", serde(with = "crate::serde_schema::ufix64"))]
   pub collateral_ratio: UFix64<N9>,
   pub stability_controller: StabilityController,
   pub stability_mode: StabilityMode,
@@ -55,8 +73,31 @@ impl<C: SolanaClock> ExchangeContext<C> {
     let total_sol = total_sol_cache.get_validated(clock.epoch())?;
     let sol_usd_price =
       query_pyth_price(&clock, sol_usd_pyth_feed, oracle_config)?;
-    let stablecoin_supply = UFix64::new(stablecoin_mint.supply);
-    let levercoin_supply = levercoin_mint.map(|m| UFix64::new(m.supply));
+    ExchangeContext::new(
+      clock,
+      total_sol,
+      sol_usd_price,
+      UFix64::new(stablecoin_mint.supply),
+      levercoin_mint.map(|m| UFix64::new(m.supply)),
+      stability_controller,
+      stablecoin_fees,
+      levercoin_fees,
+    )
+  }
+
+  /// Creates context from already loaded values, deriving collateral ratio
+  /// and stability mode.
+  #[allow(clippy::too_many_arguments)]
+  pub fn new(
+    clock: C,
+    total_sol: UFix64<N9>,
+    sol_usd_price: PriceRange<N8>,
+    stablecoin_supply: UFix64<N6>,
+    levercoin_supply: Option<UFix64<N6>>,
+    stability_controller: StabilityController,
+    stablecoin_fees: StablecoinFees,
+    levercoin_fees: LevercoinFees,
+  ) -> Result<ExchangeContext<C>> {
     let collateral_ratio =
       collateral_ratio(total_sol, sol_usd_price.lower, stablecoin_supply)?;
     let stability_mode =
@@ -75,6 +116,41 @@ impl<C: SolanaClock> ExchangeContext<C> {
     })
   }
 
+  /// Rebuilds the context over new collateral, price and token supplies,
+  /// keeping the clock, stability thresholds and fee configuration.
+  /// Useful for projecting protocol state under hypothetical scenarios.
+  pub fn reload(
+    &self,
+    total_sol: UFix64<N9>,
+    sol_usd_price: PriceRange<N8>,
+    stablecoin_supply: UFix64<N6>,
+    levercoin_supply: Option<UFix64<N6>>,
+  ) -> Result<ExchangeContext<C>>
+  where
+    C: Clone,
+  {
+    ExchangeContext::new(
+      self.clock.clone(),
+      total_sol,
+      sol_usd_price,
+      stablecoin_supply,
+      levercoin_supply,
+      self.stability_controller,
+      self.stablecoin_fees,
+      self.levercoin_fees,
+    )
+  }
+
+  #[must_use]
+  pub fn stablecoin_fees(&self) -> &StablecoinFees {
+    &self.stablecoin_fees
+  }
+
+  #[must_use]
+  pub fn levercoin_fees(&self) -> &LevercoinFees {
+    &self.levercoin_fees
+  }
+
   /// Computes TVL in USD, maintaining precision at 9 decimals.
   pub fn total_value_locked(&self) -> Result<UFix64<N9>> {
     total_value_locked(self.total_sol, self.sol_usd_price.lower)
@@ -421,3 +497,39 @@ impl<C: SolanaClock> ExchangeContext<C> {
     )
   }
 }
+
+/// Serialized inputs of [`ExchangeContext`], rebuilt through
+/// [`ExchangeContext::new`].
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+struct ExchangeContextInputs<C> {
+  clock: C,
+  #[serde(with = "crate::serde_schema::ufix64")]
+  total_sol: UFix64<N9>,
+  sol_usd_price: PriceRange<N8>,
+  #[serde(with = "crate::serde_schema::ufix64")]
+  stablecoin_supply: UFix64<N6>,
+  #[serde(with = "crate::serde_schema::option_ufix64")]
+  levercoin_supply: Option<UFix64<N6>>,
+  stability_controller: StabilityController,
+  stablecoin_fees: StablecoinFees,
+  levercoin_fees: LevercoinFees,
+}
+
+#[cfg(feature = "serde")]
+impl<C: SolanaClock> TryFrom<ExchangeContextInputs<C>> for ExchangeContext<C> {
+  type Error = Error;
+
+  fn try_from(inputs: ExchangeContextInputs<C>) -> Result<ExchangeContext<C>> {
+    ExchangeContext::new(
+      inputs.clock,
+      inputs.total_sol,
+      inputs.sol_usd_price,
+      inputs.stablecoin_supply,
+      inputs.levercoin_supply,
+      inputs.stability_controller,
+      inputs.stablecoin_fees,
+      inputs.levercoin_fees,
+    )
+  }
+}
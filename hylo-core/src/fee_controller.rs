@@ -11,8 +11,17 @@ use crate::stability_mode::StabilityMode::{self, Depeg, Mode1, Mode2, Normal};
 /// All fees must be in basis points to represent a fractional percentage
 /// directly applicable to a token amount e.g. `0.XXXX` or `bips x 10^-4`.
 #[derive(Copy, Clone, InitSpace, AnchorSerialize, AnchorDeserialize)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct FeePair {
+  #[cfg_attr(
+    feature = "serde",
+    serde(with = "crate::serde_schema::ufix_value64")
+  )]
   mint: UFixValue64,
+  #[cfg_attr(
+    feature = "serde",
+    serde(with = "crate::serde_schema::ufix_value64")
+  )]
   redeem: UFixValue64,
 }
 
@@ -76,6 +85,7 @@ impl<Exp> FeeExtract<Exp> {
 }
 
 #[derive(Copy, Clone, InitSpace, AnchorSerialize, AnchorDeserialize)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct StablecoinFees {
   normal: FeePair,
   mode_1: FeePair,
@@ -116,6 +126,7 @@ impl FeeController for StablecoinFees {
 }
 
 #[derive(Copy, Clone, InitSpace, AnchorDeserialize, AnchorSerialize)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct LevercoinFees {
   normal: FeePair,
   mode_1: FeePair,
@@ -58,6 +58,15 @@ impl From<hylo_idl::exchange::types::YieldHarvestCache> for YieldHarvestCache {
   }
 }
 
+impl From<hylo_idl::exchange::types::SlippageConfig> for SlippageConfig {
+  fn from(idl: hylo_idl::exchange::types::SlippageConfig) -> Self {
+    SlippageConfig {
+      expected_token_out: idl.expected_token_out.into(),
+      slippage_tolerance: idl.slippage_tolerance.into(),
+    }
+  }
+}
+
 impl From<SlippageConfig> for hylo_idl::exchange::types::SlippageConfig {
   fn from(val: SlippageConfig) -> Self {
     hylo_idl::exchange::types::SlippageConfig {
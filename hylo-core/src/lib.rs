@@ -1,5 +1,6 @@
 #![allow(clippy::missing_errors_doc)]
 
+pub mod analytics;
 pub mod conversion;
 pub mod error;
 pub mod exchange_context;
@@ -10,6 +11,7 @@ pub mod idl_type_bridge;
 pub mod lst_sol_price;
 pub mod lst_swap_config;
 pub mod pyth;
+pub mod serde_schema;
 pub mod slippage_config;
 pub mod solana_clock;
 pub mod stability_mode;
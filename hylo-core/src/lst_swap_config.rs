@@ -5,7 +5,9 @@ use crate::error::CoreError::InvalidFees;
 use crate::fee_controller::FeeExtract;
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct LstSwapConfig {
+  #[cfg_attr(feature = "serde", serde(with = "crate::serde_schema::ufix64"))]
   pub fee: UFix64<N4>,
 }
 
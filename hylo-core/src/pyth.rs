@@ -43,8 +43,12 @@ impl<Exp> OracleConfig<Exp> {
 /// Spread of an asset price, with a lower and upper quote.
 /// Use lower in minting, higher in redeeming.
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(bound = ""))]
 pub struct PriceRange<Exp: Integer> {
+  #[cfg_attr(feature = "serde", serde(with = "crate::serde_schema::ufix64"))]
   pub lower: UFix64<Exp>,
+  #[cfg_attr(feature = "serde", serde(with = "crate::serde_schema::ufix64"))]
   pub upper: UFix64<Exp>,
 }
 
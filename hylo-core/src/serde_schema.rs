@@ -0,0 +1,261 @@
+//! JSON representation of protocol types.
+//!
+//! Types deriving `Serialize` and `Deserialize` in this crate and in
+//! `hylo-quotes` route their fields through these modules, so the schema is
+//! the same everywhere. Beyond [`crate::slippage_config::SlippageConfig`],
+//! the derives are enabled by the `serde` feature:
+//!
+//! * Fixed point values are objects of raw bits, exponent and exact decimal,
+//!   e.g. `{"bits": "1500000", "exp": -6, "decimal": "1.5"}`. Bits are strings
+//!   so 64-bit values survive JSON number parsing. Only `bits` and `exp` are
+//!   read back, and an `exp` other than the field's fails.
+//! * Pubkeys are base58 strings.
+//! * Field names are `snake_case` and unit enums are variant name strings.
+
+use std::str::FromStr;
+
+use anchor_lang::prelude::Pubkey;
+use fix::prelude::{UFix64, UFixValue64};
+use fix::typenum::Integer;
+use serde::de::Error;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::util::decimal_string;
+
+#[derive(Serialize)]
+struct FixedOut {
+  bits: String,
+  exp: i8,
+  decimal: String,
+}
+
+#[derive(Deserialize)]
+struct FixedIn {
+  bits: String,
+  exp: i8,
+}
+
+impl From<UFixValue64> for FixedOut {
+  fn from(value: UFixValue64) -> FixedOut {
+    FixedOut {
+      bits: value.bits.to_string(),
+      exp: value.exp,
+      decimal: decimal_string(value),
+    }
+  }
+}
+
+impl FixedIn {
+  fn value<E: Error>(self) -> Result<UFixValue64, E> {
+    let bits = self.bits.parse().map_err(|_| {
+      E::custom(format!("invalid fixed point bits {}", self.bits))
+    })?;
+    Ok(UFixValue64::new(bits, self.exp))
+  }
+}
+
+/// [`UFixValue64`] with its exponent.
+pub mod ufix_value64 {
+  use super::*;
+
+  pub fn serialize<S: Serializer>(
+    value: &UFixValue64,
+    serializer: S,
+  ) -> Result<S::Ok, S::Error> {
+    FixedOut::from(*value).serialize(serializer)
+  }
+
+  pub fn deserialize<'de, D: Deserializer<'de>>(
+    deserializer: D,
+  ) -> Result<UFixValue64, D::Error> {
+    FixedIn::deserialize(deserializer)?.value()
+  }
+}
+
+/// [`UFix64`] whose exponent must match the type's.
+pub mod ufix64 {
+  use super::*;
+
+  pub fn serialize<Exp: Integer, S: Serializer>(
+    value: &UFix64<Exp>,
+    serializer: S,
+  ) -> Result<S::Ok, S::Error> {
+    FixedOut::from(UFixValue64::from(*value)).serialize(serializer)
+  }
+
+  pub fn deserialize<'de, Exp: Integer, D: Deserializer<'de>>(
+    deserializer: D,
+  ) -> Result<UFix64<Exp>, D::Error> {
+    let value: UFixValue64 = FixedIn::deserialize(deserializer)?.value()?;
+    UFix64::try_from(value).map_err(|_| {
+      D::Error::custom(format!(
+        "expected exponent {}, found {}",
+        Exp::to_i8(),
+        value.exp
+      ))
+    })
+  }
+}
+
+/// Optional [`UFix64`], `null` when absent.
+pub mod option_ufix64 {
+  use super::*;
+
+  #[derive(Serialize, Deserialize)]
+  #[serde(bound = "")]
+  struct Wrapper<Exp: Integer>(#[serde(with = "super::ufix64")] UFix64<Exp>);
+
+  pub fn serialize<Exp: Integer, S: Serializer>(
+    value: &Option<UFix64<Exp>>,
+    serializer: S,
+  ) -> Result<S::Ok, S::Error> {
+    value.map(Wrapper).serialize(serializer)
+  }
+
+  pub fn deserialize<'de, Exp: Integer, D: Deserializer<'de>>(
+    deserializer: D,
+  ) -> Result<Option<UFix64<Exp>>, D::Error> {
+    let value = Option::<Wrapper<Exp>>::deserialize(deserializer)?;
+    Ok(value.map(|Wrapper(value)| value))
+  }
+}
+
+/// [`Pubkey`] as a base58 string.
+pub mod pubkey {
+  use super::*;
+
+  pub fn serialize<S: Serializer>(
+    pubkey: &Pubkey,
+    serializer: S,
+  ) -> Result<S::Ok, S::Error> {
+    serializer.collect_str(pubkey)
+  }
+
+  pub fn deserialize<'de, D: Deserializer<'de>>(
+    deserializer: D,
+  ) -> Result<Pubkey, D::Error> {
+    let s = String::deserialize(deserializer)?;
+    Pubkey::from_str(&s)
+      .map_err(|_| D::Error::custom(format!("invalid pubkey {s}")))
+  }
+}
+
+/// Optional [`Pubkey`], `null` when absent.
+pub mod option_pubkey {
+  use super::*;
+
+  #[derive(Serialize, Deserialize)]
+  struct Wrapper(#[serde(with = "super::pubkey")] Pubkey);
+
+  pub fn serialize<S: Serializer>(
+    pubkey: &Option<Pubkey>,
+    serializer: S,
+  ) -> Result<S::Ok, S::Error> {
+    pubkey.map(Wrapper).serialize(serializer)
+  }
+
+  pub fn deserialize<'de, D: Deserializer<'de>>(
+    deserializer: D,
+  ) -> Result<Option<Pubkey>, D::Error> {
+    let pubkey = Option::<Wrapper>::deserialize(deserializer)?;
+    Ok(pubkey.map(|Wrapper(pubkey)| pubkey))
+  }
+}
+
+/// List of [`Pubkey`] as base58 strings.
+pub mod pubkeys {
+  use super::*;
+
+  #[derive(Serialize, Deserialize)]
+  struct Wrapper(#[serde(with = "super::pubkey")] Pubkey);
+
+  pub fn serialize<S: Serializer>(
+    pubkeys: &[Pubkey],
+    serializer: S,
+  ) -> Result<S::Ok, S::Error> {
+    serializer.collect_seq(pubkeys.iter().copied().map(Wrapper))
+  }
+
+  pub fn deserialize<'de, D: Deserializer<'de>>(
+    deserializer: D,
+  ) -> Result<Vec<Pubkey>, D::Error> {
+    let pubkeys = Vec::<Wrapper>::deserialize(deserializer)?;
+    Ok(pubkeys.into_iter().map(|Wrapper(pubkey)| pubkey).collect())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use fix::prelude::*;
+  use serde_json::json;
+
+  use super::*;
+  use crate::slippage_config::SlippageConfig;
+
+  #[derive(Debug, PartialEq, Serialize, Deserialize)]
+  struct Fields {
+    #[serde(with = "ufix64")]
+    amount: UFix64<N6>,
+    #[serde(with = "option_ufix64")]
+    fee: Option<UFix64<N4>>,
+    #[serde(with = "ufix_value64")]
+    value: UFixValue64,
+    #[serde(with = "pubkey")]
+    mint: Pubkey,
+  }
+
+  #[test]
+  fn round_trips_schema() -> anyhow::Result<()> {
+    let fields = Fields {
+      amount: UFix64::new(1_500_000),
+      fee: None,
+      value: UFixValue64::new(u64::MAX, -9),
+      mint: Pubkey::new_unique(),
+    };
+    let value = serde_json::to_value(&fields)?;
+    assert_eq!(
+      value,
+      json!({
+        "amount": { "bits": "1500000", "exp": -6, "decimal": "1.5" },
+        "fee": null,
+        "value": {
+          "bits": "18446744073709551615",
+          "exp": -9,
+          "decimal": "18446744073.709551615",
+        },
+        "mint": fields.mint.to_string(),
+      })
+    );
+    assert_eq!(serde_json::from_value::<Fields>(value)?, fields);
+    Ok(())
+  }
+
+  #[test]
+  fn rejects_mismatched_exponent() {
+    let value = json!({
+      "amount": { "bits": "15", "exp": -1 },
+      "fee": { "bits": "5", "exp": -4 },
+      "value": { "bits": "1", "exp": 0 },
+      "mint": Pubkey::default().to_string(),
+    });
+    let err = serde_json::from_value::<Fields>(value).unwrap_err();
+    assert!(err.to_string().contains("expected exponent -6, found -1"));
+  }
+
+  #[test]
+  fn slippage_config_follows_schema() -> anyhow::Result<()> {
+    let config =
+      SlippageConfig::new(UFix64::<N6>::new(1_500_000), UFix64::<N4>::new(50));
+    let value = serde_json::to_value(&config)?;
+    assert_eq!(
+      value,
+      json!({
+        "expected_token_out": { "bits": "1500000", "exp": -6, "decimal": "1.5" },
+        "slippage_tolerance": { "bits": "50", "exp": -4, "decimal": "0.005" },
+      })
+    );
+    let config = serde_json::from_value::<SlippageConfig>(value)?;
+    assert_eq!(config.min_token_out::<N6>()?, UFix64::new(1_492_500));
+    Ok(())
+  }
+}
@@ -8,7 +8,9 @@ use crate::error::CoreError::{SlippageArithmetic, SlippageExceeded};
 /// Client specified slippage tolerance paired with expected token amount.
 #[derive(Debug, AnchorSerialize, AnchorDeserialize, Serialize, Deserialize)]
 pub struct SlippageConfig {
+  #[serde(with = "crate::serde_schema::ufix_value64")]
   pub expected_token_out: UFixValue64,
+  #[serde(with = "crate::serde_schema::ufix_value64")]
   pub slippage_tolerance: UFixValue64,
 }
 
@@ -32,19 +34,23 @@ impl SlippageConfig {
     self.slippage_tolerance.try_into()
   }
 
-  /// Checks token amount against the configured lowest tolerable amount
-  pub fn validate_token_out<Exp: Integer>(
-    &self,
-    token_out: UFix64<Exp>,
-  ) -> Result<()> {
+  /// Lowest tolerable token amount, the expected amount less tolerance
+  pub fn min_token_out<Exp: Integer>(&self) -> Result<UFix64<Exp>> {
     let expected = self.expected_token_out()?;
     let tolerance = self.slippage_tolerance()?;
     // Invert slippage and multiply with expected amount
-    let tolerable_amount = UFix64::<N4>::one()
+    UFix64::<N4>::one()
       .checked_sub(&tolerance)
       .and_then(|factor| expected.mul_div_floor(factor, UFix64::one()))
-      .ok_or(SlippageArithmetic)?;
-    if token_out >= tolerable_amount {
+      .ok_or(SlippageArithmetic.into())
+  }
+
+  /// Checks token amount against the configured lowest tolerable amount
+  pub fn validate_token_out<Exp: Integer>(
+    &self,
+    token_out: UFix64<Exp>,
+  ) -> Result<()> {
+    if token_out >= self.min_token_out()? {
       Ok(())
     } else {
       Err(SlippageExceeded.into())
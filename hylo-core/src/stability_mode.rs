@@ -11,6 +11,7 @@ use crate::stability_mode::StabilityMode::{Depeg, Mode1, Mode2, Normal};
 #[derive(
   Copy, Clone, Debug, AnchorSerialize, AnchorDeserialize, PartialEq, PartialOrd,
 )]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum StabilityMode {
   Normal,
   Mode1,
@@ -30,8 +31,11 @@ impl Display for StabilityMode {
 }
 
 #[derive(Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct StabilityController {
+  #[cfg_attr(feature = "serde", serde(with = "crate::serde_schema::ufix64"))]
   pub stability_threshold_1: UFix64<N2>,
+  #[cfg_attr(feature = "serde", serde(with = "crate::serde_schema::ufix64"))]
   pub stability_threshold_2: UFix64<N2>,
 }
 
@@ -79,6 +79,20 @@ impl TotalSolCache {
   }
 }
 
+/// Whether total SOL or any LST price was cached before `current_epoch`.
+/// Exchange instructions reading prices fail until `update_lst_prices` runs.
+#[must_use]
+pub fn prices_outdated(
+  total_sol_epoch: u64,
+  lst_price_epochs: impl IntoIterator<Item = u64>,
+  current_epoch: u64,
+) -> bool {
+  total_sol_epoch < current_epoch
+    || lst_price_epochs
+      .into_iter()
+      .any(|epoch| epoch < current_epoch)
+}
+
 #[cfg(test)]
 mod tests {
   use super::*;
@@ -112,6 +126,15 @@ mod tests {
     assert!(dec.is_err_and(|e| e == TotalSolCacheDecrement.into()));
   }
 
+  #[test]
+  fn outdated_prices() {
+    let next = CURRENT_EPOCH + 1;
+    assert!(!prices_outdated(next, [next, next], next));
+    assert!(prices_outdated(CURRENT_EPOCH, [next], next));
+    assert!(prices_outdated(next, [next, CURRENT_EPOCH], next));
+    assert!(!prices_outdated(next, [], next));
+  }
+
   #[test]
   fn overflow_underflow_err() -> Result<()> {
     let mut cache = TotalSolCache::new(CURRENT_EPOCH);
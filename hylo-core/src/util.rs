@@ -1,3 +1,5 @@
+use fix::prelude::UFixValue64;
+
 #[macro_export]
 macro_rules! eq_tolerance {
   ($l:expr, $r:expr, $place:ty, $tol:expr) => {{
@@ -6,6 +8,28 @@ macro_rules! eq_tolerance {
   }};
 }
 
+/// Exact decimal rendering of a fixed point value, without trailing zeros.
+#[must_use]
+pub fn decimal_string(value: impl Into<UFixValue64>) -> String {
+  let UFixValue64 { bits, exp } = value.into();
+  let digits = bits.to_string();
+  if bits == 0 {
+    return digits;
+  }
+  let scale = usize::from(exp.unsigned_abs());
+  if exp >= 0 {
+    return digits + &"0".repeat(scale);
+  }
+  let digits = format!("{digits:0>width$}", width = scale + 1);
+  let (int, frac) = digits.split_at(digits.len() - scale);
+  let frac = frac.trim_end_matches('0');
+  if frac.is_empty() {
+    int.to_string()
+  } else {
+    format!("{int}.{frac}")
+  }
+}
+
 #[cfg(test)]
 pub mod proptest {
   use fix::prelude::*;
@@ -153,9 +177,23 @@ mod tests {
   use fix::aliases::si::{Micro, Nano};
   use fix::prelude::*;
 
+  use super::decimal_string;
   use crate::error::CoreError::SlippageExceeded;
   use crate::slippage_config::SlippageConfig;
 
+  #[test]
+  fn renders_exact_decimals() {
+    assert_eq!(decimal_string(UFixValue64::new(1_500_000, -6)), "1.5");
+    assert_eq!(decimal_string(UFixValue64::new(25, -4)), "0.0025");
+    assert_eq!(decimal_string(UFixValue64::new(2_000, -3)), "2");
+    assert_eq!(decimal_string(UFixValue64::new(0, -9)), "0");
+    assert_eq!(decimal_string(UFixValue64::new(3, 2)), "300");
+    assert_eq!(
+      decimal_string(UFixValue64::new(u64::MAX, -9)),
+      "18446744073.709551615"
+    );
+  }
+
   #[test]
   fn one_nano() {
     let one = Nano::<u64>::one();
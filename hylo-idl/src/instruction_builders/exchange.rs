@@ -10,7 +10,28 @@ use crate::exchange::account_builders;
 use crate::exchange::client::{accounts, args};
 use crate::pda::{self, metadata};
 use crate::tokens::{TokenMint, HYUSD, XSOL};
-use crate::{exchange, stability_pool};
+use crate::{ata, exchange, stability_pool};
+
+/// Points the `sol_usd_pyth_feed` account of an exchange or stability pool
+/// instruction at `feed` instead of [`pda::SOL_USD_PYTH_FEED`], e.g. at a
+/// `PriceUpdateV2` account posted earlier in the same bundle. Instructions of
+/// other programs are returned unchanged.
+#[must_use]
+pub fn with_sol_usd_feed(
+  mut instruction: Instruction,
+  feed: Pubkey,
+) -> Instruction {
+  if instruction.program_id == exchange::ID
+    || instruction.program_id == stability_pool::ID
+  {
+    instruction
+      .accounts
+      .iter_mut()
+      .filter(|meta| meta.pubkey == pda::SOL_USD_PYTH_FEED)
+      .for_each(|meta| meta.pubkey = feed);
+  }
+  instruction
+}
 
 #[must_use]
 pub fn mint_stablecoin(
@@ -369,3 +390,161 @@ pub fn update_lst_swap_fee(
     data: args.data(),
   }
 }
+
+#[must_use]
+pub fn update_admin(
+  payer: Pubkey,
+  upgrade_authority: Pubkey,
+  args: &args::UpdateAdmin,
+) -> Instruction {
+  let accounts = accounts::UpdateAdmin {
+    payer,
+    upgrade_authority,
+    hylo: *pda::HYLO,
+    program_data: *pda::EXCHANGE_PROGRAM_DATA,
+    hylo_exchange: exchange::ID,
+    event_authority: *pda::EXCHANGE_EVENT_AUTH,
+    program: exchange::ID,
+  };
+  Instruction {
+    program_id: exchange::ID,
+    accounts: accounts.to_account_metas(None),
+    data: args.data(),
+  }
+}
+
+#[must_use]
+pub fn update_levercoin_fees(
+  admin: Pubkey,
+  args: &args::UpdateLevercoinFees,
+) -> Instruction {
+  let accounts = accounts::UpdateLevercoinFees {
+    admin,
+    hylo: *pda::HYLO,
+    event_authority: *pda::EXCHANGE_EVENT_AUTH,
+    program: exchange::ID,
+  };
+  Instruction {
+    program_id: exchange::ID,
+    accounts: accounts.to_account_metas(None),
+    data: args.data(),
+  }
+}
+
+#[must_use]
+pub fn update_stablecoin_fees(
+  admin: Pubkey,
+  args: &args::UpdateStablecoinFees,
+) -> Instruction {
+  let accounts = accounts::UpdateStablecoinFees {
+    admin,
+    hylo: *pda::HYLO,
+    event_authority: *pda::EXCHANGE_EVENT_AUTH,
+    program: exchange::ID,
+  };
+  Instruction {
+    program_id: exchange::ID,
+    accounts: accounts.to_account_metas(None),
+    data: args.data(),
+  }
+}
+
+#[must_use]
+pub fn update_stability_thresholds(
+  admin: Pubkey,
+  args: &args::UpdateStabilityThresholds,
+) -> Instruction {
+  let accounts = accounts::UpdateStabilityThresholds {
+    admin,
+    hylo: *pda::HYLO,
+    event_authority: *pda::EXCHANGE_EVENT_AUTH,
+    program: exchange::ID,
+  };
+  Instruction {
+    program_id: exchange::ID,
+    accounts: accounts.to_account_metas(None),
+    data: args.data(),
+  }
+}
+
+#[must_use]
+pub fn update_treasury(
+  admin: Pubkey,
+  args: &args::UpdateTreasury,
+) -> Instruction {
+  let accounts = accounts::UpdateTreasury {
+    admin,
+    hylo: *pda::HYLO,
+    event_authority: *pda::EXCHANGE_EVENT_AUTH,
+    program: exchange::ID,
+  };
+  Instruction {
+    program_id: exchange::ID,
+    accounts: accounts.to_account_metas(None),
+    data: args.data(),
+  }
+}
+
+#[must_use]
+pub fn update_yield_harvest_config(
+  admin: Pubkey,
+  args: &args::UpdateYieldHarvestConfig,
+) -> Instruction {
+  let accounts = accounts::UpdateYieldHarvestConfig {
+    admin,
+    hylo: *pda::HYLO,
+    event_authority: *pda::EXCHANGE_EVENT_AUTH,
+    program: exchange::ID,
+  };
+  Instruction {
+    program_id: exchange::ID,
+    accounts: accounts.to_account_metas(None),
+    data: args.data(),
+  }
+}
+
+#[must_use]
+pub fn update_oracle_interval(
+  admin: Pubkey,
+  args: &args::UpdateOracleInterval,
+) -> Instruction {
+  let accounts = accounts::UpdateOracleInterval {
+    admin,
+    hylo: *pda::HYLO,
+    event_authority: *pda::EXCHANGE_EVENT_AUTH,
+    program: exchange::ID,
+  };
+  Instruction {
+    program_id: exchange::ID,
+    accounts: accounts.to_account_metas(None),
+    data: args.data(),
+  }
+}
+
+#[must_use]
+pub fn withdraw_fees(
+  payer: Pubkey,
+  treasury: Pubkey,
+  fee_token_mint: Pubkey,
+) -> Instruction {
+  let accounts = accounts::WithdrawFees {
+    payer,
+    treasury,
+    hylo: *pda::HYLO,
+    fee_auth: pda::fee_auth(fee_token_mint),
+    fee_vault: pda::fee_vault(fee_token_mint),
+    treasury_ata: ata!(treasury, fee_token_mint),
+    fee_token_mint,
+    associated_token_program: associated_token::ID,
+    token_program: token::ID,
+    system_program: system_program::ID,
+    event_authority: *pda::EXCHANGE_EVENT_AUTH,
+    program: exchange::ID,
+  };
+  let args = args::WithdrawFees {};
+  Instruction {
+    program_id: exchange::ID,
+    accounts: accounts.to_account_metas(None),
+    data: args.data(),
+  }
+}
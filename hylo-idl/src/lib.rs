@@ -14,12 +14,18 @@ pub mod exchange {
   pub use super::account_builders::exchange as account_builders;
   pub use super::codegen::hylo_exchange::*;
   pub use super::instruction_builders::exchange as instruction_builders;
+
+  /// Raw Anchor IDL, e.g. for decoding error codes.
+  pub const IDL_JSON: &str = include_str!("../idls/hylo_exchange.json");
 }
 
 pub mod stability_pool {
   pub use super::account_builders::stability_pool as account_builders;
   pub use super::codegen::hylo_stability_pool::*;
   pub use super::instruction_builders::stability_pool as instruction_builders;
+
+  /// Raw Anchor IDL, e.g. for decoding error codes.
+  pub const IDL_JSON: &str = include_str!("../idls/hylo_stability_pool.json");
 }
 
 pub mod pda;
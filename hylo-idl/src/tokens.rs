@@ -70,3 +70,30 @@ impl TokenMint for HYLOSOL {
 }
 
 try_from_pubkey!(HYLOSOL);
+
+/// Mint and ticker shown to users of every token known to this SDK.
+const TOKENS: [(Pubkey, &str); 5] = [
+  (HYUSD::MINT, "hyUSD"),
+  (SHYUSD::MINT, "sHYUSD"),
+  (XSOL::MINT, "xSOL"),
+  (JITOSOL::MINT, "JitoSOL"),
+  (HYLOSOL::MINT, "hyloSOL"),
+];
+
+/// Symbol of a known token mint.
+#[must_use]
+pub fn symbol(mint: &Pubkey) -> Option<&'static str> {
+  TOKENS
+    .iter()
+    .find(|(token, _)| token == mint)
+    .map(|(_, symbol)| *symbol)
+}
+
+/// Mint of a known token by case-insensitive symbol.
+#[must_use]
+pub fn mint_from_symbol(symbol: &str) -> Option<Pubkey> {
+  TOKENS
+    .iter()
+    .find(|(_, token)| token.eq_ignore_ascii_case(symbol))
+    .map(|(mint, _)| *mint)
+}
@@ -53,3 +53,12 @@ impl From<UFixValue64> for crate::exchange::types::UFixValue64 {
     }
   }
 }
+
+impl From<UFixValue64> for crate::stability_pool::types::UFixValue64 {
+  fn from(idl: UFixValue64) -> Self {
+    crate::stability_pool::types::UFixValue64 {
+      bits: idl.bits,
+      exp: idl.exp,
+    }
+  }
+}
@@ -1,8 +1,10 @@
+use std::collections::BTreeMap;
 use std::marker::PhantomData;
 
 use anchor_lang::prelude::Pubkey;
+use anchor_lang::AccountDeserialize;
 use anchor_spl::token::{Mint, TokenAccount};
-use anyhow::{anyhow, Context, Result};
+use anyhow::{anyhow, ensure, Context, Result};
 use hylo_core::idl::exchange::accounts::{Hylo, LstHeader};
 use hylo_core::idl::stability_pool::accounts::PoolConfig;
 use hylo_core::idl::tokens::{
@@ -12,13 +14,15 @@ use hylo_core::idl::{exchange, pda, stability_pool};
 use hylo_core::pyth::SOL_USD_PYTH_FEED;
 use hylo_jupiter_amm_interface::{
   AccountMap, Amm, AmmContext, ClockRef, KeyedAccount, Quote, QuoteParams,
-  SwapAndAccountMetas, SwapParams,
+  SwapAndAccountMetas, SwapMode, SwapParams,
 };
 use hylo_quotes::protocol_state::ProtocolState;
 use pyth_solana_receiver_sdk::price_update::PriceUpdateV2;
 
 use crate::account_metas;
-use crate::util::{account_map_get, quote, validate_swap_params};
+use crate::util::{
+  account_map_get, quote, runtime_quote, validate_swap_params,
+};
 
 /// Bidirectional single-pair Jupiter AMM client.
 pub struct HyloJupiterPair<IN, OUT>
@@ -47,7 +51,7 @@ pub trait PairConfig<IN: TokenMint, OUT: TokenMint> {
   fn label() -> &'static str;
   fn key() -> Pubkey;
 
-  /// Generate a quote for the given pair.
+  /// Generate a quote for the given pair and swap mode.
   ///
   /// # Errors
   /// * Unsupported pair
@@ -57,6 +61,7 @@ pub trait PairConfig<IN: TokenMint, OUT: TokenMint> {
     amount: u64,
     input_mint: Pubkey,
     output_mint: Pubkey,
+    swap_mode: SwapMode,
   ) -> Result<Quote>;
 
   /// Return related accounts for one direction of the pair.
@@ -86,10 +91,15 @@ impl PairConfig<JITOSOL, HYUSD> for HyloJupiterPair<JITOSOL, HYUSD> {
     amount: u64,
     input_mint: Pubkey,
     output_mint: Pubkey,
+    swap_mode: SwapMode,
   ) -> Result<Quote> {
     match (input_mint, output_mint) {
-      (JITOSOL::MINT, HYUSD::MINT) => quote::<JITOSOL, HYUSD>(state, amount),
-      (HYUSD::MINT, JITOSOL::MINT) => quote::<HYUSD, JITOSOL>(state, amount),
+      (JITOSOL::MINT, HYUSD::MINT) => {
+        quote::<JITOSOL, HYUSD>(state, amount, swap_mode)
+      }
+      (HYUSD::MINT, JITOSOL::MINT) => {
+        quote::<HYUSD, JITOSOL>(state, amount, swap_mode)
+      }
       _ => Err(anyhow!("Invalid mint pair")),
     }
   }
@@ -127,10 +137,15 @@ impl PairConfig<HYLOSOL, HYUSD> for HyloJupiterPair<HYLOSOL, HYUSD> {
     amount: u64,
     input_mint: Pubkey,
     output_mint: Pubkey,
+    swap_mode: SwapMode,
   ) -> Result<Quote> {
     match (input_mint, output_mint) {
-      (HYLOSOL::MINT, HYUSD::MINT) => quote::<HYLOSOL, HYUSD>(state, amount),
-      (HYUSD::MINT, HYLOSOL::MINT) => quote::<HYUSD, HYLOSOL>(state, amount),
+      (HYLOSOL::MINT, HYUSD::MINT) => {
+        quote::<HYLOSOL, HYUSD>(state, amount, swap_mode)
+      }
+      (HYUSD::MINT, HYLOSOL::MINT) => {
+        quote::<HYUSD, HYLOSOL>(state, amount, swap_mode)
+      }
       _ => Err(anyhow!("Invalid mint pair")),
     }
   }
@@ -168,10 +183,15 @@ impl PairConfig<JITOSOL, XSOL> for HyloJupiterPair<JITOSOL, XSOL> {
     amount: u64,
     input_mint: Pubkey,
     output_mint: Pubkey,
+    swap_mode: SwapMode,
   ) -> Result<Quote> {
     match (input_mint, output_mint) {
-      (JITOSOL::MINT, XSOL::MINT) => quote::<JITOSOL, XSOL>(state, amount),
-      (XSOL::MINT, JITOSOL::MINT) => quote::<XSOL, JITOSOL>(state, amount),
+      (JITOSOL::MINT, XSOL::MINT) => {
+        quote::<JITOSOL, XSOL>(state, amount, swap_mode)
+      }
+      (XSOL::MINT, JITOSOL::MINT) => {
+        quote::<XSOL, JITOSOL>(state, amount, swap_mode)
+      }
       _ => Err(anyhow!("Invalid mint pair")),
     }
   }
@@ -209,10 +229,15 @@ impl PairConfig<HYLOSOL, XSOL> for HyloJupiterPair<HYLOSOL, XSOL> {
     amount: u64,
     input_mint: Pubkey,
     output_mint: Pubkey,
+    swap_mode: SwapMode,
   ) -> Result<Quote> {
     match (input_mint, output_mint) {
-      (HYLOSOL::MINT, XSOL::MINT) => quote::<HYLOSOL, XSOL>(state, amount),
-      (XSOL::MINT, HYLOSOL::MINT) => quote::<XSOL, HYLOSOL>(state, amount),
+      (HYLOSOL::MINT, XSOL::MINT) => {
+        quote::<HYLOSOL, XSOL>(state, amount, swap_mode)
+      }
+      (XSOL::MINT, HYLOSOL::MINT) => {
+        quote::<XSOL, HYLOSOL>(state, amount, swap_mode)
+      }
       _ => Err(anyhow!("Invalid mint pair")),
     }
   }
@@ -250,10 +275,15 @@ impl PairConfig<HYUSD, XSOL> for HyloJupiterPair<HYUSD, XSOL> {
     amount: u64,
     input_mint: Pubkey,
     output_mint: Pubkey,
+    swap_mode: SwapMode,
   ) -> Result<Quote> {
     match (input_mint, output_mint) {
-      (HYUSD::MINT, XSOL::MINT) => quote::<HYUSD, XSOL>(state, amount),
-      (XSOL::MINT, HYUSD::MINT) => quote::<XSOL, HYUSD>(state, amount),
+      (HYUSD::MINT, XSOL::MINT) => {
+        quote::<HYUSD, XSOL>(state, amount, swap_mode)
+      }
+      (XSOL::MINT, HYUSD::MINT) => {
+        quote::<XSOL, HYUSD>(state, amount, swap_mode)
+      }
       _ => Err(anyhow!("Invalid mint pair")),
     }
   }
@@ -291,10 +321,15 @@ impl PairConfig<HYUSD, SHYUSD> for HyloJupiterPair<HYUSD, SHYUSD> {
     amount: u64,
     input_mint: Pubkey,
     output_mint: Pubkey,
+    swap_mode: SwapMode,
   ) -> Result<Quote> {
     match (input_mint, output_mint) {
-      (HYUSD::MINT, SHYUSD::MINT) => quote::<HYUSD, SHYUSD>(state, amount),
-      (SHYUSD::MINT, HYUSD::MINT) => quote::<SHYUSD, HYUSD>(state, amount),
+      (HYUSD::MINT, SHYUSD::MINT) => {
+        quote::<HYUSD, SHYUSD>(state, amount, swap_mode)
+      }
+      (SHYUSD::MINT, HYUSD::MINT) => {
+        quote::<SHYUSD, HYUSD>(state, amount, swap_mode)
+      }
       _ => Err(anyhow!("Invalid mint pair")),
     }
   }
@@ -353,50 +388,14 @@ where
   }
 
   fn get_accounts_to_update(&self) -> Vec<Pubkey> {
-    vec![
-      *pda::HYLO,
-      HYUSD::MINT,
-      XSOL::MINT,
-      pda::lst_header(JITOSOL::MINT),
-      pda::lst_header(HYLOSOL::MINT),
-      SOL_USD_PYTH_FEED,
-      SHYUSD::MINT,
-      *pda::HYUSD_POOL,
-      *pda::XSOL_POOL,
-      *pda::POOL_CONFIG,
-    ]
+    accounts_to_update(&[JITOSOL::MINT, HYLOSOL::MINT])
   }
 
   fn update(&mut self, account_map: &AccountMap) -> Result<()> {
-    let hylo: Hylo = account_map_get(account_map, &pda::HYLO)?;
-    let hyusd_mint: Mint = account_map_get(account_map, &HYUSD::MINT)?;
-    let xsol_mint: Mint = account_map_get(account_map, &XSOL::MINT)?;
-    let jitosol_header: LstHeader =
-      account_map_get(account_map, &pda::lst_header(JITOSOL::MINT))?;
-    let hylosol_header: LstHeader =
-      account_map_get(account_map, &pda::lst_header(HYLOSOL::MINT))?;
-    let sol_usd: PriceUpdateV2 =
-      account_map_get(account_map, &SOL_USD_PYTH_FEED)?;
-    let shyusd_mint: Mint = account_map_get(account_map, &SHYUSD::MINT)?;
-    let hyusd_pool: TokenAccount =
-      account_map_get(account_map, &pda::HYUSD_POOL)?;
-    let xsol_pool: TokenAccount =
-      account_map_get(account_map, &pda::XSOL_POOL)?;
-    let pool_config: PoolConfig =
-      account_map_get(account_map, &pda::POOL_CONFIG)?;
-
-    self.state = Some(ProtocolState::build(
+    self.state = Some(load_state(
       self.clock.clone(),
-      &hylo,
-      jitosol_header,
-      hylosol_header,
-      hyusd_mint,
-      xsol_mint,
-      shyusd_mint,
-      pool_config,
-      hyusd_pool,
-      xsol_pool,
-      &sol_usd,
+      account_map,
+      &[JITOSOL::MINT, HYLOSOL::MINT],
     )?);
     Ok(())
   }
@@ -408,9 +407,16 @@ where
       params.amount,
       params.input_mint,
       params.output_mint,
+      params.swap_mode,
     )
   }
 
+  /// Hylo instructions take an exact input amount, so swaps are exact-in
+  /// only. `ExactOut` quotes remain available through [`Amm::quote`].
+  fn supports_exact_out(&self) -> bool {
+    false
+  }
+
   fn get_swap_and_account_metas(
     &self,
     p: &SwapParams,
@@ -433,10 +439,200 @@ where
   }
 }
 
+/// Accounts backing [`ProtocolState`] with headers for `lst_mints`.
+fn accounts_to_update(lst_mints: &[Pubkey]) -> Vec<Pubkey> {
+  let mut accounts = vec![
+    *pda::HYLO,
+    HYUSD::MINT,
+    XSOL::MINT,
+    SOL_USD_PYTH_FEED,
+    SHYUSD::MINT,
+    *pda::HYUSD_POOL,
+    *pda::XSOL_POOL,
+    *pda::POOL_CONFIG,
+  ];
+  accounts.extend(lst_mints.iter().map(|mint| pda::lst_header(*mint)));
+  accounts
+}
+
+/// Builds [`ProtocolState`] from accounts listed by [`accounts_to_update`].
+///
+/// # Errors
+/// * Missing account
+/// * Deserialization
+fn load_state(
+  clock: ClockRef,
+  account_map: &AccountMap,
+  lst_mints: &[Pubkey],
+) -> Result<ProtocolState<ClockRef>> {
+  let hylo: Hylo = account_map_get(account_map, &pda::HYLO)?;
+  let hyusd_mint: Mint = account_map_get(account_map, &HYUSD::MINT)?;
+  let xsol_mint: Mint = account_map_get(account_map, &XSOL::MINT)?;
+  let lst_headers = lst_mints
+    .iter()
+    .map(|mint| {
+      let header: LstHeader =
+        account_map_get(account_map, &pda::lst_header(*mint))?;
+      Ok((*mint, header))
+    })
+    .collect::<Result<BTreeMap<_, _>>>()?;
+  let sol_usd: PriceUpdateV2 =
+    account_map_get(account_map, &SOL_USD_PYTH_FEED)?;
+  let shyusd_mint: Mint = account_map_get(account_map, &SHYUSD::MINT)?;
+  let hyusd_pool: TokenAccount =
+    account_map_get(account_map, &pda::HYUSD_POOL)?;
+  let xsol_pool: TokenAccount = account_map_get(account_map, &pda::XSOL_POOL)?;
+  let pool_config: PoolConfig =
+    account_map_get(account_map, &pda::POOL_CONFIG)?;
+  ProtocolState::build(
+    clock,
+    &hylo,
+    lst_headers,
+    hyusd_mint,
+    xsol_mint,
+    shyusd_mint,
+    pool_config,
+    hyusd_pool,
+    xsol_pool,
+    &sol_usd,
+  )
+}
+
+/// Jupiter AMM for any registered LST against hyUSD and xSOL.
+///
+/// Keyed by the LST header, whose mint selects the LST, so LSTs registered
+/// on-chain after this SDK was released are tradable without new types.
+#[derive(Clone)]
+pub struct HyloJupiterLst {
+  lst_mint: Pubkey,
+  clock: ClockRef,
+  state: Option<ProtocolState<ClockRef>>,
+}
+
+impl HyloJupiterLst {
+  /// LST mint this AMM trades.
+  #[must_use]
+  pub fn lst_mint(&self) -> Pubkey {
+    self.lst_mint
+  }
+
+  /// Account metas builder for swapping `input_mint` into `output_mint`.
+  ///
+  /// # Errors
+  /// * Pair does not trade this LST against hyUSD or xSOL
+  fn pair_account_metas(
+    &self,
+    input_mint: Pubkey,
+    output_mint: Pubkey,
+  ) -> Result<fn(Pubkey, Pubkey) -> SwapAndAccountMetas> {
+    let lst = self.lst_mint;
+    match (input_mint, output_mint) {
+      (mint, HYUSD::MINT) if mint == lst => Ok(account_metas::mint_stablecoin),
+      (HYUSD::MINT, mint) if mint == lst => {
+        Ok(account_metas::redeem_stablecoin)
+      }
+      (mint, XSOL::MINT) if mint == lst => Ok(account_metas::mint_levercoin),
+      (XSOL::MINT, mint) if mint == lst => Ok(account_metas::redeem_levercoin),
+      _ => Err(anyhow!("Invalid mint pair")),
+    }
+  }
+}
+
+impl Amm for HyloJupiterLst {
+  fn from_keyed_account(
+    keyed_account: &KeyedAccount,
+    amm_context: &AmmContext,
+  ) -> Result<Self>
+  where
+    Self: Sized,
+  {
+    let header =
+      LstHeader::try_deserialize(&mut keyed_account.account.data.as_slice())?;
+    ensure!(
+      keyed_account.key == pda::lst_header(header.mint),
+      "{} is not the LST header of {}",
+      keyed_account.key,
+      header.mint
+    );
+    Ok(HyloJupiterLst {
+      lst_mint: header.mint,
+      clock: amm_context.clock_ref.clone(),
+      state: None,
+    })
+  }
+
+  fn label(&self) -> String {
+    "Hylo LST<->HYUSD/XSOL".to_string()
+  }
+
+  fn program_id(&self) -> Pubkey {
+    exchange::ID
+  }
+
+  fn key(&self) -> Pubkey {
+    pda::lst_header(self.lst_mint)
+  }
+
+  fn get_reserve_mints(&self) -> Vec<Pubkey> {
+    vec![self.lst_mint, HYUSD::MINT, XSOL::MINT]
+  }
+
+  fn get_accounts_to_update(&self) -> Vec<Pubkey> {
+    accounts_to_update(&[self.lst_mint])
+  }
+
+  fn update(&mut self, account_map: &AccountMap) -> Result<()> {
+    self.state = Some(load_state(
+      self.clock.clone(),
+      account_map,
+      &[self.lst_mint],
+    )?);
+    Ok(())
+  }
+
+  fn quote(&self, params: &QuoteParams) -> Result<Quote> {
+    self.pair_account_metas(params.input_mint, params.output_mint)?;
+    let state = self.state.as_ref().context("`state` not set")?;
+    runtime_quote(
+      state,
+      params.input_mint,
+      params.output_mint,
+      params.amount,
+      params.swap_mode,
+    )
+  }
+
+  /// Hylo instructions take an exact input amount, so swaps are exact-in
+  /// only. `ExactOut` quotes remain available through [`Amm::quote`].
+  fn supports_exact_out(&self) -> bool {
+    false
+  }
+
+  fn get_swap_and_account_metas(
+    &self,
+    p: &SwapParams,
+  ) -> Result<SwapAndAccountMetas> {
+    let SwapParams {
+      source_mint,
+      destination_mint,
+      token_transfer_authority: user,
+      ..
+    } = validate_swap_params(p)?;
+    let account_metas =
+      self.pair_account_metas(*source_mint, *destination_mint)?;
+    Ok(account_metas(*user, self.lst_mint))
+  }
+
+  fn clone_amm(&self) -> Box<dyn Amm + Send + Sync> {
+    Box::new(self.clone())
+  }
+}
+
 #[cfg(test)]
 mod tests {
   use anchor_client::solana_client::nonblocking::rpc_client::RpcClient;
   use anchor_lang::pubkey;
+  use anchor_lang::solana_program::clock::Clock;
   use fix::prelude::*;
   use hylo_clients::prelude::{
     MintArgs, RedeemArgs, StabilityPoolArgs, SwapArgs, TransactionSyntax,
@@ -455,10 +651,13 @@ mod tests {
     UserDepositEvent, UserWithdrawEventV1,
   };
   use hylo_jupiter_amm_interface::{KeyedAccount, SwapMode};
+  use hylo_quotes::format::fee_ratio;
+  use hylo_quotes::test_fixtures::{fixture_accounts, fixture_state};
+  use hylo_quotes::token_operation::TokenOperationExt;
   use rust_decimal::Decimal;
 
   use super::*;
-  use crate::util::{fee_pct_decimal, load_account_map, load_amm_context};
+  use crate::util::{load_account_map, load_amm_context};
 
   macro_rules! assert_mint {
     ($sim:expr, $quote:expr) => {
@@ -478,8 +677,8 @@ mod tests {
       assert_eq!($sim.fees_deposited.bits, $quote.fee_amount);
 
       // Fee percentage
-      let fee_pct = fee_pct_decimal(
-        $sim.fees_deposited.try_into()?,
+      let fee_pct = fee_ratio(
+        UFix64::<N9>::try_from($sim.fees_deposited)?,
         UFix64::<N9>::new($quote.in_amount),
       )?;
       assert_eq!(fee_pct, $quote.fee_pct);
@@ -503,8 +702,8 @@ mod tests {
         .bits
         .checked_add($sim.fees_deposited.bits)
         .ok_or(anyhow!("assert_redeem fee percentage"))?;
-      let fee_pct = fee_pct_decimal(
-        $sim.fees_deposited.try_into()?,
+      let fee_pct = fee_ratio(
+        UFix64::<N9>::try_from($sim.fees_deposited)?,
         UFix64::<N9>::new(total_out),
       )?;
       assert_eq!(fee_pct, $quote.fee_pct);
@@ -685,7 +884,7 @@ mod tests {
     assert_eq!(sim.stablecoin_fees.bits, quote.fee_amount);
 
     // Fee percentage
-    let fee_pct = fee_pct_decimal(fees, total_in)?;
+    let fee_pct = fee_ratio(fees, total_in)?;
     assert_eq!(fee_pct, quote.fee_pct);
     Ok(())
   }
@@ -727,7 +926,7 @@ mod tests {
     let fees: UFix64<N6> = sim.stablecoin_minted_fees.try_into()?;
     let out = sim.stablecoin_minted_user.try_into()?;
     let total_in = fees.checked_add(&out).ok_or(anyhow!("total_in"))?;
-    let fee_pct = fee_pct_decimal(fees, total_in)?;
+    let fee_pct = fee_ratio(fees, total_in)?;
     assert_eq!(fee_pct, quote.fee_pct);
     Ok(())
   }
@@ -805,8 +1004,166 @@ mod tests {
     let out = UFix64::<N6>::new(sim.stablecoin_withdrawn.bits);
     let fees = UFix64::<N6>::new(sim.stablecoin_fees.bits);
     let total_hyusd = out.checked_add(&fees).ok_or(anyhow!("total_hyusd"))?;
-    let fee_pct = fee_pct_decimal(fees, total_hyusd)?;
+    let fee_pct = fee_ratio(fees, total_hyusd)?;
     assert_eq!(fee_pct, quote.fee_pct);
     Ok(())
   }
+
+  #[test]
+  fn exact_out_swaps_rejected() {
+    let jupiter_program_id = Pubkey::new_unique();
+    let params = |swap_mode| SwapParams {
+      swap_mode,
+      in_amount: 1,
+      out_amount: 1,
+      source_mint: JITOSOL::MINT,
+      destination_mint: HYUSD::MINT,
+      source_token_account: Pubkey::new_unique(),
+      destination_token_account: Pubkey::new_unique(),
+      token_transfer_authority: TESTER,
+      quote_mint_to_referrer: None,
+      jupiter_program_id: &jupiter_program_id,
+      missing_dynamic_accounts_as_default: false,
+    };
+    assert!(validate_swap_params(&params(SwapMode::ExactIn)).is_ok());
+    assert!(validate_swap_params(&params(SwapMode::ExactOut)).is_err());
+  }
+
+  /// JitoSOL AMM loaded and updated from the fixture snapshot.
+  fn fixture_lst_amm() -> Result<HyloJupiterLst> {
+    let accounts = fixture_accounts()?;
+    let clock: Clock = bincode::deserialize(&accounts.clock.data)?;
+    let amm_context = AmmContext {
+      clock_ref: ClockRef::from(clock),
+    };
+    let header = KeyedAccount {
+      key: pda::lst_header(JITOSOL::MINT),
+      account: accounts.jitosol_header.clone(),
+      params: None,
+    };
+    let mut amm = HyloJupiterLst::from_keyed_account(&header, &amm_context)?;
+    let account_map = accounts.keyed().into_iter().collect::<AccountMap>();
+    amm.update(&account_map)?;
+    Ok(amm)
+  }
+
+  fn exact_in(
+    input_mint: Pubkey,
+    output_mint: Pubkey,
+    amount: u64,
+  ) -> QuoteParams {
+    QuoteParams {
+      amount,
+      input_mint,
+      output_mint,
+      swap_mode: SwapMode::ExactIn,
+    }
+  }
+
+  fn swap_metas(
+    amm: &HyloJupiterLst,
+    source_mint: Pubkey,
+    destination_mint: Pubkey,
+  ) -> Result<SwapAndAccountMetas> {
+    amm.get_swap_and_account_metas(&SwapParams {
+      swap_mode: SwapMode::ExactIn,
+      in_amount: 1_000_000,
+      out_amount: 0,
+      source_mint,
+      destination_mint,
+      source_token_account: Pubkey::new_unique(),
+      destination_token_account: Pubkey::new_unique(),
+      token_transfer_authority: TESTER,
+      quote_mint_to_referrer: None,
+      jupiter_program_id: &Pubkey::new_unique(),
+      missing_dynamic_accounts_as_default: false,
+    })
+  }
+
+  #[test]
+  fn lst_amm_from_fixture_header() -> Result<()> {
+    let amm = fixture_lst_amm()?;
+    assert_eq!(amm.lst_mint(), JITOSOL::MINT);
+    assert_eq!(amm.key(), pda::lst_header(JITOSOL::MINT));
+    assert_eq!(
+      amm.get_reserve_mints(),
+      vec![JITOSOL::MINT, HYUSD::MINT, XSOL::MINT]
+    );
+    Ok(())
+  }
+
+  #[test]
+  fn lst_amm_rejects_header_under_foreign_key() -> Result<()> {
+    let accounts = fixture_accounts()?;
+    let clock: Clock = bincode::deserialize(&accounts.clock.data)?;
+    let header = KeyedAccount {
+      key: pda::lst_header(HYLOSOL::MINT),
+      account: accounts.jitosol_header,
+      params: None,
+    };
+    let amm_context = AmmContext {
+      clock_ref: ClockRef::from(clock),
+    };
+    assert!(HyloJupiterLst::from_keyed_account(&header, &amm_context).is_err());
+    Ok(())
+  }
+
+  #[test]
+  fn lst_amm_quotes_match_protocol_state() -> Result<()> {
+    let amm = fixture_lst_amm()?;
+    let state = fixture_state()?;
+    let amount = UFix64::<N9>::one();
+    let mint = amm.quote(&exact_in(JITOSOL::MINT, HYUSD::MINT, amount.bits))?;
+    let expected = state.output::<JITOSOL, HYUSD>(amount)?;
+    assert_eq!(mint.in_amount, expected.in_amount.bits);
+    assert_eq!(mint.out_amount, expected.out_amount.bits);
+    assert_eq!(mint.fee_amount, expected.fee_amount.bits);
+    let redeem = amm.quote(&exact_in(XSOL::MINT, JITOSOL::MINT, 1_000_000))?;
+    let expected = state.output::<XSOL, JITOSOL>(UFix64::new(1_000_000))?;
+    assert_eq!(redeem.out_amount, expected.out_amount.bits);
+    Ok(())
+  }
+
+  #[test]
+  fn lst_amm_rejects_pairs_without_lst() -> Result<()> {
+    let amm = fixture_lst_amm()?;
+    for (input, output) in [
+      (HYUSD::MINT, XSOL::MINT),
+      (XSOL::MINT, HYUSD::MINT),
+      (HYLOSOL::MINT, HYUSD::MINT),
+      (JITOSOL::MINT, SHYUSD::MINT),
+    ] {
+      assert!(amm.quote(&exact_in(input, output, 1_000_000)).is_err());
+      assert!(swap_metas(&amm, input, output).is_err());
+    }
+    Ok(())
+  }
+
+  #[test]
+  fn lst_amm_swap_metas_follow_pair() -> Result<()> {
+    let amm = fixture_lst_amm()?;
+    let lst = JITOSOL::MINT;
+    for (input, output, expected) in [
+      (
+        lst,
+        HYUSD::MINT,
+        account_metas::mint_stablecoin(TESTER, lst),
+      ),
+      (
+        HYUSD::MINT,
+        lst,
+        account_metas::redeem_stablecoin(TESTER, lst),
+      ),
+      (lst, XSOL::MINT, account_metas::mint_levercoin(TESTER, lst)),
+      (
+        XSOL::MINT,
+        lst,
+        account_metas::redeem_levercoin(TESTER, lst),
+      ),
+    ] {
+      let metas = swap_metas(&amm, input, output)?;
+      assert_eq!(metas.account_metas, expected.account_metas);
+    }
+    Ok(())
+  }
 }
@@ -2,4 +2,4 @@ pub mod account_metas;
 pub mod jupiter;
 pub mod util;
 
-pub use jupiter::{HyloJupiterPair, PairConfig};
+pub use jupiter::{HyloJupiterLst, HyloJupiterPair, PairConfig};
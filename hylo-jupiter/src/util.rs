@@ -1,17 +1,17 @@
 use anchor_client::solana_client::nonblocking::rpc_client::RpcClient;
 use anchor_lang::prelude::{AccountDeserialize, Pubkey};
 use anchor_lang::solana_program::sysvar::clock::{self, Clock};
-use anyhow::{anyhow, Context, Result};
-use fix::num_traits::FromPrimitive;
+use anyhow::{anyhow, Result};
 use fix::prelude::UFix64;
 use fix::typenum::Integer;
 use hylo_core::idl::tokens::TokenMint;
 use hylo_jupiter_amm_interface::{
   AccountMap, AmmContext, ClockRef, Quote, SwapMode, SwapParams,
 };
+use hylo_quotes::format::fee_ratio;
 use hylo_quotes::protocol_state::ProtocolState;
 use hylo_quotes::token_operation::{
-  OperationOutput, TokenOperation, TokenOperationExt,
+  OperationOutput, OperationOutputValue, TokenOperation, TokenOperationExt,
 };
 use rust_decimal::Decimal;
 
@@ -20,18 +20,12 @@ use rust_decimal::Decimal;
 /// # Errors
 /// * Conversions
 /// * Arithmetic
-pub fn fee_pct_decimal<Exp>(
+#[deprecated(note = "use `hylo_quotes::format::fee_ratio`")]
+pub fn fee_pct_decimal<Exp: Integer>(
   fees_extracted: UFix64<Exp>,
   fee_base: UFix64<Exp>,
 ) -> Result<Decimal> {
-  if fee_base == UFix64::new(0) {
-    Ok(Decimal::ZERO)
-  } else {
-    Decimal::from_u64(fees_extracted.bits)
-      .zip(Decimal::from_u64(fee_base.bits))
-      .and_then(|(num, denom)| num.checked_div(denom))
-      .context("Arithmetic error in `fee_pct_decimal`")
-  }
+  fee_ratio(fees_extracted, fee_base)
 }
 
 /// Converts [`OperationOutput`] to Jupiter [`Quote`].
@@ -46,7 +40,7 @@ where
   OutExp: Integer,
   FeeExp: Integer,
 {
-  let fee_pct = fee_pct_decimal(op.fee_amount, op.fee_base)?;
+  let fee_pct = fee_ratio(op.fee_amount, op.fee_base)?;
   Ok(Quote {
     in_amount: op.in_amount.bits,
     out_amount: op.out_amount.bits,
@@ -58,12 +52,16 @@ where
 
 /// Generic Jupiter quote for any `IN -> OUT` pair.
 ///
+/// For [`SwapMode::ExactOut`], `amount` is the desired output and the quote
+/// carries the minimum input required to receive it.
+///
 /// # Errors
 /// * Quote math
 /// * Fee decimal conversion
 pub fn quote<IN, OUT>(
   state: &ProtocolState<ClockRef>,
   amount: u64,
+  swap_mode: SwapMode,
 ) -> Result<Quote>
 where
   IN: TokenMint,
@@ -71,10 +69,50 @@ where
   ProtocolState<ClockRef>: TokenOperation<IN, OUT>,
   <ProtocolState<ClockRef> as TokenOperation<IN, OUT>>::FeeExp: Integer,
 {
-  let op = state.output::<IN, OUT>(UFix64::new(amount))?;
+  let op = match swap_mode {
+    SwapMode::ExactIn => state.output::<IN, OUT>(UFix64::new(amount))?,
+    SwapMode::ExactOut => state.input::<IN, OUT>(UFix64::new(amount))?,
+  };
   operation_to_quote(op)
 }
 
+/// Jupiter quote between runtime mints, for LSTs without a token type.
+///
+/// # Errors
+/// * Unsupported pair or unregistered LST
+/// * Quote math
+/// * Fee decimal conversion
+pub fn runtime_quote(
+  state: &ProtocolState<ClockRef>,
+  input_mint: Pubkey,
+  output_mint: Pubkey,
+  amount: u64,
+  swap_mode: SwapMode,
+) -> Result<Quote> {
+  let op = match swap_mode {
+    SwapMode::ExactIn => {
+      state.runtime_output(input_mint, output_mint, amount)?
+    }
+    SwapMode::ExactOut => {
+      state.runtime_input(input_mint, output_mint, amount)?
+    }
+  };
+  let OperationOutputValue {
+    in_amount,
+    out_amount,
+    fee_amount,
+    fee_mint,
+    fee_base,
+  } = op;
+  Ok(Quote {
+    in_amount: in_amount.bits,
+    out_amount: out_amount.bits,
+    fee_amount: fee_amount.bits,
+    fee_mint,
+    fee_pct: fee_ratio(fee_amount, fee_base)?,
+  })
+}
+
 /// Finds and deserializes an account in Jupiter's `AccountMap`.
 ///
 /// # Errors
@@ -128,15 +166,15 @@ pub async fn load_amm_context(client: &RpcClient) -> Result<AmmContext> {
 /// Validates Jupiter swap parameters for Hylo compatibility.
 ///
 /// # Errors
-/// * `ExactOut` mode
 /// * Dynamic accounts
+/// * [`SwapMode::ExactOut`], as Hylo instructions are exact-in
 pub fn validate_swap_params<'a>(
   params: &'a SwapParams<'a, 'a>,
 ) -> Result<&'a SwapParams<'a, 'a>> {
-  if params.swap_mode == SwapMode::ExactOut {
-    Err(anyhow!("ExactOut not supported"))
-  } else if params.missing_dynamic_accounts_as_default {
+  if params.missing_dynamic_accounts_as_default {
     Err(anyhow!("Dynamic accounts replacement not supported"))
+  } else if params.swap_mode == SwapMode::ExactOut {
+    Err(anyhow!("ExactOut swaps not supported"))
   } else {
     Ok(params)
   }
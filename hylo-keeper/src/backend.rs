@@ -0,0 +1,80 @@
+//! Chain access for the keeper
+
+use std::sync::Arc;
+
+use anchor_client::solana_client::nonblocking::rpc_client::RpcClient;
+use anchor_client::solana_sdk::signature::Signature;
+use anyhow::Result;
+use async_trait::async_trait;
+use hylo_clients::prelude::{
+  ExchangeClient, ProgramClient, StabilityPoolClient,
+};
+use hylo_quotes::prelude::RpcStateProvider;
+
+use crate::observation::Observation;
+use crate::task::Task;
+
+/// Source of observations and executor of maintenance tasks.
+#[async_trait]
+pub trait KeeperBackend: Send + Sync {
+  /// Reads the current maintenance status.
+  ///
+  /// # Errors
+  /// * Account fetch or deserialization
+  async fn observe(&self) -> Result<Observation>;
+
+  /// Sends and confirms the transaction for `task`.
+  ///
+  /// # Errors
+  /// * Transaction build, send or confirmation failure
+  async fn execute(&self, task: Task) -> Result<Signature>;
+}
+
+/// RPC backed keeper, signing with the clients' signer.
+pub struct RpcBackend {
+  state_provider: RpcStateProvider,
+  exchange: ExchangeClient,
+  stability_pool: StabilityPoolClient,
+}
+
+impl RpcBackend {
+  #[must_use]
+  pub fn new(
+    rpc: Arc<RpcClient>,
+    exchange: ExchangeClient,
+    stability_pool: StabilityPoolClient,
+  ) -> RpcBackend {
+    RpcBackend {
+      state_provider: RpcStateProvider::new(rpc),
+      exchange,
+      stability_pool,
+    }
+  }
+}
+
+#[async_trait]
+impl KeeperBackend for RpcBackend {
+  async fn observe(&self) -> Result<Observation> {
+    let accounts = self.state_provider.fetch_accounts().await?;
+    Observation::from_accounts(&accounts)
+  }
+
+  async fn execute(&self, task: Task) -> Result<Signature> {
+    match task {
+      Task::UpdateLstPrices => {
+        let vtd = self.exchange.update_lst_prices().await?;
+        self.exchange.send_v0_transaction(&vtd).await
+      }
+      Task::HarvestYield => {
+        let vtd = self.exchange.harvest_yield().await?;
+        self.exchange.send_v0_transaction(&vtd).await
+      }
+      Task::RebalanceStableToLever => {
+        self.stability_pool.rebalance_stable_to_lever().await
+      }
+      Task::RebalanceLeverToStable => {
+        self.stability_pool.rebalance_lever_to_stable().await
+      }
+    }
+  }
+}
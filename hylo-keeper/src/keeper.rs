@@ -0,0 +1,165 @@
+//! Keeper loop state and task execution
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use anchor_client::solana_sdk::signature::Signature;
+use anyhow::Result;
+use fix::prelude::{UFix64, N6};
+use hylo_clients::program_error::HyloTransactionError;
+use hylo_core::stability_mode::StabilityMode;
+
+use crate::backend::KeeperBackend;
+use crate::metrics::Metrics;
+use crate::task::{plan, Task};
+
+/// Scheduling and retry configuration.
+#[derive(Clone, Debug)]
+pub struct KeeperConfig {
+  /// Delay between ticks
+  pub interval: Duration,
+  /// Transactions attempted per task and tick
+  pub max_attempts: u32,
+  /// Delay before the first retry, doubled on each further retry
+  pub retry_backoff: Duration,
+  /// Smallest rebalance worth a transaction, in stablecoin swapped
+  pub min_rebalance: UFix64<N6>,
+}
+
+impl Default for KeeperConfig {
+  fn default() -> KeeperConfig {
+    KeeperConfig {
+      interval: Duration::from_secs(30),
+      max_attempts: 3,
+      retry_backoff: Duration::from_secs(2),
+      min_rebalance: UFix64::new(1_000_000),
+    }
+  }
+}
+
+/// Result of one task within a tick.
+#[derive(Debug)]
+pub struct TaskOutcome {
+  pub task: Task,
+  /// Transactions attempted, including retries
+  pub attempts: u32,
+  pub result: Result<Signature>,
+}
+
+/// What the keeper saw and did in one tick.
+#[derive(Debug)]
+pub struct Tick {
+  pub epoch: u64,
+  /// Stability mode, `None` while prices are outdated
+  pub stability_mode: Option<StabilityMode>,
+  /// Epoch differs from the previous tick's
+  pub epoch_changed: bool,
+  /// Stability mode differs from the last one observed
+  pub mode_changed: bool,
+  pub outcomes: Vec<TaskOutcome>,
+}
+
+/// Watches the protocol and executes due maintenance tasks.
+pub struct Keeper<B: KeeperBackend> {
+  backend: B,
+  config: KeeperConfig,
+  metrics: Arc<Metrics>,
+  last_epoch: Option<u64>,
+  last_mode: Option<StabilityMode>,
+}
+
+impl<B: KeeperBackend> Keeper<B> {
+  #[must_use]
+  pub fn new(
+    backend: B,
+    config: KeeperConfig,
+    metrics: Arc<Metrics>,
+  ) -> Keeper<B> {
+    Keeper {
+      backend,
+      config,
+      metrics,
+      last_epoch: None,
+      last_mode: None,
+    }
+  }
+
+  #[must_use]
+  pub fn config(&self) -> &KeeperConfig {
+    &self.config
+  }
+
+  /// Observes the protocol and executes every due task in order. Tasks run
+  /// after an [`Task::UpdateLstPrices`] failure are skipped, as they need
+  /// current prices.
+  ///
+  /// # Errors
+  /// * Observation failure
+  /// * Task planning, see [`plan`]
+  pub async fn tick(&mut self) -> Result<Tick> {
+    let observation = match self.backend.observe().await {
+      Ok(observation) => observation,
+      Err(err) => {
+        self.metrics.observe_failure();
+        return Err(err.context("Failed to observe protocol"));
+      }
+    };
+    self.metrics.observe(&observation);
+
+    let epoch = observation.clock.epoch;
+    let stability_mode = observation.stability_mode();
+    let epoch_changed = self.last_epoch.is_some_and(|last| last != epoch);
+    let mode_changed = match (self.last_mode, stability_mode) {
+      (Some(last), Some(mode)) => last != mode,
+      _ => false,
+    };
+    self.last_epoch = Some(epoch);
+    self.last_mode = stability_mode.or(self.last_mode);
+
+    let mut outcomes = Vec::new();
+    for task in plan(&observation, self.config.min_rebalance)? {
+      let outcome = self.execute(task).await;
+      let blocking = task == Task::UpdateLstPrices && outcome.result.is_err();
+      outcomes.push(outcome);
+      if blocking {
+        break;
+      }
+    }
+    Ok(Tick {
+      epoch,
+      stability_mode,
+      epoch_changed,
+      mode_changed,
+      outcomes,
+    })
+  }
+
+  /// Executes `task`, retrying with exponential backoff unless a program
+  /// rejected the transaction, which would fail again.
+  async fn execute(&self, task: Task) -> TaskOutcome {
+    let mut attempts = 0;
+    let mut backoff = self.config.retry_backoff;
+    let result = loop {
+      attempts += 1;
+      match self.backend.execute(task).await {
+        Ok(signature) => break Ok(signature),
+        Err(err)
+          if attempts >= self.config.max_attempts
+            || HyloTransactionError::find(&err).is_some() =>
+        {
+          break Err(err);
+        }
+        Err(_) => {
+          tokio::time::sleep(backoff).await;
+          backoff = backoff.saturating_mul(2);
+        }
+      }
+    };
+    self.metrics.observe_task(task, result.is_ok(), attempts);
+    TaskOutcome {
+      task,
+      attempts,
+      result,
+    }
+  }
+}
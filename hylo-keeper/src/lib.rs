@@ -0,0 +1,74 @@
+//! Keeper for permissionless Hylo maintenance instructions
+//!
+//! Each [`Keeper::tick`] reads the protocol accounts and executes the tasks
+//! that are due, retrying transient failures:
+//!
+//! * `update_lst_prices` once LST prices or total SOL are from a previous epoch
+//! * `harvest_yield` once per epoch
+//! * `rebalance_stable_to_lever` and `rebalance_lever_to_stable` when the
+//!   stability pool math yields a swap of at least
+//!   [`KeeperConfig::min_rebalance`] in the current stability mode
+//!
+//! [`router`] exposes Prometheus metrics at `GET /metrics` and liveness at
+//! `GET /health`, failing once no observation has succeeded recently.
+//!
+//! ```rust,no_run
+//! use std::sync::Arc;
+//!
+//! use anchor_client::solana_client::nonblocking::rpc_client::RpcClient;
+//! use hylo_clients::prelude::*;
+//! use hylo_keeper::{Keeper, KeeperConfig, Metrics, RpcBackend};
+//!
+//! # async fn run(keypair: anchor_client::solana_sdk::signature::Keypair) -> anyhow::Result<()> {
+//! let cluster = Cluster::Localnet;
+//! let commitment = CommitmentConfig::confirmed();
+//! let signer: Arc<dyn TransactionSigner> = Arc::new(keypair);
+//! let rpc = Arc::new(RpcClient::new(cluster.url().to_string()));
+//! let backend = RpcBackend::new(
+//!   rpc,
+//!   ExchangeClient::new_from_signer(cluster.clone(), signer.clone(), commitment)?,
+//!   StabilityPoolClient::new_from_signer(cluster, signer, commitment)?,
+//! );
+//! let mut keeper =
+//!   Keeper::new(backend, KeeperConfig::default(), Arc::new(Metrics::new()?));
+//! for outcome in keeper.tick().await?.outcomes {
+//!   println!("{}: {:?}", outcome.task, outcome.result);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+pub mod backend;
+pub mod keeper;
+pub mod metrics;
+pub mod observation;
+mod routes;
+pub mod task;
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use axum::routing::get;
+use axum::Router;
+
+pub use crate::backend::{KeeperBackend, RpcBackend};
+pub use crate::keeper::{Keeper, KeeperConfig, TaskOutcome, Tick};
+pub use crate::metrics::Metrics;
+pub use crate::observation::Observation;
+pub use crate::task::{plan, Task};
+
+/// State shared by the health and metrics handlers.
+#[derive(Clone)]
+pub struct HealthState {
+  pub metrics: Arc<Metrics>,
+  /// Age of the last successful observation before `/health` fails
+  pub max_observation_age: Duration,
+}
+
+/// Routes of the health and metrics API.
+pub fn router(state: HealthState) -> Router {
+  Router::new()
+    .route("/health", get(routes::health))
+    .route("/metrics", get(routes::metrics))
+    .with_state(state)
+}
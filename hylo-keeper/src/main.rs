@@ -0,0 +1,133 @@
+//! `hylo-keeper`
+//!
+//! ```text
+//! hylo-keeper --rpc-url <URL> --keypair ~/.config/solana/id.json
+//! ```
+
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+
+use anchor_client::solana_client::nonblocking::rpc_client::RpcClient;
+use anchor_client::solana_sdk::signature::read_keypair_file;
+use anyhow::{anyhow, Result};
+use clap::Parser;
+use fix::prelude::UFix64;
+use hylo_clients::prelude::{
+  Cluster, CommitmentConfig, ExchangeClient, ProgramClient,
+  StabilityPoolClient, TransactionSigner,
+};
+use hylo_keeper::{
+  router, HealthState, Keeper, KeeperConfig, Metrics, RpcBackend,
+};
+
+#[derive(Parser, Debug)]
+#[command(version, about = "Keeper for permissionless Hylo maintenance")]
+struct Args {
+  /// RPC endpoint to observe and send transactions through
+  #[arg(long, default_value = "https://api.mainnet-beta.solana.com")]
+  rpc_url: String,
+
+  /// Websocket endpoint, derived from the RPC URL when omitted
+  #[arg(long)]
+  ws_url: Option<String>,
+
+  /// Keypair paying for and signing maintenance transactions
+  #[arg(long, default_value = "~/.config/solana/id.json")]
+  keypair: PathBuf,
+
+  /// Address to serve `/metrics` and `/health` on
+  #[arg(long, default_value = "127.0.0.1:9090")]
+  bind: SocketAddr,
+
+  /// Seconds between ticks
+  #[arg(long, default_value_t = 30)]
+  interval_secs: u64,
+
+  /// Transactions attempted per task and tick
+  #[arg(long, default_value_t = 3)]
+  max_attempts: u32,
+
+  /// Smallest rebalance worth a transaction, in base units of stablecoin
+  #[arg(long, default_value_t = 1_000_000)]
+  min_rebalance: u64,
+}
+
+/// Expands a leading `~` to the home directory.
+fn expand_home(path: PathBuf) -> PathBuf {
+  match (path.strip_prefix("~"), std::env::var_os("HOME")) {
+    (Ok(rest), Some(home)) => PathBuf::from(home).join(rest),
+    _ => path,
+  }
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+  let args = Args::parse();
+  let ws_url = args.ws_url.clone().unwrap_or_else(|| {
+    args
+      .rpc_url
+      .replacen("https://", "wss://", 1)
+      .replacen("http://", "ws://", 1)
+  });
+  let cluster = Cluster::Custom(args.rpc_url.clone(), ws_url);
+  let commitment = CommitmentConfig::confirmed();
+  let keypair_path = expand_home(args.keypair);
+  let keypair = read_keypair_file(&keypair_path).map_err(|e| {
+    anyhow!("Failed to read keypair {}: {e}", keypair_path.display())
+  })?;
+  let signer: Arc<dyn TransactionSigner> = Arc::new(keypair);
+  let rpc = Arc::new(RpcClient::new_with_commitment(
+    args.rpc_url.clone(),
+    commitment,
+  ));
+  let backend = RpcBackend::new(
+    rpc,
+    ExchangeClient::new_from_signer(
+      cluster.clone(),
+      signer.clone(),
+      commitment,
+    )?,
+    StabilityPoolClient::new_from_signer(cluster, signer, commitment)?,
+  );
+  let config = KeeperConfig {
+    interval: Duration::from_secs(args.interval_secs),
+    max_attempts: args.max_attempts,
+    min_rebalance: UFix64::new(args.min_rebalance),
+    ..KeeperConfig::default()
+  };
+  let metrics = Arc::new(Metrics::new()?);
+  let health = HealthState {
+    metrics: metrics.clone(),
+    max_observation_age: config.interval * 3,
+  };
+  let listener = tokio::net::TcpListener::bind(args.bind).await?;
+  println!("Serving metrics on {}", listener.local_addr()?);
+  tokio::spawn(async move { axum::serve(listener, router(health)).await });
+
+  let mut keeper = Keeper::new(backend, config, metrics);
+  loop {
+    match keeper.tick().await {
+      Ok(tick) => {
+        if tick.epoch_changed {
+          println!("Epoch {}", tick.epoch);
+        }
+        if let (true, Some(mode)) = (tick.mode_changed, tick.stability_mode) {
+          println!("Stability mode changed to {mode:?}");
+        }
+        for outcome in tick.outcomes {
+          match outcome.result {
+            Ok(signature) => println!("{}: {signature}", outcome.task),
+            Err(err) => eprintln!(
+              "{} failed after {} attempts: {err:#}",
+              outcome.task, outcome.attempts
+            ),
+          }
+        }
+      }
+      Err(err) => eprintln!("{err:#}"),
+    }
+    tokio::time::sleep(keeper.config().interval).await;
+  }
+}
@@ -0,0 +1,150 @@
+//! Prometheus health metrics for the keeper
+
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use anyhow::Result;
+use hylo_core::stability_mode::StabilityMode;
+use prometheus::{
+  IntCounterVec, IntGauge, IntGaugeVec, Opts, Registry, TextEncoder,
+};
+
+use crate::observation::Observation;
+use crate::task::Task;
+
+/// Observation and task counters, with gauges for the last observed epoch,
+/// stability mode and success times.
+pub struct Metrics {
+  registry: Registry,
+  observations: IntCounterVec,
+  tasks: IntCounterVec,
+  task_attempts: IntCounterVec,
+  last_task_success: IntGaugeVec,
+  last_observation: IntGauge,
+  epoch: IntGauge,
+  stability_mode: IntGauge,
+}
+
+impl Metrics {
+  /// Registers the keeper metrics in a fresh registry.
+  ///
+  /// # Errors
+  /// * Invalid metric definition
+  pub fn new() -> Result<Metrics> {
+    let registry = Registry::new();
+    let observations = IntCounterVec::new(
+      Opts::new("hylo_keeper_observations_total", "Protocol state reads"),
+      &["result"],
+    )?;
+    let tasks = IntCounterVec::new(
+      Opts::new("hylo_keeper_tasks_total", "Maintenance tasks executed"),
+      &["task", "result"],
+    )?;
+    let task_attempts = IntCounterVec::new(
+      Opts::new(
+        "hylo_keeper_task_attempts_total",
+        "Transactions attempted per task, including retries",
+      ),
+      &["task"],
+    )?;
+    let last_task_success = IntGaugeVec::new(
+      Opts::new(
+        "hylo_keeper_last_task_success_timestamp_seconds",
+        "Unix time a task last succeeded",
+      ),
+      &["task"],
+    )?;
+    let last_observation = IntGauge::new(
+      "hylo_keeper_last_observation_timestamp_seconds",
+      "Unix time of the last successful protocol state read",
+    )?;
+    let epoch = IntGauge::new("hylo_keeper_epoch", "Last observed epoch")?;
+    let stability_mode = IntGauge::new(
+      "hylo_keeper_stability_mode",
+      "Last observed stability mode from 0 (Normal) to 3 (Depeg), -1 while \
+       prices are outdated",
+    )?;
+    registry.register(Box::new(observations.clone()))?;
+    registry.register(Box::new(tasks.clone()))?;
+    registry.register(Box::new(task_attempts.clone()))?;
+    registry.register(Box::new(last_task_success.clone()))?;
+    registry.register(Box::new(last_observation.clone()))?;
+    registry.register(Box::new(epoch.clone()))?;
+    registry.register(Box::new(stability_mode.clone()))?;
+    Ok(Metrics {
+      registry,
+      observations,
+      tasks,
+      task_attempts,
+      last_task_success,
+      last_observation,
+      epoch,
+      stability_mode,
+    })
+  }
+
+  /// Records a successful read of the protocol state.
+  pub fn observe(&self, observation: &Observation) {
+    self.observations.with_label_values(&["ok"]).inc();
+    self.last_observation.set(unix_now());
+    self
+      .epoch
+      .set(i64::try_from(observation.clock.epoch).unwrap_or(i64::MAX));
+    self
+      .stability_mode
+      .set(observation.stability_mode().map_or(-1, mode_level));
+  }
+
+  /// Records a failed read of the protocol state.
+  pub fn observe_failure(&self) {
+    self.observations.with_label_values(&["error"]).inc();
+  }
+
+  /// Records the outcome of a task after `attempts` transactions.
+  pub fn observe_task(&self, task: Task, success: bool, attempts: u32) {
+    let result = if success { "ok" } else { "error" };
+    self.tasks.with_label_values(&[task.as_str(), result]).inc();
+    self
+      .task_attempts
+      .with_label_values(&[task.as_str()])
+      .inc_by(u64::from(attempts));
+    if success {
+      self
+        .last_task_success
+        .with_label_values(&[task.as_str()])
+        .set(unix_now());
+    }
+  }
+
+  /// Whether protocol state was read within `max_age`.
+  #[must_use]
+  pub fn healthy(&self, max_age: Duration) -> bool {
+    let last = self.last_observation.get();
+    let max_age = i64::try_from(max_age.as_secs()).unwrap_or(i64::MAX);
+    last > 0 && unix_now().saturating_sub(last) <= max_age
+  }
+
+  /// Metrics in the Prometheus text exposition format.
+  ///
+  /// # Errors
+  /// * Encoding failure
+  pub fn render(&self) -> Result<String> {
+    Ok(TextEncoder::new().encode_to_string(&self.registry.gather())?)
+  }
+}
+
+fn mode_level(mode: StabilityMode) -> i64 {
+  match mode {
+    StabilityMode::Normal => 0,
+    StabilityMode::Mode1 => 1,
+    StabilityMode::Mode2 => 2,
+    StabilityMode::Depeg => 3,
+  }
+}
+
+fn unix_now() -> i64 {
+  SystemTime::now()
+    .duration_since(UNIX_EPOCH)
+    .map_or(0, |elapsed| {
+      i64::try_from(elapsed.as_secs()).unwrap_or(i64::MAX)
+    })
+}
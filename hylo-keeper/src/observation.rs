@@ -0,0 +1,97 @@
+//! Protocol maintenance status read from raw accounts
+
+use std::collections::BTreeMap;
+
+use anchor_client::solana_sdk::clock::Clock;
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::AccountDeserialize;
+use anyhow::{anyhow, Result};
+use hylo_core::stability_mode::StabilityMode;
+use hylo_core::total_sol_cache::prices_outdated;
+use hylo_idl::exchange::accounts::{Hylo, LstHeader};
+use hylo_idl::tokens::{TokenMint, HYLOSOL, JITOSOL};
+use hylo_quotes::prelude::{ProtocolAccounts, ProtocolState};
+
+/// Cache epochs and protocol state observed at one clock.
+///
+/// Read from raw accounts rather than [`ProtocolState`], which fails to load
+/// until outdated LST prices and total SOL are updated.
+#[derive(Clone)]
+pub struct Observation {
+  pub clock: Clock,
+  /// Epoch of the cached total SOL
+  pub total_sol_epoch: u64,
+  /// Epoch yield was last harvested in
+  pub yield_harvest_epoch: u64,
+  /// Epoch of each registered LST's cached SOL price, keyed by mint
+  pub lst_price_epochs: BTreeMap<Pubkey, u64>,
+  /// Protocol state, `None` while prices are outdated
+  pub state: Option<ProtocolState<Clock>>,
+}
+
+impl Observation {
+  /// Reads cache epochs from `accounts`, loading the protocol state when
+  /// prices are current.
+  ///
+  /// # Errors
+  /// * Account deserialization
+  /// * Protocol state fails to load despite current prices, e.g. stale SOL/USD
+  ///   oracle
+  pub fn from_accounts(accounts: &ProtocolAccounts) -> Result<Observation> {
+    let clock: Clock = bincode::deserialize(&accounts.clock.data)
+      .map_err(|e| anyhow!("Failed to deserialize clock: {e}"))?;
+    let hylo = Hylo::try_deserialize(&mut accounts.hylo.data.as_slice())?;
+    let lst_price_epochs = [
+      (JITOSOL::MINT, &accounts.jitosol_header),
+      (HYLOSOL::MINT, &accounts.hylosol_header),
+    ]
+    .into_iter()
+    .chain(
+      accounts
+        .lst_headers
+        .iter()
+        .map(|(mint, header)| (*mint, header)),
+    )
+    .map(|(mint, header)| {
+      let header = LstHeader::try_deserialize(&mut header.data.as_slice())?;
+      Ok((mint, header.price_sol.epoch))
+    })
+    .collect::<Result<BTreeMap<_, _>>>()?;
+    let mut observation = Observation {
+      clock,
+      total_sol_epoch: hylo.total_sol_cache.current_update_epoch,
+      yield_harvest_epoch: hylo.yield_harvest_cache.epoch,
+      lst_price_epochs,
+      state: None,
+    };
+    if !observation.prices_outdated() {
+      observation.state = Some(ProtocolState::try_from(accounts)?);
+    }
+    Ok(observation)
+  }
+
+  /// Whether total SOL or any LST price is from a previous epoch.
+  #[must_use]
+  pub fn prices_outdated(&self) -> bool {
+    prices_outdated(
+      self.total_sol_epoch,
+      self.lst_price_epochs.values().copied(),
+      self.clock.epoch,
+    )
+  }
+
+  /// Whether yield has not been harvested this epoch.
+  #[must_use]
+  pub fn yield_unharvested(&self) -> bool {
+    self.yield_harvest_epoch < self.clock.epoch
+  }
+
+  /// Current stability mode, `None` while prices are outdated.
+  #[must_use]
+  pub fn stability_mode(&self) -> Option<StabilityMode> {
+    self
+      .state
+      .as_ref()
+      .map(|state| state.exchange_context.stability_mode)
+  }
+}
@@ -0,0 +1,25 @@
+use axum::extract::State;
+use axum::http::{header, StatusCode};
+use axum::response::{IntoResponse, Response};
+
+use crate::HealthState;
+
+/// `200` while protocol state was read within the configured age, `503`
+/// otherwise.
+pub(crate) async fn health(State(state): State<HealthState>) -> StatusCode {
+  if state.metrics.healthy(state.max_observation_age) {
+    StatusCode::OK
+  } else {
+    StatusCode::SERVICE_UNAVAILABLE
+  }
+}
+
+pub(crate) async fn metrics(State(state): State<HealthState>) -> Response {
+  match state.metrics.render() {
+    Ok(body) => ([(header::CONTENT_TYPE, "text/plain; version=0.0.4")], body)
+      .into_response(),
+    Err(err) => {
+      (StatusCode::INTERNAL_SERVER_ERROR, err.to_string()).into_response()
+    }
+  }
+}
@@ -0,0 +1,78 @@
+//! Maintenance tasks and when they are due
+
+use std::fmt::{self, Display, Formatter};
+
+use anyhow::Result;
+use fix::prelude::{UFix64, N6};
+use hylo_quotes::prelude::Rebalance;
+
+use crate::observation::Observation;
+
+/// Permissionless maintenance instruction.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Task {
+  /// Refresh LST prices and total SOL for the current epoch
+  UpdateLstPrices,
+  /// Harvest LST yield into the stability pool
+  HarvestYield,
+  /// Swap pool stablecoin to levercoin, raising the collateral ratio
+  RebalanceStableToLever,
+  /// Swap pool levercoin back to stablecoin
+  RebalanceLeverToStable,
+}
+
+impl Task {
+  #[must_use]
+  pub const fn as_str(&self) -> &'static str {
+    match self {
+      Task::UpdateLstPrices => "update_lst_prices",
+      Task::HarvestYield => "harvest_yield",
+      Task::RebalanceStableToLever => "rebalance_stable_to_lever",
+      Task::RebalanceLeverToStable => "rebalance_lever_to_stable",
+    }
+  }
+}
+
+impl Display for Task {
+  fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+    f.write_str(self.as_str())
+  }
+}
+
+/// Tasks due for `observation`, in execution order.
+///
+/// * Outdated prices are updated first, as every other instruction needs them
+/// * Yield is harvested once per epoch
+/// * Rebalances follow [`Rebalance::for_state`], skipping swaps of less than
+///   `min_rebalance` stablecoin, and wait for prices to be current
+///
+/// # Errors
+/// * Stability pool math failure
+pub fn plan(
+  observation: &Observation,
+  min_rebalance: UFix64<N6>,
+) -> Result<Vec<Task>> {
+  let mut tasks = Vec::new();
+  if observation.prices_outdated() {
+    tasks.push(Task::UpdateLstPrices);
+  }
+  if observation.yield_unharvested() {
+    tasks.push(Task::HarvestYield);
+  }
+  if let Some(state) = &observation.state {
+    match Rebalance::for_state(state)? {
+      Some(Rebalance::StableToLever { stablecoin_in, .. })
+        if stablecoin_in >= min_rebalance =>
+      {
+        tasks.push(Task::RebalanceStableToLever);
+      }
+      Some(Rebalance::LeverToStable { stablecoin_out, .. })
+        if stablecoin_out >= min_rebalance =>
+      {
+        tasks.push(Task::RebalanceLeverToStable);
+      }
+      _ => {}
+    }
+  }
+  Ok(tasks)
+}
@@ -0,0 +1,241 @@
+//! Keeper planning, retries and metrics against the fixture state.
+
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use anchor_client::solana_sdk::clock::Clock;
+use anchor_client::solana_sdk::message::{Message, VersionedMessage};
+use anchor_client::solana_sdk::signature::Signature;
+use anchor_client::solana_sdk::transaction::TransactionError;
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use fix::prelude::UFix64;
+use hylo_clients::prelude::HyloTransactionError;
+use hylo_keeper::{
+  plan, Keeper, KeeperBackend, KeeperConfig, Metrics, Observation, Task,
+};
+use hylo_quotes::prelude::ProtocolAccounts;
+use hylo_quotes::test_fixtures::fixture_accounts;
+
+/// Fixture accounts with the clock moved into the next epoch.
+fn next_epoch_accounts() -> Result<ProtocolAccounts> {
+  let mut accounts = fixture_accounts()?;
+  let mut clock: Clock = bincode::deserialize(&accounts.clock.data)?;
+  clock.epoch += 1;
+  accounts.clock.data = bincode::serialize(&clock)?;
+  Ok(accounts)
+}
+
+fn program_rejection() -> anyhow::Error {
+  HyloTransactionError::new(
+    None,
+    TransactionError::AccountInUse,
+    &VersionedMessage::Legacy(Message::default()),
+    Vec::new(),
+  )
+  .into()
+}
+
+/// Backend serving fixed accounts, failing each task with scripted errors
+/// before succeeding.
+#[derive(Default)]
+struct MockBackend {
+  accounts: Option<ProtocolAccounts>,
+  failures: Mutex<Vec<(Task, VecDeque<anyhow::Error>)>>,
+  executed: Arc<Mutex<Vec<Task>>>,
+}
+
+impl MockBackend {
+  fn new(accounts: ProtocolAccounts) -> MockBackend {
+    MockBackend {
+      accounts: Some(accounts),
+      ..MockBackend::default()
+    }
+  }
+
+  fn failing(
+    self,
+    task: Task,
+    errors: impl IntoIterator<Item = anyhow::Error>,
+  ) -> MockBackend {
+    self
+      .failures
+      .lock()
+      .unwrap()
+      .push((task, errors.into_iter().collect()));
+    self
+  }
+}
+
+#[async_trait]
+impl KeeperBackend for MockBackend {
+  async fn observe(&self) -> Result<Observation> {
+    let accounts = self
+      .accounts
+      .as_ref()
+      .ok_or_else(|| anyhow!("RPC unavailable"))?;
+    Observation::from_accounts(accounts)
+  }
+
+  async fn execute(&self, task: Task) -> Result<Signature> {
+    self.executed.lock().unwrap().push(task);
+    let mut failures = self.failures.lock().unwrap();
+    let scripted = failures
+      .iter_mut()
+      .find(|(failing, _)| *failing == task)
+      .and_then(|(_, errors)| errors.pop_front());
+    match scripted {
+      Some(err) => Err(err),
+      None => Ok(Signature::new_unique()),
+    }
+  }
+}
+
+fn config() -> KeeperConfig {
+  KeeperConfig {
+    retry_backoff: Duration::ZERO,
+    ..KeeperConfig::default()
+  }
+}
+
+#[test]
+fn observes_current_fixture() -> Result<()> {
+  let observation = Observation::from_accounts(&fixture_accounts()?)?;
+  assert!(!observation.prices_outdated());
+  assert!(observation.state.is_some());
+  assert!(observation.stability_mode().is_some());
+  let tasks = plan(&observation, UFix64::new(1_000_000))?;
+  assert!(!tasks.contains(&Task::UpdateLstPrices));
+  Ok(())
+}
+
+#[test]
+fn plans_update_and_harvest_at_epoch_boundary() -> Result<()> {
+  let observation = Observation::from_accounts(&next_epoch_accounts()?)?;
+  assert!(observation.prices_outdated());
+  assert!(observation.yield_unharvested());
+  assert!(observation.state.is_none());
+  assert!(observation.stability_mode().is_none());
+  let tasks = plan(&observation, UFix64::new(1_000_000))?;
+  assert_eq!(tasks, vec![Task::UpdateLstPrices, Task::HarvestYield]);
+  Ok(())
+}
+
+#[tokio::test]
+async fn retries_transient_failures() -> Result<()> {
+  let backend = MockBackend::new(next_epoch_accounts()?).failing(
+    Task::UpdateLstPrices,
+    [anyhow!("blockhash not found"), anyhow!("timeout")],
+  );
+  let executed = backend.executed.clone();
+  let mut keeper = Keeper::new(backend, config(), Arc::new(Metrics::new()?));
+  let tick = keeper.tick().await?;
+  assert_eq!(tick.outcomes.len(), 2);
+  assert_eq!(tick.outcomes[0].task, Task::UpdateLstPrices);
+  assert_eq!(tick.outcomes[0].attempts, 3);
+  assert!(tick.outcomes[0].result.is_ok());
+  assert_eq!(tick.outcomes[1].attempts, 1);
+  assert_eq!(
+    *executed.lock().unwrap(),
+    vec![
+      Task::UpdateLstPrices,
+      Task::UpdateLstPrices,
+      Task::UpdateLstPrices,
+      Task::HarvestYield,
+    ]
+  );
+  Ok(())
+}
+
+#[tokio::test]
+async fn stops_after_failed_price_update() -> Result<()> {
+  let backend = MockBackend::new(next_epoch_accounts()?)
+    .failing(Task::UpdateLstPrices, (0..3).map(|_| anyhow!("timeout")));
+  let mut keeper = Keeper::new(backend, config(), Arc::new(Metrics::new()?));
+  let tick = keeper.tick().await?;
+  assert_eq!(tick.outcomes.len(), 1);
+  assert_eq!(tick.outcomes[0].attempts, 3);
+  assert!(tick.outcomes[0].result.is_err());
+  Ok(())
+}
+
+#[tokio::test]
+async fn does_not_retry_program_rejections() -> Result<()> {
+  let backend = MockBackend::new(next_epoch_accounts()?)
+    .failing(Task::HarvestYield, [program_rejection()]);
+  let mut keeper = Keeper::new(backend, config(), Arc::new(Metrics::new()?));
+  let tick = keeper.tick().await?;
+  let harvest = &tick.outcomes[1];
+  assert_eq!(harvest.task, Task::HarvestYield);
+  assert_eq!(harvest.attempts, 1);
+  let err = harvest.result.as_ref().unwrap_err();
+  assert!(HyloTransactionError::find(err).is_some());
+  Ok(())
+}
+
+#[tokio::test]
+async fn records_metrics() -> Result<()> {
+  let metrics = Arc::new(Metrics::new()?);
+  let mut keeper = Keeper::new(
+    MockBackend::new(next_epoch_accounts()?),
+    config(),
+    metrics.clone(),
+  );
+  assert!(!metrics.healthy(Duration::from_mins(1)));
+  let tick = keeper.tick().await?;
+  assert!(!tick.epoch_changed);
+  assert!(metrics.healthy(Duration::from_mins(1)));
+  let rendered = metrics.render()?;
+  assert!(rendered.contains(&format!("hylo_keeper_epoch {}", tick.epoch)));
+  assert!(rendered.contains("hylo_keeper_stability_mode -1"));
+  assert!(rendered.contains(
+    "hylo_keeper_tasks_total{result=\"ok\",task=\"update_lst_prices\"} 1"
+  ));
+  Ok(())
+}
+
+#[tokio::test]
+async fn counts_failed_observations() -> Result<()> {
+  let metrics = Arc::new(Metrics::new()?);
+  let mut keeper =
+    Keeper::new(MockBackend::default(), config(), metrics.clone());
+  assert!(keeper.tick().await.is_err());
+  assert!(!metrics.healthy(Duration::from_mins(1)));
+  assert!(metrics
+    .render()?
+    .contains("hylo_keeper_observations_total{result=\"error\"} 1"));
+  Ok(())
+}
+
+#[tokio::test]
+#[ignore = "requires local validator with Hylo deployed"]
+async fn ticks_against_local_validator() -> Result<()> {
+  use anchor_client::solana_client::nonblocking::rpc_client::RpcClient;
+  use anchor_client::solana_sdk::signature::Keypair;
+  use hylo_clients::prelude::{
+    Cluster, CommitmentConfig, ExchangeClient, ProgramClient,
+    StabilityPoolClient, TransactionSigner,
+  };
+  use hylo_keeper::RpcBackend;
+
+  let cluster = Cluster::Localnet;
+  let commitment = CommitmentConfig::confirmed();
+  let signer: Arc<dyn TransactionSigner> = Arc::new(Keypair::new());
+  let rpc = Arc::new(RpcClient::new_with_commitment(
+    cluster.url().to_string(),
+    commitment,
+  ));
+  let backend = RpcBackend::new(
+    rpc,
+    ExchangeClient::new_from_signer(
+      cluster.clone(),
+      signer.clone(),
+      commitment,
+    )?,
+    StabilityPoolClient::new_from_signer(cluster, signer, commitment)?,
+  );
+  let mut keeper = Keeper::new(backend, config(), Arc::new(Metrics::new()?));
+  keeper.tick().await?;
+  Ok(())
+}
@@ -0,0 +1,155 @@
+//! Request and response bodies of the quote API
+//!
+//! Fields are camelCase. Fixed point amounts and price ranges follow the
+//! shared schema of [`hylo_core::serde_schema`]: raw bits as a string, so
+//! 64-bit values survive JSON number parsing, alongside the exponent and an
+//! exact decimal rendering.
+
+use fix::prelude::{UFix64, UFixValue64, N4, N6, N8, N9};
+use hylo_core::pyth::PriceRange;
+use hylo_quotes::prelude::ScenarioMetrics;
+use serde::{Deserialize, Serialize};
+
+/// Slippage tolerance applied when `slippageBps` is omitted.
+pub const DEFAULT_SLIPPAGE_BPS: u64 = 50;
+
+/// Query parameters of `GET /quote`.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QuoteParams {
+  pub input_mint: String,
+  pub output_mint: String,
+  /// Input amount in base units
+  pub amount: u64,
+  #[serde(default = "default_slippage_bps")]
+  pub slippage_bps: u64,
+  /// Wallet to build the transaction for, quote only when absent
+  pub user: Option<String>,
+}
+
+fn default_slippage_bps() -> u64 {
+  DEFAULT_SLIPPAGE_BPS
+}
+
+/// Body of `GET /quote`.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QuoteResponse {
+  pub input_mint: String,
+  pub output_mint: String,
+  #[serde(with = "hylo_core::serde_schema::ufix_value64")]
+  pub amount_in: UFixValue64,
+  #[serde(with = "hylo_core::serde_schema::ufix_value64")]
+  pub amount_out: UFixValue64,
+  #[serde(with = "hylo_core::serde_schema::ufix_value64")]
+  pub fee_amount: UFixValue64,
+  pub fee_mint: String,
+  pub operation: String,
+  pub description: String,
+  pub compute_units: u64,
+  pub compute_unit_strategy: String,
+  /// Base64 bincode unsigned v0 transaction for `user`, with a compute unit
+  /// limit instruction prepended
+  pub transaction: Option<String>,
+}
+
+/// Registered LST and its current price.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LstSummary {
+  pub mint: String,
+  #[serde(with = "hylo_core::serde_schema::ufix_value64")]
+  pub price_sol: UFixValue64,
+  pub price_epoch: u64,
+}
+
+/// Stability pool balances and configuration.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PoolSummary {
+  #[serde(with = "hylo_core::serde_schema::ufix_value64")]
+  pub stablecoin: UFixValue64,
+  #[serde(with = "hylo_core::serde_schema::ufix_value64")]
+  pub levercoin: UFixValue64,
+  #[serde(with = "hylo_core::serde_schema::ufix_value64")]
+  pub withdrawal_fee: UFixValue64,
+}
+
+/// Body of `GET /state`, a summary of the cached protocol state.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StateResponse {
+  pub slot: u64,
+  pub epoch: u64,
+  pub fetched_at: i64,
+  pub stability_mode: String,
+  #[serde(with = "hylo_core::serde_schema::ufix64")]
+  pub total_sol: UFix64<N9>,
+  #[serde(with = "hylo_core::serde_schema::ufix_value64")]
+  pub stablecoin_supply: UFixValue64,
+  #[serde(with = "hylo_core::serde_schema::ufix_value64")]
+  pub levercoin_supply: UFixValue64,
+  #[serde(with = "hylo_core::serde_schema::ufix_value64")]
+  pub lp_token_supply: UFixValue64,
+  pub stability_pool: PoolSummary,
+  pub lsts: Vec<LstSummary>,
+}
+
+/// Body of `GET /stats`, derived protocol metrics.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StatsResponse {
+  pub sol_usd_price: PriceRange<N8>,
+  #[serde(with = "hylo_core::serde_schema::ufix64")]
+  pub collateral_ratio: UFix64<N9>,
+  pub stability_mode: String,
+  #[serde(with = "hylo_core::serde_schema::ufix64")]
+  pub stablecoin_nav: UFix64<N9>,
+  pub levercoin_nav: PriceRange<N9>,
+  #[serde(with = "hylo_core::serde_schema::ufix64")]
+  pub stablecoin_supply: UFix64<N6>,
+  #[serde(with = "hylo_core::serde_schema::ufix64")]
+  pub levercoin_supply: UFix64<N6>,
+  /// `None` while minting is disabled in the current mode
+  #[serde(with = "hylo_core::serde_schema::option_ufix64")]
+  pub stablecoin_mint_fee: Option<UFix64<N4>>,
+  #[serde(with = "hylo_core::serde_schema::option_ufix64")]
+  pub stablecoin_redeem_fee: Option<UFix64<N4>>,
+  #[serde(with = "hylo_core::serde_schema::option_ufix64")]
+  pub levercoin_mint_fee: Option<UFix64<N4>>,
+  #[serde(with = "hylo_core::serde_schema::option_ufix64")]
+  pub levercoin_redeem_fee: Option<UFix64<N4>>,
+  #[serde(with = "hylo_core::serde_schema::ufix64")]
+  pub stablecoin_in_pool: UFix64<N6>,
+  #[serde(with = "hylo_core::serde_schema::ufix64")]
+  pub levercoin_in_pool: UFix64<N6>,
+  #[serde(with = "hylo_core::serde_schema::ufix64")]
+  pub lp_token_nav: UFix64<N6>,
+}
+
+impl From<ScenarioMetrics> for StatsResponse {
+  fn from(metrics: ScenarioMetrics) -> StatsResponse {
+    StatsResponse {
+      sol_usd_price: metrics.sol_usd_price,
+      collateral_ratio: metrics.collateral_ratio,
+      stability_mode: format!("{:?}", metrics.stability_mode),
+      stablecoin_nav: metrics.stablecoin_nav,
+      levercoin_nav: metrics.levercoin_nav,
+      stablecoin_supply: metrics.stablecoin_supply,
+      levercoin_supply: metrics.levercoin_supply,
+      stablecoin_mint_fee: metrics.stablecoin_mint_fee,
+      stablecoin_redeem_fee: metrics.stablecoin_redeem_fee,
+      levercoin_mint_fee: metrics.levercoin_mint_fee,
+      levercoin_redeem_fee: metrics.levercoin_redeem_fee,
+      stablecoin_in_pool: metrics.stablecoin_in_pool,
+      levercoin_in_pool: metrics.levercoin_in_pool,
+      lp_token_nav: metrics.lp_token_nav,
+    }
+  }
+}
+
+/// Body of every error response.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ErrorResponse {
+  pub error: String,
+}
@@ -0,0 +1,101 @@
+//! Chain data needed to turn quotes into transactions
+
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+
+use anchor_client::solana_client::nonblocking::rpc_client::RpcClient;
+use anchor_client::solana_sdk::address_lookup_table::AddressLookupTableAccount;
+use anchor_client::solana_sdk::hash::Hash;
+use anchor_lang::prelude::Pubkey;
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use hylo_clients::util::deserialize_lookup_table;
+
+/// Source of blockhashes and address lookup tables for built transactions.
+#[async_trait]
+pub trait ChainContext: Send + Sync {
+  /// Blockhash to build transactions against.
+  ///
+  /// # Errors
+  /// * Blockhash fetch
+  async fn latest_blockhash(&self) -> Result<Hash>;
+
+  /// Lookup tables at `addresses`, in order.
+  ///
+  /// # Errors
+  /// * Missing or malformed lookup table account
+  async fn lookup_tables(
+    &self,
+    addresses: &[Pubkey],
+  ) -> Result<Vec<AddressLookupTableAccount>>;
+}
+
+/// RPC backed chain context.
+///
+/// Lookup tables are append-only, so cached copies stay valid for compiling
+/// messages and are fetched once per address.
+pub struct RpcChainContext {
+  rpc: Arc<RpcClient>,
+  lookup_tables: RwLock<HashMap<Pubkey, AddressLookupTableAccount>>,
+}
+
+impl RpcChainContext {
+  #[must_use]
+  pub fn new(rpc: Arc<RpcClient>) -> RpcChainContext {
+    RpcChainContext {
+      rpc,
+      lookup_tables: RwLock::default(),
+    }
+  }
+
+  fn cached(&self, address: &Pubkey) -> Option<AddressLookupTableAccount> {
+    self
+      .lookup_tables
+      .read()
+      .ok()
+      .and_then(|tables| tables.get(address).cloned())
+  }
+}
+
+#[async_trait]
+impl ChainContext for RpcChainContext {
+  async fn latest_blockhash(&self) -> Result<Hash> {
+    Ok(self.rpc.get_latest_blockhash().await?)
+  }
+
+  async fn lookup_tables(
+    &self,
+    addresses: &[Pubkey],
+  ) -> Result<Vec<AddressLookupTableAccount>> {
+    let missing: Vec<Pubkey> = addresses
+      .iter()
+      .filter(|address| self.cached(address).is_none())
+      .copied()
+      .collect();
+    if !missing.is_empty() {
+      let accounts = self.rpc.get_multiple_accounts(&missing).await?;
+      let fetched = missing
+        .iter()
+        .zip(accounts)
+        .map(|(address, account)| {
+          let account = account
+            .ok_or_else(|| anyhow!("Lookup table {address} not found"))?;
+          deserialize_lookup_table(address, &account)
+        })
+        .collect::<Result<Vec<_>>>()?;
+      let mut tables = self
+        .lookup_tables
+        .write()
+        .map_err(|_| anyhow!("Lookup table cache poisoned"))?;
+      tables.extend(fetched.into_iter().map(|table| (table.key, table)));
+    }
+    addresses
+      .iter()
+      .map(|address| {
+        self
+          .cached(address)
+          .ok_or_else(|| anyhow!("Lookup table {address} not found"))
+      })
+      .collect()
+  }
+}
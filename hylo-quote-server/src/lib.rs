@@ -0,0 +1,94 @@
+//! HTTP quote service for the Hylo protocol
+//!
+//! Serves [`RuntimeQuoteStrategy`] quotes over a cached [`StateProvider`]:
+//!
+//! * `GET /quote?inputMint&outputMint&amount&slippageBps&user` - amounts, fees,
+//!   [`QuoteMetadata`], compute units and, when `user` is given, a base64
+//!   unsigned transaction for the user to sign
+//! * `GET /state` - summary of the protocol state quotes are computed from
+//! * `GET /stats` - NAVs, collateral ratio, fees and stability pool metrics
+//! * `GET /metrics` - Prometheus request counts and latencies per [`Operation`]
+//!
+//! ```rust,no_run
+//! use std::sync::Arc;
+//! use std::time::Duration;
+//!
+//! use anchor_client::solana_client::nonblocking::rpc_client::RpcClient;
+//! use hylo_quote_server::chain::RpcChainContext;
+//! use hylo_quote_server::{router, AppState};
+//! use hylo_quotes::prelude::{CachedStateProvider, RpcStateProvider};
+//!
+//! # async fn run() -> anyhow::Result<()> {
+//! let rpc = Arc::new(RpcClient::new("https://api.mainnet-beta.solana.com".into()));
+//! let provider = CachedStateProvider::new(
+//!   RpcStateProvider::new(rpc.clone()),
+//!   Duration::from_secs(1),
+//! );
+//! let app = AppState::new(Arc::new(provider), Arc::new(RpcChainContext::new(rpc)))?;
+//! let listener = tokio::net::TcpListener::bind("127.0.0.1:8080").await?;
+//! axum::serve(listener, router(app)).await?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! [`RuntimeQuoteStrategy`]: hylo_quotes::prelude::RuntimeQuoteStrategy
+//! [`QuoteMetadata`]: hylo_quotes::prelude::QuoteMetadata
+//! [`Operation`]: hylo_quotes::prelude::Operation
+
+pub mod api;
+pub mod chain;
+pub mod metrics;
+mod routes;
+
+use std::sync::Arc;
+
+use anchor_client::solana_sdk::clock::Clock;
+use anyhow::Result;
+use axum::routing::get;
+use axum::Router;
+use hylo_quotes::prelude::{ProtocolStateStrategy, StateProvider};
+
+use crate::chain::ChainContext;
+use crate::metrics::Metrics;
+
+/// Protocol state source shared by every request.
+pub type SharedStateProvider = Arc<dyn StateProvider<Clock>>;
+
+/// State shared by the request handlers.
+#[derive(Clone)]
+pub struct AppState {
+  pub strategy: Arc<ProtocolStateStrategy<SharedStateProvider>>,
+  pub chain: Arc<dyn ChainContext>,
+  pub metrics: Arc<Metrics>,
+}
+
+impl AppState {
+  /// Quotes against `state_provider`, which should cache state across
+  /// requests, e.g. [`CachedStateProvider`] or [`SubscriptionStateProvider`].
+  ///
+  /// # Errors
+  /// * Metrics registration
+  ///
+  /// [`CachedStateProvider`]: hylo_quotes::prelude::CachedStateProvider
+  /// [`SubscriptionStateProvider`]: hylo_quotes::prelude::SubscriptionStateProvider
+  pub fn new(
+    state_provider: SharedStateProvider,
+    chain: Arc<dyn ChainContext>,
+  ) -> Result<AppState> {
+    Ok(AppState {
+      strategy: Arc::new(ProtocolStateStrategy::new(state_provider)),
+      chain,
+      metrics: Arc::new(Metrics::new()?),
+    })
+  }
+}
+
+/// Routes of the quote API.
+pub fn router(state: AppState) -> Router {
+  Router::new()
+    .route("/quote", get(routes::quote))
+    .route("/state", get(routes::state))
+    .route("/stats", get(routes::stats))
+    .route("/metrics", get(routes::metrics))
+    .with_state(state)
+}
@@ -0,0 +1,76 @@
+//! `hylo-quote-server`
+//!
+//! ```text
+//! hylo-quote-server --rpc-url <URL> --ws-url <WS_URL> --bind 0.0.0.0:8080
+//! hylo-quote-server --snapshot protocol-state.json
+//! ```
+
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+
+use anchor_client::solana_client::nonblocking::rpc_client::RpcClient;
+use anchor_client::solana_sdk::commitment_config::CommitmentConfig;
+use anyhow::Result;
+use clap::Parser;
+use hylo_quote_server::chain::RpcChainContext;
+use hylo_quote_server::{router, AppState, SharedStateProvider};
+use hylo_quotes::prelude::{
+  CachedStateProvider, FileStateProvider, RpcStateProvider,
+  SubscriptionStateProvider,
+};
+
+#[derive(Parser, Debug)]
+#[command(version, about = "HTTP quote service for Hylo protocol")]
+struct Args {
+  /// RPC endpoint for state, blockhashes and lookup tables
+  #[arg(long, default_value = "https://api.mainnet-beta.solana.com")]
+  rpc_url: String,
+
+  /// Websocket endpoint to stream state from instead of polling RPC
+  #[arg(long)]
+  ws_url: Option<String>,
+
+  /// Serve state from a snapshot file instead of the chain
+  #[arg(long, conflicts_with = "ws_url")]
+  snapshot: Option<PathBuf>,
+
+  /// Address to listen on
+  #[arg(long, default_value = "127.0.0.1:8080")]
+  bind: SocketAddr,
+
+  /// How long polled RPC state is reused across requests
+  #[arg(long, default_value_t = 1000)]
+  cache_ttl_ms: u64,
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+  let args = Args::parse();
+  let rpc = Arc::new(RpcClient::new_with_commitment(
+    args.rpc_url.clone(),
+    CommitmentConfig::confirmed(),
+  ));
+  let state_provider: SharedStateProvider = match (&args.snapshot, &args.ws_url)
+  {
+    (Some(path), _) => Arc::new(FileStateProvider::from_file(path.clone())?),
+    (None, Some(ws_url)) => Arc::new(
+      SubscriptionStateProvider::connect(
+        rpc.clone(),
+        ws_url.clone(),
+        Duration::from_secs(5),
+      )
+      .await?,
+    ),
+    (None, None) => Arc::new(CachedStateProvider::new(
+      RpcStateProvider::new(rpc.clone()),
+      Duration::from_millis(args.cache_ttl_ms),
+    )),
+  };
+  let app = AppState::new(state_provider, Arc::new(RpcChainContext::new(rpc)))?;
+  let listener = tokio::net::TcpListener::bind(args.bind).await?;
+  println!("Serving quotes on {}", listener.local_addr()?);
+  axum::serve(listener, router(app)).await?;
+  Ok(())
+}
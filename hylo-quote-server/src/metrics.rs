@@ -0,0 +1,72 @@
+//! Prometheus metrics for served quotes
+
+use std::time::Duration;
+
+use anyhow::Result;
+use hylo_quotes::prelude::Operation;
+use prometheus::{
+  HistogramOpts, HistogramVec, IntCounterVec, Opts, Registry, TextEncoder,
+};
+
+/// Label for quotes that failed before an operation was resolved.
+const UNKNOWN_OPERATION: &str = "unknown";
+
+/// Quote counters and latencies, labelled by [`Operation`].
+pub struct Metrics {
+  registry: Registry,
+  quotes: IntCounterVec,
+  quote_duration: HistogramVec,
+}
+
+impl Metrics {
+  /// Registers the quote metrics in a fresh registry.
+  ///
+  /// # Errors
+  /// * Invalid metric definition
+  pub fn new() -> Result<Metrics> {
+    let registry = Registry::new();
+    let quotes = IntCounterVec::new(
+      Opts::new("hylo_quote_requests_total", "Quote requests served"),
+      &["operation", "result"],
+    )?;
+    let quote_duration = HistogramVec::new(
+      HistogramOpts::new(
+        "hylo_quote_duration_seconds",
+        "Time to compute a quote and build its transaction",
+      ),
+      &["operation"],
+    )?;
+    registry.register(Box::new(quotes.clone()))?;
+    registry.register(Box::new(quote_duration.clone()))?;
+    Ok(Metrics {
+      registry,
+      quotes,
+      quote_duration,
+    })
+  }
+
+  /// Records one quote request, `operation` being `None` when the request
+  /// failed before a route was found.
+  pub fn observe_quote(
+    &self,
+    operation: Option<Operation>,
+    success: bool,
+    elapsed: Duration,
+  ) {
+    let operation = operation.map_or(UNKNOWN_OPERATION, |op| op.as_str());
+    let result = if success { "ok" } else { "error" };
+    self.quotes.with_label_values(&[operation, result]).inc();
+    self
+      .quote_duration
+      .with_label_values(&[operation])
+      .observe(elapsed.as_secs_f64());
+  }
+
+  /// Metrics in the Prometheus text exposition format.
+  ///
+  /// # Errors
+  /// * Encoding failure
+  pub fn render(&self) -> Result<String> {
+    Ok(TextEncoder::new().encode_to_string(&self.registry.gather())?)
+  }
+}
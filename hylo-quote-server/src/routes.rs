@@ -0,0 +1,226 @@
+//! Request handlers
+
+use std::str::FromStr;
+use std::time::Instant;
+
+use anchor_client::solana_sdk::pubkey::Pubkey;
+use anchor_spl::token::Mint;
+use anyhow::Result;
+use axum::extract::rejection::QueryRejection;
+use axum::extract::{Query, State};
+use axum::http::{header, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+use base64::prelude::{Engine, BASE64_STANDARD};
+use fix::prelude::UFixValue64;
+use hylo_clients::prelude::{FeePolicy, VersionedTransactionData};
+use hylo_clients::util::{build_unsigned_v0_transaction, REFERENCE_WALLET};
+use hylo_core::solana_clock::SolanaClock;
+use hylo_quotes::prelude::{
+  ExecutableQuoteValue, Operation, QuoteMetadata, RuntimeQuoteStrategy,
+  ScenarioMetrics, StateProvider,
+};
+use solana_compute_budget_interface::ComputeBudgetInstruction;
+
+use crate::api::{
+  ErrorResponse, LstSummary, PoolSummary, QuoteParams, QuoteResponse,
+  StateResponse, StatsResponse,
+};
+use crate::AppState;
+
+/// Error rendered as a JSON [`ErrorResponse`].
+pub(crate) struct ApiError {
+  status: StatusCode,
+  message: String,
+}
+
+impl ApiError {
+  fn bad_request(message: impl Into<String>) -> ApiError {
+    ApiError {
+      status: StatusCode::BAD_REQUEST,
+      message: message.into(),
+    }
+  }
+
+  fn unprocessable(error: &anyhow::Error) -> ApiError {
+    ApiError {
+      status: StatusCode::UNPROCESSABLE_ENTITY,
+      message: error.to_string(),
+    }
+  }
+}
+
+impl From<anyhow::Error> for ApiError {
+  fn from(error: anyhow::Error) -> ApiError {
+    ApiError {
+      status: StatusCode::INTERNAL_SERVER_ERROR,
+      message: error.to_string(),
+    }
+  }
+}
+
+impl IntoResponse for ApiError {
+  fn into_response(self) -> Response {
+    let body = ErrorResponse {
+      error: self.message,
+    };
+    (self.status, Json(body)).into_response()
+  }
+}
+
+fn parse_pubkey(name: &str, value: &str) -> Result<Pubkey, ApiError> {
+  Pubkey::from_str(value)
+    .map_err(|_| ApiError::bad_request(format!("Invalid {name}: {value}")))
+}
+
+/// `GET /quote`
+pub(crate) async fn quote(
+  State(app): State<AppState>,
+  params: Result<Query<QuoteParams>, QueryRejection>,
+) -> Result<Json<QuoteResponse>, ApiError> {
+  let start = Instant::now();
+  let result = match params {
+    Ok(Query(params)) => quote_response(&app, &params).await,
+    Err(rejection) => Err(ApiError::bad_request(rejection.body_text())),
+  };
+  let operation = result.as_ref().ok().map(|(_, operation)| *operation);
+  app
+    .metrics
+    .observe_quote(operation, result.is_ok(), start.elapsed());
+  result.map(|(response, _)| Json(response))
+}
+
+async fn quote_response(
+  app: &AppState,
+  params: &QuoteParams,
+) -> Result<(QuoteResponse, Operation), ApiError> {
+  let input_mint = parse_pubkey("inputMint", &params.input_mint)?;
+  let output_mint = parse_pubkey("outputMint", &params.output_mint)?;
+  let user = params
+    .user
+    .as_deref()
+    .map(|user| parse_pubkey("user", user))
+    .transpose()?;
+  let (
+    quote,
+    QuoteMetadata {
+      operation,
+      description,
+    },
+  ) = app
+    .strategy
+    .runtime_quote_with_metadata(
+      input_mint,
+      output_mint,
+      params.amount,
+      user.unwrap_or(REFERENCE_WALLET),
+      params.slippage_bps,
+    )
+    .await
+    .map_err(|e| ApiError::unprocessable(&e))?;
+  let transaction = match user {
+    Some(user) => Some(build_transaction(app, &user, &quote).await?),
+    None => None,
+  };
+  let response = QuoteResponse {
+    input_mint: input_mint.to_string(),
+    output_mint: output_mint.to_string(),
+    amount_in: quote.amount_in,
+    amount_out: quote.amount_out,
+    fee_amount: quote.fee_amount,
+    fee_mint: quote.fee_mint.to_string(),
+    operation: operation.to_string(),
+    description,
+    compute_units: quote.compute_units,
+    compute_unit_strategy: format!("{:?}", quote.compute_unit_strategy),
+    transaction,
+  };
+  Ok((response, operation))
+}
+
+/// Base64 unsigned transaction executing `quote` for `user`.
+///
+/// The compute unit limit is padded like clients pad it under
+/// [`FeePolicy::UNPRICED`], so served transactions match client-built ones.
+async fn build_transaction(
+  app: &AppState,
+  user: &Pubkey,
+  quote: &ExecutableQuoteValue,
+) -> Result<String> {
+  let lookup_tables = app
+    .chain
+    .lookup_tables(&quote.address_lookup_tables)
+    .await?;
+  let instructions =
+    std::iter::once(ComputeBudgetInstruction::set_compute_unit_limit(
+      FeePolicy::UNPRICED.compute_unit_limit(quote.compute_units)?,
+    ))
+    .chain(quote.instructions.iter().cloned())
+    .collect();
+  let vtd = VersionedTransactionData::new(instructions, lookup_tables);
+  let blockhash = app.chain.latest_blockhash().await?;
+  let tx = build_unsigned_v0_transaction(&vtd, user, blockhash)?;
+  Ok(BASE64_STANDARD.encode(bincode::serialize(&tx)?))
+}
+
+/// Token `amount` in base units of `mint`.
+fn token_amount(amount: u64, mint: &Mint) -> Result<UFixValue64> {
+  let exp = -i8::try_from(mint.decimals)?;
+  Ok(UFixValue64::new(amount, exp))
+}
+
+/// `GET /state`
+pub(crate) async fn state(
+  State(app): State<AppState>,
+) -> Result<Json<StateResponse>, ApiError> {
+  let state = app.strategy.state_provider.fetch_state().await?;
+  let ctx = &state.exchange_context;
+  let lsts = state
+    .lst_headers
+    .values()
+    .map(|header| LstSummary {
+      mint: header.mint.to_string(),
+      price_sol: header.price_sol.price.into(),
+      price_epoch: header.price_sol.epoch,
+    })
+    .collect();
+  let stability_pool = PoolSummary {
+    stablecoin: token_amount(state.hyusd_pool.amount, &state.hyusd_mint)?,
+    levercoin: token_amount(state.xsol_pool.amount, &state.xsol_mint)?,
+    withdrawal_fee: state.pool_config.withdrawal_fee.into(),
+  };
+  Ok(Json(StateResponse {
+    slot: ctx.clock.slot(),
+    epoch: ctx.clock.epoch(),
+    fetched_at: state.fetched_at,
+    stability_mode: format!("{:?}", ctx.stability_mode),
+    total_sol: ctx.total_sol,
+    stablecoin_supply: token_amount(
+      state.hyusd_mint.supply,
+      &state.hyusd_mint,
+    )?,
+    levercoin_supply: token_amount(state.xsol_mint.supply, &state.xsol_mint)?,
+    lp_token_supply: token_amount(
+      state.shyusd_mint.supply,
+      &state.shyusd_mint,
+    )?,
+    stability_pool,
+    lsts,
+  }))
+}
+
+/// `GET /stats`
+pub(crate) async fn stats(
+  State(app): State<AppState>,
+) -> Result<Json<StatsResponse>, ApiError> {
+  let state = app.strategy.state_provider.fetch_state().await?;
+  Ok(Json(ScenarioMetrics::from_state(&state)?.into()))
+}
+
+/// `GET /metrics`
+pub(crate) async fn metrics(
+  State(app): State<AppState>,
+) -> Result<impl IntoResponse, ApiError> {
+  let body = app.metrics.render()?;
+  Ok(([(header::CONTENT_TYPE, "text/plain; version=0.0.4")], body))
+}
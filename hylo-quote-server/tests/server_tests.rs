@@ -0,0 +1,188 @@
+//! Quote API tests against the fixture state.
+
+use std::sync::Arc;
+
+use anchor_client::solana_sdk::address_lookup_table::AddressLookupTableAccount;
+use anchor_client::solana_sdk::hash::Hash;
+use anchor_client::solana_sdk::transaction::VersionedTransaction;
+use anchor_lang::prelude::Pubkey;
+use anyhow::Result;
+use async_trait::async_trait;
+use axum::body::Body;
+use axum::http::{Request, StatusCode};
+use axum::Router;
+use base64::prelude::{Engine, BASE64_STANDARD};
+use fix::prelude::UFixValue64;
+use http_body_util::BodyExt;
+use hylo_clients::prelude::FeePolicy;
+use hylo_quote_server::api::{
+  ErrorResponse, QuoteResponse, StateResponse, StatsResponse,
+};
+use hylo_quote_server::chain::ChainContext;
+use hylo_quote_server::{router, AppState};
+use hylo_quotes::prelude::{TokenMint, HYUSD, JITOSOL};
+use hylo_quotes::test_fixtures::fixture_provider;
+use serde::de::DeserializeOwned;
+use solana_compute_budget_interface::ComputeBudgetInstruction;
+use tower::ServiceExt;
+
+const USER: &str = "GDNtJq5YAZnfjmBuqr7xMaa2WCjxgYujvsMB1Fp3TMo2";
+
+/// Chain context serving empty lookup tables and a fixed blockhash.
+struct StaticChain;
+
+#[async_trait]
+impl ChainContext for StaticChain {
+  async fn latest_blockhash(&self) -> Result<Hash> {
+    Ok(Hash::default())
+  }
+
+  async fn lookup_tables(
+    &self,
+    addresses: &[Pubkey],
+  ) -> Result<Vec<AddressLookupTableAccount>> {
+    Ok(
+      addresses
+        .iter()
+        .map(|key| AddressLookupTableAccount {
+          key: *key,
+          addresses: vec![],
+        })
+        .collect(),
+    )
+  }
+}
+
+fn app() -> Result<Router> {
+  let provider = fixture_provider()?;
+  let state = AppState::new(Arc::new(provider), Arc::new(StaticChain))?;
+  Ok(router(state))
+}
+
+async fn get(app: &Router, uri: &str) -> Result<(StatusCode, Vec<u8>)> {
+  let response = app
+    .clone()
+    .oneshot(Request::get(uri).body(Body::empty())?)
+    .await?;
+  let status = response.status();
+  let body = response.into_body().collect().await?.to_bytes();
+  Ok((status, body.to_vec()))
+}
+
+async fn get_json<T: DeserializeOwned>(
+  app: &Router,
+  uri: &str,
+) -> Result<(StatusCode, T)> {
+  let (status, body) = get(app, uri).await?;
+  Ok((status, serde_json::from_slice(&body)?))
+}
+
+fn quote_uri(amount: u64, user: Option<&str>) -> String {
+  let uri = format!(
+    "/quote?inputMint={}&outputMint={}&amount={amount}&slippageBps=50",
+    JITOSOL::MINT,
+    HYUSD::MINT
+  );
+  match user {
+    Some(user) => format!("{uri}&user={user}"),
+    None => uri,
+  }
+}
+
+#[tokio::test]
+async fn quote_with_transaction() -> Result<()> {
+  let app = app()?;
+  let (status, quote) =
+    get_json::<QuoteResponse>(&app, &quote_uri(1_000_000_000, Some(USER)))
+      .await?;
+  assert_eq!(status, StatusCode::OK);
+  assert_eq!(quote.operation, "mint_stablecoin");
+  assert_eq!(quote.amount_in, UFixValue64::new(1_000_000_000, -9));
+  assert_eq!(quote.amount_out.exp, -6);
+  assert!(quote.compute_units > 0);
+  let tx: VersionedTransaction = bincode::deserialize(
+    &BASE64_STANDARD.decode(quote.transaction.expect("transaction"))?,
+  )?;
+  assert_eq!(tx.message.static_account_keys()[0].to_string(), USER);
+  let limit = ComputeBudgetInstruction::set_compute_unit_limit(
+    FeePolicy::UNPRICED.compute_unit_limit(quote.compute_units)?,
+  );
+  assert_eq!(tx.message.instructions()[0].data, limit.data);
+  Ok(())
+}
+
+#[tokio::test]
+async fn quote_without_user_skips_transaction() -> Result<()> {
+  let app = app()?;
+  let (status, quote) =
+    get_json::<QuoteResponse>(&app, &quote_uri(1_000_000_000, None)).await?;
+  assert_eq!(status, StatusCode::OK);
+  assert!(quote.transaction.is_none());
+  Ok(())
+}
+
+#[tokio::test]
+async fn rejects_invalid_params() -> Result<()> {
+  let app = app()?;
+  let (status, error) = get_json::<ErrorResponse>(
+    &app,
+    &format!("/quote?inputMint=sol&outputMint={}&amount=1", HYUSD::MINT),
+  )
+  .await?;
+  assert_eq!(status, StatusCode::BAD_REQUEST);
+  assert_eq!(error.error, "Invalid inputMint: sol");
+
+  let (status, _) =
+    get_json::<ErrorResponse>(&app, "/quote?inputMint=x").await?;
+  assert_eq!(status, StatusCode::BAD_REQUEST);
+
+  let (status, _) = get_json::<ErrorResponse>(
+    &app,
+    &format!(
+      "/quote?inputMint={}&outputMint={}&amount=1",
+      HYUSD::MINT,
+      HYUSD::MINT
+    ),
+  )
+  .await?;
+  assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+  Ok(())
+}
+
+#[tokio::test]
+async fn state_and_stats() -> Result<()> {
+  let app = app()?;
+  let (status, state) = get_json::<StateResponse>(&app, "/state").await?;
+  assert_eq!(status, StatusCode::OK);
+  assert!(state
+    .lsts
+    .iter()
+    .any(|lst| lst.mint == JITOSOL::MINT.to_string()));
+
+  let (status, stats) = get_json::<StatsResponse>(&app, "/stats").await?;
+  assert_eq!(status, StatusCode::OK);
+  assert_eq!(stats.stability_mode, state.stability_mode);
+  assert_eq!(
+    UFixValue64::from(stats.stablecoin_supply),
+    state.stablecoin_supply
+  );
+  Ok(())
+}
+
+#[tokio::test]
+async fn metrics_count_quotes_per_operation() -> Result<()> {
+  let app = app()?;
+  get(&app, &quote_uri(1_000_000_000, None)).await?;
+  get(&app, "/quote?inputMint=x").await?;
+  let (status, body) = get(&app, "/metrics").await?;
+  let body = String::from_utf8(body)?;
+  assert_eq!(status, StatusCode::OK);
+  assert!(body.contains(
+    "hylo_quote_requests_total{operation=\"mint_stablecoin\",result=\"ok\"} 1"
+  ));
+  assert!(body.contains(
+    "hylo_quote_requests_total{operation=\"unknown\",result=\"error\"} 1"
+  ));
+  assert!(body.contains("hylo_quote_duration_seconds_count"));
+  Ok(())
+}
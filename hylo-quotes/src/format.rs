@@ -0,0 +1,333 @@
+//! Decimal conversion and human-readable formatting of fixed point amounts.
+//!
+//! Token amounts convert to and from [`Decimal`] at their [`TokenMint`]
+//! exponent, so callers never scale raw bits by hand:
+//!
+//! ```rust
+//! use hylo_quotes::format::{parse_amount, TokenAmount};
+//! use hylo_quotes::prelude::*;
+//!
+//! let amount = parse_amount::<JITOSOL>("1.5 JitoSOL")?;
+//! assert_eq!(amount, UFix64::<N9>::new(1_500_000_000));
+//! assert_eq!(TokenAmount::new::<JITOSOL>(amount)?.to_string(), "1.5 JitoSOL");
+//! # Ok::<(), anyhow::Error>(())
+//! ```
+//!
+//! [`Usd`] and [`Percent`] render NAVs, prices and fees. Like every type
+//! here they honour format precision, e.g. `format!("{:.2}", Usd::new(nav)?)`.
+
+use std::fmt::{self, Display, Formatter};
+use std::str::FromStr;
+
+use anchor_lang::prelude::Pubkey;
+use anyhow::{anyhow, ensure, Context, Result};
+use fix::prelude::{UFix64, UFixValue64};
+use fix::typenum::Integer;
+use hylo_core::util::decimal_string;
+use hylo_idl::tokens::{symbol, TokenMint};
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::{Decimal, RoundingStrategy};
+
+/// Exact [`Decimal`] value of a fixed point number.
+///
+/// # Errors
+/// * Value out of `Decimal` range or precision
+pub fn to_decimal(value: impl Into<UFixValue64>) -> Result<Decimal> {
+  let UFixValue64 { bits, exp } = value.into();
+  let scale = u32::from(exp.unsigned_abs());
+  if exp <= 0 {
+    Decimal::try_from_i128_with_scale(i128::from(bits), scale)
+      .map_err(|_| anyhow!("{bits}e{exp} exceeds decimal precision"))
+  } else {
+    10u64
+      .checked_pow(scale)
+      .and_then(|factor| Decimal::from(bits).checked_mul(factor.into()))
+      .ok_or_else(|| anyhow!("{bits}e{exp} exceeds decimal range"))
+  }
+}
+
+/// Fixed point value of `value` at exponent `Exp`, without rounding.
+///
+/// # Errors
+/// * Negative value
+/// * More decimal places than `Exp` holds
+/// * Out of `u64` range
+pub fn from_decimal<Exp: Integer>(value: Decimal) -> Result<UFix64<Exp>> {
+  ensure!(!value.is_sign_negative(), "{value} is negative");
+  let exp = Exp::to_i8();
+  let factor = Decimal::from(
+    10u64
+      .checked_pow(u32::from(exp.unsigned_abs()))
+      .context("Exponent out of range")?,
+  );
+  let scaled = if exp <= 0 {
+    value.checked_mul(factor)
+  } else {
+    value.checked_div(factor)
+  }
+  .ok_or_else(|| anyhow!("{value} out of range"))?;
+  ensure!(
+    scaled.fract().is_zero(),
+    "{value} has more precision than 10^{exp}"
+  );
+  let bits = scaled
+    .to_u64()
+    .ok_or_else(|| anyhow!("{value} out of range"))?;
+  Ok(UFix64::new(bits))
+}
+
+/// Parses an amount of `T`, either a bare number like `1.5` or followed by
+/// the token's [`token_label`] like `1.5 JitoSOL` (case-insensitive).
+///
+/// # Errors
+/// * Symbol other than `T`'s
+/// * Malformed number
+/// * Conversion, see [`from_decimal`]
+pub fn parse_amount<T: TokenMint>(s: &str) -> Result<UFix64<T::Exp>> {
+  let s = s.trim();
+  let number = match s.split_once(char::is_whitespace) {
+    Some((number, unit)) => {
+      let unit = unit.trim();
+      let label = token_label(&T::MINT);
+      ensure!(
+        unit.eq_ignore_ascii_case(&label),
+        "Expected {label} amount, found {unit}"
+      );
+      number
+    }
+    None => s,
+  };
+  let value = Decimal::from_str(number)
+    .map_err(|_| anyhow!("Invalid amount {number}"))?;
+  from_decimal(value)
+}
+
+/// Share of `base` taken by `fee`, e.g. `0.0025` for a 25 bps fee.
+/// Zero when `base` is zero.
+///
+/// # Errors
+/// * Amounts at different exponents
+/// * Arithmetic
+pub fn fee_ratio(
+  fee: impl Into<UFixValue64>,
+  base: impl Into<UFixValue64>,
+) -> Result<Decimal> {
+  let (fee, base) = (fee.into(), base.into());
+  ensure!(
+    fee.exp == base.exp,
+    "Fee exponent {} differs from base exponent {}",
+    fee.exp,
+    base.exp
+  );
+  if base.bits == 0 {
+    Ok(Decimal::ZERO)
+  } else {
+    Decimal::from(fee.bits)
+      .checked_div(Decimal::from(base.bits))
+      .context("Arithmetic error in `fee_ratio`")
+  }
+}
+
+/// Symbol of a known token, or its base58 mint otherwise.
+#[must_use]
+pub fn token_label(mint: &Pubkey) -> String {
+  symbol(mint).map_or_else(|| mint.to_string(), str::to_string)
+}
+
+/// Writes `value` rounded half away from zero to the formatter's precision,
+/// or exactly without trailing zeros.
+fn write_decimal(f: &mut Formatter<'_>, value: Decimal) -> fmt::Result {
+  match f.precision() {
+    Some(precision) => {
+      let rounded = value.round_dp_with_strategy(
+        u32::try_from(precision).unwrap_or(u32::MAX),
+        RoundingStrategy::MidpointAwayFromZero,
+      );
+      write!(f, "{rounded:.precision$}")
+    }
+    None => write!(f, "{}", value.normalize()),
+  }
+}
+
+/// Token amount with its symbol, displayed as `1.5 JitoSOL`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TokenAmount {
+  pub amount: Decimal,
+  pub symbol: String,
+}
+
+impl TokenAmount {
+  /// Amount of a typed token, labelled by [`token_label`].
+  ///
+  /// # Errors
+  /// * Decimal conversion
+  pub fn new<T: TokenMint>(amount: UFix64<T::Exp>) -> Result<TokenAmount> {
+    TokenAmount::from_mint(amount, &T::MINT)
+  }
+
+  /// Amount of `mint`, labelled by [`token_label`].
+  ///
+  /// # Errors
+  /// * Decimal conversion
+  pub fn from_mint(
+    amount: impl Into<UFixValue64>,
+    mint: &Pubkey,
+  ) -> Result<TokenAmount> {
+    Ok(TokenAmount {
+      amount: to_decimal(amount)?,
+      symbol: token_label(mint),
+    })
+  }
+}
+
+impl Display for TokenAmount {
+  fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+    write_decimal(f, self.amount)?;
+    write!(f, " {}", self.symbol)
+  }
+}
+
+/// US dollar value such as a NAV or oracle price, displayed as `$1.0234`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Usd(pub Decimal);
+
+impl Usd {
+  /// # Errors
+  /// * Decimal conversion
+  pub fn new(value: impl Into<UFixValue64>) -> Result<Usd> {
+    to_decimal(value).map(Usd)
+  }
+}
+
+impl Display for Usd {
+  fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+    f.write_str("$")?;
+    write_decimal(f, self.0)
+  }
+}
+
+/// Fraction such as a fee or collateral ratio, displayed as a percentage,
+/// e.g. `0.0025` as `0.25%`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Percent(pub Decimal);
+
+impl Percent {
+  /// # Errors
+  /// * Decimal conversion
+  pub fn new(ratio: impl Into<UFixValue64>) -> Result<Percent> {
+    to_decimal(ratio).map(Percent)
+  }
+}
+
+impl Display for Percent {
+  fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+    write_decimal(f, self.0.saturating_mul(Decimal::ONE_HUNDRED))?;
+    f.write_str("%")
+  }
+}
+
+/// Fixed point value displayed as an exact decimal. A format precision
+/// rounds it, within `Decimal` range.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Fixed(pub UFixValue64);
+
+impl Display for Fixed {
+  fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+    match (f.precision(), to_decimal(self.0)) {
+      (Some(_), Ok(value)) => write_decimal(f, value),
+      _ => f.write_str(&decimal_string(self.0)),
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use fix::prelude::*;
+  use hylo_idl::tokens::{HYUSD, JITOSOL};
+
+  use super::*;
+  use crate::token_operation::MintOperationOutput;
+
+  #[test]
+  fn converts_exactly() -> Result<()> {
+    let amount = UFix64::<N6>::new(1_234_500);
+    let value = to_decimal(amount)?;
+    assert_eq!(value, Decimal::new(12_345, 4));
+    assert_eq!(from_decimal::<N6>(value)?, amount);
+    assert_eq!(to_decimal(UFixValue64::new(3, 2))?, Decimal::from(300));
+    assert!(from_decimal::<N6>(Decimal::new(1, 7)).is_err());
+    assert!(from_decimal::<N6>(Decimal::new(-1, 0)).is_err());
+    assert!(from_decimal::<N9>(Decimal::from(u64::MAX)).is_err());
+    Ok(())
+  }
+
+  #[test]
+  fn parses_token_amounts() -> Result<()> {
+    let jitosol = UFix64::<N9>::new(1_500_000_000);
+    assert_eq!(parse_amount::<JITOSOL>("1.5 JitoSOL")?, jitosol);
+    assert_eq!(parse_amount::<JITOSOL>(" 1.5 jitosol ")?, jitosol);
+    assert_eq!(parse_amount::<JITOSOL>("1.5")?, jitosol);
+    assert!(parse_amount::<JITOSOL>("1.5 hyUSD").is_err());
+    assert!(parse_amount::<HYUSD>("0.0000001").is_err());
+    assert!(parse_amount::<HYUSD>("one").is_err());
+    Ok(())
+  }
+
+  #[test]
+  fn labels_unlisted_tokens_by_mint() -> Result<()> {
+    struct Unlisted;
+    impl TokenMint for Unlisted {
+      type Exp = N6;
+      const MINT: Pubkey = Pubkey::new_from_array([7; 32]);
+    }
+    let amount = UFix64::<N6>::new(2_500_000);
+    let label = Unlisted::MINT.to_string();
+    assert_eq!(parse_amount::<Unlisted>("2.5")?, amount);
+    assert_eq!(parse_amount::<Unlisted>(&format!("2.5 {label}"))?, amount);
+    assert!(parse_amount::<Unlisted>("2.5 hyUSD").is_err());
+    let display = TokenAmount::new::<Unlisted>(amount)?.to_string();
+    assert_eq!(display, format!("2.5 {label}"));
+    Ok(())
+  }
+
+  #[test]
+  fn displays_amounts_prices_and_fees() -> Result<()> {
+    let amount = TokenAmount::new::<HYUSD>(UFix64::new(154_211_899))?;
+    assert_eq!(amount.to_string(), "154.211899 hyUSD");
+    assert_eq!(format!("{amount:.2}"), "154.21 hyUSD");
+    let unknown = Pubkey::new_unique();
+    assert_eq!(
+      TokenAmount::from_mint(UFix64::<N9>::new(1), &unknown)?.to_string(),
+      format!("0.000000001 {unknown}")
+    );
+    let nav = Usd::new(UFix64::<N9>::new(1_023_456_789))?;
+    assert_eq!(nav.to_string(), "$1.023456789");
+    assert_eq!(format!("{nav:.4}"), "$1.0235");
+    assert_eq!(Percent::new(UFix64::<N4>::new(25))?.to_string(), "0.25%");
+    Ok(())
+  }
+
+  #[test]
+  fn displays_operation_output() {
+    let op = MintOperationOutput {
+      in_amount: UFix64::new(1_000_000_000),
+      out_amount: UFix64::new(154_211_899),
+      fee_amount: UFix64::new(1_000_000),
+      fee_mint: JITOSOL::MINT,
+      fee_base: UFix64::new(1_000_000_000),
+    };
+    assert_eq!(op.to_string(), "1 -> 154.211899, fee 0.001 JitoSOL (0.1%)");
+  }
+
+  #[test]
+  fn computes_fee_ratio() -> Result<()> {
+    let fee = UFix64::<N9>::new(5);
+    assert_eq!(
+      fee_ratio(fee, UFix64::<N9>::new(1_000))?,
+      Decimal::new(5, 3)
+    );
+    assert_eq!(fee_ratio(fee, UFix64::<N9>::new(0))?, Decimal::ZERO);
+    assert!(fee_ratio(fee, UFix64::<N6>::new(1)).is_err());
+    Ok(())
+  }
+}
@@ -97,21 +97,43 @@
 //! # Ok(())
 //! # }
 //! ```
+//!
+//! # Features
+//!
+//! - **`serde`**: `Serialize` and `Deserialize` for quotes, operation outputs,
+//!   [`QuoteMetadata`] and [`protocol_state::ProtocolState`], following the
+//!   JSON schema documented in the `serde_schema` module.
+//! - **`test-fixtures`**: `test_fixtures` module loading the protocol state
+//!   snapshot the workspace test suites run against.
 
 use anchor_client::solana_sdk::instruction::Instruction;
 use anchor_lang::prelude::Pubkey;
+use anyhow::Result;
 use fix::prelude::{UFix64, UFixValue64};
 use fix::typenum::Integer;
+use hylo_clients::prelude::{ProgramClient, VersionedTransactionData};
+use hylo_core::solana_clock::SolanaClock;
 use hylo_idl::tokens::{HYLOSOL, JITOSOL};
 
+use crate::protocol_state::{ProtocolState, PRICE_UPDATE_CUS};
+
+pub mod format;
+pub mod portfolio;
 pub mod prelude;
 pub mod protocol_state;
 mod protocol_state_strategy;
 mod quote_metadata;
 mod quote_strategy;
+pub mod route_planner;
 mod runtime_quote_strategy;
+pub mod scenario;
+#[cfg(feature = "serde")]
+pub mod serde_schema;
 pub mod simulated_operation;
 mod simulation_strategy;
+pub mod state_transition;
+#[cfg(feature = "test-fixtures")]
+pub mod test_fixtures;
 pub mod token_operation;
 
 pub use hylo_clients::util::LST;
@@ -149,14 +171,39 @@ pub struct ExecutableQuote<InExp: Integer, OutExp: Integer, FeeExp: Integer> {
 
 /// Executable quote with runtime exponent information.
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ExecutableQuoteValue {
+  #[cfg_attr(
+    feature = "serde",
+    serde(with = "hylo_core::serde_schema::ufix_value64")
+  )]
   pub amount_in: UFixValue64,
+  #[cfg_attr(
+    feature = "serde",
+    serde(with = "hylo_core::serde_schema::ufix_value64")
+  )]
   pub amount_out: UFixValue64,
   pub compute_units: u64,
   pub compute_unit_strategy: ComputeUnitStrategy,
+  #[cfg_attr(
+    feature = "serde",
+    serde(with = "hylo_core::serde_schema::ufix_value64")
+  )]
   pub fee_amount: UFixValue64,
+  #[cfg_attr(
+    feature = "serde",
+    serde(with = "hylo_core::serde_schema::pubkey")
+  )]
   pub fee_mint: Pubkey,
+  #[cfg_attr(
+    feature = "serde",
+    serde(with = "crate::serde_schema::instructions")
+  )]
   pub instructions: Vec<Instruction>,
+  #[cfg_attr(
+    feature = "serde",
+    serde(with = "hylo_core::serde_schema::pubkeys")
+  )]
   pub address_lookup_tables: Vec<Pubkey>,
 }
 
@@ -179,7 +226,98 @@ impl<InExp: Integer, OutExp: Integer, FeeExp: Integer>
   }
 }
 
+impl<InExp: Integer, OutExp: Integer, FeeExp: Integer> QuotedTransaction
+  for ExecutableQuote<InExp, OutExp, FeeExp>
+{
+  fn transaction_parts(
+    &mut self,
+  ) -> (&mut Vec<Instruction>, &mut Vec<Pubkey>, &mut u64) {
+    (
+      &mut self.instructions,
+      &mut self.address_lookup_tables,
+      &mut self.compute_units,
+    )
+  }
+}
+
+impl ExecutableQuoteValue {
+  /// Loads the quote's lookup tables through `client` into transaction data
+  /// carrying the quote's compute unit estimate.
+  ///
+  /// # Errors
+  /// * Failed to load a lookup table
+  pub async fn transaction_data<C: ProgramClient + Sync>(
+    self,
+    client: &C,
+  ) -> Result<VersionedTransactionData> {
+    transaction_data(
+      client,
+      self.instructions,
+      &self.address_lookup_tables,
+      self.compute_units,
+    )
+    .await
+  }
+}
+
+impl QuotedTransaction for ExecutableQuoteValue {
+  fn transaction_parts(
+    &mut self,
+  ) -> (&mut Vec<Instruction>, &mut Vec<Pubkey>, &mut u64) {
+    (
+      &mut self.instructions,
+      &mut self.address_lookup_tables,
+      &mut self.compute_units,
+    )
+  }
+}
+
+/// Quote carrying the instructions, lookup tables and compute units of its
+/// transaction.
+pub(crate) trait QuotedTransaction: Sized {
+  fn transaction_parts(
+    &mut self,
+  ) -> (&mut Vec<Instruction>, &mut Vec<Pubkey>, &mut u64);
+
+  /// Runs the pending `update_lst_prices` of a projected `state` first, paid
+  /// by `payer`.
+  ///
+  /// # Errors
+  /// * Malformed LST registry
+  fn with_price_update<C: SolanaClock>(
+    mut self,
+    state: &ProtocolState<C>,
+    payer: Pubkey,
+  ) -> Result<Self> {
+    if let Some(update) = &state.pending_price_update {
+      let (instructions, address_lookup_tables, compute_units) =
+        self.transaction_parts();
+      update.prepend(payer, instructions, address_lookup_tables)?;
+      *compute_units += PRICE_UPDATE_CUS;
+    }
+    Ok(self)
+  }
+}
+
+/// Transaction data from quoted instructions, with lookup tables loaded
+/// through `client` and the quoted compute units attached.
+pub(crate) async fn transaction_data<C: ProgramClient + Sync>(
+  client: &C,
+  instructions: Vec<Instruction>,
+  address_lookup_tables: &[Pubkey],
+  compute_units: u64,
+) -> Result<VersionedTransactionData> {
+  let lookup_tables = client
+    .load_multiple_lookup_tables(address_lookup_tables)
+    .await?;
+  Ok(
+    VersionedTransactionData::new(instructions, lookup_tables)
+      .with_compute_units(compute_units),
+  )
+}
+
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum ComputeUnitStrategy {
   /// Estimated compute units based on historical data
   Estimated,
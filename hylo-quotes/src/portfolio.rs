@@ -0,0 +1,350 @@
+//! Wallet positions in Hylo tokens and their value
+//!
+//! Reads a wallet's hyUSD, xSOL, sHYUSD and registered LST token accounts and
+//! values them against a [`ProtocolState`]:
+//!
+//! * hyUSD at its NAV and xSOL at its redeem NAV
+//! * sHYUSD at the LP token NAV, decomposed into its pro-rata share of the
+//!   hyUSD and xSOL held by the stability pool
+//! * LSTs at their epoch SOL price and the lower SOL/USD oracle bound
+//!
+//! Every position also reports the LST it redeems for, net of the fees the
+//! protocol charges in its current stability mode.
+//!
+//! ```rust,no_run
+//! use std::sync::Arc;
+//!
+//! use anchor_client::solana_client::nonblocking::rpc_client::RpcClient;
+//! use hylo_quotes::prelude::*;
+//!
+//! # async fn example(owner: Pubkey) -> anyhow::Result<()> {
+//! let rpc_client = Arc::new(RpcClient::new(
+//!   "https://api.mainnet-beta.solana.com".to_string(),
+//! ));
+//! let state = RpcStateProvider::new(rpc_client.clone()).fetch_state().await?;
+//! let portfolio =
+//!   Portfolio::fetch(&rpc_client, &state, owner, JITOSOL::MINT).await?;
+//! println!("{}", Usd::new(portfolio.total_usd_value()?)?);
+//! # Ok(())
+//! # }
+//! ```
+
+use std::collections::BTreeMap;
+
+use anchor_client::solana_client::nonblocking::rpc_client::RpcClient;
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::AccountDeserialize;
+use anchor_spl::associated_token::get_associated_token_address;
+use anchor_spl::token::TokenAccount;
+use anyhow::{anyhow, Context, Result};
+use fix::prelude::*;
+use fix::typenum::Integer;
+use hylo_core::solana_clock::SolanaClock;
+use hylo_core::stability_pool_math::{amount_token_to_withdraw, lp_token_nav};
+use hylo_idl::pda;
+
+use crate::protocol_state::ProtocolState;
+use crate::token_operation::RedeemOperationOutput;
+
+/// Raw token balances of a wallet, zero for absent token accounts.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct WalletBalances {
+  pub hyusd: UFix64<N6>,
+  pub xsol: UFix64<N6>,
+  pub shyusd: UFix64<N6>,
+  /// Balance of each LST, keyed by mint
+  pub lsts: BTreeMap<Pubkey, UFix64<N9>>,
+}
+
+impl WalletBalances {
+  /// Reads the associated token accounts of `owner` for the protocol tokens
+  /// and each of `lst_mints` in a single `getMultipleAccounts`.
+  ///
+  /// # Errors
+  /// * RPC failure
+  /// * Token account deserialization
+  pub async fn fetch(
+    rpc_client: &RpcClient,
+    owner: Pubkey,
+    lst_mints: impl IntoIterator<Item = Pubkey>,
+  ) -> Result<WalletBalances> {
+    let lst_mints = lst_mints.into_iter().collect::<Vec<_>>();
+    let pubkeys = [
+      pda::hyusd_ata(owner),
+      pda::xsol_ata(owner),
+      pda::shyusd_ata(owner),
+    ]
+    .into_iter()
+    .chain(
+      lst_mints
+        .iter()
+        .map(|mint| get_associated_token_address(&owner, mint)),
+    )
+    .collect::<Vec<_>>();
+    let accounts = rpc_client
+      .get_multiple_accounts(&pubkeys)
+      .await
+      .map_err(|e| anyhow!("Failed to fetch accounts from RPC: {e}"))?;
+    let amounts = accounts
+      .iter()
+      .zip(&pubkeys)
+      .map(|(account, pubkey)| match account {
+        Some(account) => TokenAccount::try_deserialize(&mut &account.data[..])
+          .map(|token_account| token_account.amount)
+          .with_context(|| format!("Invalid token account {pubkey}")),
+        None => Ok(0),
+      })
+      .collect::<Result<Vec<_>>>()?;
+    let (protocol, lsts) = amounts.split_at(3);
+    Ok(WalletBalances {
+      hyusd: UFix64::new(protocol[0]),
+      xsol: UFix64::new(protocol[1]),
+      shyusd: UFix64::new(protocol[2]),
+      lsts: lst_mints
+        .into_iter()
+        .zip(lsts.iter().map(|amount| UFix64::new(*amount)))
+        .collect(),
+    })
+  }
+}
+
+/// Holding of a single token.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Position<Exp: Integer> {
+  pub amount: UFix64<Exp>,
+  /// USD value at current NAVs, before fees
+  pub usd_value: UFix64<N6>,
+  /// LST received for the whole position after fees, `None` if the
+  /// protocol can't currently redeem it, e.g. in a restricted stability mode
+  pub redeemable: Option<UFix64<N9>>,
+}
+
+/// sHYUSD holding with its claim on the stability pool.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct StabilityPoolPosition {
+  pub position: Position<N6>,
+  /// hyUSD withdrawn for the whole position, before the withdrawal fee
+  pub hyusd_share: UFix64<N6>,
+  /// xSOL withdrawn for the whole position
+  pub xsol_share: UFix64<N6>,
+}
+
+/// LST holding.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LstPosition {
+  pub mint: Pubkey,
+  pub position: Position<N9>,
+}
+
+/// Valued positions of a wallet, redeemable amounts denominated in
+/// `redeem_lst`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Portfolio {
+  pub owner: Pubkey,
+  pub redeem_lst: Pubkey,
+  pub hyusd: Position<N6>,
+  pub xsol: Position<N6>,
+  pub shyusd: StabilityPoolPosition,
+  /// Registered LSTs held by the wallet
+  pub lsts: Vec<LstPosition>,
+}
+
+impl Portfolio {
+  /// Fetches the balances of `owner` and values them against `state`.
+  ///
+  /// # Errors
+  /// * Balance fetch, see [`WalletBalances::fetch`]
+  /// * Valuation, see [`Portfolio::new`]
+  pub async fn fetch<C: SolanaClock>(
+    rpc_client: &RpcClient,
+    state: &ProtocolState<C>,
+    owner: Pubkey,
+    redeem_lst: Pubkey,
+  ) -> Result<Portfolio> {
+    let balances =
+      WalletBalances::fetch(rpc_client, owner, state.lst_mints().copied())
+        .await?;
+    Portfolio::new(state, owner, redeem_lst, &balances)
+  }
+
+  /// Values `balances` against `state`, quoting redemptions in `redeem_lst`.
+  ///
+  /// # Errors
+  /// * `redeem_lst` or a held LST is not registered
+  /// * Outdated LST price
+  /// * NAV or arithmetic failure
+  pub fn new<C: SolanaClock>(
+    state: &ProtocolState<C>,
+    owner: Pubkey,
+    redeem_lst: Pubkey,
+    balances: &WalletBalances,
+  ) -> Result<Portfolio> {
+    state.lst_header_by_mint(&redeem_lst)?;
+    let ctx = &state.exchange_context;
+    let hyusd = Position {
+      amount: balances.hyusd,
+      usd_value: usd_value(balances.hyusd, ctx.stablecoin_nav()?)?,
+      redeemable: redeemable(balances.hyusd, |amount| {
+        state.redeem_stablecoin_output(redeem_lst, amount)
+      }),
+    };
+    let xsol = Position {
+      amount: balances.xsol,
+      usd_value: usd_value(balances.xsol, ctx.levercoin_redeem_nav()?)?,
+      redeemable: redeemable(balances.xsol, |amount| {
+        state.redeem_levercoin_output(redeem_lst, amount)
+      }),
+    };
+    let lsts = balances
+      .lsts
+      .iter()
+      .filter(|(_, amount)| **amount > UFix64::zero())
+      .map(|(mint, amount)| lst_position(state, redeem_lst, *mint, *amount))
+      .collect::<Result<_>>()?;
+    Ok(Portfolio {
+      owner,
+      redeem_lst,
+      hyusd,
+      xsol,
+      shyusd: stability_pool_position(state, redeem_lst, balances.shyusd)?,
+      lsts,
+    })
+  }
+
+  /// USD value of every position.
+  ///
+  /// # Errors
+  /// * Arithmetic overflow
+  pub fn total_usd_value(&self) -> Result<UFix64<N6>> {
+    self
+      .lsts
+      .iter()
+      .map(|lst| lst.position.usd_value)
+      .chain([
+        self.hyusd.usd_value,
+        self.xsol.usd_value,
+        self.shyusd.position.usd_value,
+      ])
+      .try_fold(UFix64::zero(), |total, value| total.checked_add(&value))
+      .context("Overflow summing portfolio value")
+  }
+
+  /// `redeem_lst` received for every redeemable position, skipping those
+  /// the protocol can't currently redeem.
+  ///
+  /// # Errors
+  /// * Arithmetic overflow
+  pub fn total_redeemable(&self) -> Result<UFix64<N9>> {
+    self
+      .lsts
+      .iter()
+      .map(|lst| lst.position.redeemable)
+      .chain([
+        self.hyusd.redeemable,
+        self.xsol.redeemable,
+        self.shyusd.position.redeemable,
+      ])
+      .flatten()
+      .try_fold(UFix64::zero(), |total, value| total.checked_add(&value))
+      .context("Overflow summing redeemable LST")
+  }
+}
+
+/// USD value of a protocol token amount at `nav`.
+fn usd_value(amount: UFix64<N6>, nav: UFix64<N9>) -> Result<UFix64<N6>> {
+  amount
+    .mul_div_floor(nav, UFix64::one())
+    .context("Overflow computing position value")
+}
+
+/// Output of redeeming `amount` through `redeem`, zero for empty positions.
+fn redeemable(
+  amount: UFix64<N6>,
+  redeem: impl FnOnce(UFix64<N6>) -> Result<RedeemOperationOutput>,
+) -> Option<UFix64<N9>> {
+  if amount == UFix64::zero() {
+    Some(UFix64::zero())
+  } else {
+    redeem(amount).ok().map(|op| op.out_amount)
+  }
+}
+
+fn stability_pool_position<C: SolanaClock>(
+  state: &ProtocolState<C>,
+  redeem_lst: Pubkey,
+  amount: UFix64<N6>,
+) -> Result<StabilityPoolPosition> {
+  let lp_token_supply = UFix64::new(state.shyusd_mint.supply);
+  if lp_token_supply == UFix64::zero() {
+    // Nothing to value or withdraw before the pool is seeded
+    return Ok(StabilityPoolPosition {
+      position: Position {
+        amount,
+        usd_value: UFix64::zero(),
+        redeemable: (amount == UFix64::zero()).then(UFix64::zero),
+      },
+      hyusd_share: UFix64::zero(),
+      xsol_share: UFix64::zero(),
+    });
+  }
+  let ctx = &state.exchange_context;
+  let hyusd_in_pool = UFix64::new(state.hyusd_pool.amount);
+  let xsol_in_pool = UFix64::new(state.xsol_pool.amount);
+  let nav = lp_token_nav(
+    ctx.stablecoin_nav()?,
+    hyusd_in_pool,
+    ctx.levercoin_mint_nav()?,
+    xsol_in_pool,
+    lp_token_supply,
+  )?;
+  let (hyusd_share, xsol_share) = if amount == UFix64::zero() {
+    (UFix64::zero(), UFix64::zero())
+  } else {
+    (
+      amount_token_to_withdraw(amount, lp_token_supply, hyusd_in_pool)?,
+      amount_token_to_withdraw(amount, lp_token_supply, xsol_in_pool)?,
+    )
+  };
+  Ok(StabilityPoolPosition {
+    position: Position {
+      amount,
+      usd_value: amount
+        .mul_div_floor(nav, UFix64::one())
+        .context("Overflow computing sHYUSD value")?,
+      redeemable: redeemable(amount, |amount| {
+        state.withdraw_and_redeem_output(redeem_lst, amount)
+      }),
+    },
+    hyusd_share,
+    xsol_share,
+  })
+}
+
+fn lst_position<C: SolanaClock>(
+  state: &ProtocolState<C>,
+  redeem_lst: Pubkey,
+  mint: Pubkey,
+  amount: UFix64<N9>,
+) -> Result<LstPosition> {
+  let header = state.lst_header_by_mint(&mint)?;
+  let usd_value = state
+    .exchange_context
+    .token_conversion(&header.price_sol.into())?
+    .lst_to_token(amount, UFix64::one())?;
+  let redeemable = if mint == redeem_lst {
+    Some(amount)
+  } else {
+    state
+      .lst_swap_output(mint, redeem_lst, amount)
+      .ok()
+      .map(|op| op.out_amount)
+  };
+  Ok(LstPosition {
+    mint,
+    position: Position {
+      amount,
+      usd_value,
+      redeemable,
+    },
+  })
+}
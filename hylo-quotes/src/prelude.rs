@@ -9,19 +9,34 @@ pub use fix::prelude::*;
 // Token types
 pub use hylo_idl::tokens::{TokenMint, HYLOSOL, HYUSD, JITOSOL, SHYUSD, XSOL};
 
+// Decimal formatting
+pub use crate::format::{Percent, TokenAmount, Usd};
+// Wallet positions
+pub use crate::portfolio::{
+  LstPosition, Portfolio, Position, StabilityPoolPosition, WalletBalances,
+};
 // Protocol state
 pub use crate::protocol_state::{
-  ProtocolAccounts, ProtocolState, RpcStateProvider, StateProvider,
+  CachedStateProvider, FileStateProvider, PendingPriceUpdate,
+  PriceUpdateAccounts, ProtocolAccounts, ProtocolSnapshot, ProtocolState,
+  RecordingStateProvider, RpcStateProvider, StateProvider,
+  SubscriptionStateProvider,
 };
+// Multi-hop routing
+pub use crate::route_planner::{Leg, Route, RoutePlanner, RouteQuote};
+// Offline price scenarios
+pub use crate::scenario::{Rebalance, Scenario, ScenarioMetrics, ScenarioStep};
 // SimulatedOperation (event extraction)
 pub use crate::simulated_operation::{
   SimulatedOperation, SimulatedOperationExt,
 };
+// Post-trade state transitions
+pub use crate::state_transition::{TokenTransition, Transition};
 // TokenOperation (pure math)
 pub use crate::token_operation::{
   LstSwapOperationOutput, MintOperationOutput, OperationOutput,
-  RedeemOperationOutput, SwapOperationOutput, TokenOperation,
-  TokenOperationExt,
+  OperationOutputValue, RedeemOperationOutput, SwapOperationOutput,
+  TokenOperation, TokenOperationExt,
 };
 // Strategy implementations
 pub use crate::ProtocolStateStrategy;
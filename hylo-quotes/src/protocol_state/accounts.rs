@@ -3,13 +3,19 @@
 use std::convert::TryFrom;
 
 use anchor_client::solana_sdk::account::Account;
+use anchor_client::solana_sdk::clock::Clock;
 use anchor_lang::prelude::Pubkey;
 use anchor_lang::solana_program::sysvar;
+use anchor_lang::AccountDeserialize;
 use anyhow::{anyhow, ensure, Context, Result};
+use hylo_core::total_sol_cache::prices_outdated;
+use hylo_idl::exchange::accounts::{Hylo, LstHeader};
 use hylo_idl::pda;
 use hylo_idl::tokens::{TokenMint, HYLOSOL, HYUSD, JITOSOL, SHYUSD, XSOL};
 use serde::{Deserialize, Serialize};
 
+use crate::protocol_state::price_update::PriceUpdateAccounts;
+
 /// Type-safe collection of protocol state accounts
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ProtocolAccounts {
@@ -45,6 +51,16 @@ pub struct ProtocolAccounts {
 
   /// Solana clock sysvar
   pub clock: Account,
+
+  /// Headers of registered LSTs other than `JitoSOL` and `HyloSOL`, keyed by
+  /// LST mint
+  #[serde(default)]
+  pub lst_headers: Vec<(Pubkey, Account)>,
+
+  /// Accounts to project `update_lst_prices` from, present only while LST
+  /// prices or total SOL are outdated
+  #[serde(default)]
+  pub price_update: Option<PriceUpdateAccounts>,
 }
 
 impl ProtocolAccounts {
@@ -74,6 +90,102 @@ impl ProtocolAccounts {
     11
   }
 
+  /// Replace the account at `index` in [`Self::pubkeys`] order
+  ///
+  /// # Errors
+  /// Returns error if `index` is out of range.
+  pub fn set(&mut self, index: usize, account: Account) -> Result<()> {
+    let slot = match index {
+      0 => &mut self.hylo,
+      1 => &mut self.jitosol_header,
+      2 => &mut self.hylosol_header,
+      3 => &mut self.hyusd_mint,
+      4 => &mut self.shyusd_mint,
+      5 => &mut self.xsol_mint,
+      6 => &mut self.pool_config,
+      7 => &mut self.hyusd_pool,
+      8 => &mut self.xsol_pool,
+      9 => &mut self.sol_usd_pyth,
+      10 => &mut self.clock,
+      _ => return Err(anyhow!("Account index {index} out of range")),
+    };
+    *slot = account;
+    Ok(())
+  }
+
+  /// Every account keyed by its address, e.g. to seed a local cluster
+  #[must_use]
+  pub fn keyed(&self) -> Vec<(Pubkey, Account)> {
+    let fields = [
+      &self.hylo,
+      &self.jitosol_header,
+      &self.hylosol_header,
+      &self.hyusd_mint,
+      &self.shyusd_mint,
+      &self.xsol_mint,
+      &self.pool_config,
+      &self.hyusd_pool,
+      &self.xsol_pool,
+      &self.sol_usd_pyth,
+      &self.clock,
+    ];
+    let lst_headers = self
+      .lst_headers
+      .iter()
+      .map(|(mint, header)| (pda::lst_header(*mint), header.clone()));
+    let price_update = self.price_update.iter().flat_map(|update| {
+      std::iter::once((
+        hylo_clients::util::LST_REGISTRY_LOOKUP_TABLE,
+        update.lst_registry.clone(),
+      ))
+      .chain(update.accounts.iter().cloned())
+    });
+    Self::pubkeys()
+      .into_iter()
+      .zip(fields.into_iter().cloned())
+      .chain(lst_headers)
+      .chain(price_update)
+      .collect()
+  }
+
+  /// Mints of the additional LSTs in [`Self::lst_headers`]
+  pub fn lst_mints(&self) -> impl Iterator<Item = Pubkey> + '_ {
+    self.lst_headers.iter().map(|(mint, _)| *mint)
+  }
+
+  /// Replace the header of an additional LST, ignoring unknown mints
+  pub fn set_lst_header(&mut self, mint: &Pubkey, account: Account) {
+    if let Some((_, header)) =
+      self.lst_headers.iter_mut().find(|(m, _)| m == mint)
+    {
+      *header = account;
+    }
+  }
+
+  /// Whether total SOL or any LST price was cached in a previous epoch
+  ///
+  /// # Errors
+  /// Returns error if the Hylo account, an LST header or the clock fails
+  /// deserialization.
+  pub fn prices_outdated(&self) -> Result<bool> {
+    let hylo = Hylo::try_deserialize(&mut self.hylo.data.as_slice())?;
+    let lst_price_epochs = [&self.jitosol_header, &self.hylosol_header]
+      .into_iter()
+      .chain(self.lst_headers.iter().map(|(_, header)| header))
+      .map(|header| {
+        let header = LstHeader::try_deserialize(&mut header.data.as_slice())?;
+        Ok(header.price_sol.epoch)
+      })
+      .collect::<Result<Vec<_>>>()?;
+    let clock: Clock = bincode::deserialize(&self.clock.data)
+      .map_err(|e| anyhow!("Failed to deserialize clock: {e}"))?;
+    Ok(prices_outdated(
+      hylo.total_sol_cache.current_update_epoch,
+      lst_price_epochs,
+      clock.epoch,
+    ))
+  }
+
   /// Validate that pubkeys and accounts match expected protocol accounts
   ///
   /// Validates:
@@ -185,6 +297,10 @@ impl TryFrom<(&[Pubkey], &[Option<Account>])> for ProtocolAccounts {
         .as_ref()
         .context("Clock sysvar not found")?
         .clone(),
+
+      lst_headers: Vec::new(),
+
+      price_update: None,
     })
   }
 }
@@ -1,7 +1,18 @@
 mod accounts;
+mod price_update;
 mod provider;
+mod snapshot;
 mod state;
+mod subscription;
 
 pub use accounts::ProtocolAccounts;
-pub use provider::{RpcStateProvider, StateProvider};
+pub use price_update::{
+  PendingPriceUpdate, PriceUpdateAccounts, PRICE_UPDATE_CUS,
+};
+pub use provider::{
+  CachedStateProvider, FileStateProvider, RecordingStateProvider,
+  RpcStateProvider, StateProvider,
+};
+pub use snapshot::ProtocolSnapshot;
 pub use state::ProtocolState;
+pub use subscription::{SubscriptionStateProvider, DEFAULT_POLL_INTERVAL};
@@ -0,0 +1,197 @@
+//! Projection of `update_lst_prices` across an epoch rollover
+//!
+//! From the first slot of an epoch until someone runs `update_lst_prices`,
+//! cached LST prices and total SOL are outdated and every operation reading
+//! them fails. States loaded in that window are projected through the update
+//! from each LST's stake pool exchange rate and vault balance, and carry a
+//! [`PendingPriceUpdate`] that transactions run first.
+
+use std::collections::{BTreeMap, HashMap};
+
+use anchor_client::solana_sdk::account::Account;
+use anchor_client::solana_sdk::address_lookup_table::AddressLookupTableAccount;
+use anchor_client::solana_sdk::instruction::Instruction;
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::AccountDeserialize;
+use anchor_spl::token::TokenAccount;
+use anyhow::{anyhow, bail, ensure, Context, Result};
+use fix::prelude::*;
+use hylo_clients::util::{
+  build_lst_registry, deserialize_lookup_table, LST_REGISTRY_LOOKUP_TABLE,
+};
+use hylo_core::idl::exchange::accounts::{Hylo, LstHeader};
+use hylo_core::idl::exchange::types::{
+  LstSolPrice as IdlLstSolPrice, LstStakePoolProgram, TotalSolCache,
+};
+use hylo_core::lst_sol_price::LstSolPrice;
+use hylo_idl::exchange::instruction_builders;
+use serde::{Deserialize, Serialize};
+
+/// Compute units reserved for a prepended `update_lst_prices`.
+pub const PRICE_UPDATE_CUS: u64 = 200_000;
+
+/// Offset of `total_lamports` in SPL stake pool state, preceded by the
+/// account type, three authorities, a bump seed and five addresses.
+/// `pool_token_supply` and `last_update_epoch` follow.
+const SPL_TOTAL_LAMPORTS_OFFSET: usize = 1 + 32 * 3 + 1 + 32 * 5;
+
+/// Accounts `update_lst_prices` reads, fetched only while prices are
+/// outdated.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PriceUpdateAccounts {
+  /// LST registry lookup table
+  pub lst_registry: Account,
+
+  /// Vault token accounts and stake pool states of every registered LST,
+  /// keyed by address
+  pub accounts: Vec<(Pubkey, Account)>,
+}
+
+/// `update_lst_prices` a projected state assumes has run.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PendingPriceUpdate {
+  /// Addresses in the LST registry lookup table, passed to the instruction as
+  /// remaining accounts
+  #[cfg_attr(
+    feature = "serde",
+    serde(with = "hylo_core::serde_schema::pubkeys")
+  )]
+  pub lst_registry: Vec<Pubkey>,
+}
+
+impl PendingPriceUpdate {
+  /// `update_lst_prices` paid by `payer`.
+  ///
+  /// # Errors
+  /// * Malformed LST registry
+  pub fn instruction(&self, payer: Pubkey) -> Result<Instruction> {
+    let table = AddressLookupTableAccount {
+      key: LST_REGISTRY_LOOKUP_TABLE,
+      addresses: self.lst_registry.clone(),
+    };
+    let (remaining_accounts, _) = build_lst_registry(table)?;
+    Ok(instruction_builders::update_lst_prices(
+      payer,
+      LST_REGISTRY_LOOKUP_TABLE,
+      remaining_accounts,
+    ))
+  }
+
+  /// Runs the update, paid by `payer`, before `instructions`, adding the LST
+  /// registry to `lookup_tables`.
+  ///
+  /// # Errors
+  /// * Malformed LST registry
+  pub fn prepend(
+    &self,
+    payer: Pubkey,
+    instructions: &mut Vec<Instruction>,
+    lookup_tables: &mut Vec<Pubkey>,
+  ) -> Result<()> {
+    instructions.insert(0, self.instruction(payer)?);
+    if !lookup_tables.contains(&LST_REGISTRY_LOOKUP_TABLE) {
+      lookup_tables.push(LST_REGISTRY_LOOKUP_TABLE);
+    }
+    Ok(())
+  }
+}
+
+/// Applies `update_lst_prices` at `epoch` to the cached prices in
+/// `lst_headers` and total SOL in `hylo`.
+///
+/// * Outdated LST prices become their stake pool's SOL per pool token
+/// * Total SOL becomes the SOL value of every vault at the new prices
+///
+/// # Errors
+/// * Missing or malformed vault or stake pool account
+/// * Stake pool not yet updated for `epoch`, or of an unsupported program
+/// * Arithmetic overflow
+pub(crate) fn project(
+  hylo: &mut Hylo,
+  lst_headers: &mut BTreeMap<Pubkey, LstHeader>,
+  accounts: &PriceUpdateAccounts,
+  epoch: u64,
+) -> Result<PendingPriceUpdate> {
+  let lst_registry = deserialize_lookup_table(
+    &LST_REGISTRY_LOOKUP_TABLE,
+    &accounts.lst_registry,
+  )?
+  .addresses;
+  let fetched = accounts
+    .accounts
+    .iter()
+    .map(|(key, account)| (*key, account))
+    .collect::<HashMap<_, _>>();
+  let fetched = |key: &Pubkey| {
+    fetched
+      .get(key)
+      .copied()
+      .ok_or_else(|| anyhow!("Account {key} not fetched for price update"))
+  };
+  let mut total_sol = UFix64::<N9>::zero();
+  for (mint, header) in lst_headers.iter_mut() {
+    if header.price_sol.epoch < epoch {
+      let pool_state = fetched(&header.pool_state)?;
+      let price = stake_pool_price(&header.stake_program, pool_state, epoch)
+        .with_context(|| format!("Failed to project price of LST {mint}"))?;
+      header.prev_price_sol = header.price_sol;
+      header.price_sol = IdlLstSolPrice {
+        price: UFixValue64::from(price).into(),
+        epoch,
+      };
+    }
+    let vault = TokenAccount::try_deserialize(
+      &mut fetched(&header.vault)?.data.as_slice(),
+    )?;
+    let price: LstSolPrice = header.price_sol.into();
+    let vault_sol = price.convert_sol(UFix64::new(vault.amount), epoch)?;
+    total_sol = total_sol
+      .checked_add(&vault_sol)
+      .ok_or_else(|| anyhow!("Total SOL overflow"))?;
+  }
+  hylo.total_sol_cache = TotalSolCache {
+    current_update_epoch: epoch,
+    total_sol: UFixValue64::from(total_sol).into(),
+  };
+  Ok(PendingPriceUpdate { lst_registry })
+}
+
+/// SOL per pool token of a stake pool updated for `epoch`.
+fn stake_pool_price(
+  program: &LstStakePoolProgram,
+  pool_state: &Account,
+  epoch: u64,
+) -> Result<UFix64<N9>> {
+  match program {
+    LstStakePoolProgram::Spl
+    | LstStakePoolProgram::SanctumSpl
+    | LstStakePoolProgram::SanctumSplMulti => {
+      let field = |index: usize| {
+        let start = SPL_TOTAL_LAMPORTS_OFFSET + index * 8;
+        pool_state
+          .data
+          .get(start..start + 8)
+          .and_then(|bytes| bytes.try_into().ok())
+          .map(u64::from_le_bytes)
+          .ok_or_else(|| anyhow!("Malformed stake pool state"))
+      };
+      let (total_lamports, pool_token_supply, last_update_epoch) =
+        (field(0)?, field(1)?, field(2)?);
+      ensure!(pool_token_supply > 0, "Stake pool has no supply");
+      ensure!(
+        last_update_epoch >= epoch,
+        "Stake pool not updated for epoch {epoch}"
+      );
+      UFix64::<N9>::one()
+        .mul_div_floor(
+          UFix64::<N9>::new(total_lamports),
+          UFix64::<N9>::new(pool_token_supply),
+        )
+        .ok_or_else(|| anyhow!("Stake pool price overflow"))
+    }
+    LstStakePoolProgram::Marinade => {
+      bail!("Marinade stake pool prices cannot be projected")
+    }
+  }
+}
@@ -2,15 +2,28 @@
 //!
 //! Provides abstractions for fetching Hylo protocol state from various sources.
 
-use std::sync::Arc;
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
 
 use anchor_client::solana_client::nonblocking::rpc_client::RpcClient;
-use anchor_lang::prelude::Clock;
-use anyhow::{anyhow, Result};
+use anchor_client::solana_sdk::account::Account;
+use anchor_lang::prelude::{Clock, Pubkey};
+use anyhow::{anyhow, ensure, Context, Result};
 use async_trait::async_trait;
+use hylo_clients::util::{
+  deserialize_lookup_table, parse_lst_registry, LstRegistryEntry,
+  LST_REGISTRY_LOOKUP_TABLE,
+};
 use hylo_core::solana_clock::SolanaClock;
+use hylo_idl::tokens::{TokenMint, HYLOSOL, JITOSOL};
+use tokio::sync::Mutex;
 
-use crate::protocol_state::{ProtocolAccounts, ProtocolState};
+use crate::protocol_state::{
+  PriceUpdateAccounts, ProtocolAccounts, ProtocolSnapshot, ProtocolState,
+};
 
 /// Trait for fetching protocol state from a data source
 #[async_trait]
@@ -24,7 +37,7 @@ pub trait StateProvider<C: SolanaClock>: Send + Sync {
 
 // Implement StateProvider for Arc<T> where T: StateProvider
 #[async_trait]
-impl<T: StateProvider<C>, C: SolanaClock> StateProvider<C>
+impl<T: StateProvider<C> + ?Sized, C: SolanaClock> StateProvider<C>
   for std::sync::Arc<T>
 {
   async fn fetch_state(&self) -> Result<ProtocolState<C>> {
@@ -37,8 +50,15 @@ impl<T: StateProvider<C>, C: SolanaClock> StateProvider<C>
 // ============================================================================
 
 /// State provider that fetches protocol state via Solana RPC
+///
+/// LSTs registered beyond `JitoSOL` and `HyloSOL` are discovered from the LST
+/// registry lookup table on every fetch. Known registry entries are cached so
+/// that steady state fetches take a single `getMultipleAccounts`. While LST
+/// prices are outdated, the vaults and stake pools needed to project
+/// `update_lst_prices` are fetched as well.
 pub struct RpcStateProvider {
   rpc_client: Arc<RpcClient>,
+  lsts: RwLock<Vec<LstRegistryEntry>>,
 }
 
 impl RpcStateProvider {
@@ -48,27 +68,338 @@ impl RpcStateProvider {
   /// * `rpc_client` - Solana RPC client for fetching account data
   #[must_use]
   pub fn new(rpc_client: Arc<RpcClient>) -> Self {
-    Self { rpc_client }
+    Self {
+      rpc_client,
+      lsts: RwLock::new(Vec::new()),
+    }
+  }
+
+  /// Fetch raw protocol accounts without deserializing them
+  ///
+  /// # Errors
+  /// Returns error if the RPC call fails or any account is missing.
+  pub async fn fetch_accounts(&self) -> Result<ProtocolAccounts> {
+    let known = self
+      .lsts
+      .read()
+      .map_err(|_| anyhow!("LST cache poisoned"))?
+      .clone();
+    let base = ProtocolAccounts::pubkeys();
+    let pubkeys = base
+      .iter()
+      .copied()
+      .chain([LST_REGISTRY_LOOKUP_TABLE])
+      .chain(known.iter().map(|entry| entry.header))
+      .collect::<Vec<_>>();
+    let account_data = self.get_multiple_accounts(&pubkeys).await?;
+    let (base_data, rest) = account_data.split_at(base.len());
+    let mut accounts =
+      ProtocolAccounts::try_from((base.as_slice(), base_data))?;
+
+    // Absent on clusters without a registry, leaving only the fixed LSTs
+    let registered = match &rest[0] {
+      Some(table) => {
+        let table =
+          deserialize_lookup_table(&LST_REGISTRY_LOOKUP_TABLE, table)?;
+        parse_lst_registry(&table)?
+          .into_iter()
+          .filter(|entry| ![JITOSOL::MINT, HYLOSOL::MINT].contains(&entry.mint))
+          .collect()
+      }
+      None => Vec::new(),
+    };
+    let mut headers = known
+      .iter()
+      .map(|entry| entry.header)
+      .zip(rest[1..].iter().cloned())
+      .collect::<HashMap<_, _>>();
+    let missing = registered
+      .iter()
+      .map(|entry| entry.header)
+      .filter(|header| !headers.contains_key(header))
+      .collect::<Vec<_>>();
+    if !missing.is_empty() {
+      let missing_data = self.get_multiple_accounts(&missing).await?;
+      headers.extend(missing.into_iter().zip(missing_data));
+    }
+    accounts.lst_headers = registered
+      .iter()
+      .map(|entry| {
+        headers
+          .remove(&entry.header)
+          .flatten()
+          .map(|header| (entry.mint, header))
+          .with_context(|| format!("LST header not found for {}", entry.mint))
+      })
+      .collect::<Result<_>>()?;
+    *self
+      .lsts
+      .write()
+      .map_err(|_| anyhow!("LST cache poisoned"))? = registered;
+    if accounts.prices_outdated()? {
+      let lst_registry = rest[0]
+        .clone()
+        .context("LST registry required to project outdated prices")?;
+      accounts.price_update =
+        Some(self.fetch_price_update(lst_registry).await?);
+    }
+    Ok(accounts)
+  }
+
+  /// Fetch the vaults and stake pools `update_lst_prices` reads
+  async fn fetch_price_update(
+    &self,
+    lst_registry: Account,
+  ) -> Result<PriceUpdateAccounts> {
+    let table =
+      deserialize_lookup_table(&LST_REGISTRY_LOOKUP_TABLE, &lst_registry)?;
+    let pubkeys = parse_lst_registry(&table)?
+      .into_iter()
+      .flat_map(|entry| [entry.vault, entry.pool_state])
+      .collect::<Vec<_>>();
+    let data = self.get_multiple_accounts(&pubkeys).await?;
+    let accounts = pubkeys
+      .into_iter()
+      .zip(data)
+      .map(|(pubkey, account)| {
+        account
+          .map(|account| (pubkey, account))
+          .with_context(|| format!("Price update account {pubkey} not found"))
+      })
+      .collect::<Result<_>>()?;
+    Ok(PriceUpdateAccounts {
+      lst_registry,
+      accounts,
+    })
+  }
+
+  async fn get_multiple_accounts(
+    &self,
+    pubkeys: &[Pubkey],
+  ) -> Result<Vec<Option<Account>>> {
+    self
+      .rpc_client
+      .get_multiple_accounts(pubkeys)
+      .await
+      .map_err(|e| anyhow!("Failed to fetch accounts from RPC: {e}"))
   }
 }
 
 #[async_trait]
 impl StateProvider<Clock> for RpcStateProvider {
   async fn fetch_state(&self) -> Result<ProtocolState<Clock>> {
-    let pubkeys = ProtocolAccounts::pubkeys();
-    let account_data = self
-      .rpc_client
-      .get_multiple_accounts(&pubkeys)
-      .await
-      .map_err(|e| anyhow!("Failed to fetch accounts from RPC: {e}"))?;
-    let accounts = ProtocolAccounts::try_from((
-      pubkeys.as_slice(),
-      account_data.as_slice(),
-    ))?;
+    let accounts = self.fetch_accounts().await?;
     ProtocolState::try_from(&accounts)
   }
 }
 
+// ============================================================================
+// FILE STATE PROVIDER
+// ============================================================================
+
+/// State provider that replays [`ProtocolSnapshot`] files from disk
+///
+/// Snapshots are indexed by slot. Unless a slot is selected, the latest
+/// snapshot is served.
+pub struct FileStateProvider {
+  snapshots: BTreeMap<u64, PathBuf>,
+  selected: Option<u64>,
+}
+
+impl FileStateProvider {
+  /// Create a provider serving a single snapshot file
+  ///
+  /// # Arguments
+  /// * `path` - Snapshot or bare `ProtocolAccounts` JSON file
+  ///
+  /// # Errors
+  /// Returns error if the file cannot be read as a snapshot.
+  pub fn from_file(path: impl Into<PathBuf>) -> Result<Self> {
+    let path = path.into();
+    let snapshot = ProtocolSnapshot::read(&path)?;
+    Ok(Self {
+      snapshots: BTreeMap::from([(snapshot.slot, path)]),
+      selected: None,
+    })
+  }
+
+  /// Create a provider over every snapshot in a directory
+  ///
+  /// Only files named by [`ProtocolSnapshot::file_name`] are indexed.
+  ///
+  /// # Arguments
+  /// * `dir` - Directory written to by [`RecordingStateProvider`]
+  ///
+  /// # Errors
+  /// Returns error if the directory cannot be read or holds no snapshots.
+  pub fn from_dir(dir: &Path) -> Result<Self> {
+    let snapshots = fs::read_dir(dir)
+      .with_context(|| format!("Failed to read {}", dir.display()))?
+      .map(|entry| entry.map(|e| e.path()))
+      .collect::<std::io::Result<Vec<_>>>()?
+      .into_iter()
+      .filter_map(|path| {
+        ProtocolSnapshot::slot_from_path(&path).map(|slot| (slot, path))
+      })
+      .collect::<BTreeMap<_, _>>();
+    ensure!(
+      !snapshots.is_empty(),
+      "No snapshots found in {}",
+      dir.display()
+    );
+    Ok(Self {
+      snapshots,
+      selected: None,
+    })
+  }
+
+  /// Slots of all available snapshots in ascending order
+  pub fn slots(&self) -> impl Iterator<Item = u64> + '_ {
+    self.snapshots.keys().copied()
+  }
+
+  /// Serve the snapshot taken exactly at `slot`
+  ///
+  /// # Errors
+  /// Returns error if no snapshot exists for `slot`.
+  pub fn at_slot(mut self, slot: u64) -> Result<Self> {
+    ensure!(
+      self.snapshots.contains_key(&slot),
+      "No snapshot found for slot {slot}"
+    );
+    self.selected = Some(slot);
+    Ok(self)
+  }
+
+  /// Serve the most recent snapshot taken at or before `slot`
+  ///
+  /// # Errors
+  /// Returns error if every snapshot is newer than `slot`.
+  pub fn at_or_before_slot(mut self, slot: u64) -> Result<Self> {
+    let (found, _) = self
+      .snapshots
+      .range(..=slot)
+      .next_back()
+      .ok_or_else(|| anyhow!("No snapshot found at or before slot {slot}"))?;
+    self.selected = Some(*found);
+    Ok(self)
+  }
+
+  /// Read the currently selected snapshot
+  ///
+  /// # Errors
+  /// Returns error if the snapshot file cannot be read.
+  pub fn fetch_snapshot(&self) -> Result<ProtocolSnapshot> {
+    let path = match self.selected {
+      Some(slot) => self.snapshots.get(&slot),
+      None => self.snapshots.values().next_back(),
+    }
+    .ok_or_else(|| anyhow!("No snapshot selected"))?;
+    ProtocolSnapshot::read(path)
+  }
+}
+
+#[async_trait]
+impl StateProvider<Clock> for FileStateProvider {
+  async fn fetch_state(&self) -> Result<ProtocolState<Clock>> {
+    let snapshot = self.fetch_snapshot()?;
+    ProtocolState::try_from(&snapshot.accounts)
+  }
+}
+
+// ============================================================================
+// RECORDING STATE PROVIDER
+// ============================================================================
+
+/// State provider that fetches via RPC and records every snapshot to disk
+///
+/// Recorded directories can be replayed with [`FileStateProvider::from_dir`].
+pub struct RecordingStateProvider {
+  inner: RpcStateProvider,
+  dir: PathBuf,
+}
+
+impl RecordingStateProvider {
+  /// Create a new recording state provider
+  ///
+  /// # Arguments
+  /// * `inner` - RPC provider to fetch accounts from
+  /// * `dir` - Directory to write snapshots into, created if missing
+  #[must_use]
+  pub fn new(inner: RpcStateProvider, dir: impl Into<PathBuf>) -> Self {
+    Self {
+      inner,
+      dir: dir.into(),
+    }
+  }
+}
+
+#[async_trait]
+impl StateProvider<Clock> for RecordingStateProvider {
+  async fn fetch_state(&self) -> Result<ProtocolState<Clock>> {
+    let accounts = self.inner.fetch_accounts().await?;
+    let snapshot = ProtocolSnapshot::new(accounts)?;
+    snapshot.write_to_dir(&self.dir)?;
+    ProtocolState::try_from(&snapshot.accounts)
+  }
+}
+
+// ============================================================================
+// CACHED STATE PROVIDER
+// ============================================================================
+
+/// State provider reusing the last fetched state for up to `ttl`
+///
+/// Concurrent fetches of an expired state wait on a single refresh, so
+/// request bursts against a server cost one upstream fetch per `ttl`.
+pub struct CachedStateProvider<S, C: SolanaClock> {
+  inner: S,
+  ttl: Duration,
+  cached: Mutex<Option<(Instant, ProtocolState<C>)>>,
+}
+
+impl<S, C: SolanaClock> CachedStateProvider<S, C> {
+  /// Create a caching wrapper around `inner`
+  ///
+  /// # Arguments
+  /// * `inner` - Provider fetched on cache misses
+  /// * `ttl` - How long a fetched state is served
+  #[must_use]
+  pub fn new(inner: S, ttl: Duration) -> Self {
+    Self {
+      inner,
+      ttl,
+      cached: Mutex::new(None),
+    }
+  }
+
+  /// Drop the cached state, forcing a fetch on next use
+  pub async fn invalidate(&self) {
+    *self.cached.lock().await = None;
+  }
+}
+
+#[async_trait]
+impl<S, C> StateProvider<C> for CachedStateProvider<S, C>
+where
+  S: StateProvider<C>,
+  C: SolanaClock + Clone + Send + Sync,
+{
+  async fn fetch_state(&self) -> Result<ProtocolState<C>> {
+    let mut cached = self.cached.lock().await;
+    match &*cached {
+      Some((fetched, state)) if fetched.elapsed() < self.ttl => {
+        Ok(state.clone())
+      }
+      _ => {
+        let state = self.inner.fetch_state().await?;
+        *cached = Some((Instant::now(), state.clone()));
+        Ok(state)
+      }
+    }
+  }
+}
+
 #[cfg(test)]
 mod tests {
   use std::sync::Arc;
@@ -0,0 +1,104 @@
+//! Serialized protocol account snapshots
+//!
+//! Snapshots pair [`ProtocolAccounts`] with the slot and timestamp of the
+//! clock sysvar they were fetched alongside, so that quotes can be replayed
+//! deterministically from disk.
+
+use std::fs::{self, File};
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+use anchor_client::solana_sdk::clock::{Clock, UnixTimestamp};
+use anyhow::{anyhow, Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::protocol_state::ProtocolAccounts;
+
+const FILE_PREFIX: &str = "protocol-snapshot-";
+const FILE_EXTENSION: &str = "json";
+
+/// Protocol accounts captured at a known slot
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProtocolSnapshot {
+  /// Slot of the clock sysvar in `accounts`
+  pub slot: u64,
+
+  /// Unix timestamp of the clock sysvar in `accounts`
+  pub timestamp: UnixTimestamp,
+
+  /// Raw protocol accounts
+  pub accounts: ProtocolAccounts,
+}
+
+impl ProtocolSnapshot {
+  /// Wraps accounts, reading slot and timestamp from the clock sysvar.
+  ///
+  /// # Errors
+  /// * Clock sysvar fails deserialization
+  pub fn new(accounts: ProtocolAccounts) -> Result<Self> {
+    let clock: Clock = bincode::deserialize(&accounts.clock.data)
+      .map_err(|e| anyhow!("Failed to deserialize clock: {e}"))?;
+    Ok(Self {
+      slot: clock.slot,
+      timestamp: clock.unix_timestamp,
+      accounts,
+    })
+  }
+
+  /// Canonical file name for a snapshot at `slot`.
+  #[must_use]
+  pub fn file_name(slot: u64) -> String {
+    format!("{FILE_PREFIX}{slot}.{FILE_EXTENSION}")
+  }
+
+  /// Parses the slot out of a canonical snapshot file name.
+  #[must_use]
+  pub fn slot_from_path(path: &Path) -> Option<u64> {
+    if path.extension()? != FILE_EXTENSION {
+      return None;
+    }
+    path
+      .file_stem()?
+      .to_str()?
+      .strip_prefix(FILE_PREFIX)?
+      .parse()
+      .ok()
+  }
+
+  /// Reads a snapshot from a JSON file.
+  ///
+  /// Files containing bare [`ProtocolAccounts`] are also accepted, with slot
+  /// and timestamp taken from the clock sysvar.
+  ///
+  /// # Errors
+  /// * File IO
+  /// * File is neither a snapshot nor protocol accounts
+  pub fn read(path: &Path) -> Result<Self> {
+    let bytes = fs::read(path)
+      .with_context(|| format!("Failed to read {}", path.display()))?;
+    serde_json::from_slice::<Self>(&bytes).or_else(|_| {
+      let accounts = serde_json::from_slice::<ProtocolAccounts>(&bytes)
+        .with_context(|| {
+          format!("Failed to parse snapshot {}", path.display())
+        })?;
+      Self::new(accounts)
+    })
+  }
+
+  /// Writes this snapshot into `dir` under its canonical file name,
+  /// overwriting any existing snapshot for the same slot.
+  ///
+  /// # Errors
+  /// * File IO
+  /// * Serialization
+  pub fn write_to_dir(&self, dir: &Path) -> Result<PathBuf> {
+    fs::create_dir_all(dir)?;
+    let path = dir.join(Self::file_name(self.slot));
+    let file = File::create(&path)
+      .with_context(|| format!("Failed to create {}", path.display()))?;
+    let mut writer = BufWriter::new(file);
+    serde_json::to_writer(&mut writer, self)?;
+    writer.flush()?;
+    Ok(path)
+  }
+}
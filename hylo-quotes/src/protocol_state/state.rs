@@ -3,7 +3,10 @@
 //! Contains the `ProtocolState` struct and its construction from protocol
 //! accounts.
 
+use std::collections::BTreeMap;
+
 use anchor_client::solana_sdk::clock::{Clock, UnixTimestamp};
+use anchor_lang::prelude::Pubkey;
 use anchor_lang::AccountDeserialize;
 use anchor_spl::token::{Mint, TokenAccount};
 use anyhow::{anyhow, Result};
@@ -15,41 +18,59 @@ use hylo_core::lst_swap_config::LstSwapConfig;
 use hylo_core::pyth::OracleConfig;
 use hylo_core::solana_clock::SolanaClock;
 use hylo_core::stability_mode::StabilityController;
-use hylo_core::total_sol_cache::TotalSolCache;
+use hylo_core::total_sol_cache::{prices_outdated, TotalSolCache};
 use hylo_idl::tokens::{TokenMint, HYLOSOL, JITOSOL};
 use pyth_solana_receiver_sdk::price_update::PriceUpdateV2;
 
+use crate::protocol_state::price_update::{project, PendingPriceUpdate};
 use crate::protocol_state::ProtocolAccounts;
 use crate::LST;
 
 /// Complete snapshot of Hylo protocol state
 #[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ProtocolState<C: SolanaClock> {
   /// Exchange context with all protocol parameters
   pub exchange_context: ExchangeContext<C>,
 
-  /// `JitoSOL` LST header
-  pub jitosol_header: LstHeader,
-
-  /// `HyloSOL` LST header
-  pub hylosol_header: LstHeader,
+  /// Headers of every registered LST, keyed by LST mint
+  #[cfg_attr(
+    feature = "serde",
+    serde(with = "crate::serde_schema::lst_headers")
+  )]
+  pub lst_headers: BTreeMap<Pubkey, LstHeader>,
 
   /// HYUSD mint account
+  #[cfg_attr(feature = "serde", serde(with = "crate::serde_schema::mint"))]
   pub hyusd_mint: Mint,
 
   /// XSOL mint account
+  #[cfg_attr(feature = "serde", serde(with = "crate::serde_schema::mint"))]
   pub xsol_mint: Mint,
 
   /// SHYUSD mint account
+  #[cfg_attr(feature = "serde", serde(with = "crate::serde_schema::mint"))]
   pub shyusd_mint: Mint,
 
   /// Stability pool configuration
+  #[cfg_attr(
+    feature = "serde",
+    serde(with = "crate::serde_schema::pool_config")
+  )]
   pub pool_config: PoolConfig,
 
   /// HYUSD stability pool token account
+  #[cfg_attr(
+    feature = "serde",
+    serde(with = "crate::serde_schema::token_account")
+  )]
   pub hyusd_pool: TokenAccount,
 
   /// XSOL stability pool token account
+  #[cfg_attr(
+    feature = "serde",
+    serde(with = "crate::serde_schema::token_account")
+  )]
   pub xsol_pool: TokenAccount,
 
   /// Timestamp of when this state was fetched
@@ -57,6 +78,11 @@ pub struct ProtocolState<C: SolanaClock> {
 
   /// LST swap configuration
   pub lst_swap_config: LstSwapConfig,
+
+  /// `update_lst_prices` this state was projected through, `None` unless
+  /// prices were outdated on chain when loaded
+  #[cfg_attr(feature = "serde", serde(default))]
+  pub pending_price_update: Option<PendingPriceUpdate>,
 }
 
 impl<C: SolanaClock> ProtocolState<C> {
@@ -68,8 +94,7 @@ impl<C: SolanaClock> ProtocolState<C> {
   pub fn build(
     clock: C,
     hylo: &Hylo,
-    jitosol_header: LstHeader,
-    hylosol_header: LstHeader,
+    lst_headers: BTreeMap<Pubkey, LstHeader>,
     hyusd_mint: Mint,
     xsol_mint: Mint,
     shyusd_mint: Mint,
@@ -104,8 +129,7 @@ impl<C: SolanaClock> ProtocolState<C> {
     )?;
     Ok(Self {
       exchange_context,
-      jitosol_header,
-      hylosol_header,
+      lst_headers,
       hyusd_mint,
       xsol_mint,
       shyusd_mint,
@@ -114,19 +138,45 @@ impl<C: SolanaClock> ProtocolState<C> {
       xsol_pool,
       fetched_at,
       lst_swap_config,
+      pending_price_update: None,
     })
   }
 
-  /// Selects an [`LstHeader`] field given a token implementing [`LST`].
+  /// Selects an [`LstHeader`] given a token implementing [`LST`].
   ///
   /// # Errors
-  /// * LST does not have a corresponding header field in this struct
+  /// * LST is not registered in this state
   pub fn lst_header<L: LST>(&self) -> Result<&LstHeader> {
-    match L::MINT {
-      JITOSOL::MINT => Ok(&self.jitosol_header),
-      HYLOSOL::MINT => Ok(&self.hylosol_header),
-      _ => Err(anyhow!("LstHeader not found for {}", L::MINT)),
-    }
+    self.lst_header_by_mint(&L::MINT)
+  }
+
+  /// Selects an [`LstHeader`] by LST mint.
+  ///
+  /// # Errors
+  /// * LST is not registered in this state
+  pub fn lst_header_by_mint(&self, mint: &Pubkey) -> Result<&LstHeader> {
+    self
+      .lst_headers
+      .get(mint)
+      .ok_or_else(|| anyhow!("LstHeader not found for {mint}"))
+  }
+
+  /// Whether `mint` is a registered LST.
+  #[must_use]
+  pub fn is_lst(&self, mint: &Pubkey) -> bool {
+    self.lst_headers.contains_key(mint)
+  }
+
+  /// Whether this state was projected through an `update_lst_prices` that
+  /// transactions must run first.
+  #[must_use]
+  pub fn is_projected(&self) -> bool {
+    self.pending_price_update.is_some()
+  }
+
+  /// Mints of every registered LST.
+  pub fn lst_mints(&self) -> impl Iterator<Item = &Pubkey> {
+    self.lst_headers.keys()
   }
 }
 
@@ -135,16 +185,34 @@ impl TryFrom<&ProtocolAccounts> for ProtocolState<Clock> {
 
   /// Build `ProtocolState` from protocol accounts
   ///
+  /// While LST prices or total SOL are outdated and
+  /// [`ProtocolAccounts::price_update`] is present, the state is projected
+  /// through `update_lst_prices` and carries the pending update.
+  ///
   /// # Errors
-  /// Returns error if any account fails deserialization.
+  /// Returns error if any account fails deserialization, or prices are
+  /// outdated and cannot be projected.
   fn try_from(accounts: &ProtocolAccounts) -> Result<Self> {
-    let hylo = Hylo::try_deserialize(&mut accounts.hylo.data.as_slice())?;
-
-    let jitosol_header =
-      LstHeader::try_deserialize(&mut accounts.jitosol_header.data.as_slice())?;
+    let mut hylo = Hylo::try_deserialize(&mut accounts.hylo.data.as_slice())?;
 
-    let hylosol_header =
-      LstHeader::try_deserialize(&mut accounts.hylosol_header.data.as_slice())?;
+    let mut lst_headers = [
+      (JITOSOL::MINT, &accounts.jitosol_header),
+      (HYLOSOL::MINT, &accounts.hylosol_header),
+    ]
+    .into_iter()
+    .chain(
+      accounts
+        .lst_headers
+        .iter()
+        .map(|(mint, header)| (*mint, header)),
+    )
+    .map(|(mint, header)| {
+      Ok((
+        mint,
+        LstHeader::try_deserialize(&mut header.data.as_slice())?,
+      ))
+    })
+    .collect::<Result<BTreeMap<_, _>>>()?;
 
     let hyusd_mint =
       Mint::try_deserialize(&mut accounts.hyusd_mint.data.as_slice())?;
@@ -172,11 +240,22 @@ impl TryFrom<&ProtocolAccounts> for ProtocolState<Clock> {
     let clock: Clock = bincode::deserialize(&accounts.clock.data)
       .map_err(|e| anyhow!("Failed to deserialize clock: {e}"))?;
 
-    Self::build(
+    let outdated = prices_outdated(
+      hylo.total_sol_cache.current_update_epoch,
+      lst_headers.values().map(|header| header.price_sol.epoch),
+      clock.epoch,
+    );
+    let pending_price_update = match &accounts.price_update {
+      Some(update) if outdated => {
+        Some(project(&mut hylo, &mut lst_headers, update, clock.epoch)?)
+      }
+      _ => None,
+    };
+
+    let state = Self::build(
       clock,
       &hylo,
-      jitosol_header,
-      hylosol_header,
+      lst_headers,
       hyusd_mint,
       xsol_mint,
       shyusd_mint,
@@ -184,6 +263,10 @@ impl TryFrom<&ProtocolAccounts> for ProtocolState<Clock> {
       hyusd_pool,
       xsol_pool,
       &sol_usd,
-    )
+    )?;
+    Ok(Self {
+      pending_price_update,
+      ..state
+    })
   }
 }
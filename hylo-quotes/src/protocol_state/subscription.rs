@@ -0,0 +1,212 @@
+//! Websocket subscription state provider
+//!
+//! Keeps protocol accounts current via `accountSubscribe`, falling back to
+//! RPC polling while the websocket is unavailable.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use anchor_client::solana_account_decoder::UiAccountEncoding;
+use anchor_client::solana_client::nonblocking::pubsub_client::PubsubClient;
+use anchor_client::solana_client::nonblocking::rpc_client::RpcClient;
+use anchor_client::solana_client::rpc_config::RpcAccountInfoConfig;
+use anchor_client::solana_sdk::account::Account;
+use anchor_lang::prelude::Clock;
+use anyhow::{anyhow, Context, Result};
+use async_trait::async_trait;
+use futures::future::try_join_all;
+use futures::stream::{self, select_all, Stream, StreamExt};
+use hylo_idl::pda;
+use tokio::sync::watch;
+use tokio::task::JoinHandle;
+use tokio::time::timeout;
+
+use crate::protocol_state::{
+  ProtocolAccounts, ProtocolState, RpcStateProvider, StateProvider,
+};
+
+/// Default interval between RPC polls while the websocket is down
+pub const DEFAULT_POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Upper bound on connecting and subscribing before falling back to polling
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// State provider backed by websocket account subscriptions
+///
+/// Subscribes to every account in [`ProtocolAccounts::pubkeys`] plus the
+/// headers of additional registered LSTs known on connect, and serves
+/// [`StateProvider::fetch_state`] from memory. On disconnect, accounts are
+/// polled over RPC every `poll_interval` until the websocket reconnects.
+/// The background task is aborted when the provider is dropped.
+pub struct SubscriptionStateProvider {
+  accounts: watch::Receiver<ProtocolAccounts>,
+  connected: Arc<AtomicBool>,
+  task: JoinHandle<()>,
+}
+
+impl SubscriptionStateProvider {
+  /// Fetch the initial accounts over RPC and start subscribing
+  ///
+  /// # Arguments
+  /// * `rpc_client` - RPC client for the initial fetch and polling fallback,
+  ///   whose commitment is also used for subscriptions
+  /// * `ws_url` - Websocket endpoint serving `accountSubscribe`
+  /// * `poll_interval` - Delay between polls and reconnect attempts while
+  ///   disconnected
+  ///
+  /// # Errors
+  /// Returns error if the initial RPC fetch fails.
+  pub async fn connect(
+    rpc_client: Arc<RpcClient>,
+    ws_url: impl Into<String>,
+    poll_interval: Duration,
+  ) -> Result<Self> {
+    let subscription = Subscription {
+      rpc: RpcStateProvider::new(rpc_client.clone()),
+      config: RpcAccountInfoConfig {
+        encoding: Some(UiAccountEncoding::Base64),
+        commitment: Some(rpc_client.commitment()),
+        ..Default::default()
+      },
+      ws_url: ws_url.into(),
+      poll_interval,
+      connected: Arc::new(AtomicBool::new(false)),
+    };
+    let initial = subscription.rpc.fetch_accounts().await?;
+    let (sender, accounts) = watch::channel(initial);
+    let connected = subscription.connected.clone();
+    let task = tokio::spawn(subscription.run(sender));
+    Ok(Self {
+      accounts,
+      connected,
+      task,
+    })
+  }
+
+  /// Whether account updates are currently streamed over the websocket
+  #[must_use]
+  pub fn is_connected(&self) -> bool {
+    self.connected.load(Ordering::Relaxed)
+  }
+
+  /// Latest raw protocol accounts
+  #[must_use]
+  pub fn accounts(&self) -> ProtocolAccounts {
+    self.accounts.borrow().clone()
+  }
+
+  /// Stream of protocol states, yielding after every account update
+  ///
+  /// Updates arriving faster than the stream is polled are coalesced into
+  /// the latest state. The stream ends when the provider is dropped.
+  pub fn changes(
+    &self,
+  ) -> impl Stream<Item = Result<ProtocolState<Clock>>> + Send + 'static {
+    let mut receiver = self.accounts.clone();
+    receiver.mark_unchanged();
+    stream::unfold(receiver, |mut receiver| async move {
+      receiver.changed().await.ok()?;
+      let state = ProtocolState::try_from(&*receiver.borrow_and_update());
+      Some((state, receiver))
+    })
+  }
+}
+
+impl Drop for SubscriptionStateProvider {
+  fn drop(&mut self) {
+    self.task.abort();
+  }
+}
+
+#[async_trait]
+impl StateProvider<Clock> for SubscriptionStateProvider {
+  async fn fetch_state(&self) -> Result<ProtocolState<Clock>> {
+    ProtocolState::try_from(&*self.accounts.borrow())
+  }
+}
+
+/// Background task state
+struct Subscription {
+  rpc: RpcStateProvider,
+  config: RpcAccountInfoConfig,
+  ws_url: String,
+  poll_interval: Duration,
+  connected: Arc<AtomicBool>,
+}
+
+impl Subscription {
+  /// Alternates between streaming subscriptions and polling forever
+  async fn run(self, sender: watch::Sender<ProtocolAccounts>) {
+    loop {
+      // Errors only signal a dropped websocket, polling takes over below
+      let _ = self.stream(&sender).await;
+      self.connected.store(false, Ordering::Relaxed);
+      if let Ok(accounts) = self.rpc.fetch_accounts().await {
+        sender.send_replace(accounts);
+      }
+      tokio::time::sleep(self.poll_interval).await;
+    }
+  }
+
+  /// Subscribes to all protocol accounts and applies updates until the
+  /// websocket closes
+  async fn stream(
+    &self,
+    sender: &watch::Sender<ProtocolAccounts>,
+  ) -> Result<()> {
+    let client = timeout(CONNECT_TIMEOUT, PubsubClient::new(&self.ws_url))
+      .await
+      .with_context(|| format!("Timed out connecting to {}", self.ws_url))?
+      .with_context(|| format!("Failed to connect to {}", self.ws_url))?;
+    let base = ProtocolAccounts::pubkeys();
+    let lst_mints = sender.borrow().lst_mints().collect::<Vec<_>>();
+    let pubkeys = base
+      .iter()
+      .copied()
+      .chain(lst_mints.iter().map(|mint| pda::lst_header(*mint)))
+      .collect::<Vec<_>>();
+    let subscriptions = timeout(
+      CONNECT_TIMEOUT,
+      try_join_all(pubkeys.iter().map(|pubkey| {
+        client.account_subscribe(pubkey, Some(self.config.clone()))
+      })),
+    )
+    .await
+    .context("Timed out subscribing to protocol accounts")?
+    .context("Failed to subscribe to protocol accounts")?;
+    let mut updates = select_all(subscriptions.into_iter().enumerate().map(
+      |(index, (notifications, _unsubscribe))| {
+        notifications.map(move |response| (index, response))
+      },
+    ));
+
+    // Catch up on changes made before the subscriptions were active
+    sender.send_replace(self.rpc.fetch_accounts().await?);
+    self.connected.store(true, Ordering::Relaxed);
+
+    while let Some((index, response)) = updates.next().await {
+      let account = response
+        .value
+        .decode::<Account>()
+        .ok_or_else(|| anyhow!("Failed to decode {}", pubkeys[index]))?;
+      let mut result = Ok(());
+      sender.send_modify(|accounts| match index.checked_sub(base.len()) {
+        Some(lst) => accounts.set_lst_header(&lst_mints[lst], account),
+        None => result = accounts.set(index, account),
+      });
+      result?;
+
+      // Projecting outdated prices needs accounts outside the subscription,
+      // which go stale once prices are updated
+      let refetch = {
+        let accounts = sender.borrow();
+        accounts.prices_outdated()? != accounts.price_update.is_some()
+      };
+      if refetch {
+        sender.send_replace(self.rpc.fetch_accounts().await?);
+      }
+    }
+    Err(anyhow!("Websocket subscription to {} closed", self.ws_url))
+  }
+}
@@ -0,0 +1,118 @@
+//! Mint-keyed quotes for LSTs discovered from the on-chain LST registry.
+
+use anchor_lang::prelude::Pubkey;
+use anyhow::{bail, Result};
+use fix::prelude::*;
+use hylo_clients::instructions::ExchangeInstructionBuilder as ExchangeIB;
+use hylo_clients::transaction::{LstSwapArgs, MintArgs, RedeemArgs};
+use hylo_core::slippage_config::SlippageConfig;
+use hylo_core::solana_clock::SolanaClock;
+
+use crate::format::token_label;
+use crate::protocol_state::ProtocolState;
+use crate::protocol_state_strategy::withdraw_and_redeem_instructions;
+use crate::{
+  ComputeUnitStrategy, ExecutableQuoteValue, Operation, QuoteMetadata,
+  QuotedTransaction, DEFAULT_CUS_WITH_BUFFER, DEFAULT_CUS_WITH_BUFFER_X3,
+};
+
+/// Quotes an LST operation between runtime mints.
+///
+/// # Errors
+/// * Pair is not an LST operation on a registered LST
+/// * Quote math
+/// * Instruction building
+pub(crate) fn dynamic_quote<C: SolanaClock>(
+  state: &ProtocolState<C>,
+  input_mint: Pubkey,
+  output_mint: Pubkey,
+  amount_in: u64,
+  user: Pubkey,
+  slippage_tolerance: u64,
+) -> Result<(ExecutableQuoteValue, QuoteMetadata)> {
+  let operation = state.runtime_operation(input_mint, output_mint)?;
+  let (input, output) = (token_label(&input_mint), token_label(&output_mint));
+  let op = state.runtime_output(input_mint, output_mint, amount_in)?;
+  let slippage_config = Some(SlippageConfig {
+    expected_token_out: op.out_amount,
+    slippage_tolerance: UFix64::<N4>::new(slippage_tolerance).into(),
+  });
+  let (instructions, address_lookup_tables, compute_units, description) =
+    match operation {
+      Operation::MintStablecoin | Operation::MintLevercoin => {
+        let args = MintArgs {
+          amount: UFix64::new(amount_in),
+          user,
+          slippage_config,
+        };
+        (
+          ExchangeIB::mint_with_lst(input_mint, output_mint, args)?,
+          ExchangeIB::LST_LOOKUP_TABLES.to_vec(),
+          DEFAULT_CUS_WITH_BUFFER,
+          format!("Mint {output} with {input}"),
+        )
+      }
+      Operation::RedeemStablecoin | Operation::RedeemLevercoin => {
+        let args = RedeemArgs {
+          amount: UFix64::new(amount_in),
+          user,
+          slippage_config,
+        };
+        (
+          ExchangeIB::redeem_for_lst(input_mint, output_mint, args)?,
+          ExchangeIB::LST_LOOKUP_TABLES.to_vec(),
+          DEFAULT_CUS_WITH_BUFFER,
+          format!("Redeem {input} for {output}"),
+        )
+      }
+      Operation::LstSwap => {
+        let args = LstSwapArgs {
+          amount_lst_a: UFix64::new(amount_in),
+          lst_a_mint: input_mint,
+          lst_b_mint: output_mint,
+          user,
+          slippage_config,
+        };
+        (
+          ExchangeIB::swap_lsts(args),
+          ExchangeIB::LST_LOOKUP_TABLES.to_vec(),
+          DEFAULT_CUS_WITH_BUFFER,
+          format!("Swap {input} to {output}"),
+        )
+      }
+      Operation::WithdrawAndRedeemFromStabilityPool => {
+        let (instructions, address_lookup_tables) =
+          withdraw_and_redeem_instructions(
+            state,
+            output_mint,
+            UFix64::new(amount_in),
+            user,
+            Some(UFix64::new(slippage_tolerance)),
+          )?;
+        (
+          instructions,
+          address_lookup_tables,
+          DEFAULT_CUS_WITH_BUFFER_X3,
+          format!("Withdraw sHYUSD and redeem for {output}"),
+        )
+      }
+      Operation::SwapStableToLever
+      | Operation::SwapLeverToStable
+      | Operation::DepositToStabilityPool
+      | Operation::WithdrawFromStabilityPool => {
+        bail!("{operation} does not involve an LST")
+      }
+    };
+  let quote = ExecutableQuoteValue {
+    amount_in: op.in_amount,
+    amount_out: op.out_amount,
+    compute_units,
+    compute_unit_strategy: ComputeUnitStrategy::Estimated,
+    fee_amount: op.fee_amount,
+    fee_mint: op.fee_mint,
+    instructions,
+    address_lookup_tables,
+  }
+  .with_price_update(state, user)?;
+  Ok((quote, QuoteMetadata::new(operation, description)))
+}
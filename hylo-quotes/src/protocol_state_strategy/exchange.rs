@@ -16,7 +16,7 @@ use crate::protocol_state_strategy::ProtocolStateStrategy;
 use crate::token_operation::{TokenOperation, TokenOperationExt};
 use crate::{
   ComputeUnitStrategy, ExecutableQuote, Local, QuoteStrategy,
-  DEFAULT_CUS_WITH_BUFFER, LST,
+  QuotedTransaction, DEFAULT_CUS_WITH_BUFFER, LST,
 };
 
 type MintQuote = ExecutableQuote<N9, N6, N9>;
@@ -39,7 +39,7 @@ where
     user: Pubkey,
     slippage_tolerance: u64,
   ) -> Result<MintQuote> {
-    let state = self.state_provider.fetch_state().await?;
+    let state = self.fetch_state().await?;
     let op = state.compute_output(UFix64::new(amount_in))?;
     let args = MintArgs {
       amount: UFix64::<N9>::new(amount_in),
@@ -51,7 +51,7 @@ where
     };
     let instructions = ExchangeIB::build_instructions::<L, HYUSD>(args)?;
     let address_lookup_tables = ExchangeIB::lookup_tables::<L, HYUSD>().into();
-    Ok(ExecutableQuote {
+    ExecutableQuote {
       amount_in: op.in_amount,
       amount_out: op.out_amount,
       compute_units: DEFAULT_CUS_WITH_BUFFER,
@@ -60,7 +60,8 @@ where
       fee_mint: op.fee_mint,
       instructions,
       address_lookup_tables,
-    })
+    }
+    .with_price_update(&state, user)
   }
 }
 
@@ -79,7 +80,7 @@ where
     user: Pubkey,
     slippage_tolerance: u64,
   ) -> Result<RedeemQuote> {
-    let state = self.state_provider.fetch_state().await?;
+    let state = self.fetch_state().await?;
     let op = state.compute_output(UFix64::new(amount_in))?;
     let args = RedeemArgs {
       amount: UFix64::<N6>::new(amount_in),
@@ -91,7 +92,7 @@ where
     };
     let instructions = ExchangeIB::build_instructions::<HYUSD, L>(args)?;
     let address_lookup_tables = ExchangeIB::lookup_tables::<HYUSD, L>().into();
-    Ok(ExecutableQuote {
+    ExecutableQuote {
       amount_in: op.in_amount,
       amount_out: op.out_amount,
       compute_units: DEFAULT_CUS_WITH_BUFFER,
@@ -100,7 +101,8 @@ where
       fee_mint: op.fee_mint,
       instructions,
       address_lookup_tables,
-    })
+    }
+    .with_price_update(&state, user)
   }
 }
 
@@ -119,7 +121,7 @@ where
     user: Pubkey,
     slippage_tolerance: u64,
   ) -> Result<MintQuote> {
-    let state = self.state_provider.fetch_state().await?;
+    let state = self.fetch_state().await?;
     let op = state.compute_output(UFix64::new(amount_in))?;
     let args = MintArgs {
       amount: UFix64::<N9>::new(amount_in),
@@ -131,7 +133,7 @@ where
     };
     let instructions = ExchangeIB::build_instructions::<L, XSOL>(args)?;
     let address_lookup_tables = ExchangeIB::lookup_tables::<L, XSOL>().into();
-    Ok(ExecutableQuote {
+    ExecutableQuote {
       amount_in: op.in_amount,
       amount_out: op.out_amount,
       compute_units: DEFAULT_CUS_WITH_BUFFER,
@@ -140,7 +142,8 @@ where
       fee_mint: op.fee_mint,
       instructions,
       address_lookup_tables,
-    })
+    }
+    .with_price_update(&state, user)
   }
 }
 
@@ -159,7 +162,7 @@ where
     user: Pubkey,
     slippage_tolerance: u64,
   ) -> Result<RedeemQuote> {
-    let state = self.state_provider.fetch_state().await?;
+    let state = self.fetch_state().await?;
     let op = state.compute_output(UFix64::new(amount_in))?;
     let args = RedeemArgs {
       amount: UFix64::<N6>::new(amount_in),
@@ -171,7 +174,7 @@ where
     };
     let instructions = ExchangeIB::build_instructions::<XSOL, L>(args)?;
     let address_lookup_tables = ExchangeIB::lookup_tables::<XSOL, L>().into();
-    Ok(ExecutableQuote {
+    ExecutableQuote {
       amount_in: op.in_amount,
       amount_out: op.out_amount,
       compute_units: DEFAULT_CUS_WITH_BUFFER,
@@ -180,7 +183,8 @@ where
       fee_mint: op.fee_mint,
       instructions,
       address_lookup_tables,
-    })
+    }
+    .with_price_update(&state, user)
   }
 }
 
@@ -197,7 +201,7 @@ impl<S: StateProvider<C>, C: SolanaClock> QuoteStrategy<HYUSD, XSOL, C>
     user: Pubkey,
     slippage_tolerance: u64,
   ) -> Result<SwapQuote> {
-    let state = self.state_provider.fetch_state().await?;
+    let state = self.fetch_state().await?;
     let op = state.output::<HYUSD, XSOL>(UFix64::new(amount_in))?;
     let args = SwapArgs {
       amount: UFix64::<N6>::new(amount_in),
@@ -210,7 +214,7 @@ impl<S: StateProvider<C>, C: SolanaClock> QuoteStrategy<HYUSD, XSOL, C>
     let instructions = ExchangeIB::build_instructions::<HYUSD, XSOL>(args)?;
     let address_lookup_tables =
       ExchangeIB::lookup_tables::<HYUSD, XSOL>().into();
-    Ok(ExecutableQuote {
+    ExecutableQuote {
       amount_in: op.in_amount,
       amount_out: op.out_amount,
       compute_units: DEFAULT_CUS_WITH_BUFFER,
@@ -219,7 +223,8 @@ impl<S: StateProvider<C>, C: SolanaClock> QuoteStrategy<HYUSD, XSOL, C>
       fee_mint: op.fee_mint,
       instructions,
       address_lookup_tables,
-    })
+    }
+    .with_price_update(&state, user)
   }
 }
 
@@ -236,7 +241,7 @@ impl<S: StateProvider<C>, C: SolanaClock> QuoteStrategy<XSOL, HYUSD, C>
     user: Pubkey,
     slippage_tolerance: u64,
   ) -> Result<SwapQuote> {
-    let state = self.state_provider.fetch_state().await?;
+    let state = self.fetch_state().await?;
     let op = state.output::<XSOL, HYUSD>(UFix64::new(amount_in))?;
     let args = SwapArgs {
       amount: UFix64::<N6>::new(amount_in),
@@ -249,7 +254,7 @@ impl<S: StateProvider<C>, C: SolanaClock> QuoteStrategy<XSOL, HYUSD, C>
     let instructions = ExchangeIB::build_instructions::<XSOL, HYUSD>(args)?;
     let address_lookup_tables =
       ExchangeIB::lookup_tables::<XSOL, HYUSD>().into();
-    Ok(ExecutableQuote {
+    ExecutableQuote {
       amount_in: op.in_amount,
       amount_out: op.out_amount,
       compute_units: DEFAULT_CUS_WITH_BUFFER,
@@ -258,7 +263,8 @@ impl<S: StateProvider<C>, C: SolanaClock> QuoteStrategy<XSOL, HYUSD, C>
       fee_mint: op.fee_mint,
       instructions,
       address_lookup_tables,
-    })
+    }
+    .with_price_update(&state, user)
   }
 }
 
@@ -277,7 +283,7 @@ where
     user: Pubkey,
     slippage_tolerance: u64,
   ) -> Result<LstSwapQuote> {
-    let state = self.state_provider.fetch_state().await?;
+    let state = self.fetch_state().await?;
     let amount = UFix64::<N9>::new(amount_in);
     let op = state.compute_output(amount)?;
     let args = LstSwapArgs {
@@ -292,7 +298,7 @@ where
     };
     let instructions = ExchangeIB::build_instructions::<L1, L2>(args)?;
     let address_lookup_tables = ExchangeIB::lookup_tables::<L1, L2>().into();
-    Ok(ExecutableQuote {
+    ExecutableQuote {
       amount_in: op.in_amount,
       amount_out: op.out_amount,
       compute_units: DEFAULT_CUS_WITH_BUFFER,
@@ -301,6 +307,7 @@ where
       fee_mint: op.fee_mint,
       instructions,
       address_lookup_tables,
-    })
+    }
+    .with_price_update(&state, user)
   }
 }
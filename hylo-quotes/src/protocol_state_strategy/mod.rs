@@ -3,23 +3,90 @@
 //! Computes quotes using protocol state and SDK machinery like
 //! `ExchangeContext`, without requiring transaction simulation.
 
+mod dynamic;
 mod exchange;
 mod stability_pool;
 
+use anchor_lang::prelude::Pubkey;
+use anyhow::Result;
 use async_trait::async_trait;
 use hylo_core::solana_clock::SolanaClock;
+pub(crate) use stability_pool::withdraw_and_redeem_instructions;
 
-use crate::protocol_state::StateProvider;
+use crate::protocol_state::{ProtocolState, StateProvider};
+use crate::route_planner::{RoutePlanner, RouteQuote};
 use crate::runtime_quote_strategy::RuntimeQuoteStrategy;
+use crate::{ExecutableQuoteValue, QuoteMetadata};
 
 pub struct ProtocolStateStrategy<S> {
   pub state_provider: S,
+  /// Whether quotes against a projected state run its pending
+  /// `update_lst_prices` first, `true` by default. Callers that prepend the
+  /// update themselves, e.g. through
+  /// [`ProgramClient::with_lst_price_update`], can turn it off.
+  ///
+  /// [`ProgramClient::with_lst_price_update`]: hylo_clients::prelude::ProgramClient::with_lst_price_update
+  pub prepend_price_update: bool,
 }
 
 impl<S> ProtocolStateStrategy<S> {
   #[must_use]
   pub fn new(state_provider: S) -> Self {
-    Self { state_provider }
+    Self {
+      state_provider,
+      prepend_price_update: true,
+    }
+  }
+
+  /// Leaves the pending `update_lst_prices` of projected states out of
+  /// quotes.
+  #[must_use]
+  pub fn without_price_update(self) -> Self {
+    Self {
+      prepend_price_update: false,
+      ..self
+    }
+  }
+
+  /// Fetches state, dropping its pending price update unless
+  /// [`Self::prepend_price_update`].
+  async fn fetch_state<C: SolanaClock>(&self) -> Result<ProtocolState<C>>
+  where
+    S: StateProvider<C>,
+  {
+    let mut state = self.state_provider.fetch_state().await?;
+    if !self.prepend_price_update {
+      state.pending_price_update = None;
+    }
+    Ok(state)
+  }
+
+  /// Quotes the best multi-hop route between two mints against freshly
+  /// fetched state.
+  ///
+  /// # Errors
+  /// * State fetch
+  /// * No valid route between the mints
+  /// * Instruction building
+  pub async fn route_quote<C: SolanaClock>(
+    &self,
+    input_mint: Pubkey,
+    output_mint: Pubkey,
+    amount_in: u64,
+    user: Pubkey,
+    slippage_tolerance: u64,
+  ) -> Result<RouteQuote>
+  where
+    S: StateProvider<C>,
+  {
+    let state = self.fetch_state().await?;
+    RoutePlanner::new(state).quote(
+      input_mint,
+      output_mint,
+      amount_in,
+      user,
+      slippage_tolerance,
+    )
   }
 }
 
@@ -27,4 +94,22 @@ impl<S> ProtocolStateStrategy<S> {
 impl<S: StateProvider<C> + Sync, C: SolanaClock> RuntimeQuoteStrategy<C>
   for ProtocolStateStrategy<S>
 {
+  async fn dynamic_quote_with_metadata(
+    &self,
+    input_mint: Pubkey,
+    output_mint: Pubkey,
+    amount_in: u64,
+    user: Pubkey,
+    slippage_tolerance: u64,
+  ) -> Result<(ExecutableQuoteValue, QuoteMetadata)> {
+    let state = self.fetch_state().await?;
+    dynamic::dynamic_quote(
+      &state,
+      input_mint,
+      output_mint,
+      amount_in,
+      user,
+      slippage_tolerance,
+    )
+  }
 }
@@ -1,6 +1,7 @@
 //! `QuoteStrategy` implementations for stability pool pairs using
 //! `TokenOperation`.
 
+use anchor_client::solana_sdk::instruction::Instruction;
 use anchor_lang::prelude::Pubkey;
 use anyhow::Result;
 use async_trait::async_trait;
@@ -13,18 +14,19 @@ use hylo_clients::syntax_helpers::InstructionBuilderExt;
 use hylo_clients::transaction::{RedeemArgs, StabilityPoolArgs};
 use hylo_clients::util::user_ata_instruction;
 use hylo_core::fee_controller::FeeExtract;
+use hylo_core::slippage_config::SlippageConfig;
 use hylo_core::solana_clock::SolanaClock;
 use hylo_core::stability_pool_math::{
   amount_token_to_withdraw, stablecoin_withdrawal_fee,
 };
 use hylo_idl::tokens::{TokenMint, HYUSD, SHYUSD, XSOL};
 
-use crate::protocol_state::StateProvider;
+use crate::protocol_state::{ProtocolState, StateProvider};
 use crate::protocol_state_strategy::ProtocolStateStrategy;
 use crate::token_operation::TokenOperationExt;
 use crate::{
   ComputeUnitStrategy, ExecutableQuote, Local, QuoteStrategy,
-  DEFAULT_CUS_WITH_BUFFER, DEFAULT_CUS_WITH_BUFFER_X3, LST,
+  QuotedTransaction, DEFAULT_CUS_WITH_BUFFER, DEFAULT_CUS_WITH_BUFFER_X3, LST,
 };
 
 type DepositQuote = ExecutableQuote<N6, N6, N6>;
@@ -44,7 +46,7 @@ impl<S: StateProvider<C>, C: SolanaClock> QuoteStrategy<HYUSD, SHYUSD, C>
     user: Pubkey,
     _slippage_tolerance: u64,
   ) -> Result<DepositQuote> {
-    let state = self.state_provider.fetch_state().await?;
+    let state = self.fetch_state().await?;
     let op = state.output::<HYUSD, SHYUSD>(UFix64::new(amount_in))?;
     let args = StabilityPoolArgs {
       amount: UFix64::<N6>::new(amount_in),
@@ -54,7 +56,7 @@ impl<S: StateProvider<C>, C: SolanaClock> QuoteStrategy<HYUSD, SHYUSD, C>
       StabilityPoolIB::build_instructions::<HYUSD, SHYUSD>(args)?;
     let address_lookup_tables =
       StabilityPoolIB::lookup_tables::<HYUSD, SHYUSD>().into();
-    Ok(ExecutableQuote {
+    ExecutableQuote {
       amount_in: op.in_amount,
       amount_out: op.out_amount,
       compute_units: DEFAULT_CUS_WITH_BUFFER,
@@ -63,7 +65,8 @@ impl<S: StateProvider<C>, C: SolanaClock> QuoteStrategy<HYUSD, SHYUSD, C>
       fee_mint: op.fee_mint,
       instructions,
       address_lookup_tables,
-    })
+    }
+    .with_price_update(&state, user)
   }
 }
 
@@ -80,7 +83,7 @@ impl<S: StateProvider<C>, C: SolanaClock> QuoteStrategy<SHYUSD, HYUSD, C>
     user: Pubkey,
     _slippage_tolerance: u64,
   ) -> Result<WithdrawQuote> {
-    let state = self.state_provider.fetch_state().await?;
+    let state = self.fetch_state().await?;
     let op = state.output::<SHYUSD, HYUSD>(UFix64::new(amount_in))?;
     let args = StabilityPoolArgs {
       amount: UFix64::<N6>::new(amount_in),
@@ -90,7 +93,7 @@ impl<S: StateProvider<C>, C: SolanaClock> QuoteStrategy<SHYUSD, HYUSD, C>
       StabilityPoolIB::build_instructions::<SHYUSD, HYUSD>(args)?;
     let address_lookup_tables =
       StabilityPoolIB::lookup_tables::<SHYUSD, HYUSD>().into();
-    Ok(ExecutableQuote {
+    ExecutableQuote {
       amount_in: op.in_amount,
       amount_out: op.out_amount,
       compute_units: DEFAULT_CUS_WITH_BUFFER,
@@ -99,7 +102,8 @@ impl<S: StateProvider<C>, C: SolanaClock> QuoteStrategy<SHYUSD, HYUSD, C>
       fee_mint: op.fee_mint,
       instructions,
       address_lookup_tables,
-    })
+    }
+    .with_price_update(&state, user)
   }
 }
 
@@ -114,86 +118,21 @@ impl<L: LST + Local, S: StateProvider<C>, C: SolanaClock>
     &self,
     amount_in: u64,
     user: Pubkey,
-    _slippage_tolerance: u64,
+    slippage_tolerance: u64,
   ) -> Result<WithdrawRedeemQuote> {
-    let state = self.state_provider.fetch_state().await?;
+    let state = self.fetch_state().await?;
     let lp_tokens_to_burn = UFix64::<N6>::new(amount_in);
-
-    // Compute quote
     let op = state.output::<SHYUSD, L>(lp_tokens_to_burn)?;
-
-    // Recompute withdrawal amounts for instruction building
-    let lp_token_supply = UFix64::new(state.shyusd_mint.supply);
-    let stablecoin_in_pool = UFix64::new(state.hyusd_pool.amount);
-    let levercoin_in_pool = UFix64::new(state.xsol_pool.amount);
-    let stablecoin_to_withdraw = amount_token_to_withdraw(
-      lp_tokens_to_burn,
-      lp_token_supply,
-      stablecoin_in_pool,
-    )?;
-    let levercoin_to_withdraw = amount_token_to_withdraw(
-      lp_tokens_to_burn,
-      lp_token_supply,
-      levercoin_in_pool,
-    )?;
-
-    // Compute stablecoin after withdrawal fee
-    let withdrawal_fee = state.pool_config.withdrawal_fee.try_into()?;
-    let stablecoin_nav = state.exchange_context.stablecoin_nav()?;
-    let levercoin_nav = state.exchange_context.levercoin_mint_nav()?;
-    let FeeExtract {
-      amount_remaining: stablecoin_amount_remaining,
-      ..
-    } = stablecoin_withdrawal_fee(
-      stablecoin_in_pool,
-      stablecoin_to_withdraw,
-      stablecoin_nav,
-      levercoin_to_withdraw,
-      levercoin_nav,
-      withdrawal_fee,
-    )?;
-
-    // Build instructions
-    let withdraw_args = StabilityPoolArgs {
-      amount: lp_tokens_to_burn,
-      user,
-    };
-    let mut instructions = vec![user_ata_instruction(&user, &L::MINT)];
-    instructions.extend(StabilityPoolIB::build_instructions::<SHYUSD, HYUSD>(
-      withdraw_args,
-    )?);
-
-    // Redeem stablecoin if any
-    if stablecoin_amount_remaining > UFix64::zero() {
-      instructions.push(user_ata_instruction(&user, &HYUSD::MINT));
-      let redeem_args = RedeemArgs {
-        amount: stablecoin_amount_remaining,
+    let (instructions, address_lookup_tables) =
+      withdraw_and_redeem_instructions(
+        &state,
+        L::MINT,
+        lp_tokens_to_burn,
         user,
-        slippage_config: None,
-      };
-      instructions
-        .extend(ExchangeIB::build_instructions::<HYUSD, L>(redeem_args)?);
-    }
-
-    // Redeem levercoin if any
-    if levercoin_to_withdraw > UFix64::zero() {
-      instructions.push(user_ata_instruction(&user, &XSOL::MINT));
-      let redeem_args = RedeemArgs {
-        amount: levercoin_to_withdraw,
-        user,
-        slippage_config: None,
-      };
-      instructions
-        .extend(ExchangeIB::build_instructions::<XSOL, L>(redeem_args)?);
-    }
-
-    // Set up lookup tables
-    let mut address_lookup_tables: Vec<Pubkey> =
-      StabilityPoolIB::lookup_tables::<SHYUSD, HYUSD>().to_vec();
-    address_lookup_tables.extend(ExchangeIB::lookup_tables::<HYUSD, L>());
-    address_lookup_tables.dedup();
+        Some(UFix64::new(slippage_tolerance)),
+      )?;
 
-    Ok(ExecutableQuote {
+    ExecutableQuote {
       amount_in: op.in_amount,
       amount_out: op.out_amount,
       compute_units: DEFAULT_CUS_WITH_BUFFER_X3,
@@ -202,6 +141,117 @@ impl<L: LST + Local, S: StateProvider<C>, C: SolanaClock>
       fee_mint: op.fee_mint,
       instructions,
       address_lookup_tables,
-    })
+    }
+    .with_price_update(&state, user)
+  }
+}
+
+/// Builds the withdraw and redeem instructions for `SHYUSD -> LST`.
+///
+/// Withdraws pro-rata hyUSD and xSOL from the stability pool, then redeems
+/// whatever is nonzero for `lst_mint`. With a `slippage_tolerance`, each
+/// redemption is slippage checked against its own quoted output.
+///
+/// # Errors
+/// * Stability pool math
+/// * Redemption quote
+/// * Instruction building
+pub(crate) fn withdraw_and_redeem_instructions<C: SolanaClock>(
+  state: &ProtocolState<C>,
+  lst_mint: Pubkey,
+  lp_tokens_to_burn: UFix64<N6>,
+  user: Pubkey,
+  slippage_tolerance: Option<UFix64<N4>>,
+) -> Result<(Vec<Instruction>, Vec<Pubkey>)> {
+  // Recompute withdrawal amounts for instruction building
+  let lp_token_supply = UFix64::new(state.shyusd_mint.supply);
+  let stablecoin_in_pool = UFix64::new(state.hyusd_pool.amount);
+  let levercoin_in_pool = UFix64::new(state.xsol_pool.amount);
+  let stablecoin_to_withdraw = amount_token_to_withdraw(
+    lp_tokens_to_burn,
+    lp_token_supply,
+    stablecoin_in_pool,
+  )?;
+  let levercoin_to_withdraw = amount_token_to_withdraw(
+    lp_tokens_to_burn,
+    lp_token_supply,
+    levercoin_in_pool,
+  )?;
+
+  // Compute stablecoin after withdrawal fee
+  let withdrawal_fee = state.pool_config.withdrawal_fee.try_into()?;
+  let stablecoin_nav = state.exchange_context.stablecoin_nav()?;
+  let levercoin_nav = state.exchange_context.levercoin_mint_nav()?;
+  let FeeExtract {
+    amount_remaining: stablecoin_amount_remaining,
+    ..
+  } = stablecoin_withdrawal_fee(
+    stablecoin_in_pool,
+    stablecoin_to_withdraw,
+    stablecoin_nav,
+    levercoin_to_withdraw,
+    levercoin_nav,
+    withdrawal_fee,
+  )?;
+
+  // Build instructions
+  let withdraw_args = StabilityPoolArgs {
+    amount: lp_tokens_to_burn,
+    user,
+  };
+  let mut instructions = vec![user_ata_instruction(&user, &lst_mint)];
+  instructions.extend(StabilityPoolIB::build_instructions::<SHYUSD, HYUSD>(
+    withdraw_args,
+  )?);
+
+  // Redeem stablecoin if any
+  if stablecoin_amount_remaining > UFix64::zero() {
+    instructions.push(user_ata_instruction(&user, &HYUSD::MINT));
+    let op = state.runtime_output(
+      HYUSD::MINT,
+      lst_mint,
+      stablecoin_amount_remaining.bits,
+    )?;
+    let redeem_args = RedeemArgs {
+      amount: stablecoin_amount_remaining,
+      user,
+      slippage_config: slippage_tolerance.map(|tolerance| SlippageConfig {
+        expected_token_out: op.out_amount,
+        slippage_tolerance: tolerance.into(),
+      }),
+    };
+    instructions.extend(ExchangeIB::redeem_for_lst(
+      HYUSD::MINT,
+      lst_mint,
+      redeem_args,
+    )?);
   }
+
+  // Redeem levercoin if any
+  if levercoin_to_withdraw > UFix64::zero() {
+    instructions.push(user_ata_instruction(&user, &XSOL::MINT));
+    let op =
+      state.runtime_output(XSOL::MINT, lst_mint, levercoin_to_withdraw.bits)?;
+    let redeem_args = RedeemArgs {
+      amount: levercoin_to_withdraw,
+      user,
+      slippage_config: slippage_tolerance.map(|tolerance| SlippageConfig {
+        expected_token_out: op.out_amount,
+        slippage_tolerance: tolerance.into(),
+      }),
+    };
+    instructions.extend(ExchangeIB::redeem_for_lst(
+      XSOL::MINT,
+      lst_mint,
+      redeem_args,
+    )?);
+  }
+
+  // Set up lookup tables
+  let mut address_lookup_tables: Vec<Pubkey> =
+    StabilityPoolIB::lookup_tables::<SHYUSD, HYUSD>().to_vec();
+  address_lookup_tables.extend(ExchangeIB::LST_LOOKUP_TABLES);
+  address_lookup_tables.dedup();
+
+  Ok((instructions, address_lookup_tables))
 }
@@ -2,6 +2,8 @@
 
 /// Operation type for a quote
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
 pub enum Operation {
   MintStablecoin,
   RedeemStablecoin,
@@ -9,9 +11,13 @@ pub enum Operation {
   RedeemLevercoin,
   SwapStableToLever,
   SwapLeverToStable,
+  #[cfg_attr(feature = "serde", serde(rename = "swap_lst"))]
   LstSwap,
+  #[cfg_attr(feature = "serde", serde(rename = "user_deposit"))]
   DepositToStabilityPool,
+  #[cfg_attr(feature = "serde", serde(rename = "user_withdraw"))]
   WithdrawFromStabilityPool,
+  #[cfg_attr(feature = "serde", serde(rename = "user_withdraw_and_redeem"))]
   WithdrawAndRedeemFromStabilityPool,
 }
 
@@ -49,6 +55,7 @@ impl std::fmt::Display for Operation {
 
 /// Metadata for a quote route.
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct QuoteMetadata {
   /// The operation this quote represents (useful for metrics)
   pub operation: Operation,
@@ -57,6 +64,12 @@ pub struct QuoteMetadata {
   pub description: String,
 }
 
+impl std::fmt::Display for QuoteMetadata {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    f.write_str(&self.description)
+  }
+}
+
 impl QuoteMetadata {
   #[must_use]
   pub fn new(operation: Operation, description: impl Into<String>) -> Self {
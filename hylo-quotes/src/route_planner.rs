@@ -0,0 +1,534 @@
+//! Multi-hop routing over the Hylo token graph.
+//!
+//! Every direct pair with a [`TokenOperation`] impl is an edge. The planner
+//! enumerates simple paths between two mints, prices each against a single
+//! [`ProtocolState`], and builds one combined instruction list for the best
+//! route.
+//!
+//! Each leg spends exactly the quoted output of the previous one, and only the
+//! final leg is slippage checked against the route output. An intermediate
+//! leg falling short of its quote fails the next leg, so the tolerance
+//! applies once to the whole route. Stability pool deposits and withdrawals
+//! take no slippage config, so they are unchecked when they end the route.
+
+use anchor_client::solana_sdk::instruction::Instruction;
+use anchor_lang::prelude::Pubkey;
+use anyhow::{anyhow, ensure, Context, Result};
+use fix::prelude::*;
+use hylo_clients::instructions::{
+  ExchangeInstructionBuilder as ExchangeIB, InstructionBuilder,
+  StabilityPoolInstructionBuilder as StabilityPoolIB,
+};
+use hylo_clients::prelude::{ProgramClient, VersionedTransactionData};
+use hylo_clients::syntax_helpers::InstructionBuilderExt;
+use hylo_clients::transaction::{
+  LstSwapArgs, MintArgs, RedeemArgs, StabilityPoolArgs, SwapArgs,
+};
+use hylo_core::slippage_config::SlippageConfig;
+use hylo_core::solana_clock::SolanaClock;
+use hylo_idl::tokens::{TokenMint, HYLOSOL, HYUSD, JITOSOL, SHYUSD, XSOL};
+
+use crate::protocol_state::ProtocolState;
+use crate::protocol_state_strategy::withdraw_and_redeem_instructions;
+use crate::token_operation::TokenOperationExt;
+use crate::{
+  ComputeUnitStrategy, Local, Operation, QuotedTransaction,
+  DEFAULT_CUS_WITH_BUFFER, DEFAULT_CUS_WITH_BUFFER_X3, LST,
+};
+
+/// Default maximum number of legs in a route.
+pub const DEFAULT_MAX_HOPS: usize = 3;
+
+/// Direct token pair operation forming one edge of a route.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Leg {
+  pub input_mint: Pubkey,
+  pub output_mint: Pubkey,
+  pub operation: Operation,
+}
+
+impl Leg {
+  /// Buffered compute unit estimate for this leg.
+  #[must_use]
+  pub fn compute_units(&self) -> u64 {
+    match self.operation {
+      Operation::WithdrawAndRedeemFromStabilityPool => {
+        DEFAULT_CUS_WITH_BUFFER_X3
+      }
+      _ => DEFAULT_CUS_WITH_BUFFER,
+    }
+  }
+}
+
+/// Priced path through the token graph.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Route {
+  /// Legs in execution order
+  pub legs: Vec<Leg>,
+
+  /// Token amounts between legs, starting with the route input
+  pub amounts: Vec<u64>,
+}
+
+impl Route {
+  #[must_use]
+  pub fn amount_in(&self) -> u64 {
+    self.amounts.first().copied().unwrap_or_default()
+  }
+
+  #[must_use]
+  pub fn amount_out(&self) -> u64 {
+    self.amounts.last().copied().unwrap_or_default()
+  }
+}
+
+/// Best route with its combined instructions.
+#[derive(Debug, Clone)]
+pub struct RouteQuote {
+  pub route: Route,
+  pub compute_units: u64,
+  pub compute_unit_strategy: ComputeUnitStrategy,
+  pub instructions: Vec<Instruction>,
+  pub address_lookup_tables: Vec<Pubkey>,
+}
+
+impl QuotedTransaction for RouteQuote {
+  fn transaction_parts(
+    &mut self,
+  ) -> (&mut Vec<Instruction>, &mut Vec<Pubkey>, &mut u64) {
+    (
+      &mut self.instructions,
+      &mut self.address_lookup_tables,
+      &mut self.compute_units,
+    )
+  }
+}
+
+impl RouteQuote {
+  /// Loads the route's lookup tables through `client` into transaction data
+  /// carrying the route's compute unit estimate.
+  ///
+  /// # Errors
+  /// * Failed to load a lookup table
+  pub async fn transaction_data<C: ProgramClient + Sync>(
+    self,
+    client: &C,
+  ) -> Result<VersionedTransactionData> {
+    crate::transaction_data(
+      client,
+      self.instructions,
+      &self.address_lookup_tables,
+      self.compute_units,
+    )
+    .await
+  }
+}
+
+/// Plans routes against one snapshot of protocol state.
+pub struct RoutePlanner<C: SolanaClock> {
+  state: ProtocolState<C>,
+  max_hops: usize,
+}
+
+impl<C: SolanaClock> RoutePlanner<C> {
+  #[must_use]
+  pub fn new(state: ProtocolState<C>) -> Self {
+    Self {
+      state,
+      max_hops: DEFAULT_MAX_HOPS,
+    }
+  }
+
+  /// Limits the number of legs considered per route.
+  #[must_use]
+  pub fn with_max_hops(mut self, max_hops: usize) -> Self {
+    self.max_hops = max_hops;
+    self
+  }
+
+  /// Enumerates simple paths from `input_mint` to `output_mint`.
+  ///
+  /// Identical mints have no paths, so round trips are never planned.
+  #[must_use]
+  pub fn paths(
+    &self,
+    input_mint: Pubkey,
+    output_mint: Pubkey,
+  ) -> Vec<Vec<Leg>> {
+    let mut paths = Vec::new();
+    if input_mint == output_mint {
+      return paths;
+    }
+    let mut visited = vec![input_mint];
+    let mut path = Vec::new();
+    self.extend_paths(
+      input_mint,
+      output_mint,
+      &mut visited,
+      &mut path,
+      &mut paths,
+    );
+    paths
+  }
+
+  fn extend_paths(
+    &self,
+    from: Pubkey,
+    to: Pubkey,
+    visited: &mut Vec<Pubkey>,
+    path: &mut Vec<Leg>,
+    paths: &mut Vec<Vec<Leg>>,
+  ) {
+    if path.len() == self.max_hops {
+      return;
+    }
+    for leg in LEGS.iter().filter(|leg| leg.input_mint == from) {
+      if leg.output_mint == to {
+        let mut found = path.clone();
+        found.push(*leg);
+        paths.push(found);
+      } else if !visited.contains(&leg.output_mint) {
+        visited.push(leg.output_mint);
+        path.push(*leg);
+        self.extend_paths(leg.output_mint, to, visited, path, paths);
+        path.pop();
+        visited.pop();
+      }
+    }
+  }
+
+  /// Prices a path, failing if any leg fails.
+  ///
+  /// # Errors
+  /// * Leg math or mode restrictions
+  pub fn price(&self, legs: Vec<Leg>, amount_in: u64) -> Result<Route> {
+    let amounts = legs.iter().try_fold(vec![amount_in], |mut acc, leg| {
+      let amount = *acc.last().context("empty amounts")?;
+      acc.push(leg_output(&self.state, leg, amount)?);
+      Ok::<_, anyhow::Error>(acc)
+    })?;
+    Ok(Route { legs, amounts })
+  }
+
+  /// Prices every path between two mints, best output first.
+  ///
+  /// Paths with a failing leg are omitted. Ties favor fewer legs.
+  #[must_use]
+  pub fn routes(
+    &self,
+    input_mint: Pubkey,
+    output_mint: Pubkey,
+    amount_in: u64,
+  ) -> Vec<Route> {
+    let mut routes = self
+      .paths(input_mint, output_mint)
+      .into_iter()
+      .filter_map(|legs| self.price(legs, amount_in).ok())
+      .collect::<Vec<_>>();
+    routes.sort_by(|a, b| {
+      b.amount_out()
+        .cmp(&a.amount_out())
+        .then(a.legs.len().cmp(&b.legs.len()))
+    });
+    routes
+  }
+
+  /// Finds the route with the highest output.
+  ///
+  /// # Errors
+  /// * No valid route between the mints
+  pub fn best_route(
+    &self,
+    input_mint: Pubkey,
+    output_mint: Pubkey,
+    amount_in: u64,
+  ) -> Result<Route> {
+    self
+      .routes(input_mint, output_mint, amount_in)
+      .into_iter()
+      .next()
+      .ok_or_else(|| anyhow!("No route from {input_mint} to {output_mint}"))
+  }
+
+  /// Builds the combined instructions for a priced route, preceded by the
+  /// pending `update_lst_prices` of a projected state.
+  ///
+  /// Each leg spends the quoted output of the previous one, so the built
+  /// instructions move exactly [`Route::amounts`]. Only the final leg is
+  /// slippage checked, against [`Route::amount_out`] at `slippage_tolerance`;
+  /// an intermediate leg falling short of its quote fails the transaction.
+  ///
+  /// # Errors
+  /// * Empty route or amounts not matching its legs
+  /// * Instruction building
+  pub fn build(
+    &self,
+    route: Route,
+    user: Pubkey,
+    slippage_tolerance: u64,
+  ) -> Result<RouteQuote> {
+    ensure!(!route.legs.is_empty(), "Empty route");
+    ensure!(
+      route.amounts.len() == route.legs.len() + 1,
+      "Route amounts do not match its legs"
+    );
+    let builder = LegBuilder {
+      state: &self.state,
+      user,
+    };
+    let last = route.legs.len() - 1;
+    let mut instructions = Vec::new();
+    let mut address_lookup_tables: Vec<Pubkey> = Vec::new();
+    for (index, (leg, amounts)) in
+      route.legs.iter().zip(route.amounts.windows(2)).enumerate()
+    {
+      let slippage = (index == last).then(|| LegSlippage {
+        expected_out: amounts[1],
+        tolerance: UFix64::new(slippage_tolerance),
+      });
+      let (leg_instructions, leg_luts) =
+        leg_instructions(&builder, leg, amounts[0], slippage)?;
+      instructions.extend(leg_instructions);
+      leg_luts.into_iter().for_each(|lut| {
+        if !address_lookup_tables.contains(&lut) {
+          address_lookup_tables.push(lut);
+        }
+      });
+    }
+    RouteQuote {
+      compute_units: route.legs.iter().map(Leg::compute_units).sum(),
+      compute_unit_strategy: ComputeUnitStrategy::Estimated,
+      route,
+      instructions,
+      address_lookup_tables,
+    }
+    .with_price_update(&self.state, user)
+  }
+
+  /// Plans the best route and builds its instructions.
+  ///
+  /// # Errors
+  /// * No valid route between the mints
+  /// * Instruction building
+  pub fn quote(
+    &self,
+    input_mint: Pubkey,
+    output_mint: Pubkey,
+    amount_in: u64,
+    user: Pubkey,
+    slippage_tolerance: u64,
+  ) -> Result<RouteQuote> {
+    let route = self.best_route(input_mint, output_mint, amount_in)?;
+    self.build(route, user, slippage_tolerance)
+  }
+}
+
+// ============================================================================
+// LEG DISPATCH
+// ============================================================================
+
+type LegInstructions = (Vec<Instruction>, Vec<Pubkey>);
+
+/// Untyped slippage bound for the final leg of a route.
+struct LegSlippage {
+  expected_out: u64,
+  tolerance: UFix64<N4>,
+}
+
+impl LegSlippage {
+  fn config<OUT: TokenMint>(&self) -> SlippageConfig {
+    SlippageConfig::new(
+      UFix64::<OUT::Exp>::new(self.expected_out),
+      self.tolerance,
+    )
+  }
+}
+
+/// Instruction builders for each kind of leg.
+struct LegBuilder<'a, C: SolanaClock> {
+  state: &'a ProtocolState<C>,
+  user: Pubkey,
+}
+
+impl<C: SolanaClock> LegBuilder<'_, C> {
+  fn mint<IN: TokenMint, OUT: TokenMint>(
+    &self,
+    amount: u64,
+    slippage: Option<LegSlippage>,
+  ) -> Result<LegInstructions>
+  where
+    ExchangeIB: InstructionBuilder<IN, OUT, Inputs = MintArgs>,
+  {
+    let args = MintArgs {
+      amount: UFix64::new(amount),
+      user: self.user,
+      slippage_config: slippage.map(|slippage| slippage.config::<OUT>()),
+    };
+    Ok((
+      ExchangeIB::build_instructions::<IN, OUT>(args)?,
+      ExchangeIB::lookup_tables::<IN, OUT>().to_vec(),
+    ))
+  }
+
+  fn redeem<IN: TokenMint, OUT: TokenMint>(
+    &self,
+    amount: u64,
+    slippage: Option<LegSlippage>,
+  ) -> Result<LegInstructions>
+  where
+    ExchangeIB: InstructionBuilder<IN, OUT, Inputs = RedeemArgs>,
+  {
+    let args = RedeemArgs {
+      amount: UFix64::new(amount),
+      user: self.user,
+      slippage_config: slippage.map(|slippage| slippage.config::<OUT>()),
+    };
+    Ok((
+      ExchangeIB::build_instructions::<IN, OUT>(args)?,
+      ExchangeIB::lookup_tables::<IN, OUT>().to_vec(),
+    ))
+  }
+
+  fn swap<IN: TokenMint, OUT: TokenMint>(
+    &self,
+    amount: u64,
+    slippage: Option<LegSlippage>,
+  ) -> Result<LegInstructions>
+  where
+    ExchangeIB: InstructionBuilder<IN, OUT, Inputs = SwapArgs>,
+  {
+    let args = SwapArgs {
+      amount: UFix64::new(amount),
+      user: self.user,
+      slippage_config: slippage.map(|slippage| slippage.config::<OUT>()),
+    };
+    Ok((
+      ExchangeIB::build_instructions::<IN, OUT>(args)?,
+      ExchangeIB::lookup_tables::<IN, OUT>().to_vec(),
+    ))
+  }
+
+  fn lst_swap<IN: TokenMint, OUT: TokenMint>(
+    &self,
+    amount: u64,
+    slippage: Option<LegSlippage>,
+  ) -> Result<LegInstructions>
+  where
+    ExchangeIB: InstructionBuilder<IN, OUT, Inputs = LstSwapArgs>,
+  {
+    let args = LstSwapArgs {
+      amount_lst_a: UFix64::new(amount),
+      lst_a_mint: IN::MINT,
+      lst_b_mint: OUT::MINT,
+      user: self.user,
+      slippage_config: slippage.map(|slippage| slippage.config::<OUT>()),
+    };
+    Ok((
+      ExchangeIB::build_instructions::<IN, OUT>(args)?,
+      ExchangeIB::lookup_tables::<IN, OUT>().to_vec(),
+    ))
+  }
+
+  /// `user_deposit` and `user_withdraw` take no slippage config, so their
+  /// output is unbounded even as the final leg.
+  fn stability_pool<IN: TokenMint, OUT: TokenMint>(
+    &self,
+    amount: u64,
+    _slippage: Option<LegSlippage>,
+  ) -> Result<LegInstructions>
+  where
+    StabilityPoolIB: InstructionBuilder<IN, OUT, Inputs = StabilityPoolArgs>,
+  {
+    let args = StabilityPoolArgs {
+      amount: UFix64::new(amount),
+      user: self.user,
+    };
+    Ok((
+      StabilityPoolIB::build_instructions::<IN, OUT>(args)?,
+      StabilityPoolIB::lookup_tables::<IN, OUT>().to_vec(),
+    ))
+  }
+
+  /// `IN` is always `SHYUSD`, kept for uniform dispatch in `route_legs!`.
+  /// As the final leg, each redemption is bounded by the route tolerance.
+  #[allow(clippy::extra_unused_type_parameters)]
+  fn withdraw_and_redeem<IN, L: LST + Local>(
+    &self,
+    amount: u64,
+    slippage: Option<LegSlippage>,
+  ) -> Result<LegInstructions> {
+    withdraw_and_redeem_instructions(
+      self.state,
+      L::MINT,
+      UFix64::new(amount),
+      self.user,
+      slippage.map(|slippage| slippage.tolerance),
+    )
+  }
+}
+
+macro_rules! route_legs {
+  ($(($in:ty, $out:ty, $op:expr, $build:ident)),* $(,)?) => {
+    /// Every direct pair usable as a route leg.
+    pub const LEGS: &[Leg] = &[
+      $(
+        Leg {
+          input_mint: <$in>::MINT,
+          output_mint: <$out>::MINT,
+          operation: $op,
+        },
+      )*
+    ];
+
+    fn leg_output<C: SolanaClock>(
+      state: &ProtocolState<C>,
+      leg: &Leg,
+      amount_in: u64,
+    ) -> Result<u64> {
+      match (leg.input_mint, leg.output_mint) {
+        $(
+          (<$in>::MINT, <$out>::MINT) => {
+            let op = state.output::<$in, $out>(UFix64::new(amount_in))?;
+            Ok(op.out_amount.bits)
+          }
+        )*
+        _ => Err(anyhow!("Unsupported pair")),
+      }
+    }
+
+    fn leg_instructions<C: SolanaClock>(
+      builder: &LegBuilder<'_, C>,
+      leg: &Leg,
+      amount_in: u64,
+      slippage: Option<LegSlippage>,
+    ) -> Result<LegInstructions> {
+      match (leg.input_mint, leg.output_mint) {
+        $(
+          (<$in>::MINT, <$out>::MINT) => {
+            builder.$build::<$in, $out>(amount_in, slippage)
+          }
+        )*
+        _ => Err(anyhow!("Unsupported pair")),
+      }
+    }
+  };
+}
+
+route_legs! {
+  (JITOSOL, HYUSD, Operation::MintStablecoin, mint),
+  (HYUSD, JITOSOL, Operation::RedeemStablecoin, redeem),
+  (HYLOSOL, HYUSD, Operation::MintStablecoin, mint),
+  (HYUSD, HYLOSOL, Operation::RedeemStablecoin, redeem),
+  (JITOSOL, XSOL, Operation::MintLevercoin, mint),
+  (XSOL, JITOSOL, Operation::RedeemLevercoin, redeem),
+  (HYLOSOL, XSOL, Operation::MintLevercoin, mint),
+  (XSOL, HYLOSOL, Operation::RedeemLevercoin, redeem),
+  (HYUSD, XSOL, Operation::SwapStableToLever, swap),
+  (XSOL, HYUSD, Operation::SwapLeverToStable, swap),
+  (JITOSOL, HYLOSOL, Operation::LstSwap, lst_swap),
+  (HYLOSOL, JITOSOL, Operation::LstSwap, lst_swap),
+  (HYUSD, SHYUSD, Operation::DepositToStabilityPool, stability_pool),
+  (SHYUSD, HYUSD, Operation::WithdrawFromStabilityPool, stability_pool),
+  (SHYUSD, JITOSOL, Operation::WithdrawAndRedeemFromStabilityPool, withdraw_and_redeem),
+  (SHYUSD, HYLOSOL, Operation::WithdrawAndRedeemFromStabilityPool, withdraw_and_redeem),
+}
@@ -29,7 +29,10 @@ macro_rules! runtime_quote_strategies {
                 Ok(quote.into())
               },
             )*
-            _ => Err(anyhow!("Unsupported pair")),
+            _ => {
+              let (quote, _) = self.dynamic_quote_with_metadata(input_mint, output_mint, amount_in, user, slippage_tolerance).await?;
+              Ok(quote)
+            }
           }
         }
 
@@ -49,9 +52,22 @@ macro_rules! runtime_quote_strategies {
                 Ok((quote.into(), QuoteMetadata::new($op, $desc)))
               },
             )*
-            _ => Err(anyhow!("Unsupported pair")),
+            _ => self.dynamic_quote_with_metadata(input_mint, output_mint, amount_in, user, slippage_tolerance).await,
           }
         }
+
+        /// Fetches quote for a pair outside the typed table, such as an LST
+        /// registered after this SDK was released. Unsupported by default.
+        async fn dynamic_quote_with_metadata(
+          &self,
+          _input_mint: Pubkey,
+          _output_mint: Pubkey,
+          _amount_in: u64,
+          _user: Pubkey,
+          _slippage_tolerance: u64,
+        ) -> Result<(ExecutableQuoteValue, QuoteMetadata)> {
+          Err(anyhow!("Unsupported pair"))
+        }
       }
     };
 }
@@ -0,0 +1,400 @@
+//! Offline price scenario simulation
+//!
+//! Replays a path of SOL/USD prices over a [`ProtocolState`], recomputing the
+//! exchange context and applying stability pool rebalances at every step.
+//! Each step yields a projected state, usable for quoting, alongside a summary
+//! of protocol metrics.
+//!
+//! ```rust,ignore
+//! use hylo_quotes::prelude::*;
+//!
+//! let state = FileStateProvider::from_file(path)?.fetch_state().await?;
+//! // SOL drops 40% over 10 steps
+//! let scenario = Scenario::decline(&state, UFix64::new(4000), 10)?;
+//! for step in scenario.run(&state)? {
+//!   println!("{}: {}", step.metrics.sol_usd_price.lower, step.metrics.stability_mode);
+//! }
+//! ```
+
+use anyhow::{anyhow, Result};
+use fix::prelude::*;
+use hylo_core::fee_controller::FeeController;
+use hylo_core::pyth::PriceRange;
+use hylo_core::solana_clock::SolanaClock;
+use hylo_core::stability_mode::StabilityMode;
+use hylo_core::stability_pool_math::{
+  amount_lever_to_swap, amount_stable_to_swap, lp_token_nav,
+};
+
+use crate::protocol_state::ProtocolState;
+use crate::state_transition::{with_amount, with_supply};
+
+/// Stability pool swap applied during a scenario step.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Rebalance {
+  /// Stablecoin from the pool swapped to levercoin, raising the CR.
+  StableToLever {
+    stablecoin_in: UFix64<N6>,
+    levercoin_out: UFix64<N6>,
+  },
+  /// Levercoin from the pool swapped back to stablecoin.
+  LeverToStable {
+    levercoin_in: UFix64<N6>,
+    stablecoin_out: UFix64<N6>,
+  },
+}
+
+/// Protocol metrics observed at the end of a scenario step.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ScenarioMetrics {
+  pub sol_usd_price: PriceRange<N8>,
+  pub collateral_ratio: UFix64<N9>,
+  pub stability_mode: StabilityMode,
+  pub stablecoin_nav: UFix64<N9>,
+  pub levercoin_nav: PriceRange<N9>,
+  pub stablecoin_supply: UFix64<N6>,
+  pub levercoin_supply: UFix64<N6>,
+  /// Stablecoin mint fee, `None` if minting is disabled in this mode.
+  pub stablecoin_mint_fee: Option<UFix64<N4>>,
+  pub stablecoin_redeem_fee: Option<UFix64<N4>>,
+  /// Levercoin mint fee, `None` if minting is disabled in this mode.
+  pub levercoin_mint_fee: Option<UFix64<N4>>,
+  /// Levercoin redeem fee, `None` if redeeming is disabled in this mode.
+  pub levercoin_redeem_fee: Option<UFix64<N4>>,
+  pub stablecoin_in_pool: UFix64<N6>,
+  pub levercoin_in_pool: UFix64<N6>,
+  pub lp_token_nav: UFix64<N6>,
+}
+
+/// Single point in a scenario timeline.
+#[derive(Clone)]
+pub struct ScenarioStep<C: SolanaClock> {
+  /// Zero-based index into the scenario's price path
+  pub step: usize,
+
+  /// Rebalance applied by the stability pool, if any
+  pub rebalance: Option<Rebalance>,
+
+  /// Metrics after the rebalance
+  pub metrics: ScenarioMetrics,
+
+  /// Projected protocol state after the rebalance
+  pub state: ProtocolState<C>,
+}
+
+/// Path of SOL/USD prices to replay over a protocol state.
+#[derive(Clone, Debug)]
+pub struct Scenario {
+  prices: Vec<PriceRange<N8>>,
+  rebalance: bool,
+}
+
+impl Scenario {
+  /// Scenario over an explicit price path, with rebalancing enabled.
+  #[must_use]
+  pub fn new(prices: Vec<PriceRange<N8>>) -> Scenario {
+    Scenario {
+      prices,
+      rebalance: true,
+    }
+  }
+
+  /// Linear path of `steps` prices from `start` (exclusive) to `end`
+  /// (inclusive). Both bounds of the price range are interpolated.
+  ///
+  /// # Errors
+  /// * Arithmetic overflow
+  pub fn linear(
+    start: PriceRange<N8>,
+    end: PriceRange<N8>,
+    steps: u64,
+  ) -> Result<Scenario> {
+    let prices = (1..=steps)
+      .map(|i| {
+        Ok(PriceRange::new(
+          interpolate(start.lower, end.lower, i, steps)?,
+          interpolate(start.upper, end.upper, i, steps)?,
+        ))
+      })
+      .collect::<Result<_>>()?;
+    Ok(Scenario::new(prices))
+  }
+
+  /// Linear decline of the state's current SOL/USD price by `decline`
+  /// (in basis points) over `steps`.
+  ///
+  /// # Errors
+  /// * Decline over 100%
+  /// * Arithmetic overflow
+  pub fn decline<C: SolanaClock>(
+    state: &ProtocolState<C>,
+    decline: UFix64<N4>,
+    steps: u64,
+  ) -> Result<Scenario> {
+    let start = state.exchange_context.sol_usd_price;
+    let remaining = UFix64::<N4>::one()
+      .checked_sub(&decline)
+      .ok_or(anyhow!("Decline must not exceed 100%"))?;
+    let scale = |price: UFix64<N8>| {
+      price
+        .mul_div_floor(remaining.convert::<N8>(), UFix64::one())
+        .ok_or(anyhow!("Overflow scaling SOL/USD price"))
+    };
+    let end = PriceRange::new(scale(start.lower)?, scale(start.upper)?);
+    Scenario::linear(start, end, steps)
+  }
+
+  /// Disables stability pool rebalancing between steps.
+  #[must_use]
+  pub fn without_rebalance(self) -> Scenario {
+    Scenario {
+      rebalance: false,
+      ..self
+    }
+  }
+
+  /// Price path of this scenario.
+  #[must_use]
+  pub fn prices(&self) -> &[PriceRange<N8>] {
+    &self.prices
+  }
+
+  /// Runs the scenario over a clone of `state`, returning one step per price.
+  /// Total SOL collateral is held constant; only the price and the stability
+  /// pool's swaps move the protocol.
+  ///
+  /// # Errors
+  /// * Exchange context or stability pool math failure
+  pub fn run<C: SolanaClock + Clone>(
+    &self,
+    state: &ProtocolState<C>,
+  ) -> Result<Vec<ScenarioStep<C>>> {
+    let mut current = state.clone();
+    self
+      .prices
+      .iter()
+      .enumerate()
+      .map(|(step, price)| {
+        let repriced = with_price(&current, *price)?;
+        let (next, rebalance) = if self.rebalance {
+          rebalance(&repriced)?
+        } else {
+          (repriced, None)
+        };
+        let metrics = ScenarioMetrics::from_state(&next)?;
+        current = next.clone();
+        Ok(ScenarioStep {
+          step,
+          rebalance,
+          metrics,
+          state: next,
+        })
+      })
+      .collect()
+  }
+}
+
+impl ScenarioMetrics {
+  /// Reads metrics off a protocol state.
+  ///
+  /// # Errors
+  /// * NAV or LP token NAV computation
+  pub fn from_state<C: SolanaClock>(
+    state: &ProtocolState<C>,
+  ) -> Result<ScenarioMetrics> {
+    let ctx = &state.exchange_context;
+    let mode = ctx.stability_mode;
+    let stablecoin_nav = ctx.stablecoin_nav()?;
+    let levercoin_nav =
+      PriceRange::new(ctx.levercoin_redeem_nav()?, ctx.levercoin_mint_nav()?);
+    let stablecoin_in_pool = UFix64::new(state.hyusd_pool.amount);
+    let levercoin_in_pool = UFix64::new(state.xsol_pool.amount);
+    let lp_token_nav = lp_token_nav(
+      stablecoin_nav,
+      stablecoin_in_pool,
+      levercoin_nav.upper,
+      levercoin_in_pool,
+      UFix64::new(state.shyusd_mint.supply),
+    )?;
+    Ok(ScenarioMetrics {
+      sol_usd_price: ctx.sol_usd_price,
+      collateral_ratio: ctx.collateral_ratio,
+      stability_mode: mode,
+      stablecoin_nav,
+      levercoin_nav,
+      stablecoin_supply: ctx.stablecoin_supply,
+      levercoin_supply: ctx.levercoin_supply()?,
+      stablecoin_mint_fee: ctx.stablecoin_fees().mint_fee(mode).ok(),
+      stablecoin_redeem_fee: ctx.stablecoin_fees().redeem_fee(mode).ok(),
+      levercoin_mint_fee: ctx.levercoin_fees().mint_fee(mode).ok(),
+      levercoin_redeem_fee: ctx.levercoin_fees().redeem_fee(mode).ok(),
+      stablecoin_in_pool,
+      levercoin_in_pool,
+      lp_token_nav,
+    })
+  }
+}
+
+/// `start + (end - start) * i / steps`, for either direction of movement.
+fn interpolate(
+  start: UFix64<N8>,
+  end: UFix64<N8>,
+  i: u64,
+  steps: u64,
+) -> Result<UFix64<N8>> {
+  let delta = start
+    .abs_diff(&end)
+    .bits
+    .checked_mul(i)
+    .and_then(|d| d.checked_div(steps))
+    .map(UFix64::new)
+    .ok_or(anyhow!("Overflow interpolating SOL/USD price"))?;
+  if end < start {
+    start.checked_sub(&delta)
+  } else {
+    start.checked_add(&delta)
+  }
+  .ok_or(anyhow!("Overflow interpolating SOL/USD price"))
+}
+
+/// Substitutes the SOL/USD price, recomputing the exchange context.
+fn with_price<C: SolanaClock + Clone>(
+  state: &ProtocolState<C>,
+  price: PriceRange<N8>,
+) -> Result<ProtocolState<C>> {
+  let ctx = &state.exchange_context;
+  let exchange_context = ctx.reload(
+    ctx.total_sol,
+    price,
+    ctx.stablecoin_supply,
+    Some(ctx.levercoin_supply()?),
+  )?;
+  Ok(ProtocolState {
+    exchange_context,
+    ..state.clone()
+  })
+}
+
+/// Applies the stability pool's rebalance for the state's stability mode.
+fn rebalance<C: SolanaClock + Clone>(
+  state: &ProtocolState<C>,
+) -> Result<(ProtocolState<C>, Option<Rebalance>)> {
+  match Rebalance::for_state(state)? {
+    Some(r) => Ok((apply(state, r)?, Some(r))),
+    None => Ok((state.clone(), None)),
+  }
+}
+
+impl Rebalance {
+  /// Rebalance the stability pool would currently execute, `None` if there
+  /// is nothing to swap.
+  ///
+  /// * Below `Normal`: swaps pool stablecoin into levercoin until the next
+  ///   higher threshold is reached or the pool is out of stablecoin.
+  /// * In `Normal`: swaps pool levercoin back into stablecoin, bounded by the
+  ///   stablecoin swappable before the next lower threshold.
+  ///
+  /// # Errors
+  /// * NAV or stability pool math failure
+  pub fn for_state<C: SolanaClock>(
+    state: &ProtocolState<C>,
+  ) -> Result<Option<Rebalance>> {
+    let ctx = &state.exchange_context;
+    let stablecoin_in_pool = UFix64::<N6>::new(state.hyusd_pool.amount);
+    let levercoin_in_pool = UFix64::<N6>::new(state.xsol_pool.amount);
+    let conversion = ctx.swap_conversion()?;
+    let rebalance = match ctx
+      .stability_controller
+      .prev_stability_threshold(ctx.stability_mode)
+    {
+      Some(target) if stablecoin_in_pool > UFix64::zero() => {
+        let stablecoin_in = amount_stable_to_swap(
+          stablecoin_in_pool,
+          target,
+          ctx.stablecoin_supply,
+          ctx.total_value_locked()?,
+        )?;
+        let levercoin_out = conversion.stable_to_lever(stablecoin_in)?;
+        Some(Rebalance::StableToLever {
+          stablecoin_in,
+          levercoin_out,
+        })
+      }
+      None if levercoin_in_pool > UFix64::zero() => {
+        let levercoin_in = amount_lever_to_swap(
+          levercoin_in_pool,
+          conversion.levercoin_nav,
+          ctx.max_swappable_stablecoin_to_next_threshold()?,
+        )?;
+        let stablecoin_out = conversion.lever_to_stable(levercoin_in)?;
+        Some(Rebalance::LeverToStable {
+          levercoin_in,
+          stablecoin_out,
+        })
+      }
+      _ => None,
+    };
+    Ok(rebalance.filter(|r| !r.is_empty()))
+  }
+
+  fn is_empty(&self) -> bool {
+    match self {
+      Rebalance::StableToLever { stablecoin_in, .. } => {
+        *stablecoin_in == UFix64::zero()
+      }
+      Rebalance::LeverToStable { levercoin_in, .. } => {
+        *levercoin_in == UFix64::zero()
+      }
+    }
+  }
+}
+
+/// Moves supplies and pool balances by a rebalance swap and recomputes the
+/// exchange context. TVL is unchanged by a swap.
+fn apply<C: SolanaClock + Clone>(
+  state: &ProtocolState<C>,
+  rebalance: Rebalance,
+) -> Result<ProtocolState<C>> {
+  let ctx = &state.exchange_context;
+  let overflow = || anyhow!("Overflow applying stability pool rebalance");
+  let (stablecoin_supply, levercoin_supply, hyusd_pool, xsol_pool) =
+    match rebalance {
+      Rebalance::StableToLever {
+        stablecoin_in,
+        levercoin_out,
+      } => (
+        ctx.stablecoin_supply.checked_sub(&stablecoin_in),
+        ctx.levercoin_supply()?.checked_add(&levercoin_out),
+        state.hyusd_pool.amount.checked_sub(stablecoin_in.bits),
+        state.xsol_pool.amount.checked_add(levercoin_out.bits),
+      ),
+      Rebalance::LeverToStable {
+        levercoin_in,
+        stablecoin_out,
+      } => (
+        ctx.stablecoin_supply.checked_add(&stablecoin_out),
+        ctx.levercoin_supply()?.checked_sub(&levercoin_in),
+        state.hyusd_pool.amount.checked_add(stablecoin_out.bits),
+        state.xsol_pool.amount.checked_sub(levercoin_in.bits),
+      ),
+    };
+  let stablecoin_supply = stablecoin_supply.ok_or_else(overflow)?;
+  let levercoin_supply = levercoin_supply.ok_or_else(overflow)?;
+  let exchange_context = ctx.reload(
+    ctx.total_sol,
+    ctx.sol_usd_price,
+    stablecoin_supply,
+    Some(levercoin_supply),
+  )?;
+  Ok(ProtocolState {
+    exchange_context,
+    hyusd_mint: with_supply(&state.hyusd_mint, stablecoin_supply.bits)?,
+    xsol_mint: with_supply(&state.xsol_mint, levercoin_supply.bits)?,
+    hyusd_pool: with_amount(
+      &state.hyusd_pool,
+      hyusd_pool.ok_or_else(overflow)?,
+    )?,
+    xsol_pool: with_amount(&state.xsol_pool, xsol_pool.ok_or_else(overflow)?)?,
+    ..state.clone()
+  })
+}
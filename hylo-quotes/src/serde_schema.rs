@@ -0,0 +1,345 @@
+//! JSON representation of quotes and protocol state, enabled by the `serde`
+//! feature.
+//!
+//! Extends [`hylo_core::serde_schema`], whose fixed point and pubkey
+//! representations apply here too, with:
+//!
+//! * Instructions as `{"program_id", "accounts": [{"pubkey", "is_signer",
+//!   "is_writable"}], "data"}` with base64 data.
+//! * Mint and token accounts as their decoded SPL fields.
+//! * LST headers keyed by base58 mint and pool config as their IDL fields.
+//!   Reserved padding is omitted and reads back as zeroes.
+
+use std::collections::BTreeMap;
+
+use anchor_client::solana_sdk::instruction::{AccountMeta, Instruction};
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::program_option::COption;
+use anchor_lang::solana_program::program_pack::Pack;
+use anchor_lang::AccountDeserialize;
+use anchor_spl::token::spl_token::state::{
+  Account as SplAccount, AccountState, Mint as SplMint,
+};
+use anchor_spl::token::{Mint, TokenAccount};
+use base64::prelude::{Engine, BASE64_STANDARD};
+use fix::prelude::UFixValue64;
+use hylo_core::idl::exchange::accounts::LstHeader;
+use hylo_core::idl::exchange::types::{LstSolPrice, LstStakePoolProgram};
+use hylo_core::idl::stability_pool::accounts::PoolConfig;
+use hylo_core::serde_schema::{option_pubkey, pubkey, ufix_value64};
+use serde::de::Error;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// [`Instruction`] list with base64 data.
+pub mod instructions {
+  use super::*;
+
+  #[derive(Serialize, Deserialize)]
+  struct AccountRepr {
+    #[serde(with = "pubkey")]
+    pubkey: Pubkey,
+    is_signer: bool,
+    is_writable: bool,
+  }
+
+  #[derive(Serialize, Deserialize)]
+  struct InstructionRepr {
+    #[serde(with = "pubkey")]
+    program_id: Pubkey,
+    accounts: Vec<AccountRepr>,
+    data: String,
+  }
+
+  impl From<&Instruction> for InstructionRepr {
+    fn from(ix: &Instruction) -> InstructionRepr {
+      InstructionRepr {
+        program_id: ix.program_id,
+        accounts: ix
+          .accounts
+          .iter()
+          .map(|meta| AccountRepr {
+            pubkey: meta.pubkey,
+            is_signer: meta.is_signer,
+            is_writable: meta.is_writable,
+          })
+          .collect(),
+        data: BASE64_STANDARD.encode(&ix.data),
+      }
+    }
+  }
+
+  impl InstructionRepr {
+    fn instruction<E: Error>(self) -> Result<Instruction, E> {
+      Ok(Instruction {
+        program_id: self.program_id,
+        accounts: self
+          .accounts
+          .into_iter()
+          .map(|account| AccountMeta {
+            pubkey: account.pubkey,
+            is_signer: account.is_signer,
+            is_writable: account.is_writable,
+          })
+          .collect(),
+        data: BASE64_STANDARD.decode(&self.data).map_err(E::custom)?,
+      })
+    }
+  }
+
+  pub fn serialize<S: Serializer>(
+    instructions: &[Instruction],
+    serializer: S,
+  ) -> Result<S::Ok, S::Error> {
+    serializer.collect_seq(instructions.iter().map(InstructionRepr::from))
+  }
+
+  pub fn deserialize<'de, D: Deserializer<'de>>(
+    deserializer: D,
+  ) -> Result<Vec<Instruction>, D::Error> {
+    Vec::<InstructionRepr>::deserialize(deserializer)?
+      .into_iter()
+      .map(InstructionRepr::instruction)
+      .collect()
+  }
+}
+
+/// [`Mint`] as its SPL fields.
+pub mod mint {
+  use super::*;
+
+  #[derive(Serialize, Deserialize)]
+  struct MintRepr {
+    #[serde(with = "option_pubkey")]
+    mint_authority: Option<Pubkey>,
+    supply: u64,
+    decimals: u8,
+    is_initialized: bool,
+    #[serde(with = "option_pubkey")]
+    freeze_authority: Option<Pubkey>,
+  }
+
+  pub fn serialize<S: Serializer>(
+    mint: &Mint,
+    serializer: S,
+  ) -> Result<S::Ok, S::Error> {
+    MintRepr {
+      mint_authority: mint.mint_authority.into(),
+      supply: mint.supply,
+      decimals: mint.decimals,
+      is_initialized: mint.is_initialized,
+      freeze_authority: mint.freeze_authority.into(),
+    }
+    .serialize(serializer)
+  }
+
+  pub fn deserialize<'de, D: Deserializer<'de>>(
+    deserializer: D,
+  ) -> Result<Mint, D::Error> {
+    let repr = MintRepr::deserialize(deserializer)?;
+    let inner = SplMint {
+      mint_authority: COption::from(repr.mint_authority),
+      supply: repr.supply,
+      decimals: repr.decimals,
+      is_initialized: repr.is_initialized,
+      freeze_authority: COption::from(repr.freeze_authority),
+    };
+    let mut buf = [0u8; Mint::LEN];
+    inner.pack_into_slice(&mut buf);
+    Mint::try_deserialize_unchecked(&mut buf.as_slice())
+      .map_err(D::Error::custom)
+  }
+}
+
+/// [`TokenAccount`] as its SPL fields.
+pub mod token_account {
+  use super::*;
+
+  #[derive(Serialize, Deserialize)]
+  #[serde(remote = "AccountState")]
+  enum AccountStateDef {
+    Uninitialized,
+    Initialized,
+    Frozen,
+  }
+
+  #[derive(Serialize, Deserialize)]
+  struct TokenAccountRepr {
+    #[serde(with = "pubkey")]
+    mint: Pubkey,
+    #[serde(with = "pubkey")]
+    owner: Pubkey,
+    amount: u64,
+    #[serde(with = "option_pubkey")]
+    delegate: Option<Pubkey>,
+    #[serde(with = "AccountStateDef")]
+    state: AccountState,
+    is_native: Option<u64>,
+    delegated_amount: u64,
+    #[serde(with = "option_pubkey")]
+    close_authority: Option<Pubkey>,
+  }
+
+  pub fn serialize<S: Serializer>(
+    account: &TokenAccount,
+    serializer: S,
+  ) -> Result<S::Ok, S::Error> {
+    TokenAccountRepr {
+      mint: account.mint,
+      owner: account.owner,
+      amount: account.amount,
+      delegate: account.delegate.into(),
+      state: account.state,
+      is_native: account.is_native.into(),
+      delegated_amount: account.delegated_amount,
+      close_authority: account.close_authority.into(),
+    }
+    .serialize(serializer)
+  }
+
+  pub fn deserialize<'de, D: Deserializer<'de>>(
+    deserializer: D,
+  ) -> Result<TokenAccount, D::Error> {
+    let repr = TokenAccountRepr::deserialize(deserializer)?;
+    let inner = SplAccount {
+      mint: repr.mint,
+      owner: repr.owner,
+      amount: repr.amount,
+      delegate: COption::from(repr.delegate),
+      state: repr.state,
+      is_native: COption::from(repr.is_native),
+      delegated_amount: repr.delegated_amount,
+      close_authority: COption::from(repr.close_authority),
+    };
+    let mut buf = [0u8; TokenAccount::LEN];
+    inner.pack_into_slice(&mut buf);
+    TokenAccount::try_deserialize_unchecked(&mut buf.as_slice())
+      .map_err(D::Error::custom)
+  }
+}
+
+/// IDL fixed point value of either program.
+mod idl_ufix_value64 {
+  use super::*;
+
+  pub fn serialize<T, S>(value: &T, serializer: S) -> Result<S::Ok, S::Error>
+  where
+    T: Copy + Into<UFixValue64>,
+    S: Serializer,
+  {
+    ufix_value64::serialize(&(*value).into(), serializer)
+  }
+
+  pub fn deserialize<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+  where
+    T: From<UFixValue64>,
+    D: Deserializer<'de>,
+  {
+    ufix_value64::deserialize(deserializer).map(T::from)
+  }
+}
+
+fn reserved<const N: usize>() -> [u8; N] {
+  [0; N]
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(remote = "LstStakePoolProgram")]
+enum LstStakePoolProgramDef {
+  Spl,
+  SanctumSpl,
+  SanctumSplMulti,
+  Marinade,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(remote = "LstSolPrice")]
+struct LstSolPriceDef {
+  #[serde(with = "idl_ufix_value64")]
+  price: hylo_core::idl::exchange::types::UFixValue64,
+  epoch: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(remote = "LstHeader")]
+struct LstHeaderDef {
+  #[serde(with = "pubkey")]
+  mint: Pubkey,
+  #[serde(with = "pubkey")]
+  vault: Pubkey,
+  #[serde(with = "pubkey")]
+  pool_state: Pubkey,
+  #[serde(with = "LstStakePoolProgramDef")]
+  stake_program: LstStakePoolProgram,
+  #[serde(with = "LstSolPriceDef")]
+  prev_price_sol: LstSolPrice,
+  #[serde(with = "LstSolPriceDef")]
+  price_sol: LstSolPrice,
+  last_yield_harvest_epoch: u64,
+  #[serde(skip, default = "reserved")]
+  _reserved: [u8; 64],
+}
+
+/// LST headers as an object keyed by base58 mint.
+pub mod lst_headers {
+  use super::*;
+
+  #[derive(Serialize, Deserialize)]
+  struct Wrapper(#[serde(with = "LstHeaderDef")] LstHeader);
+
+  pub fn serialize<S: Serializer>(
+    headers: &BTreeMap<Pubkey, LstHeader>,
+    serializer: S,
+  ) -> Result<S::Ok, S::Error> {
+    serializer.collect_map(
+      headers
+        .iter()
+        .map(|(mint, header)| (mint.to_string(), Wrapper(*header))),
+    )
+  }
+
+  pub fn deserialize<'de, D: Deserializer<'de>>(
+    deserializer: D,
+  ) -> Result<BTreeMap<Pubkey, LstHeader>, D::Error> {
+    BTreeMap::<String, Wrapper>::deserialize(deserializer)?
+      .into_iter()
+      .map(|(mint, Wrapper(header))| {
+        let mint = mint
+          .parse()
+          .map_err(|_| D::Error::custom(format!("invalid pubkey {mint}")))?;
+        Ok((mint, header))
+      })
+      .collect()
+  }
+}
+
+/// Stability pool [`PoolConfig`] as its IDL fields.
+pub mod pool_config {
+  use super::*;
+
+  #[derive(Serialize, Deserialize)]
+  #[serde(remote = "PoolConfig")]
+  struct PoolConfigDef {
+    #[serde(with = "pubkey")]
+    admin: Pubkey,
+    pool_auth_bump: u8,
+    lp_token_auth_bump: u8,
+    lp_token_mint_bump: u8,
+    #[serde(with = "idl_ufix_value64")]
+    withdrawal_fee: hylo_core::idl::stability_pool::types::UFixValue64,
+    #[serde(skip, default = "reserved")]
+    _reserved: [u8; 55],
+  }
+
+  pub fn serialize<S: Serializer>(
+    config: &PoolConfig,
+    serializer: S,
+  ) -> Result<S::Ok, S::Error> {
+    PoolConfigDef::serialize(config, serializer)
+  }
+
+  pub fn deserialize<'de, D: Deserializer<'de>>(
+    deserializer: D,
+  ) -> Result<PoolConfig, D::Error> {
+    PoolConfigDef::deserialize(deserializer)
+  }
+}
@@ -7,6 +7,7 @@ use async_trait::async_trait;
 use fix::prelude::{CheckedAdd, UFix64, N6, N9};
 use hylo_clients::instructions::StabilityPoolInstructionBuilder as StabilityPoolIB;
 use hylo_clients::prelude::{ProgramClient, VersionedTransactionData};
+use hylo_clients::program_error::HyloTransactionError;
 use hylo_clients::syntax_helpers::InstructionBuilderExt;
 use hylo_clients::transaction::{
   BuildTransactionData, RedeemArgs, StabilityPoolArgs, TransactionSyntax,
@@ -210,6 +211,11 @@ impl<L: LST + Local, C: SolanaClock> QuoteStrategy<SHYUSD, L, C>
       .rpc()
       .simulate_transaction_with_config(&tx, simulation_config())
       .await?;
+    if let Some(err) =
+      HyloTransactionError::from_simulation(&tx.message, &sim_result.value)
+    {
+      return Err(err.into());
+    }
 
     // Either redemption event may be absent depending on pool state
     let from_hyusd: UFix64<N9> =
@@ -0,0 +1,360 @@
+//! Post-trade protocol state transitions
+//!
+//! [`TokenOperation`] computes what an operation pays out but leaves the
+//! [`ProtocolState`] untouched. Applying an operation moves collateral, token
+//! supplies and stability pool balances the way the programs do, then
+//! recomputes the exchange context and stability mode:
+//!
+//! * Mints add the SOL value of the LST deposited net of fees to total SOL;
+//!   redemptions remove the LST withdrawn from the vault, fees included
+//! * Swaps burn the stablecoin net of fees and mint it gross of fees, as the
+//!   stablecoin fee is paid to the treasury
+//! * LST swaps move total SOL by the SOL value of the deposited and withdrawn
+//!   LST
+//! * Stability pool deposits and withdrawals move the pool balances and LP
+//!   token supply, withdraw-and-redeem also redeems both withdrawn tokens
+//!
+//! Quoting against the returned state accounts for every earlier trade, e.g.
+//! for a batch of orders landing in the same block:
+//!
+//! ```rust,ignore
+//! use hylo_quotes::prelude::*;
+//!
+//! let state = FileStateProvider::from_file(path)?.fetch_state().await?;
+//! let batch = state.runtime_batch([
+//!   (JITOSOL::MINT, HYUSD::MINT, 1_000_000_000),
+//!   (HYUSD::MINT, XSOL::MINT, 100_000_000),
+//! ])?;
+//! for output in &batch.output {
+//!   println!("{output}");
+//! }
+//! println!("CR after batch: {}", batch.state.exchange_context.collateral_ratio);
+//! ```
+
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::program_pack::Pack;
+use anchor_lang::AccountDeserialize;
+use anchor_spl::token::{Mint, TokenAccount};
+use anyhow::{Context, Result};
+use fix::prelude::*;
+use fix::typenum::Integer;
+use hylo_core::lst_sol_price::LstSolPrice;
+use hylo_core::solana_clock::SolanaClock;
+use hylo_core::total_sol_cache::TotalSolCache;
+use hylo_idl::tokens::TokenMint;
+
+use crate::protocol_state::ProtocolState;
+use crate::token_operation::{
+  OperationOutput, OperationOutputValue, PoolWithdrawal, TokenOperation,
+  TokenOperationExt,
+};
+use crate::Operation;
+
+/// Output of an operation alongside the protocol state it leaves behind.
+#[derive(Clone)]
+pub struct Transition<O, C: SolanaClock> {
+  pub output: O,
+  pub state: ProtocolState<C>,
+}
+
+/// Typed [`Transition`] of the `IN -> OUT` operation.
+pub type TokenTransition<IN, OUT, C> = Transition<
+  OperationOutput<
+    <IN as TokenMint>::Exp,
+    <OUT as TokenMint>::Exp,
+    <ProtocolState<C> as TokenOperation<IN, OUT>>::FeeExp,
+  >,
+  C,
+>;
+
+impl<C: SolanaClock + Clone> ProtocolState<C> {
+  /// Computes the `IN -> OUT` operation for `amount_in` and applies it.
+  ///
+  /// # Errors
+  /// * Operation output, see [`TokenOperation::compute_output`]
+  /// * State update, see [`Self::apply_output`]
+  pub fn transition<IN, OUT>(
+    &self,
+    amount_in: UFix64<IN::Exp>,
+  ) -> Result<TokenTransition<IN, OUT, C>>
+  where
+    Self: TokenOperation<IN, OUT>,
+    IN: TokenMint,
+    OUT: TokenMint,
+  {
+    let output = self.output::<IN, OUT>(amount_in)?;
+    let state = self.apply_output(IN::MINT, OUT::MINT, &output.into())?;
+    Ok(Transition { output, state })
+  }
+
+  /// Runtime counterpart of [`Self::transition`], for `amount_in` base units
+  /// of `input_mint`.
+  ///
+  /// # Errors
+  /// * Operation output, see [`Self::runtime_output`]
+  /// * State update, see [`Self::apply_output`]
+  pub fn runtime_transition(
+    &self,
+    input_mint: Pubkey,
+    output_mint: Pubkey,
+    amount_in: u64,
+  ) -> Result<Transition<OperationOutputValue, C>> {
+    let output = self.runtime_output(input_mint, output_mint, amount_in)?;
+    let state = self.apply_output(input_mint, output_mint, &output)?;
+    Ok(Transition { output, state })
+  }
+
+  /// Applies `(input_mint, output_mint, amount_in)` orders in sequence, each
+  /// quoted against the state left by the orders before it.
+  ///
+  /// # Errors
+  /// * Any order fails, see [`Self::runtime_transition`]
+  pub fn runtime_batch(
+    &self,
+    orders: impl IntoIterator<Item = (Pubkey, Pubkey, u64)>,
+  ) -> Result<Transition<Vec<OperationOutputValue>, C>> {
+    orders.into_iter().enumerate().try_fold(
+      Transition {
+        output: Vec::new(),
+        state: self.clone(),
+      },
+      |Transition { mut output, state }, (i, (input, output_mint, amount))| {
+        let next = state
+          .runtime_transition(input, output_mint, amount)
+          .with_context(|| format!("Order {i} failed"))?;
+        output.push(next.output);
+        Ok(Transition {
+          output,
+          state: next.state,
+        })
+      },
+    )
+  }
+
+  /// State after the `input_mint -> output_mint` operation yielding `op` was
+  /// executed against this state.
+  ///
+  /// # Errors
+  /// * Pair is not supported, including unregistered LSTs
+  /// * Amounts at exponents other than the pair's
+  /// * Total SOL, supply or pool balance out of range
+  /// * Exchange context recomputation
+  pub fn apply_output(
+    &self,
+    input_mint: Pubkey,
+    output_mint: Pubkey,
+    op: &OperationOutputValue,
+  ) -> Result<ProtocolState<C>> {
+    let mut next = Balances::new(self)?;
+    match self.runtime_operation(input_mint, output_mint)? {
+      Operation::MintStablecoin => {
+        next.deposit_lst(self, &input_mint, net_in::<N9>(op)?)?;
+        next.stablecoin_supply =
+          add(next.stablecoin_supply, amount(op.out_amount)?)?;
+      }
+      Operation::RedeemStablecoin => {
+        next.withdraw_lst(self, &output_mint, amount(op.fee_base)?)?;
+        next.stablecoin_supply =
+          sub(next.stablecoin_supply, amount(op.in_amount)?)?;
+      }
+      Operation::MintLevercoin => {
+        next.deposit_lst(self, &input_mint, net_in::<N9>(op)?)?;
+        next.levercoin_supply =
+          add(next.levercoin_supply, amount(op.out_amount)?)?;
+      }
+      Operation::RedeemLevercoin => {
+        next.withdraw_lst(self, &output_mint, amount(op.fee_base)?)?;
+        next.levercoin_supply =
+          sub(next.levercoin_supply, amount(op.in_amount)?)?;
+      }
+      Operation::SwapStableToLever => {
+        next.stablecoin_supply =
+          sub(next.stablecoin_supply, net_in::<N6>(op)?)?;
+        next.levercoin_supply =
+          add(next.levercoin_supply, amount(op.out_amount)?)?;
+      }
+      Operation::SwapLeverToStable => {
+        next.levercoin_supply =
+          sub(next.levercoin_supply, amount(op.in_amount)?)?;
+        next.stablecoin_supply =
+          add(next.stablecoin_supply, amount(op.fee_base)?)?;
+      }
+      Operation::LstSwap => {
+        next.deposit_lst(self, &input_mint, net_in::<N9>(op)?)?;
+        next.withdraw_lst(self, &output_mint, amount(op.out_amount)?)?;
+      }
+      Operation::DepositToStabilityPool => {
+        next.stablecoin_in_pool =
+          add(next.stablecoin_in_pool, amount(op.in_amount)?)?;
+        next.lp_token_supply =
+          add(next.lp_token_supply, amount(op.out_amount)?)?;
+      }
+      Operation::WithdrawFromStabilityPool => {
+        next.lp_token_supply =
+          sub(next.lp_token_supply, amount(op.in_amount)?)?;
+        next.stablecoin_in_pool =
+          sub(next.stablecoin_in_pool, amount(op.fee_base)?)?;
+      }
+      Operation::WithdrawAndRedeemFromStabilityPool => {
+        let in_amount = amount(op.in_amount)?;
+        let withdrawal = self.pool_withdrawal(in_amount)?;
+        next.withdraw_and_redeem(self, &output_mint, in_amount, withdrawal)?;
+      }
+    }
+    next.into_state(self)
+  }
+}
+
+/// Collateral, supplies and pool balances moved by an operation.
+struct Balances {
+  epoch: u64,
+  total_sol: TotalSolCache,
+  stablecoin_supply: UFix64<N6>,
+  levercoin_supply: UFix64<N6>,
+  lp_token_supply: UFix64<N6>,
+  stablecoin_in_pool: UFix64<N6>,
+  levercoin_in_pool: UFix64<N6>,
+}
+
+impl Balances {
+  fn new<C: SolanaClock>(state: &ProtocolState<C>) -> Result<Balances> {
+    let ctx = &state.exchange_context;
+    let epoch = ctx.clock.epoch();
+    let mut total_sol = TotalSolCache::new(epoch);
+    total_sol.set(ctx.total_sol, epoch)?;
+    Ok(Balances {
+      epoch,
+      total_sol,
+      stablecoin_supply: ctx.stablecoin_supply,
+      levercoin_supply: ctx.levercoin_supply()?,
+      lp_token_supply: UFix64::new(state.shyusd_mint.supply),
+      stablecoin_in_pool: UFix64::new(state.hyusd_pool.amount),
+      levercoin_in_pool: UFix64::new(state.xsol_pool.amount),
+    })
+  }
+
+  /// Adds the SOL value of LST deposited into its vault to total SOL.
+  fn deposit_lst<C: SolanaClock>(
+    &mut self,
+    state: &ProtocolState<C>,
+    mint: &Pubkey,
+    amount: UFix64<N9>,
+  ) -> Result<()> {
+    let sol = lst_price(state, mint)?.convert_sol(amount, self.epoch)?;
+    self.total_sol.increment(sol, self.epoch)?;
+    Ok(())
+  }
+
+  /// Removes the SOL value of LST withdrawn from its vault from total SOL.
+  fn withdraw_lst<C: SolanaClock>(
+    &mut self,
+    state: &ProtocolState<C>,
+    mint: &Pubkey,
+    amount: UFix64<N9>,
+  ) -> Result<()> {
+    let sol = lst_price(state, mint)?.convert_sol(amount, self.epoch)?;
+    self.total_sol.decrement(sol, self.epoch)?;
+    Ok(())
+  }
+
+  /// Burns the LP token, takes both tokens out of the pool and redeems them
+  /// for `lst`. Like [`ProtocolState::withdraw_and_redeem_output`], both
+  /// redemptions are priced against `state`.
+  fn withdraw_and_redeem<C: SolanaClock>(
+    &mut self,
+    state: &ProtocolState<C>,
+    lst: &Pubkey,
+    in_amount: UFix64<N6>,
+    withdrawal: PoolWithdrawal,
+  ) -> Result<()> {
+    self.lp_token_supply = sub(self.lp_token_supply, in_amount)?;
+    self.stablecoin_in_pool =
+      sub(self.stablecoin_in_pool, withdrawal.stablecoin)?;
+    self.levercoin_in_pool = sub(self.levercoin_in_pool, withdrawal.levercoin)?;
+    if withdrawal.stablecoin_remaining > UFix64::zero() {
+      let op = state
+        .redeem_stablecoin_output(*lst, withdrawal.stablecoin_remaining)?;
+      self.withdraw_lst(state, lst, op.fee_base)?;
+      self.stablecoin_supply =
+        sub(self.stablecoin_supply, withdrawal.stablecoin_remaining)?;
+    }
+    if withdrawal.levercoin > UFix64::zero() {
+      let op = state.redeem_levercoin_output(*lst, withdrawal.levercoin)?;
+      self.withdraw_lst(state, lst, op.fee_base)?;
+      self.levercoin_supply = sub(self.levercoin_supply, withdrawal.levercoin)?;
+    }
+    Ok(())
+  }
+
+  /// Rebuilds `state` over these balances, recomputing the exchange context
+  /// at the current SOL/USD price.
+  fn into_state<C: SolanaClock + Clone>(
+    self,
+    state: &ProtocolState<C>,
+  ) -> Result<ProtocolState<C>> {
+    let ctx = &state.exchange_context;
+    let exchange_context = ctx.reload(
+      self.total_sol.get_validated(self.epoch)?,
+      ctx.sol_usd_price,
+      self.stablecoin_supply,
+      Some(self.levercoin_supply),
+    )?;
+    Ok(ProtocolState {
+      exchange_context,
+      hyusd_mint: with_supply(&state.hyusd_mint, self.stablecoin_supply.bits)?,
+      xsol_mint: with_supply(&state.xsol_mint, self.levercoin_supply.bits)?,
+      shyusd_mint: with_supply(&state.shyusd_mint, self.lp_token_supply.bits)?,
+      hyusd_pool: with_amount(&state.hyusd_pool, self.stablecoin_in_pool.bits)?,
+      xsol_pool: with_amount(&state.xsol_pool, self.levercoin_in_pool.bits)?,
+      ..state.clone()
+    })
+  }
+}
+
+fn lst_price<C: SolanaClock>(
+  state: &ProtocolState<C>,
+  mint: &Pubkey,
+) -> Result<LstSolPrice> {
+  Ok(state.lst_header_by_mint(mint)?.price_sol.into())
+}
+
+/// Typed amount of a runtime output field.
+fn amount<Exp: Integer>(value: UFixValue64) -> Result<UFix64<Exp>> {
+  Ok(value.try_into()?)
+}
+
+/// Input amount net of fees, for operations charging fees in the input token.
+fn net_in<Exp: Integer>(op: &OperationOutputValue) -> Result<UFix64<Exp>> {
+  sub(amount(op.in_amount)?, amount(op.fee_amount)?)
+}
+
+fn add<Exp: Integer>(a: UFix64<Exp>, b: UFix64<Exp>) -> Result<UFix64<Exp>> {
+  a.checked_add(&b).context("Overflow applying operation")
+}
+
+fn sub<Exp: Integer>(a: UFix64<Exp>, b: UFix64<Exp>) -> Result<UFix64<Exp>> {
+  a.checked_sub(&b).context("Underflow applying operation")
+}
+
+/// Copies a mint account with a new supply.
+pub(crate) fn with_supply(mint: &Mint, supply: u64) -> Result<Mint> {
+  let mut inner = **mint;
+  inner.supply = supply;
+  let mut buf = [0u8; Mint::LEN];
+  inner.pack_into_slice(&mut buf);
+  Ok(Mint::try_deserialize_unchecked(&mut buf.as_slice())?)
+}
+
+/// Copies a token account with a new balance.
+pub(crate) fn with_amount(
+  account: &TokenAccount,
+  amount: u64,
+) -> Result<TokenAccount> {
+  let mut inner = **account;
+  inner.amount = amount;
+  let mut buf = [0u8; TokenAccount::LEN];
+  inner.pack_into_slice(&mut buf);
+  Ok(TokenAccount::try_deserialize_unchecked(
+    &mut buf.as_slice(),
+  )?)
+}
@@ -0,0 +1,43 @@
+//! Protocol state snapshot shared by the test suites of the workspace crates.
+
+use std::path::PathBuf;
+
+use anchor_lang::solana_program::clock::Clock;
+use anyhow::Result;
+
+use crate::protocol_state::{
+  FileStateProvider, ProtocolAccounts, ProtocolSnapshot, ProtocolState,
+};
+
+/// Path of the protocol state snapshot at slot 918/37508.
+#[must_use]
+pub fn fixture_path() -> PathBuf {
+  PathBuf::from(format!(
+    "{}/tests/data/protocol-state-918-37508.json",
+    env!("CARGO_MANIFEST_DIR")
+  ))
+}
+
+/// Accounts of the fixture snapshot.
+///
+/// # Errors
+/// * Snapshot file is missing or fails to parse
+pub fn fixture_accounts() -> Result<ProtocolAccounts> {
+  Ok(ProtocolSnapshot::read(&fixture_path())?.accounts)
+}
+
+/// Protocol state built from the fixture accounts.
+///
+/// # Errors
+/// * Snapshot fails to load or deserialize into protocol state
+pub fn fixture_state() -> Result<ProtocolState<Clock>> {
+  ProtocolState::try_from(&fixture_accounts()?)
+}
+
+/// File provider replaying the fixture snapshot.
+///
+/// # Errors
+/// * Snapshot file is missing or fails to parse
+pub fn fixture_provider() -> Result<FileStateProvider> {
+  FileStateProvider::from_file(fixture_path())
+}
@@ -1,5 +1,6 @@
 //! `TokenOperation` implementations for exchange pairs.
 
+use anchor_lang::prelude::Pubkey;
 use anyhow::{ensure, Result};
 use fix::prelude::*;
 use hylo_core::fee_controller::FeeExtract;
@@ -15,21 +16,24 @@ use crate::token_operation::{
 };
 use crate::{Local, LST};
 
-/// Mint stablecoin (HYUSD) from LST collateral.
-impl<L: LST + Local, C: SolanaClock> TokenOperation<L, HYUSD>
-  for ProtocolState<C>
-{
-  type FeeExp = N9;
-
-  fn compute_output(
+/// Mint-keyed operations for any registered LST, backing the typed
+/// [`TokenOperation`] impls below.
+impl<C: SolanaClock> ProtocolState<C> {
+  /// Mint stablecoin (HYUSD) from LST collateral.
+  ///
+  /// # Errors
+  /// * LST is not registered
+  /// * Stability mode restrictions or arithmetic
+  pub fn mint_stablecoin_output(
     &self,
+    lst_mint: Pubkey,
     in_amount: UFix64<N9>,
   ) -> Result<MintOperationOutput> {
     ensure!(
       self.exchange_context.stability_mode <= StabilityMode::Mode1,
       "Mint operations disabled in current stability mode"
     );
-    let lst_header = self.lst_header::<L>()?;
+    let lst_header = self.lst_header_by_mint(&lst_mint)?;
     let lst_price = lst_header.price_sol.into();
     let FeeExtract {
       fees_extracted,
@@ -49,23 +53,22 @@ impl<L: LST + Local, C: SolanaClock> TokenOperation<L, HYUSD>
       in_amount,
       out_amount,
       fee_amount: fees_extracted,
-      fee_mint: L::MINT,
+      fee_mint: lst_mint,
       fee_base: in_amount,
     })
   }
-}
 
-/// Redeem stablecoin (HYUSD) for LST collateral.
-impl<L: LST + Local, C: SolanaClock> TokenOperation<HYUSD, L>
-  for ProtocolState<C>
-{
-  type FeeExp = N9;
-
-  fn compute_output(
+  /// Redeem stablecoin (HYUSD) for LST collateral.
+  ///
+  /// # Errors
+  /// * LST is not registered
+  /// * Stability mode restrictions or arithmetic
+  pub fn redeem_stablecoin_output(
     &self,
-    in_amount: UFix64<<HYUSD as TokenMint>::Exp>,
+    lst_mint: Pubkey,
+    in_amount: UFix64<N6>,
   ) -> Result<RedeemOperationOutput> {
-    let lst_header = self.lst_header::<L>()?;
+    let lst_header = self.lst_header_by_mint(&lst_mint)?;
     let lst_price = lst_header.price_sol.into();
     let stablecoin_nav = self.exchange_context.stablecoin_nav()?;
     let lst_out = self
@@ -82,27 +85,26 @@ impl<L: LST + Local, C: SolanaClock> TokenOperation<HYUSD, L>
       in_amount,
       out_amount: amount_remaining,
       fee_amount: fees_extracted,
-      fee_mint: L::MINT,
+      fee_mint: lst_mint,
       fee_base: lst_out,
     })
   }
-}
-
-/// Mint levercoin (XSOL) from LST collateral.
-impl<L: LST + Local, C: SolanaClock> TokenOperation<L, XSOL>
-  for ProtocolState<C>
-{
-  type FeeExp = N9;
 
-  fn compute_output(
+  /// Mint levercoin (XSOL) from LST collateral.
+  ///
+  /// # Errors
+  /// * LST is not registered
+  /// * Stability mode restrictions or arithmetic
+  pub fn mint_levercoin_output(
     &self,
+    lst_mint: Pubkey,
     in_amount: UFix64<N9>,
   ) -> Result<MintOperationOutput> {
     ensure!(
       self.exchange_context.stability_mode != StabilityMode::Depeg,
       "Levercoin mint disabled in current stability mode"
     );
-    let lst_header = self.lst_header::<L>()?;
+    let lst_header = self.lst_header_by_mint(&lst_mint)?;
     let lst_price = lst_header.price_sol.into();
     let FeeExtract {
       fees_extracted,
@@ -119,27 +121,26 @@ impl<L: LST + Local, C: SolanaClock> TokenOperation<L, XSOL>
       in_amount,
       out_amount,
       fee_amount: fees_extracted,
-      fee_mint: L::MINT,
+      fee_mint: lst_mint,
       fee_base: in_amount,
     })
   }
-}
-
-/// Redeem levercoin (XSOL) for LST collateral.
-impl<L: LST + Local, C: SolanaClock> TokenOperation<XSOL, L>
-  for ProtocolState<C>
-{
-  type FeeExp = N9;
 
-  fn compute_output(
+  /// Redeem levercoin (XSOL) for LST collateral.
+  ///
+  /// # Errors
+  /// * LST is not registered
+  /// * Stability mode restrictions or arithmetic
+  pub fn redeem_levercoin_output(
     &self,
-    in_amount: UFix64<<XSOL as TokenMint>::Exp>,
+    lst_mint: Pubkey,
+    in_amount: UFix64<N6>,
   ) -> Result<RedeemOperationOutput> {
     ensure!(
       self.exchange_context.stability_mode != StabilityMode::Depeg,
       "Levercoin redemption disabled in current stability mode"
     );
-    let lst_header = self.lst_header::<L>()?;
+    let lst_header = self.lst_header_by_mint(&lst_mint)?;
     let lst_price = lst_header.price_sol.into();
     let xsol_nav = self.exchange_context.levercoin_redeem_nav()?;
     let lst_out = self
@@ -156,10 +157,100 @@ impl<L: LST + Local, C: SolanaClock> TokenOperation<XSOL, L>
       in_amount,
       out_amount: amount_remaining,
       fee_amount: fees_extracted,
-      fee_mint: L::MINT,
+      fee_mint: lst_mint,
       fee_base: lst_out,
     })
   }
+
+  /// Swap LST -> LST.
+  ///
+  /// # Errors
+  /// * LST is not registered
+  /// * Stability mode restrictions or arithmetic
+  pub fn lst_swap_output(
+    &self,
+    lst_in: Pubkey,
+    lst_out: Pubkey,
+    in_amount: UFix64<N9>,
+  ) -> Result<LstSwapOperationOutput> {
+    let FeeExtract {
+      fees_extracted,
+      amount_remaining,
+    } = self.lst_swap_config.apply_fee(in_amount)?;
+
+    let epoch = self.exchange_context.clock.epoch();
+    let lst_in_header = self.lst_header_by_mint(&lst_in)?;
+    let lst_out_header = self.lst_header_by_mint(&lst_out)?;
+
+    let in_price: LstSolPrice = lst_in_header.price_sol.into();
+    let out_price: LstSolPrice = lst_out_header.price_sol.into();
+    let out_amount =
+      in_price.convert_lst_amount(epoch, amount_remaining, &out_price)?;
+
+    Ok(OperationOutput {
+      in_amount,
+      out_amount,
+      fee_amount: fees_extracted,
+      fee_mint: lst_in,
+      fee_base: in_amount,
+    })
+  }
+}
+
+/// Mint stablecoin (HYUSD) from LST collateral.
+impl<L: LST + Local, C: SolanaClock> TokenOperation<L, HYUSD>
+  for ProtocolState<C>
+{
+  type FeeExp = N9;
+
+  fn compute_output(
+    &self,
+    in_amount: UFix64<N9>,
+  ) -> Result<MintOperationOutput> {
+    self.mint_stablecoin_output(L::MINT, in_amount)
+  }
+}
+
+/// Redeem stablecoin (HYUSD) for LST collateral.
+impl<L: LST + Local, C: SolanaClock> TokenOperation<HYUSD, L>
+  for ProtocolState<C>
+{
+  type FeeExp = N9;
+
+  fn compute_output(
+    &self,
+    in_amount: UFix64<<HYUSD as TokenMint>::Exp>,
+  ) -> Result<RedeemOperationOutput> {
+    self.redeem_stablecoin_output(L::MINT, in_amount)
+  }
+}
+
+/// Mint levercoin (XSOL) from LST collateral.
+impl<L: LST + Local, C: SolanaClock> TokenOperation<L, XSOL>
+  for ProtocolState<C>
+{
+  type FeeExp = N9;
+
+  fn compute_output(
+    &self,
+    in_amount: UFix64<N9>,
+  ) -> Result<MintOperationOutput> {
+    self.mint_levercoin_output(L::MINT, in_amount)
+  }
+}
+
+/// Redeem levercoin (XSOL) for LST collateral.
+impl<L: LST + Local, C: SolanaClock> TokenOperation<XSOL, L>
+  for ProtocolState<C>
+{
+  type FeeExp = N9;
+
+  fn compute_output(
+    &self,
+    in_amount: UFix64<<XSOL as TokenMint>::Exp>,
+  ) -> Result<RedeemOperationOutput> {
+    self.redeem_levercoin_output(L::MINT, in_amount)
+  }
 }
 
 /// Swap stablecoin (HYUSD) to levercoin (XSOL).
@@ -242,26 +333,6 @@ impl<L1: LST + Local, L2: LST + Local, C: SolanaClock> TokenOperation<L1, L2>
     &self,
     in_amount: UFix64<N9>,
   ) -> Result<LstSwapOperationOutput> {
-    let FeeExtract {
-      fees_extracted,
-      amount_remaining,
-    } = self.lst_swap_config.apply_fee(in_amount)?;
-
-    let epoch = self.exchange_context.clock.epoch();
-    let lst_in_header = self.lst_header::<L1>()?;
-    let lst_out_header = self.lst_header::<L2>()?;
-
-    let in_price: LstSolPrice = lst_in_header.price_sol.into();
-    let out_price: LstSolPrice = lst_out_header.price_sol.into();
-    let out_amount =
-      in_price.convert_lst_amount(epoch, amount_remaining, &out_price)?;
-
-    Ok(OperationOutput {
-      in_amount,
-      out_amount,
-      fee_amount: fees_extracted,
-      fee_mint: L1::MINT,
-      fee_base: in_amount,
-    })
+    self.lst_swap_output(L1::MINT, L2::MINT, in_amount)
   }
 }
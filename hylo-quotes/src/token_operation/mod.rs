@@ -1,23 +1,59 @@
 //! Token operation trait for pure protocol math.
 
 mod exchange;
+mod runtime;
 mod stability_pool;
 
+use std::fmt::{self, Display, Formatter};
+
 use anchor_lang::prelude::Pubkey;
-use anyhow::Result;
+use anyhow::{anyhow, ensure, Context, Result};
 use fix::prelude::{UFix64, N6, N9};
 use fix::typenum::Integer;
 use hylo_idl::tokens::TokenMint;
+pub use runtime::OperationOutputValue;
+pub(crate) use stability_pool::PoolWithdrawal;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(bound = ""))]
 pub struct OperationOutput<InExp: Integer, OutExp: Integer, FeeExp: Integer> {
+  #[cfg_attr(
+    feature = "serde",
+    serde(with = "hylo_core::serde_schema::ufix64")
+  )]
   pub in_amount: UFix64<InExp>,
+  #[cfg_attr(
+    feature = "serde",
+    serde(with = "hylo_core::serde_schema::ufix64")
+  )]
   pub out_amount: UFix64<OutExp>,
+  #[cfg_attr(
+    feature = "serde",
+    serde(with = "hylo_core::serde_schema::ufix64")
+  )]
   pub fee_amount: UFix64<FeeExp>,
+  #[cfg_attr(
+    feature = "serde",
+    serde(with = "hylo_core::serde_schema::pubkey")
+  )]
   pub fee_mint: Pubkey,
+  #[cfg_attr(
+    feature = "serde",
+    serde(with = "hylo_core::serde_schema::ufix64")
+  )]
   pub fee_base: UFix64<FeeExp>,
 }
 
+/// Same rendering as [`OperationOutputValue`].
+impl<InExp: Integer, OutExp: Integer, FeeExp: Integer> Display
+  for OperationOutput<InExp, OutExp, FeeExp>
+{
+  fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+    OperationOutputValue::from(*self).fmt(f)
+  }
+}
+
 pub type MintOperationOutput = OperationOutput<N9, N6, N9>;
 pub type RedeemOperationOutput = OperationOutput<N6, N9, N9>;
 pub type SwapOperationOutput = OperationOutput<N6, N6, N6>;
@@ -34,6 +70,128 @@ pub trait TokenOperation<IN: TokenMint, OUT: TokenMint> {
     &self,
     amount_in: UFix64<IN::Exp>,
   ) -> Result<OperationOutput<IN::Exp, OUT::Exp, Self::FeeExp>>;
+
+  /// Exact-out counterpart of [`Self::compute_output`].
+  ///
+  /// Finds the smallest input for which `compute_output` yields at least
+  /// `amount_out`, and returns that operation. Searching over the forward math
+  /// keeps fees and rounding identical to what the program will execute.
+  ///
+  /// # Errors
+  /// * `amount_out` is zero
+  /// * No input amount yields `amount_out`
+  fn compute_input(
+    &self,
+    amount_out: UFix64<OUT::Exp>,
+  ) -> Result<OperationOutput<IN::Exp, OUT::Exp, Self::FeeExp>> {
+    min_input(
+      amount_out.bits,
+      |amount_in| self.compute_output(UFix64::new(amount_in)),
+      |op| op.out_amount.bits,
+      |op| (op.fee_amount.bits, op.fee_base.bits),
+    )
+  }
+}
+
+/// Finds the smallest input whose operation from `probe` outputs at least
+/// `amount_out`.
+///
+/// Output grows with input within a fee tier, but drops where a larger input
+/// projects into a stability mode with higher fees, and inputs beyond mint or
+/// redeem caps are rejected. The search bisects below the first rejected
+/// input towards an input satisfying `amount_out`, then retries lower fee
+/// tiers, which may satisfy it with less input before the output drops.
+///
+/// # Errors
+/// * `amount_out` is zero
+/// * No input amount yields `amount_out`
+pub(crate) fn min_input<T>(
+  amount_out: u64,
+  probe: impl Fn(u64) -> Result<T>,
+  output: impl Fn(&T) -> u64,
+  fee: impl Fn(&T) -> (u64, u64),
+) -> Result<T> {
+  ensure!(amount_out > 0, "Exact out amount must be positive");
+  let satisfying = |amount_in: u64| {
+    probe(amount_in).ok().filter(|op| output(op) >= amount_out)
+  };
+
+  // Smallest satisfying input in `(lo, hi]`, given `best` at `hi`
+  let lowest = |mut lo: u64, mut hi: u64, mut best: T| {
+    while hi - lo > 1 {
+      let mid = lo + (hi - lo) / 2;
+      if let Some(op) = satisfying(mid) {
+        hi = mid;
+        best = op;
+      } else {
+        lo = mid;
+      }
+    }
+    (hi, best)
+  };
+
+  // Exponential search for an input that satisfies `amount_out`, stopping at
+  // the first input rejected after an accepted one
+  let mut accepted = None;
+  let mut hi = 1u64;
+  let (lo, hi, best) = loop {
+    match probe(hi) {
+      Ok(op) if output(&op) >= amount_out => {
+        break (accepted.unwrap_or(0), hi, op);
+      }
+      Ok(_) => accepted = Some(hi),
+      Err(err) => {
+        if let Some(mut lo) = accepted {
+          // Largest accepted input below the cap
+          let mut rejected = hi;
+          while rejected - lo > 1 {
+            let mid = lo + (rejected - lo) / 2;
+            if probe(mid).is_ok() {
+              lo = mid;
+            } else {
+              rejected = mid;
+            }
+          }
+          let op = satisfying(lo).ok_or_else(|| {
+            anyhow!("No input yields {amount_out} out: {err:#}")
+          })?;
+          break (accepted.unwrap_or(0), lo, op);
+        }
+      }
+    }
+    hi = hi
+      .checked_mul(2)
+      .with_context(|| format!("No input yields {amount_out} out"))?;
+  };
+  let (mut hi, mut best) = lowest(lo, hi, best);
+
+  // Retry below the first input in the fee tier of `best`
+  loop {
+    let (mut lower, mut same) = (0, hi);
+    while same - lower > 1 {
+      let mid = lower + (same - lower) / 2;
+      if probe(mid).is_ok_and(|op| lower_fee_rate(fee(&op), fee(&best))) {
+        lower = mid;
+      } else {
+        same = mid;
+      }
+    }
+    match satisfying(lower) {
+      Some(op) if lower > 0 => (hi, best) = lowest(0, lower, op),
+      _ => return Ok(best),
+    }
+  }
+}
+
+/// Whether fee rate `a` is below `b`, each given as `(fee, base)`, beyond
+/// the rounding of either fee.
+fn lower_fee_rate(
+  (fee_a, base_a): (u64, u64),
+  (fee_b, base_b): (u64, u64),
+) -> bool {
+  let rounded_up = u128::from(fee_a) + 1;
+  let rounded_down = u128::from(fee_b.saturating_sub(1));
+  rounded_up * u128::from(base_b) < rounded_down * u128::from(base_a)
 }
 
 /// Turbofish helper for [`TokenOperation`].
@@ -56,6 +214,24 @@ pub trait TokenOperationExt {
     IN: TokenMint,
     OUT: TokenMint,
     <Self as TokenOperation<IN, OUT>>::FeeExp: Integer;
+
+  /// # Errors
+  /// * Output amount unreachable under arithmetic or mode restrictions.
+  fn input<IN, OUT>(
+    &self,
+    amount_out: UFix64<OUT::Exp>,
+  ) -> Result<
+    OperationOutput<
+      IN::Exp,
+      OUT::Exp,
+      <Self as TokenOperation<IN, OUT>>::FeeExp,
+    >,
+  >
+  where
+    Self: TokenOperation<IN, OUT>,
+    IN: TokenMint,
+    OUT: TokenMint,
+    <Self as TokenOperation<IN, OUT>>::FeeExp: Integer;
 }
 
 impl<X> TokenOperationExt for X {
@@ -77,4 +253,23 @@ impl<X> TokenOperationExt for X {
   {
     TokenOperation::<IN, OUT>::compute_output(self, amount_in)
   }
+
+  fn input<IN, OUT>(
+    &self,
+    amount_out: UFix64<OUT::Exp>,
+  ) -> Result<
+    OperationOutput<
+      IN::Exp,
+      OUT::Exp,
+      <Self as TokenOperation<IN, OUT>>::FeeExp,
+    >,
+  >
+  where
+    Self: TokenOperation<IN, OUT>,
+    IN: TokenMint,
+    OUT: TokenMint,
+    <Self as TokenOperation<IN, OUT>>::FeeExp: Integer,
+  {
+    TokenOperation::<IN, OUT>::compute_input(self, amount_out)
+  }
 }
@@ -0,0 +1,178 @@
+//! Runtime dispatch of token operations by mint.
+//!
+//! Covers every pair of [`TokenOperation`] with LSTs resolved against the
+//! registered headers in [`ProtocolState`], so LSTs without a type can be
+//! quoted.
+
+use std::fmt::{self, Display, Formatter};
+
+use anchor_lang::prelude::Pubkey;
+use anyhow::{anyhow, Result};
+use fix::prelude::*;
+use fix::typenum::Integer;
+use hylo_core::solana_clock::SolanaClock;
+use hylo_idl::tokens::{TokenMint, HYUSD, SHYUSD, XSOL};
+
+use crate::format::{fee_ratio, token_label, Fixed, Percent};
+use crate::protocol_state::ProtocolState;
+use crate::token_operation::{min_input, OperationOutput, TokenOperationExt};
+use crate::Operation;
+
+/// [`OperationOutput`] with runtime exponent information.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct OperationOutputValue {
+  #[cfg_attr(
+    feature = "serde",
+    serde(with = "hylo_core::serde_schema::ufix_value64")
+  )]
+  pub in_amount: UFixValue64,
+  #[cfg_attr(
+    feature = "serde",
+    serde(with = "hylo_core::serde_schema::ufix_value64")
+  )]
+  pub out_amount: UFixValue64,
+  #[cfg_attr(
+    feature = "serde",
+    serde(with = "hylo_core::serde_schema::ufix_value64")
+  )]
+  pub fee_amount: UFixValue64,
+  #[cfg_attr(
+    feature = "serde",
+    serde(with = "hylo_core::serde_schema::pubkey")
+  )]
+  pub fee_mint: Pubkey,
+  #[cfg_attr(
+    feature = "serde",
+    serde(with = "hylo_core::serde_schema::ufix_value64")
+  )]
+  pub fee_base: UFixValue64,
+}
+
+impl<InExp: Integer, OutExp: Integer, FeeExp: Integer>
+  From<OperationOutput<InExp, OutExp, FeeExp>> for OperationOutputValue
+{
+  fn from(op: OperationOutput<InExp, OutExp, FeeExp>) -> Self {
+    OperationOutputValue {
+      in_amount: op.in_amount.into(),
+      out_amount: op.out_amount.into(),
+      fee_amount: op.fee_amount.into(),
+      fee_mint: op.fee_mint,
+      fee_base: op.fee_base.into(),
+    }
+  }
+}
+
+/// Amounts as decimals with the fee in its token and as a share of its base,
+/// e.g. `1 -> 154.211899, fee 0.001 JitoSOL (0.1%)`.
+impl Display for OperationOutputValue {
+  fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+    write!(
+      f,
+      "{} -> {}, fee {} {}",
+      Fixed(self.in_amount),
+      Fixed(self.out_amount),
+      Fixed(self.fee_amount),
+      token_label(&self.fee_mint),
+    )?;
+    match fee_ratio(self.fee_amount, self.fee_base) {
+      Ok(ratio) => write!(f, " ({})", Percent(ratio)),
+      Err(_) => Ok(()),
+    }
+  }
+}
+
+impl<C: SolanaClock> ProtocolState<C> {
+  /// Classifies the operation converting `input_mint` into `output_mint`.
+  ///
+  /// # Errors
+  /// * Pair is not supported, including unregistered LSTs
+  pub fn runtime_operation(
+    &self,
+    input_mint: Pubkey,
+    output_mint: Pubkey,
+  ) -> Result<Operation> {
+    let is_lst = |mint: &Pubkey| self.is_lst(mint);
+    match (input_mint, output_mint) {
+      (HYUSD::MINT, XSOL::MINT) => Ok(Operation::SwapStableToLever),
+      (XSOL::MINT, HYUSD::MINT) => Ok(Operation::SwapLeverToStable),
+      (HYUSD::MINT, SHYUSD::MINT) => Ok(Operation::DepositToStabilityPool),
+      (SHYUSD::MINT, HYUSD::MINT) => Ok(Operation::WithdrawFromStabilityPool),
+      (lst, HYUSD::MINT) if is_lst(&lst) => Ok(Operation::MintStablecoin),
+      (HYUSD::MINT, lst) if is_lst(&lst) => Ok(Operation::RedeemStablecoin),
+      (lst, XSOL::MINT) if is_lst(&lst) => Ok(Operation::MintLevercoin),
+      (XSOL::MINT, lst) if is_lst(&lst) => Ok(Operation::RedeemLevercoin),
+      (SHYUSD::MINT, lst) if is_lst(&lst) => {
+        Ok(Operation::WithdrawAndRedeemFromStabilityPool)
+      }
+      (a, b) if a != b && is_lst(&a) && is_lst(&b) => Ok(Operation::LstSwap),
+      _ => Err(anyhow!("Unsupported pair {input_mint} -> {output_mint}")),
+    }
+  }
+
+  /// Computes the output of `amount_in` base units of `input_mint`.
+  ///
+  /// # Errors
+  /// * Pair is not supported, including unregistered LSTs
+  /// * Stability mode restrictions or arithmetic
+  pub fn runtime_output(
+    &self,
+    input_mint: Pubkey,
+    output_mint: Pubkey,
+    amount_in: u64,
+  ) -> Result<OperationOutputValue> {
+    match self.runtime_operation(input_mint, output_mint)? {
+      Operation::MintStablecoin => self
+        .mint_stablecoin_output(input_mint, UFix64::new(amount_in))
+        .map(Into::into),
+      Operation::RedeemStablecoin => self
+        .redeem_stablecoin_output(output_mint, UFix64::new(amount_in))
+        .map(Into::into),
+      Operation::MintLevercoin => self
+        .mint_levercoin_output(input_mint, UFix64::new(amount_in))
+        .map(Into::into),
+      Operation::RedeemLevercoin => self
+        .redeem_levercoin_output(output_mint, UFix64::new(amount_in))
+        .map(Into::into),
+      Operation::SwapStableToLever => self
+        .output::<HYUSD, XSOL>(UFix64::new(amount_in))
+        .map(Into::into),
+      Operation::SwapLeverToStable => self
+        .output::<XSOL, HYUSD>(UFix64::new(amount_in))
+        .map(Into::into),
+      Operation::LstSwap => self
+        .lst_swap_output(input_mint, output_mint, UFix64::new(amount_in))
+        .map(Into::into),
+      Operation::DepositToStabilityPool => self
+        .output::<HYUSD, SHYUSD>(UFix64::new(amount_in))
+        .map(Into::into),
+      Operation::WithdrawFromStabilityPool => self
+        .output::<SHYUSD, HYUSD>(UFix64::new(amount_in))
+        .map(Into::into),
+      Operation::WithdrawAndRedeemFromStabilityPool => self
+        .withdraw_and_redeem_output(output_mint, UFix64::new(amount_in))
+        .map(Into::into),
+    }
+  }
+
+  /// Exact-out counterpart of [`Self::runtime_output`], finding the smallest
+  /// input yielding at least `amount_out` base units of `output_mint`.
+  ///
+  /// # Errors
+  /// * Pair is not supported, including unregistered LSTs
+  /// * No input amount yields `amount_out`
+  pub fn runtime_input(
+    &self,
+    input_mint: Pubkey,
+    output_mint: Pubkey,
+    amount_out: u64,
+  ) -> Result<OperationOutputValue> {
+    self.runtime_operation(input_mint, output_mint)?;
+    min_input(
+      amount_out,
+      |amount_in| self.runtime_output(input_mint, output_mint, amount_in),
+      |op| op.out_amount.bits,
+      |op| (op.fee_amount.bits, op.fee_base.bits),
+    )
+  }
+}
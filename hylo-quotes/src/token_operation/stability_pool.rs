@@ -1,5 +1,6 @@
 //! `TokenOperation` implementations for stability pool pairs.
 
+use anchor_lang::prelude::Pubkey;
 use anyhow::{ensure, Context, Result};
 use fix::prelude::*;
 use hylo_core::fee_controller::FeeExtract;
@@ -8,12 +9,11 @@ use hylo_core::stability_pool_math::{
   amount_token_to_withdraw, lp_token_nav, lp_token_out,
   stablecoin_withdrawal_fee,
 };
-use hylo_idl::tokens::{TokenMint, HYUSD, SHYUSD, XSOL};
+use hylo_idl::tokens::{TokenMint, HYUSD, SHYUSD};
 
 use crate::protocol_state::ProtocolState;
 use crate::token_operation::{
   OperationOutput, RedeemOperationOutput, SwapOperationOutput, TokenOperation,
-  TokenOperationExt,
 };
 use crate::{Local, LST};
 
@@ -74,16 +74,26 @@ impl<C: SolanaClock> TokenOperation<SHYUSD, HYUSD> for ProtocolState<C> {
   }
 }
 
-/// Withdraw LP token from stability pool and redeem for LST.
-impl<L: LST + Local, C: SolanaClock> TokenOperation<SHYUSD, L>
-  for ProtocolState<C>
-{
-  type FeeExp = N9;
+/// Pro-rata share of the stability pool withdrawn for an amount of LP token.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) struct PoolWithdrawal {
+  /// Stablecoin leaving the pool, including the withdrawal fee
+  pub stablecoin: UFix64<N6>,
+  /// Stablecoin left to redeem after the withdrawal fee
+  pub stablecoin_remaining: UFix64<N6>,
+  pub levercoin: UFix64<N6>,
+}
 
-  fn compute_output(
+impl<C: SolanaClock> ProtocolState<C> {
+  /// Splits a withdrawal of `in_amount` LP token into the stablecoin and
+  /// levercoin it takes from the pool.
+  ///
+  /// # Errors
+  /// * Arithmetic
+  pub(crate) fn pool_withdrawal(
     &self,
     in_amount: UFix64<N6>,
-  ) -> Result<RedeemOperationOutput> {
+  ) -> Result<PoolWithdrawal> {
     let lp_token_supply = UFix64::new(self.shyusd_mint.supply);
     let stablecoin_in_pool = UFix64::new(self.hyusd_pool.amount);
 
@@ -101,8 +111,7 @@ impl<L: LST + Local, C: SolanaClock> TokenOperation<SHYUSD, L>
     let stablecoin_nav = self.exchange_context.stablecoin_nav()?;
     let levercoin_nav = self.exchange_context.levercoin_mint_nav()?;
     let FeeExtract {
-      amount_remaining: stablecoin_amount_remaining,
-      ..
+      amount_remaining, ..
     } = stablecoin_withdrawal_fee(
       stablecoin_in_pool,
       stablecoin_to_withdraw,
@@ -111,24 +120,49 @@ impl<L: LST + Local, C: SolanaClock> TokenOperation<SHYUSD, L>
       levercoin_nav,
       withdrawal_fee,
     )?;
+    Ok(PoolWithdrawal {
+      stablecoin: stablecoin_to_withdraw,
+      stablecoin_remaining: amount_remaining,
+      levercoin: levercoin_to_withdraw,
+    })
+  }
+
+  /// Withdraw LP token from stability pool and redeem for any registered
+  /// LST.
+  ///
+  /// # Errors
+  /// * LST is not registered
+  /// * Stability mode restrictions or arithmetic
+  pub fn withdraw_and_redeem_output(
+    &self,
+    lst_mint: Pubkey,
+    in_amount: UFix64<N6>,
+  ) -> Result<RedeemOperationOutput> {
+    let PoolWithdrawal {
+      stablecoin_remaining: stablecoin_amount_remaining,
+      levercoin: levercoin_to_withdraw,
+      ..
+    } = self.pool_withdrawal(in_amount)?;
 
     // Redeem stablecoin for LST
     let (lst_from_stablecoin, fee_from_stablecoin) =
       if stablecoin_amount_remaining > UFix64::zero() {
-        let op = self.output::<HYUSD, L>(stablecoin_amount_remaining)?;
+        let op = self
+          .redeem_stablecoin_output(lst_mint, stablecoin_amount_remaining)?;
         (op.out_amount, op.fee_amount)
       } else {
         (UFix64::zero(), UFix64::zero())
       };
 
     // Redeem levercoin for LST
-    let (lst_from_levercoin, fee_from_levercoin) =
-      if levercoin_to_withdraw > UFix64::zero() {
-        let op = self.output::<XSOL, L>(levercoin_to_withdraw)?;
-        (op.out_amount, op.fee_amount)
-      } else {
-        (UFix64::zero(), UFix64::zero())
-      };
+    let (lst_from_levercoin, fee_from_levercoin) = if levercoin_to_withdraw
+      > UFix64::zero()
+    {
+      let op = self.redeem_levercoin_output(lst_mint, levercoin_to_withdraw)?;
+      (op.out_amount, op.fee_amount)
+    } else {
+      (UFix64::zero(), UFix64::zero())
+    };
 
     // Sum LST outputs and redemption fees
     let out_amount = lst_from_stablecoin
@@ -142,10 +176,24 @@ impl<L: LST + Local, C: SolanaClock> TokenOperation<SHYUSD, L>
       in_amount,
       out_amount,
       fee_amount,
-      fee_mint: L::MINT,
+      fee_mint: lst_mint,
       fee_base: out_amount
         .checked_add(&fee_amount)
         .context("fee_base overflow")?,
     })
   }
 }
+
+/// Withdraw LP token from stability pool and redeem for LST.
+impl<L: LST + Local, C: SolanaClock> TokenOperation<SHYUSD, L>
+  for ProtocolState<C>
+{
+  type FeeExp = N9;
+
+  fn compute_output(
+    &self,
+    in_amount: UFix64<N6>,
+  ) -> Result<RedeemOperationOutput> {
+    self.withdraw_and_redeem_output(L::MINT, in_amount)
+  }
+}
@@ -0,0 +1,71 @@
+//! Protocol state fixture shared by the integration tests.
+
+// Each test crate uses a different subset of the helpers
+#![allow(dead_code, unused_imports)]
+
+use std::borrow::Cow;
+
+use anchor_client::solana_sdk::account::Account;
+use anchor_client::solana_sdk::address_lookup_table::state::{
+  AddressLookupTable, LookupTableMeta,
+};
+use anchor_lang::solana_program::clock::Clock;
+use anchor_lang::AccountDeserialize;
+use anyhow::Result;
+use async_trait::async_trait;
+use hylo_idl::exchange::accounts::LstHeader;
+use hylo_idl::pda;
+use hylo_quotes::prelude::*;
+pub use hylo_quotes::test_fixtures::{
+  fixture_accounts, fixture_path, fixture_provider, fixture_state,
+};
+
+/// Empty lookup table, standing in for the on-chain tables clients load.
+pub fn lookup_table() -> Result<Account> {
+  let data = AddressLookupTable {
+    meta: LookupTableMeta::default(),
+    addresses: Cow::Owned(Vec::new()),
+  }
+  .serialize_for_tests()?;
+  Ok(Account {
+    data,
+    ..Account::default()
+  })
+}
+
+/// LST registry lookup table listing the `JitoSOL` and `HyloSOL` headers of
+/// `accounts` after an arbitrary preamble.
+pub fn lst_registry(accounts: &ProtocolAccounts) -> Result<Account> {
+  let preamble = (0..16).map(|_| Pubkey::new_unique());
+  let entries = [
+    (JITOSOL::MINT, &accounts.jitosol_header),
+    (HYLOSOL::MINT, &accounts.hylosol_header),
+  ]
+  .into_iter()
+  .map(|(mint, header)| {
+    let header = LstHeader::try_deserialize(&mut header.data.as_slice())?;
+    Ok([pda::lst_header(mint), mint, header.vault, header.pool_state])
+  })
+  .collect::<Result<Vec<_>>>()?;
+  let data = AddressLookupTable {
+    meta: LookupTableMeta::default(),
+    addresses: Cow::Owned(
+      preamble.chain(entries.into_iter().flatten()).collect(),
+    ),
+  }
+  .serialize_for_tests()?;
+  Ok(Account {
+    data,
+    ..Account::default()
+  })
+}
+
+/// Provider serving fixed accounts, e.g. fixture accounts modified by a test.
+pub struct StaticStateProvider(pub ProtocolAccounts);
+
+#[async_trait]
+impl StateProvider<Clock> for StaticStateProvider {
+  async fn fetch_state(&self) -> Result<ProtocolState<Clock>> {
+    ProtocolState::try_from(&self.0)
+  }
+}
@@ -0,0 +1,109 @@
+//! Runtime LST tests using an extra registry LST cloned from `JitoSOL`.
+
+use anyhow::Result;
+use hylo_quotes::prelude::*;
+
+mod common;
+
+use common::{fixture_accounts, StaticStateProvider};
+
+/// Fixture accounts plus an unregistered-by-type LST sharing `JitoSOL`'s
+/// header, so its outputs must match the typed `JitoSOL` paths.
+fn accounts_with_extra_lst() -> Result<(ProtocolAccounts, Pubkey)> {
+  let mut accounts = fixture_accounts()?;
+  let mint = Pubkey::new_unique();
+  accounts
+    .lst_headers
+    .push((mint, accounts.jitosol_header.clone()));
+  Ok((accounts, mint))
+}
+
+#[test]
+fn registers_extra_lst() -> Result<()> {
+  let (accounts, mint) = accounts_with_extra_lst()?;
+  let state = ProtocolState::try_from(&accounts)?;
+  assert!(state.is_lst(&mint));
+  assert!(state.is_lst(&JITOSOL::MINT));
+  assert!(!state.is_lst(&Pubkey::new_unique()));
+  assert_eq!(state.lst_mints().count(), 3);
+  Ok(())
+}
+
+/// Typed output with fees charged in `fee_mint` where `JitoSOL` was charged
+fn as_fee_mint(
+  op: impl Into<OperationOutputValue>,
+  fee_mint: Pubkey,
+) -> OperationOutputValue {
+  let op = op.into();
+  OperationOutputValue {
+    fee_mint: if op.fee_mint == JITOSOL::MINT {
+      fee_mint
+    } else {
+      op.fee_mint
+    },
+    ..op
+  }
+}
+
+#[test]
+fn runtime_output_matches_typed() -> Result<()> {
+  let (accounts, mint) = accounts_with_extra_lst()?;
+  let state = ProtocolState::try_from(&accounts)?;
+  let amount = 1_000_000_000;
+  assert_eq!(
+    state.runtime_output(mint, HYUSD::MINT, amount)?,
+    as_fee_mint(state.output::<JITOSOL, HYUSD>(UFix64::new(amount))?, mint)
+  );
+  assert_eq!(
+    state.runtime_output(XSOL::MINT, mint, amount)?,
+    as_fee_mint(state.output::<XSOL, JITOSOL>(UFix64::new(amount))?, mint)
+  );
+  assert_eq!(
+    state.runtime_output(mint, HYLOSOL::MINT, amount)?,
+    as_fee_mint(state.output::<JITOSOL, HYLOSOL>(UFix64::new(amount))?, mint)
+  );
+  Ok(())
+}
+
+#[test]
+fn runtime_rejects_unregistered_lst() -> Result<()> {
+  let (accounts, _) = accounts_with_extra_lst()?;
+  let state = ProtocolState::try_from(&accounts)?;
+  assert!(state
+    .runtime_output(Pubkey::new_unique(), HYUSD::MINT, 1_000_000_000)
+    .is_err());
+  Ok(())
+}
+
+#[test]
+fn runtime_input_reaches_amount_out() -> Result<()> {
+  let (accounts, mint) = accounts_with_extra_lst()?;
+  let state = ProtocolState::try_from(&accounts)?;
+  let amount_out = 100_000_000;
+  let op = state.runtime_input(mint, HYUSD::MINT, amount_out)?;
+  assert!(op.out_amount.bits >= amount_out);
+  let less = state.runtime_output(mint, HYUSD::MINT, op.in_amount.bits - 1)?;
+  assert!(less.out_amount.bits < amount_out);
+  Ok(())
+}
+
+#[tokio::test]
+async fn strategy_quotes_extra_lst() -> Result<()> {
+  let (accounts, mint) = accounts_with_extra_lst()?;
+  let strategy = ProtocolStateStrategy::new(StaticStateProvider(accounts));
+  let user = Pubkey::new_unique();
+  let (quote, metadata) = strategy
+    .runtime_quote_with_metadata(mint, HYUSD::MINT, 1_000_000_000, user, 50)
+    .await?;
+  assert_eq!(metadata.operation, Operation::MintStablecoin);
+  assert!(quote.amount_out.bits > 0);
+  assert!(!quote.instructions.is_empty());
+  let (_, metadata) = strategy
+    .runtime_quote_with_metadata(SHYUSD::MINT, mint, 1_000_000, user, 50)
+    .await?;
+  assert_eq!(
+    metadata.operation,
+    Operation::WithdrawAndRedeemFromStabilityPool
+  );
+  Ok(())
+}
@@ -0,0 +1,159 @@
+//! Exact-out quoting properties against the fixture state.
+
+use std::sync::LazyLock;
+
+use anchor_lang::solana_program::clock::Clock;
+use fix::prelude::*;
+use fix::typenum::Integer;
+use hylo_idl::tokens::{TokenMint, HYLOSOL, HYUSD, JITOSOL, SHYUSD, XSOL};
+use hylo_quotes::prelude::{ProtocolState, TokenOperation, TokenOperationExt};
+use proptest::prelude::*;
+
+mod common;
+
+use common::fixture_state;
+
+static STATE: LazyLock<ProtocolState<Clock>> =
+  LazyLock::new(|| fixture_state().expect("fixture state builds"));
+
+/// Checks `compute_output(compute_input(x)) >= x`, and that one less unit of
+/// input falls short of `x`.
+fn check_exact_out<IN, OUT>(amount_out: u64) -> Result<(), TestCaseError>
+where
+  IN: TokenMint,
+  OUT: TokenMint,
+  ProtocolState<Clock>: TokenOperation<IN, OUT>,
+  <ProtocolState<Clock> as TokenOperation<IN, OUT>>::FeeExp: Integer,
+{
+  let state = &*STATE;
+  let target = UFix64::<OUT::Exp>::new(amount_out);
+  let exact_out = state
+    .input::<IN, OUT>(target)
+    .map_err(|e| TestCaseError::fail(e.to_string()))?;
+  let forward = state
+    .output::<IN, OUT>(exact_out.in_amount)
+    .map_err(|e| TestCaseError::fail(e.to_string()))?;
+  prop_assert!(forward.out_amount >= target);
+  prop_assert!(forward.out_amount == exact_out.out_amount);
+  prop_assert!(forward.fee_amount == exact_out.fee_amount);
+  let one_less = UFix64::new(exact_out.in_amount.bits - 1);
+  prop_assert!(state
+    .output::<IN, OUT>(one_less)
+    .map_or(true, |op| op.out_amount < target));
+  Ok(())
+}
+
+proptest! {
+  #[test]
+  fn jitosol_to_hyusd(amount_out in 1..100_000_000_000u64) {
+    check_exact_out::<JITOSOL, HYUSD>(amount_out)?;
+  }
+
+  #[test]
+  fn hyusd_to_jitosol(amount_out in 1..1_000_000_000_000u64) {
+    check_exact_out::<HYUSD, JITOSOL>(amount_out)?;
+  }
+
+  #[test]
+  fn hylosol_to_xsol(amount_out in 1..100_000_000_000u64) {
+    check_exact_out::<HYLOSOL, XSOL>(amount_out)?;
+  }
+
+  #[test]
+  fn xsol_to_hylosol(amount_out in 1..1_000_000_000_000u64) {
+    check_exact_out::<XSOL, HYLOSOL>(amount_out)?;
+  }
+
+  #[test]
+  fn hyusd_to_xsol(amount_out in 1..100_000_000_000u64) {
+    check_exact_out::<HYUSD, XSOL>(amount_out)?;
+  }
+
+  #[test]
+  fn xsol_to_hyusd(amount_out in 1..100_000_000_000u64) {
+    check_exact_out::<XSOL, HYUSD>(amount_out)?;
+  }
+
+  #[test]
+  fn jitosol_to_hylosol(amount_out in 1..1_000_000_000_000u64) {
+    check_exact_out::<JITOSOL, HYLOSOL>(amount_out)?;
+  }
+
+  #[test]
+  fn hyusd_to_shyusd(amount_out in 1..100_000_000_000u64) {
+    check_exact_out::<HYUSD, SHYUSD>(amount_out)?;
+  }
+
+  #[test]
+  fn shyusd_to_jitosol(amount_out in 1..100_000_000_000u64) {
+    check_exact_out::<SHYUSD, JITOSOL>(amount_out)?;
+  }
+}
+
+#[test]
+fn exact_out_rejects_zero() {
+  assert!((*STATE).input::<JITOSOL, HYUSD>(UFix64::new(0)).is_err());
+}
+
+/// Smallest input in `(lo, hi]` for which `pred` holds, given it holds at
+/// `hi` and turns true only once.
+fn first(mut lo: u64, mut hi: u64, pred: impl Fn(u64) -> bool) -> u64 {
+  while hi - lo > 1 {
+    let mid = lo + (hi - lo) / 2;
+    if pred(mid) {
+      hi = mid;
+    } else {
+      lo = mid;
+    }
+  }
+  hi
+}
+
+#[test]
+fn exact_out_prefers_lower_fee_tier() -> anyhow::Result<()> {
+  let state = &*STATE;
+  // Redeeming enough xSOL projects Mode2, doubling the 4% redeem fee
+  let doubled_fee = |amount_in: u64| {
+    state
+      .output::<XSOL, JITOSOL>(UFix64::new(amount_in))
+      .is_ok_and(|op| op.fee_amount.bits * 100 > op.fee_base.bits * 6)
+  };
+  let boundary = first(1_000_000_000_000, 20_000_000_000_000, doubled_fee);
+  let before = state.output::<XSOL, JITOSOL>(UFix64::new(boundary - 1))?;
+  let after = state.output::<XSOL, JITOSOL>(UFix64::new(boundary))?;
+  assert!(before.out_amount > after.out_amount);
+
+  // Reachable just below the boundary and again well above it
+  let exact_out = state.input::<XSOL, JITOSOL>(before.out_amount)?;
+  assert_eq!(exact_out.in_amount.bits, boundary - 1);
+  assert_eq!(exact_out.fee_amount, before.fee_amount);
+
+  // Only reachable below the boundary within the lower tier
+  let target = UFix64::new(after.out_amount.bits + 1);
+  let exact_out = state.input::<XSOL, JITOSOL>(target)?;
+  assert!(exact_out.in_amount.bits < boundary);
+  assert!(exact_out.out_amount >= target);
+  let one_less = UFix64::new(exact_out.in_amount.bits - 1);
+  assert!(state.output::<XSOL, JITOSOL>(one_less)?.out_amount < target);
+  Ok(())
+}
+
+#[test]
+fn exact_out_reaches_mint_cap() -> anyhow::Result<()> {
+  let state = &*STATE;
+  // Minting enough hyUSD projects Mode2, where stablecoin mints are rejected
+  let rejected = |amount_in: u64| {
+    state
+      .output::<JITOSOL, HYUSD>(UFix64::new(amount_in))
+      .is_err()
+  };
+  let cap = first(1_000_000_000_000, u64::MAX / 1_000, rejected) - 1;
+  let max = state.output::<JITOSOL, HYUSD>(UFix64::new(cap))?;
+
+  let exact_out = state.input::<JITOSOL, HYUSD>(max.out_amount)?;
+  assert!(exact_out.in_amount.bits <= cap);
+  assert!(exact_out.out_amount >= max.out_amount);
+  let beyond = UFix64::new(max.out_amount.bits + 1);
+  assert!(state.input::<JITOSOL, HYUSD>(beyond).is_err());
+  Ok(())
+}
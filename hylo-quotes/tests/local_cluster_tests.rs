@@ -0,0 +1,260 @@
+//! Clients and simulation quotes against an offline cluster seeded with the
+//! fixture state.
+//!
+//! No Hylo program runs here: executors stand in for the programs, so these
+//! tests cover RPC serving, event decoding and transaction building, not
+//! quote parity with on-chain execution.
+
+use anchor_client::solana_sdk::account::Account;
+use anchor_lang::solana_program::clock::Clock;
+use anyhow::{anyhow, Result};
+use hylo_clients::instructions::{
+  ExchangeInstructionBuilder as ExchangeIB,
+  StabilityPoolInstructionBuilder as StabilityPoolIB,
+};
+use hylo_clients::prelude::{
+  ExchangeClient, MintArgs, ProgramClient, StabilityPoolArgs,
+  StabilityPoolClient, TransactionSyntax,
+};
+use hylo_clients::syntax_helpers::InstructionBuilderExt;
+use hylo_clients::testing::{Execution, LocalCluster, NoPrograms};
+use hylo_clients::util::LST_REGISTRY_LOOKUP_TABLE;
+use hylo_idl::exchange::events::MintStablecoinEventV2;
+use hylo_idl::stability_pool::events::UserDepositEvent;
+use hylo_idl::{exchange, stability_pool};
+use hylo_quotes::prelude::*;
+use solana_compute_budget_interface::ComputeBudgetInstruction;
+
+mod common;
+
+use common::{
+  fixture_accounts, lookup_table, lst_registry, StaticStateProvider,
+};
+
+const SIMULATED_CUS: u64 = 123_456;
+
+/// Fixture accounts, the LST registry and the lookup tables used by the
+/// tested pairs.
+fn seed() -> Result<Vec<(Pubkey, Account)>> {
+  let accounts = fixture_accounts()?;
+  let tables = ExchangeIB::lookup_tables::<JITOSOL, HYUSD>()
+    .iter()
+    .chain(StabilityPoolIB::lookup_tables::<HYUSD, SHYUSD>())
+    .map(|key| Ok((*key, lookup_table()?)))
+    .collect::<Result<Vec<_>>>()?;
+  let registry = (LST_REGISTRY_LOOKUP_TABLE, lst_registry(&accounts)?);
+  Ok(
+    accounts
+      .keyed()
+      .into_iter()
+      .chain(tables)
+      .chain([registry])
+      .collect(),
+  )
+}
+
+async fn clients(
+  cluster: &LocalCluster,
+) -> Result<(ExchangeClient, StabilityPoolClient)> {
+  let exchange = ExchangeClient::new_random_keypair(
+    cluster.cluster(),
+    CommitmentConfig::confirmed(),
+  )?;
+  let stability_pool = StabilityPoolClient::new_random_keypair(
+    cluster.cluster(),
+    CommitmentConfig::confirmed(),
+  )?;
+  Ok((exchange, stability_pool))
+}
+
+/// Executor emitting `mint` from exchange transactions and `deposit` from
+/// stability pool transactions.
+fn emitting(
+  mint: MintStablecoinEventV2,
+  deposit: UserDepositEvent,
+) -> impl Fn(
+  &anchor_client::solana_sdk::transaction::VersionedTransaction,
+  &std::collections::HashMap<Pubkey, Account>,
+) -> Execution {
+  move |tx, _| {
+    let keys = tx.message.static_account_keys();
+    let execution = Execution::success(SIMULATED_CUS);
+    if keys.contains(&stability_pool::ID) {
+      execution.with_event(0, stability_pool::ID, &deposit)
+    } else {
+      execution.with_event(0, exchange::ID, &mint)
+    }
+  }
+}
+
+#[tokio::test]
+async fn simulation_quotes_decode_served_events() -> Result<()> {
+  let user = Pubkey::new_unique();
+  let amount_in = 1_000_000_000;
+  let mint = MintStablecoinEventV2 {
+    minted: UFixValue64::from(UFix64::<N6>::new(123_456_789)).into(),
+    nav: UFixValue64::from(UFix64::<N6>::one()).into(),
+    sol_usd_price: UFixValue64::from(UFix64::<N9>::zero()).into(),
+    lst_mint: JITOSOL::MINT,
+    lst_sol_price: UFixValue64::from(UFix64::<N9>::one()).into(),
+    collateral_deposited: UFixValue64::from(UFix64::<N9>::new(999_000_000))
+      .into(),
+    fees_deposited: UFixValue64::from(UFix64::<N9>::new(1_000_000)).into(),
+  };
+  let deposit = UserDepositEvent {
+    stablecoin_deposited: UFixValue64::from(UFix64::<N6>::new(1_000_000))
+      .into(),
+    lp_token_nav: UFixValue64::from(UFix64::<N6>::one()).into(),
+    lp_token_minted: UFixValue64::from(UFix64::<N6>::new(987_654)).into(),
+  };
+  let cluster = LocalCluster::start(seed()?, emitting(mint, deposit)).await?;
+  let (exchange, stability_pool) = clients(&cluster).await?;
+  let simulation = SimulationStrategy::new(exchange, stability_pool);
+
+  let quote = QuoteStrategy::<JITOSOL, HYUSD, Clock>::get_quote(
+    &simulation,
+    amount_in,
+    user,
+    50,
+  )
+  .await?;
+  assert_eq!(quote.amount_in, UFix64::new(amount_in));
+  assert_eq!(quote.amount_out, UFix64::new(123_456_789));
+  assert_eq!(quote.fee_amount, UFix64::new(1_000_000));
+  assert_eq!(quote.compute_units, SIMULATED_CUS);
+  assert!(matches!(
+    quote.compute_unit_strategy,
+    ComputeUnitStrategy::Simulated
+  ));
+  assert!(quote
+    .instructions
+    .iter()
+    .any(|ix| ix.program_id == exchange::ID));
+
+  let quote = QuoteStrategy::<HYUSD, SHYUSD, Clock>::get_quote(
+    &simulation,
+    1_000_000,
+    user,
+    50,
+  )
+  .await?;
+  assert_eq!(quote.amount_in, UFix64::new(1_000_000));
+  assert_eq!(quote.amount_out, UFix64::new(987_654));
+  Ok(())
+}
+
+#[tokio::test]
+async fn sends_land_offline() -> Result<()> {
+  let cluster = LocalCluster::start(seed()?, |_: &_, _: &_| {
+    Execution::success(SIMULATED_CUS)
+  })
+  .await?;
+  let (exchange, stability_pool) = clients(&cluster).await?;
+  let user = exchange.signer().address();
+  exchange
+    .run_transaction::<JITOSOL, HYUSD>(MintArgs {
+      amount: UFix64::one(),
+      user,
+      slippage_config: None,
+    })
+    .await?;
+  stability_pool
+    .run_transaction::<HYUSD, SHYUSD>(StabilityPoolArgs {
+      amount: UFix64::one(),
+      user: stability_pool.signer().address(),
+    })
+    .await?;
+  let sent = cluster.sent();
+  assert_eq!(sent.len(), 2);
+  assert!(sent.iter().all(|tx| {
+    !tx
+      .message
+      .static_account_keys()
+      .contains(&solana_compute_budget_interface::ID)
+  }));
+  assert!(sent[0]
+    .message
+    .static_account_keys()
+    .contains(&exchange::ID));
+  assert!(sent[1]
+    .message
+    .static_account_keys()
+    .contains(&stability_pool::ID));
+  Ok(())
+}
+
+#[tokio::test]
+async fn quoted_sends_set_compute_unit_limit() -> Result<()> {
+  let cluster = LocalCluster::start(seed()?, |_: &_, _: &_| {
+    Execution::success(SIMULATED_CUS)
+  })
+  .await?;
+  let (exchange, _) = clients(&cluster).await?;
+  let strategy =
+    ProtocolStateStrategy::new(StaticStateProvider(fixture_accounts()?));
+  let quote = strategy
+    .runtime_quote(
+      JITOSOL::MINT,
+      HYUSD::MINT,
+      1_000_000_000,
+      exchange.signer().address(),
+      50,
+    )
+    .await?;
+  let limit = exchange
+    .fee_policy()
+    .compute_unit_limit(quote.compute_units)?;
+  let vtd = quote.transaction_data(&exchange).await?;
+  exchange.send_v0_transaction(&vtd).await?;
+  let sent = cluster.sent();
+  let message = &sent[0].message;
+  let first = &message.instructions()[0];
+  assert_eq!(
+    message.static_account_keys()[usize::from(first.program_id_index)],
+    solana_compute_budget_interface::ID
+  );
+  assert_eq!(
+    first.data,
+    ComputeBudgetInstruction::set_compute_unit_limit(limit).data
+  );
+  assert_eq!(message.instructions().len(), vtd.instructions.len() + 1);
+  Ok(())
+}
+
+#[tokio::test]
+async fn unloaded_programs_reject_sends() -> Result<()> {
+  let cluster = LocalCluster::start(seed()?, NoPrograms).await?;
+  let (exchange, _) = clients(&cluster).await?;
+  let user = exchange.signer().address();
+  let vtd = exchange
+    .build_transaction_data::<JITOSOL, HYUSD>(MintArgs {
+      amount: UFix64::one(),
+      user,
+      slippage_config: None,
+    })
+    .await?
+    .with_compute_units(SIMULATED_CUS);
+  let err = exchange
+    .send_v0_transaction(&vtd)
+    .await
+    .err()
+    .ok_or_else(|| anyhow!("Sent without programs"))?;
+  assert!(format!("{err:#}").contains("program that does not exist"));
+  assert!(cluster.sent().is_empty());
+  Ok(())
+}
+
+#[tokio::test]
+async fn serves_fixture_accounts() -> Result<()> {
+  let accounts = fixture_accounts()?;
+  let cluster = LocalCluster::start(seed()?, NoPrograms).await?;
+  let (exchange, _) = clients(&cluster).await?;
+  assert!(!exchange.lst_prices_outdated().await?);
+  let hylo = exchange
+    .program()
+    .rpc()
+    .get_account(&hylo_idl::pda::HYLO)
+    .await?;
+  assert_eq!(hylo.data, accounts.hylo.data);
+  Ok(())
+}
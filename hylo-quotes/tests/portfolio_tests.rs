@@ -0,0 +1,147 @@
+//! Portfolio valuation over the fixture protocol state.
+
+use std::collections::BTreeMap;
+
+use anchor_lang::solana_program::program_pack::Pack;
+use anchor_spl::token::spl_token;
+use anyhow::Result;
+use hylo_core::stability_pool_math::amount_token_to_withdraw;
+use hylo_quotes::prelude::*;
+
+mod common;
+
+use common::{fixture_accounts, fixture_state};
+
+const OWNER: Pubkey =
+  Pubkey::from_str_const("GDNtJq5YAZnfjmBuqr7xMaa2WCjxgYujvsMB1Fp3TMo2");
+
+fn balances() -> WalletBalances {
+  WalletBalances {
+    hyusd: UFix64::new(1_000_000_000),
+    xsol: UFix64::new(50_000_000),
+    shyusd: UFix64::new(250_000_000),
+    lsts: BTreeMap::from([
+      (JITOSOL::MINT, UFix64::new(2_000_000_000)),
+      (HYLOSOL::MINT, UFix64::zero()),
+    ]),
+  }
+}
+
+#[test]
+fn values_positions_at_navs() -> Result<()> {
+  let state = fixture_state()?;
+  let balances = balances();
+  let portfolio = Portfolio::new(&state, OWNER, JITOSOL::MINT, &balances)?;
+  let ctx = &state.exchange_context;
+
+  let hyusd_value = balances
+    .hyusd
+    .mul_div_floor(ctx.stablecoin_nav()?, UFix64::one())
+    .unwrap();
+  assert_eq!(portfolio.hyusd.usd_value, hyusd_value);
+  let xsol_value = balances
+    .xsol
+    .mul_div_floor(ctx.levercoin_redeem_nav()?, UFix64::one())
+    .unwrap();
+  assert_eq!(portfolio.xsol.usd_value, xsol_value);
+
+  let supply = UFix64::new(state.shyusd_mint.supply);
+  assert_eq!(
+    portfolio.shyusd.hyusd_share,
+    amount_token_to_withdraw(
+      balances.shyusd,
+      supply,
+      UFix64::new(state.hyusd_pool.amount)
+    )?
+  );
+  assert_eq!(
+    portfolio.shyusd.xsol_share,
+    amount_token_to_withdraw(
+      balances.shyusd,
+      supply,
+      UFix64::new(state.xsol_pool.amount)
+    )?
+  );
+
+  // Empty LST balances are omitted
+  assert_eq!(portfolio.lsts.len(), 1);
+  assert_eq!(portfolio.lsts[0].mint, JITOSOL::MINT);
+  assert!(portfolio.lsts[0].position.usd_value > UFix64::zero());
+  Ok(())
+}
+
+#[test]
+fn redeemable_matches_quotes() -> Result<()> {
+  let state = fixture_state()?;
+  let balances = balances();
+  let portfolio = Portfolio::new(&state, OWNER, HYLOSOL::MINT, &balances)?;
+
+  let redeem_hyusd = state.output::<HYUSD, HYLOSOL>(balances.hyusd)?;
+  assert_eq!(portfolio.hyusd.redeemable, Some(redeem_hyusd.out_amount));
+  let redeem_shyusd = state.output::<SHYUSD, HYLOSOL>(balances.shyusd)?;
+  assert_eq!(
+    portfolio.shyusd.position.redeemable,
+    Some(redeem_shyusd.out_amount)
+  );
+  let swap = state.output::<JITOSOL, HYLOSOL>(UFix64::new(2_000_000_000))?;
+  assert_eq!(portfolio.lsts[0].position.redeemable, Some(swap.out_amount));
+  assert!(redeem_hyusd.fee_amount > UFix64::zero());
+
+  let expected = portfolio
+    .lsts
+    .iter()
+    .filter_map(|lst| lst.position.redeemable)
+    .chain(
+      [
+        portfolio.hyusd.redeemable,
+        portfolio.xsol.redeemable,
+        portfolio.shyusd.position.redeemable,
+      ]
+      .into_iter()
+      .flatten(),
+    )
+    .map(|amount| amount.bits)
+    .sum::<u64>();
+  assert_eq!(portfolio.total_redeemable()?.bits, expected);
+  Ok(())
+}
+
+#[test]
+fn empty_wallet_is_worth_nothing() -> Result<()> {
+  let state = fixture_state()?;
+  let portfolio =
+    Portfolio::new(&state, OWNER, JITOSOL::MINT, &WalletBalances::default())?;
+  assert_eq!(portfolio.total_usd_value()?, UFix64::zero());
+  assert_eq!(portfolio.total_redeemable()?, UFix64::zero());
+  assert!(portfolio.lsts.is_empty());
+  Ok(())
+}
+
+#[test]
+fn rejects_unregistered_redeem_lst() -> Result<()> {
+  let state = fixture_state()?;
+  let result = Portfolio::new(&state, OWNER, Pubkey::new_unique(), &balances());
+  assert!(result.is_err());
+  Ok(())
+}
+
+#[test]
+fn unseeded_stability_pool_is_worth_nothing() -> Result<()> {
+  let mut accounts = fixture_accounts()?;
+  let mut mint = spl_token::state::Mint::unpack(&accounts.shyusd_mint.data)?;
+  mint.supply = 0;
+  spl_token::state::Mint::pack(mint, &mut accounts.shyusd_mint.data)?;
+  let state = ProtocolState::try_from(&accounts)?;
+
+  let balances = WalletBalances {
+    shyusd: UFix64::zero(),
+    ..balances()
+  };
+  let portfolio = Portfolio::new(&state, OWNER, JITOSOL::MINT, &balances)?;
+  assert_eq!(portfolio.shyusd.position.usd_value, UFix64::zero());
+  assert_eq!(portfolio.shyusd.position.redeemable, Some(UFix64::zero()));
+  assert_eq!(portfolio.shyusd.hyusd_share, UFix64::zero());
+  assert_eq!(portfolio.shyusd.xsol_share, UFix64::zero());
+  assert!(portfolio.hyusd.usd_value > UFix64::zero());
+  Ok(())
+}
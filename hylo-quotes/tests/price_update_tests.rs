@@ -0,0 +1,249 @@
+//! Epoch rollover projection against the fixture state moved one epoch ahead.
+
+use anchor_client::solana_sdk::account::Account;
+use anchor_lang::solana_program::clock::Clock;
+use anchor_lang::solana_program::program_pack::Pack;
+use anchor_lang::AccountDeserialize;
+use anchor_spl::token::spl_token::state::{
+  Account as SplAccount, AccountState,
+};
+use anyhow::{anyhow, Result};
+use hylo_clients::util::{is_lst_price_update, LST_REGISTRY_LOOKUP_TABLE};
+use hylo_idl::exchange::accounts::{Hylo, LstHeader};
+use hylo_idl::pda;
+use hylo_quotes::prelude::*;
+use hylo_quotes::protocol_state::PRICE_UPDATE_CUS;
+use hylo_quotes::DEFAULT_CUS_WITH_BUFFER;
+
+mod common;
+
+use common::{fixture_accounts, lst_registry, StaticStateProvider};
+
+/// Stake pool rate of the projected `JitoSOL` price, 1.25 SOL per token.
+const JITOSOL_POOL: (u64, u64) = (1_250_000_000_000, 1_000_000_000_000);
+
+/// Stake pool rate of the projected `HyloSOL` price, 1.01 SOL per token.
+const HYLOSOL_POOL: (u64, u64) = (1_010_000_000_000, 1_000_000_000_000);
+
+const HYLOSOL_VAULT: u64 = 500_000_000_000;
+
+fn header(account: &Account) -> Result<LstHeader> {
+  Ok(LstHeader::try_deserialize(&mut account.data.as_slice())?)
+}
+
+fn total_sol(accounts: &ProtocolAccounts) -> Result<u64> {
+  let hylo = Hylo::try_deserialize(&mut accounts.hylo.data.as_slice())?;
+  Ok(hylo.total_sol_cache.total_sol.bits)
+}
+
+/// `JitoSOL` vault worth about the fixture's total SOL at the projected price.
+fn jitosol_vault(accounts: &ProtocolAccounts) -> Result<u64> {
+  Ok(total_sol(accounts)? / 5 * 4)
+}
+
+fn clock(accounts: &ProtocolAccounts) -> Result<Clock> {
+  bincode::deserialize(&accounts.clock.data).map_err(|e| anyhow!("{e}"))
+}
+
+/// SPL stake pool state with only the exchange rate fields set.
+fn stake_pool(
+  (total_lamports, pool_token_supply): (u64, u64),
+  epoch: u64,
+) -> Account {
+  let mut data = vec![0; 1 + 32 * 3 + 1 + 32 * 5];
+  data.extend(total_lamports.to_le_bytes());
+  data.extend(pool_token_supply.to_le_bytes());
+  data.extend(epoch.to_le_bytes());
+  Account {
+    data,
+    ..Account::default()
+  }
+}
+
+fn vault(mint: Pubkey, amount: u64) -> Account {
+  let mut data = vec![0; SplAccount::LEN];
+  SplAccount {
+    mint,
+    owner: *pda::HYLO,
+    amount,
+    state: AccountState::Initialized,
+    ..SplAccount::default()
+  }
+  .pack_into_slice(&mut data);
+  Account {
+    data,
+    owner: anchor_spl::token::ID,
+    ..Account::default()
+  }
+}
+
+/// Fixture accounts in the following epoch, with stake pools updated for it
+/// at `pool_epoch_offset` epochs from the new epoch.
+fn rollover(pool_epoch_offset: i64) -> Result<ProtocolAccounts> {
+  let mut accounts = fixture_accounts()?;
+  let mut clock = clock(&accounts)?;
+  clock.epoch += 1;
+  accounts.clock.data = bincode::serialize(&clock)?;
+  let pool_epoch = clock.epoch.saturating_add_signed(pool_epoch_offset);
+  let jitosol = header(&accounts.jitosol_header)?;
+  let hylosol = header(&accounts.hylosol_header)?;
+  accounts.price_update = Some(PriceUpdateAccounts {
+    lst_registry: lst_registry(&accounts)?,
+    accounts: vec![
+      (
+        jitosol.vault,
+        vault(JITOSOL::MINT, jitosol_vault(&accounts)?),
+      ),
+      (jitosol.pool_state, stake_pool(JITOSOL_POOL, pool_epoch)),
+      (hylosol.vault, vault(HYLOSOL::MINT, HYLOSOL_VAULT)),
+      (hylosol.pool_state, stake_pool(HYLOSOL_POOL, pool_epoch)),
+    ],
+  });
+  Ok(accounts)
+}
+
+#[test]
+fn detects_outdated_prices() -> Result<()> {
+  assert!(!fixture_accounts()?.prices_outdated()?);
+  assert!(rollover(0)?.prices_outdated()?);
+  Ok(())
+}
+
+#[test]
+fn projects_prices_and_total_sol() -> Result<()> {
+  let accounts = rollover(0)?;
+  let epoch = clock(&accounts)?.epoch;
+  let before = header(&accounts.jitosol_header)?;
+  let state = ProtocolState::try_from(&accounts)?;
+  assert!(state.is_projected());
+
+  let jitosol = state.lst_header::<JITOSOL>()?;
+  assert_eq!(jitosol.price_sol.epoch, epoch);
+  assert_eq!(jitosol.price_sol.price.bits, 1_250_000_000);
+  assert_eq!(jitosol.prev_price_sol.epoch, before.price_sol.epoch);
+  assert_eq!(
+    jitosol.prev_price_sol.price.bits,
+    before.price_sol.price.bits
+  );
+  let hylosol = state.lst_header::<HYLOSOL>()?;
+  assert_eq!(hylosol.price_sol.price.bits, 1_010_000_000);
+
+  let jitosol_sol = jitosol_vault(&accounts)? / 4 * 5;
+  let hylosol_sol = HYLOSOL_VAULT / 100 * 101;
+  assert_eq!(
+    state.exchange_context.total_sol.bits,
+    jitosol_sol + hylosol_sol
+  );
+  Ok(())
+}
+
+#[test]
+fn current_state_is_not_projected() -> Result<()> {
+  let mut accounts = fixture_accounts()?;
+  accounts.price_update = rollover(0)?.price_update;
+  let state = ProtocolState::try_from(&accounts)?;
+  assert!(!state.is_projected());
+  assert_eq!(state.exchange_context.total_sol.bits, total_sol(&accounts)?);
+  Ok(())
+}
+
+#[test]
+fn outdated_prices_without_update_accounts_fail() -> Result<()> {
+  let mut accounts = rollover(0)?;
+  accounts.price_update = None;
+  assert!(ProtocolState::try_from(&accounts).is_err());
+  Ok(())
+}
+
+#[test]
+fn outdated_stake_pool_fails() -> Result<()> {
+  let err = ProtocolState::try_from(&rollover(-1)?)
+    .err()
+    .ok_or_else(|| anyhow!("Projected from outdated stake pool"))?;
+  assert!(format!("{err:#}").contains("Stake pool not updated"));
+  Ok(())
+}
+
+#[tokio::test]
+async fn prepends_price_update_to_quotes() -> Result<()> {
+  let strategy = ProtocolStateStrategy::new(StaticStateProvider(rollover(0)?));
+  let user = Pubkey::new_unique();
+  let quote = QuoteStrategy::<JITOSOL, HYUSD, Clock>::get_quote(
+    &strategy,
+    1_000_000_000,
+    user,
+    50,
+  )
+  .await?;
+  let update = &quote.instructions[0];
+  assert!(is_lst_price_update(update));
+  assert_eq!(update.accounts[0].pubkey, user);
+  assert!(update.accounts[0].is_signer);
+  assert_eq!(update.accounts.len(), 6 + 16 + 4 * 2);
+  assert_eq!(
+    quote
+      .instructions
+      .iter()
+      .filter(|ix| is_lst_price_update(ix))
+      .count(),
+    1
+  );
+  assert!(quote
+    .address_lookup_tables
+    .contains(&LST_REGISTRY_LOOKUP_TABLE));
+  assert_eq!(
+    quote.compute_units,
+    DEFAULT_CUS_WITH_BUFFER + PRICE_UPDATE_CUS
+  );
+  Ok(())
+}
+
+#[tokio::test]
+async fn opted_out_quotes_skip_price_update() -> Result<()> {
+  let strategy = ProtocolStateStrategy::new(StaticStateProvider(rollover(0)?))
+    .without_price_update();
+  let quote = QuoteStrategy::<JITOSOL, HYUSD, Clock>::get_quote(
+    &strategy,
+    1_000_000_000,
+    Pubkey::new_unique(),
+    50,
+  )
+  .await?;
+  assert!(!quote.instructions.iter().any(is_lst_price_update));
+  assert_eq!(quote.compute_units, DEFAULT_CUS_WITH_BUFFER);
+  Ok(())
+}
+
+#[tokio::test]
+async fn current_quotes_skip_price_update() -> Result<()> {
+  let strategy =
+    ProtocolStateStrategy::new(StaticStateProvider(fixture_accounts()?));
+  let quote = QuoteStrategy::<JITOSOL, HYUSD, Clock>::get_quote(
+    &strategy,
+    1_000_000_000,
+    Pubkey::new_unique(),
+    50,
+  )
+  .await?;
+  assert!(!quote.instructions.iter().any(is_lst_price_update));
+  assert_eq!(quote.compute_units, DEFAULT_CUS_WITH_BUFFER);
+  Ok(())
+}
+
+#[test]
+fn prepends_price_update_to_routes() -> Result<()> {
+  let state = ProtocolState::try_from(&rollover(0)?)?;
+  let user = Pubkey::new_unique();
+  let quote = RoutePlanner::new(state).quote(
+    HYLOSOL::MINT,
+    XSOL::MINT,
+    1_000_000_000,
+    user,
+    50,
+  )?;
+  assert!(is_lst_price_update(&quote.instructions[0]));
+  assert!(quote
+    .address_lookup_tables
+    .contains(&LST_REGISTRY_LOOKUP_TABLE));
+  Ok(())
+}
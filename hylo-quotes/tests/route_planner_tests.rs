@@ -0,0 +1,182 @@
+//! Multi-hop route planning against the fixture state.
+
+use anchor_client::solana_sdk::instruction::Instruction;
+use anchor_lang::{AnchorDeserialize, Discriminator};
+use anyhow::{Context, Result};
+use fix::typenum::Integer;
+use hylo_core::slippage_config::SlippageConfig;
+use hylo_idl::exchange::client::args;
+use hylo_idl::exchange::types;
+use hylo_idl::tokens::{HYLOSOL, HYUSD, JITOSOL, SHYUSD, XSOL};
+use hylo_quotes::prelude::*;
+use hylo_quotes::route_planner::{Leg, LEGS};
+
+mod common;
+
+use common::fixture_state;
+
+/// Decodes the first instruction with `T`'s discriminator.
+fn decode<T: AnchorDeserialize + Discriminator>(
+  instructions: &[Instruction],
+) -> Option<T> {
+  instructions.iter().find_map(|ix| {
+    ix.data
+      .strip_prefix(T::DISCRIMINATOR)
+      .and_then(|mut data| T::deserialize(&mut data).ok())
+  })
+}
+
+fn leg(input_mint: Pubkey, output_mint: Pubkey) -> Result<Leg> {
+  LEGS
+    .iter()
+    .find(|leg| leg.input_mint == input_mint && leg.output_mint == output_mint)
+    .copied()
+    .context("No such leg")
+}
+
+/// Asserts `config` tolerates its minimum output and nothing below it.
+fn assert_bound<Exp: Integer>(
+  config: Option<types::SlippageConfig>,
+) -> Result<u64> {
+  let config =
+    SlippageConfig::from(config.context("Leg not slippage checked")?);
+  let min = config.min_token_out::<Exp>()?;
+  assert!(config.validate_token_out(min).is_ok());
+  let below = min.checked_sub(&UFix64::new(1)).context("Zero bound")?;
+  assert!(config.validate_token_out(below).is_err());
+  Ok(min.bits)
+}
+
+#[test]
+fn paths_are_simple_and_connected() -> Result<()> {
+  let planner = RoutePlanner::new(fixture_state()?);
+  let paths = planner.paths(JITOSOL::MINT, SHYUSD::MINT);
+  assert!(!paths.is_empty());
+  for path in paths {
+    assert!(path.len() <= 3);
+    assert_eq!(path.first().map(|l| l.input_mint), Some(JITOSOL::MINT));
+    assert_eq!(path.last().map(|l| l.output_mint), Some(SHYUSD::MINT));
+    assert!(path.windows(2).all(|w| w[0].output_mint == w[1].input_mint));
+    assert!(path.iter().all(|leg| LEGS.contains(leg)));
+  }
+  Ok(())
+}
+
+#[test]
+fn jitosol_to_shyusd_chains_mint_and_deposit() -> Result<()> {
+  let state = fixture_state()?;
+  let amount_in = UFix64::<N9>::new(1_000_000_000);
+  let hyusd = state.output::<JITOSOL, HYUSD>(amount_in)?.out_amount;
+  let shyusd = state.output::<HYUSD, SHYUSD>(hyusd)?.out_amount;
+
+  let planner = RoutePlanner::new(state);
+  let route =
+    planner.best_route(JITOSOL::MINT, SHYUSD::MINT, amount_in.bits)?;
+  assert!(route.amount_out() >= shyusd.bits);
+  assert_eq!(route.amount_in(), amount_in.bits);
+  assert_eq!(route.amounts.len(), route.legs.len() + 1);
+  Ok(())
+}
+
+#[test]
+fn best_route_beats_direct_pair() -> Result<()> {
+  let state = fixture_state()?;
+  let amount_in = UFix64::<N9>::new(1_000_000_000);
+  let direct = state.output::<HYLOSOL, XSOL>(amount_in)?.out_amount;
+  let planner = RoutePlanner::new(state);
+  let route = planner.best_route(HYLOSOL::MINT, XSOL::MINT, amount_in.bits)?;
+  assert!(route.amount_out() >= direct.bits);
+  let routes = planner.routes(HYLOSOL::MINT, XSOL::MINT, amount_in.bits);
+  assert!(routes
+    .windows(2)
+    .all(|w| w[0].amount_out() >= w[1].amount_out()));
+  Ok(())
+}
+
+#[test]
+fn single_hop_planner_matches_direct_pair() -> Result<()> {
+  let state = fixture_state()?;
+  let amount_in = UFix64::<N6>::new(1_000_000);
+  let direct = state.output::<HYUSD, JITOSOL>(amount_in)?.out_amount;
+  let planner = RoutePlanner::new(state).with_max_hops(1);
+  let route = planner.best_route(HYUSD::MINT, JITOSOL::MINT, amount_in.bits)?;
+  assert_eq!(route.legs.len(), 1);
+  assert_eq!(route.amount_out(), direct.bits);
+  Ok(())
+}
+
+#[test]
+fn quote_builds_combined_instructions() -> Result<()> {
+  let planner = RoutePlanner::new(fixture_state()?).with_max_hops(2);
+  let user = Pubkey::new_unique();
+  let route = planner.best_route(XSOL::MINT, SHYUSD::MINT, 1_000_000)?;
+  let legs = route.legs.len();
+  let quote = planner.build(route, user, 50)?;
+  assert!(quote.instructions.len() >= legs);
+  assert_eq!(quote.compute_units, DEFAULT_CUS_WITH_BUFFER * legs as u64);
+  let mut luts = quote.address_lookup_tables.clone();
+  luts.dedup();
+  assert_eq!(luts, quote.address_lookup_tables);
+  Ok(())
+}
+
+#[test]
+fn no_route_to_same_mint() -> Result<()> {
+  let planner = RoutePlanner::new(fixture_state()?);
+  assert!(planner
+    .best_route(HYUSD::MINT, HYUSD::MINT, 1_000_000)
+    .is_err());
+  Ok(())
+}
+
+#[test]
+fn legs_spend_quoted_amounts_and_bound_only_the_route() -> Result<()> {
+  let planner = RoutePlanner::new(fixture_state()?);
+  let amount_in = 1_000_000_000;
+  let legs = vec![
+    leg(JITOSOL::MINT, HYUSD::MINT)?,
+    leg(HYUSD::MINT, XSOL::MINT)?,
+  ];
+  let route = planner.price(legs, amount_in)?;
+  let quote = planner.build(route.clone(), Pubkey::new_unique(), 50)?;
+  assert_eq!(quote.route, route);
+
+  let mint = decode::<args::MintStablecoin>(&quote.instructions)
+    .context("No mint instruction")?;
+  assert_eq!(mint.amount_lst_to_deposit, amount_in);
+  assert!(mint.slippage_config.is_none());
+
+  let swap = decode::<args::SwapStableToLever>(&quote.instructions)
+    .context("No swap instruction")?;
+  assert_eq!(swap.amount_stablecoin, route.amounts[1]);
+  let swap_config = swap.slippage_config.context("Unbounded route")?;
+  assert_eq!(
+    swap_config.expected_token_out.bits,
+    quote.route.amount_out()
+  );
+  let xsol_min = assert_bound::<N6>(swap.slippage_config)?;
+  let tolerated = SlippageConfig::new(
+    UFix64::<N6>::new(route.amount_out()),
+    UFix64::<N4>::new(50),
+  )
+  .min_token_out::<N6>()?;
+  assert_eq!(xsol_min, tolerated.bits);
+  Ok(())
+}
+
+#[test]
+fn withdraw_and_redeem_redemptions_are_bounded() -> Result<()> {
+  let planner = RoutePlanner::new(fixture_state()?).with_max_hops(1);
+  let route = planner.best_route(SHYUSD::MINT, JITOSOL::MINT, 1_000_000)?;
+  let quote = planner.build(route, Pubkey::new_unique(), 50)?;
+  let stablecoin = decode::<args::RedeemStablecoin>(&quote.instructions);
+  let levercoin = decode::<args::RedeemLevercoin>(&quote.instructions);
+  assert!(stablecoin.is_some() || levercoin.is_some());
+  if let Some(redeem) = stablecoin {
+    assert_bound::<N9>(redeem.slippage_config)?;
+  }
+  if let Some(redeem) = levercoin {
+    assert_bound::<N9>(redeem.slippage_config)?;
+  }
+  Ok(())
+}
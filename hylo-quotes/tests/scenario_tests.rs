@@ -0,0 +1,92 @@
+//! Price scenario simulation against the fixture state.
+
+use anyhow::Result;
+use hylo_core::pyth::PriceRange;
+use hylo_core::stability_mode::StabilityMode;
+use hylo_quotes::prelude::*;
+
+mod common;
+
+use common::fixture_state;
+
+#[test]
+fn decline_timeline() -> Result<()> {
+  let state = fixture_state()?;
+  let start = state.exchange_context.sol_usd_price;
+  let scenario = Scenario::decline(&state, UFix64::new(4000), 10)?;
+  assert_eq!(scenario.prices().len(), 10);
+  let end = scenario.prices()[9];
+  assert_eq!(
+    end.lower,
+    start
+      .lower
+      .mul_div_floor(UFix64::<N8>::new(60_000_000), UFix64::one())
+      .expect("end price")
+  );
+
+  let timeline = scenario.clone().without_rebalance().run(&state)?;
+  assert_eq!(timeline.len(), 10);
+  for pair in timeline.windows(2) {
+    let (prev, next) = (&pair[0].metrics, &pair[1].metrics);
+    assert!(next.sol_usd_price.lower < prev.sol_usd_price.lower);
+    assert!(next.collateral_ratio < prev.collateral_ratio);
+    assert!(next.stability_mode >= prev.stability_mode);
+    assert!(next.levercoin_nav.lower <= prev.levercoin_nav.lower);
+    assert_eq!(next.stablecoin_supply, prev.stablecoin_supply);
+  }
+  assert!(timeline.iter().all(|s| s.rebalance.is_none()));
+  Ok(())
+}
+
+#[test]
+fn rebalance_restores_collateral() -> Result<()> {
+  let state = fixture_state()?;
+  let ctx = &state.exchange_context;
+  let mode1_price = ctx
+    .sol_usd_price
+    .lower
+    .mul_div_floor(
+      ctx
+        .stability_controller
+        .stability_threshold_1
+        .convert::<N9>(),
+      ctx.collateral_ratio,
+    )
+    .expect("mode1 price");
+  // Just under the Mode1 threshold
+  let price = PriceRange::one(UFix64::new(mode1_price.bits * 99 / 100));
+
+  let without = Scenario::new(vec![price]).without_rebalance().run(&state)?;
+  let with = Scenario::new(vec![price]).run(&state)?;
+  assert_eq!(without[0].metrics.stability_mode, StabilityMode::Mode1);
+  let Some(Rebalance::StableToLever {
+    stablecoin_in,
+    levercoin_out,
+  }) = with[0].rebalance
+  else {
+    panic!("expected stable to lever rebalance");
+  };
+  let (before, after) = (&without[0].metrics, &with[0].metrics);
+  assert!(after.collateral_ratio > before.collateral_ratio);
+  assert_eq!(
+    after.stablecoin_supply,
+    before
+      .stablecoin_supply
+      .checked_sub(&stablecoin_in)
+      .expect("supply")
+  );
+  assert_eq!(
+    after.levercoin_in_pool,
+    before
+      .levercoin_in_pool
+      .checked_add(&levercoin_out)
+      .expect("pool")
+  );
+
+  // Projected state quotes like any other state
+  let op = with[0]
+    .state
+    .output::<JITOSOL, HYUSD>(UFix64::<N9>::new(1_000_000_000));
+  assert!(op.is_ok() || after.stability_mode > StabilityMode::Mode1);
+  Ok(())
+}
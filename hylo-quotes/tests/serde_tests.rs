@@ -0,0 +1,139 @@
+//! JSON round trips of quotes and protocol state (`serde` feature).
+
+#![cfg(feature = "serde")]
+
+use anchor_lang::prelude::Clock;
+use anyhow::Result;
+use hylo_quotes::prelude::*;
+use serde_json::{json, Value};
+
+mod common;
+
+use common::fixture_provider;
+
+const USER: Pubkey =
+  Pubkey::from_str_const("GDNtJq5YAZnfjmBuqr7xMaa2WCjxgYujvsMB1Fp3TMo2");
+
+#[tokio::test]
+async fn protocol_state_round_trip() -> Result<()> {
+  let state = fixture_provider()?.fetch_state().await?;
+  let json = serde_json::to_value(&state)?;
+  assert_eq!(json["hyusd_mint"]["supply"], json!(state.hyusd_mint.supply));
+  assert_eq!(
+    json["exchange_context"]["stability_mode"],
+    json!(state.exchange_context.stability_mode.to_string())
+  );
+  assert!(json["lst_headers"][JITOSOL::MINT.to_string()].is_object());
+
+  let decoded: ProtocolState<Clock> = serde_json::from_value(json.clone())?;
+  assert_eq!(serde_json::to_value(&decoded)?, json);
+
+  let amount_in = UFix64::<N9>::new(1_000_000_000);
+  assert_eq!(
+    decoded.output::<JITOSOL, HYUSD>(amount_in)?,
+    state.output::<JITOSOL, HYUSD>(amount_in)?
+  );
+  Ok(())
+}
+
+#[tokio::test]
+async fn exchange_context_recomputes_derived_fields() -> Result<()> {
+  let state = fixture_provider()?.fetch_state().await?;
+  let mut json = serde_json::to_value(&state)?;
+  let expected = json["exchange_context"].clone();
+  json["exchange_context"]["stability_mode"] = json!("Depeg");
+  json["exchange_context"]["collateral_ratio"]["bits"] = json!("1");
+  let decoded: ProtocolState<Clock> = serde_json::from_value(json)?;
+  assert_eq!(serde_json::to_value(&decoded.exchange_context)?, expected);
+  Ok(())
+}
+
+#[tokio::test]
+async fn executable_quote_round_trip() -> Result<()> {
+  let strategy = ProtocolStateStrategy::new(fixture_provider()?);
+  let (quote, metadata) = strategy
+    .runtime_quote_with_metadata(
+      JITOSOL::MINT,
+      HYUSD::MINT,
+      1_000_000_000,
+      USER,
+      50,
+    )
+    .await?;
+  let json = serde_json::to_value(&quote)?;
+  assert_eq!(
+    json["amount_in"],
+    json!({
+      "bits": "1000000000",
+      "exp": -9,
+      "decimal": "1",
+    })
+  );
+  assert_eq!(json["fee_mint"], json!(quote.fee_mint.to_string()));
+  let ix = &json["instructions"][0];
+  assert_eq!(
+    ix["program_id"],
+    json!(quote.instructions[0].program_id.to_string())
+  );
+  assert!(ix["data"].is_string());
+
+  let decoded: ExecutableQuoteValue = serde_json::from_value(json.clone())?;
+  assert_eq!(decoded.instructions, quote.instructions);
+  assert_eq!(decoded.amount_out, quote.amount_out);
+  assert_eq!(serde_json::to_value(&decoded)?, json);
+
+  let metadata_json = serde_json::to_value(&metadata)?;
+  assert_eq!(metadata_json["operation"], json!("mint_stablecoin"));
+  assert_eq!(
+    serde_json::from_value::<QuoteMetadata>(metadata_json)?,
+    metadata
+  );
+  Ok(())
+}
+
+#[tokio::test]
+async fn operation_output_round_trip() -> Result<()> {
+  let state = fixture_provider()?.fetch_state().await?;
+  let op = state.output::<HYUSD, XSOL>(UFix64::new(1_000_000))?;
+  let json = serde_json::to_value(op)?;
+  assert_eq!(json["in_amount"]["decimal"], json!("1"));
+  assert_eq!(serde_json::from_value::<SwapOperationOutput>(json)?, op);
+
+  let value = OperationOutputValue::from(op);
+  let json = serde_json::to_value(value)?;
+  assert_eq!(serde_json::from_value::<OperationOutputValue>(json)?, value);
+  Ok(())
+}
+
+#[test]
+fn operation_names_match_as_str() -> Result<()> {
+  for operation in [
+    Operation::MintStablecoin,
+    Operation::RedeemStablecoin,
+    Operation::MintLevercoin,
+    Operation::RedeemLevercoin,
+    Operation::SwapStableToLever,
+    Operation::SwapLeverToStable,
+    Operation::LstSwap,
+    Operation::DepositToStabilityPool,
+    Operation::WithdrawFromStabilityPool,
+    Operation::WithdrawAndRedeemFromStabilityPool,
+  ] {
+    let json = serde_json::to_value(operation)?;
+    assert_eq!(json, Value::from(operation.as_str()));
+    assert_eq!(serde_json::from_value::<Operation>(json)?, operation);
+  }
+  Ok(())
+}
+
+#[test]
+fn rejects_wrong_exponent() {
+  let json = json!({
+    "in_amount": { "bits": "1", "exp": -9 },
+    "out_amount": { "bits": "1", "exp": -6 },
+    "fee_amount": { "bits": "1", "exp": -6 },
+    "fee_mint": HYUSD::MINT.to_string(),
+    "fee_base": { "bits": "1", "exp": -6 },
+  });
+  assert!(serde_json::from_value::<SwapOperationOutput>(json).is_err());
+}
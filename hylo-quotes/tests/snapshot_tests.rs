@@ -0,0 +1,91 @@
+//! Snapshot record/replay tests against the fixture state.
+
+use std::fs;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+
+use anchor_lang::prelude::Clock;
+use anyhow::Result;
+use async_trait::async_trait;
+use hylo_quotes::prelude::*;
+
+mod common;
+
+use common::fixture_path;
+
+#[tokio::test]
+async fn replay_fixture_file() -> Result<()> {
+  let provider = FileStateProvider::from_file(fixture_path())?;
+  let state = provider.fetch_state().await?;
+  let amount_in = UFix64::<N9>::new(1_000_000_000);
+  let op = state.output::<JITOSOL, HYUSD>(amount_in)?;
+  assert_eq!(op.out_amount, UFix64::<N6>::new(154_211_899));
+  Ok(())
+}
+
+#[tokio::test]
+async fn select_snapshot_by_slot() -> Result<()> {
+  let dir = std::env::temp_dir()
+    .join(format!("hylo-snapshot-tests-{}", std::process::id()));
+  let snapshot = ProtocolSnapshot::read(&fixture_path())?;
+  let slot = snapshot.slot;
+  let path = snapshot.write_to_dir(&dir)?;
+  assert_eq!(ProtocolSnapshot::slot_from_path(&path), Some(slot));
+  ProtocolSnapshot {
+    slot: slot + 10,
+    ..snapshot.clone()
+  }
+  .write_to_dir(&dir)?;
+
+  let provider = FileStateProvider::from_dir(&dir)?;
+  assert_eq!(provider.slots().collect::<Vec<_>>(), vec![slot, slot + 10]);
+  assert_eq!(provider.fetch_snapshot()?.slot, slot + 10);
+
+  let provider = provider.at_or_before_slot(slot + 5)?;
+  assert_eq!(provider.fetch_snapshot()?.slot, slot);
+  let state = provider.fetch_state().await?;
+  assert_eq!(state.fetched_at, snapshot.timestamp);
+
+  let provider = FileStateProvider::from_dir(&dir)?;
+  assert!(provider.at_slot(slot + 1).is_err());
+
+  fs::remove_dir_all(&dir)?;
+  Ok(())
+}
+
+/// Counts fetches served by the wrapped provider
+struct CountingProvider {
+  inner: FileStateProvider,
+  fetches: AtomicUsize,
+}
+
+#[async_trait]
+impl StateProvider<Clock> for CountingProvider {
+  async fn fetch_state(&self) -> Result<ProtocolState<Clock>> {
+    self.fetches.fetch_add(1, Ordering::Relaxed);
+    self.inner.fetch_state().await
+  }
+}
+
+#[tokio::test]
+async fn cached_provider_reuses_state_within_ttl() -> Result<()> {
+  let counting = std::sync::Arc::new(CountingProvider {
+    inner: FileStateProvider::from_file(fixture_path())?,
+    fetches: AtomicUsize::new(0),
+  });
+  let cached = CachedStateProvider::new(counting.clone(), Duration::MAX);
+  let (first, second) =
+    tokio::join!(cached.fetch_state(), cached.fetch_state());
+  assert_eq!(first?.fetched_at, second?.fetched_at);
+  assert_eq!(counting.fetches.load(Ordering::Relaxed), 1);
+
+  cached.invalidate().await;
+  cached.fetch_state().await?;
+  assert_eq!(counting.fetches.load(Ordering::Relaxed), 2);
+
+  let uncached = CachedStateProvider::new(counting.clone(), Duration::ZERO);
+  uncached.fetch_state().await?;
+  uncached.fetch_state().await?;
+  assert_eq!(counting.fetches.load(Ordering::Relaxed), 4);
+  Ok(())
+}
@@ -3,15 +3,16 @@
 use std::fs::File;
 
 use anchor_client::solana_client::nonblocking::rpc_client::RpcClient;
-use anchor_lang::solana_program::clock::Clock;
 use anyhow::Result;
 use fix::prelude::*;
 use hylo_clients::prelude::CommitmentConfig;
 use hylo_idl::tokens::{HYLOSOL, HYUSD, JITOSOL, SHYUSD, XSOL};
-use hylo_quotes::prelude::{
-  ProtocolAccounts, ProtocolState, TokenOperationExt,
-};
-use serde_json::{from_reader, to_writer};
+use hylo_quotes::prelude::{ProtocolAccounts, TokenOperationExt};
+use serde_json::to_writer;
+
+mod common;
+
+use common::fixture_state;
 
 /// Pulls needed accounts from RPC into a file indexed by epoch and slot.
 ///
@@ -38,19 +39,9 @@ pub async fn dump_protocol_accounts() -> Result<()> {
   Ok(())
 }
 
-fn load_state() -> Result<ProtocolState<Clock>> {
-  let path = format!(
-    "{}/tests/data/protocol-state-918-37508.json",
-    env!("CARGO_MANIFEST_DIR")
-  );
-  let file = File::open(path)?;
-  let accounts = from_reader::<_, ProtocolAccounts>(file)?;
-  ProtocolState::try_from(&accounts)
-}
-
 #[test]
 fn jitosol_to_hyusd() -> Result<()> {
-  let state = load_state()?;
+  let state = fixture_state()?;
   let amount_in = UFix64::<N9>::new(1_000_000_000);
   let op = state.output::<JITOSOL, HYUSD>(amount_in)?;
   assert_eq!(op.out_amount, UFix64::<N6>::new(154_211_899));
@@ -59,7 +50,7 @@ fn jitosol_to_hyusd() -> Result<()> {
 
 #[test]
 fn hyusd_to_jitosol() -> Result<()> {
-  let state = load_state()?;
+  let state = fixture_state()?;
   let amount_in = UFix64::<N6>::new(1_000_000);
   let op = state.output::<HYUSD, JITOSOL>(amount_in)?;
   assert_eq!(op.out_amount, UFix64::<N9>::new(6_434_815));
@@ -68,7 +59,7 @@ fn hyusd_to_jitosol() -> Result<()> {
 
 #[test]
 fn jitosol_to_xsol() -> Result<()> {
-  let state = load_state()?;
+  let state = fixture_state()?;
   let amount_in = UFix64::<N9>::new(1_000_000_000);
   let op = state.output::<JITOSOL, XSOL>(amount_in)?;
   assert_eq!(op.out_amount, UFix64::<N6>::new(322_028_541));
@@ -77,7 +68,7 @@ fn jitosol_to_xsol() -> Result<()> {
 
 #[test]
 fn xsol_to_jitosol() -> Result<()> {
-  let state = load_state()?;
+  let state = fixture_state()?;
   let amount_in = UFix64::<N6>::new(1_000_000);
   let op = state.output::<XSOL, JITOSOL>(amount_in)?;
   assert_eq!(op.out_amount, UFix64::<N9>::new(2_945_254));
@@ -86,7 +77,7 @@ fn xsol_to_jitosol() -> Result<()> {
 
 #[test]
 fn hyusd_to_xsol() -> Result<()> {
-  let state = load_state()?;
+  let state = fixture_state()?;
   let amount_in = UFix64::<N6>::new(1_000_000);
   let op = state.output::<HYUSD, XSOL>(amount_in)?;
   assert_eq!(op.out_amount, UFix64::<N6>::new(2_077_779));
@@ -95,7 +86,7 @@ fn hyusd_to_xsol() -> Result<()> {
 
 #[test]
 fn xsol_to_hyusd() -> Result<()> {
-  let state = load_state()?;
+  let state = fixture_state()?;
   let amount_in = UFix64::<N6>::new(1_000_000);
   let op = state.output::<XSOL, HYUSD>(amount_in)?;
   assert_eq!(op.out_amount, UFix64::<N6>::new(457_248));
@@ -104,7 +95,7 @@ fn xsol_to_hyusd() -> Result<()> {
 
 #[test]
 fn jitosol_to_hylosol() -> Result<()> {
-  let state = load_state()?;
+  let state = fixture_state()?;
   let amount_in = UFix64::<N9>::new(1_000_000_000);
   let op = state.output::<JITOSOL, HYLOSOL>(amount_in)?;
   assert_eq!(op.out_amount, UFix64::<N9>::new(1_212_807_252));
@@ -113,7 +104,7 @@ fn jitosol_to_hylosol() -> Result<()> {
 
 #[test]
 fn hyusd_to_shyusd() -> Result<()> {
-  let state = load_state()?;
+  let state = fixture_state()?;
   let amount_in = UFix64::<N6>::new(1_000_000);
   let op = state.output::<HYUSD, SHYUSD>(amount_in)?;
   assert_eq!(op.out_amount, UFix64::<N6>::new(860_623));
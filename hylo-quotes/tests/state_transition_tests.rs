@@ -0,0 +1,183 @@
+//! Post-trade state transitions over the fixture protocol state.
+
+use anchor_lang::prelude::Clock;
+use anyhow::Result;
+use hylo_core::lst_sol_price::LstSolPrice;
+use hylo_core::solana_clock::SolanaClock;
+use hylo_quotes::prelude::*;
+
+mod common;
+
+use common::fixture_state;
+
+fn sol_value(
+  state: &ProtocolState<Clock>,
+  mint: &Pubkey,
+  amount: UFix64<N9>,
+) -> Result<UFix64<N9>> {
+  let price: LstSolPrice = state.lst_header_by_mint(mint)?.price_sol.into();
+  Ok(price.convert_sol(amount, state.exchange_context.clock.epoch())?)
+}
+
+#[test]
+fn mint_moves_collateral_and_supply() -> Result<()> {
+  let state = fixture_state()?;
+  let amount = UFix64::new(1_000_000_000);
+  let first = state.transition::<JITOSOL, HYUSD>(amount)?;
+  let ctx = &first.state.exchange_context;
+
+  let deposited = amount.checked_sub(&first.output.fee_amount).unwrap();
+  assert_eq!(
+    ctx.total_sol,
+    state
+      .exchange_context
+      .total_sol
+      .checked_add(&sol_value(&state, &JITOSOL::MINT, deposited)?)
+      .unwrap()
+  );
+  assert_eq!(
+    ctx.stablecoin_supply,
+    state
+      .exchange_context
+      .stablecoin_supply
+      .checked_add(&first.output.out_amount)
+      .unwrap()
+  );
+  assert_eq!(first.state.hyusd_mint.supply, ctx.stablecoin_supply.bits);
+  assert_ne!(
+    ctx.collateral_ratio,
+    state.exchange_context.collateral_ratio
+  );
+
+  // Second quote sees the first trade
+  let second = first.state.transition::<JITOSOL, HYUSD>(amount)?;
+  assert_eq!(
+    second.state.exchange_context.stablecoin_supply,
+    ctx
+      .stablecoin_supply
+      .checked_add(&second.output.out_amount)
+      .unwrap()
+  );
+  Ok(())
+}
+
+#[test]
+fn runtime_transition_matches_typed() -> Result<()> {
+  let state = fixture_state()?;
+  let typed = state.transition::<XSOL, HYUSD>(UFix64::new(10_000_000))?;
+  let runtime =
+    state.runtime_transition(XSOL::MINT, HYUSD::MINT, 10_000_000)?;
+  assert_eq!(runtime.output, typed.output.into());
+  assert_eq!(
+    runtime.state.exchange_context.collateral_ratio,
+    typed.state.exchange_context.collateral_ratio
+  );
+  assert_eq!(runtime.state.xsol_mint.supply, typed.state.xsol_mint.supply);
+
+  // Swaps move supplies but not collateral
+  let ctx = &typed.state.exchange_context;
+  assert_eq!(ctx.total_sol, state.exchange_context.total_sol);
+  assert_eq!(
+    ctx.levercoin_supply()?,
+    state
+      .exchange_context
+      .levercoin_supply()?
+      .checked_sub(&typed.output.in_amount)
+      .unwrap()
+  );
+  assert_eq!(
+    ctx.stablecoin_supply,
+    state
+      .exchange_context
+      .stablecoin_supply
+      .checked_add(&typed.output.fee_base)
+      .unwrap()
+  );
+  Ok(())
+}
+
+#[test]
+fn redeem_lowers_collateral_ratio() -> Result<()> {
+  let state = fixture_state()?;
+  let redeem = state.transition::<XSOL, JITOSOL>(UFix64::new(100_000_000))?;
+  let ctx = &redeem.state.exchange_context;
+  assert_eq!(
+    ctx.total_sol,
+    state
+      .exchange_context
+      .total_sol
+      .checked_sub(&sol_value(&state, &JITOSOL::MINT, redeem.output.fee_base)?)
+      .unwrap()
+  );
+  assert!(ctx.collateral_ratio < state.exchange_context.collateral_ratio);
+  assert!(ctx.stability_mode >= state.exchange_context.stability_mode);
+  Ok(())
+}
+
+#[test]
+fn stability_pool_moves_pool_balances() -> Result<()> {
+  let state = fixture_state()?;
+  let deposit = state.transition::<HYUSD, SHYUSD>(UFix64::new(1_000_000))?;
+  assert_eq!(
+    deposit.state.hyusd_pool.amount,
+    state.hyusd_pool.amount + 1_000_000
+  );
+  assert_eq!(
+    deposit.state.shyusd_mint.supply,
+    state.shyusd_mint.supply + deposit.output.out_amount.bits
+  );
+  assert_eq!(
+    deposit.state.exchange_context.collateral_ratio,
+    state.exchange_context.collateral_ratio
+  );
+
+  let withdraw = deposit
+    .state
+    .transition::<SHYUSD, JITOSOL>(deposit.output.out_amount)?;
+  assert_eq!(withdraw.state.shyusd_mint.supply, state.shyusd_mint.supply);
+  assert!(withdraw.state.hyusd_pool.amount <= deposit.state.hyusd_pool.amount);
+  assert!(withdraw.state.xsol_pool.amount <= deposit.state.xsol_pool.amount);
+  assert!(
+    withdraw.state.exchange_context.total_sol
+      < deposit.state.exchange_context.total_sol
+  );
+  Ok(())
+}
+
+#[test]
+fn batch_applies_orders_in_sequence() -> Result<()> {
+  let state = fixture_state()?;
+  let orders = [
+    (JITOSOL::MINT, HYUSD::MINT, 1_000_000_000),
+    (HYUSD::MINT, XSOL::MINT, 100_000_000),
+    (JITOSOL::MINT, HYLOSOL::MINT, 1_000_000_000),
+  ];
+  let batch = state.runtime_batch(orders)?;
+  assert_eq!(batch.output.len(), 3);
+
+  let mut expected = state.clone();
+  for ((input, output, amount), quoted) in orders.iter().zip(&batch.output) {
+    let next = expected.runtime_transition(*input, *output, *amount)?;
+    assert_eq!(next.output, *quoted);
+    expected = next.state;
+  }
+  assert_eq!(
+    batch.state.exchange_context.total_sol,
+    expected.exchange_context.total_sol
+  );
+  assert_eq!(
+    batch.state.exchange_context.collateral_ratio,
+    expected.exchange_context.collateral_ratio
+  );
+
+  // A failing order names its position
+  let err = state
+    .runtime_batch([
+      (JITOSOL::MINT, HYUSD::MINT, 1_000_000_000),
+      (HYUSD::MINT, Pubkey::new_unique(), 1),
+    ])
+    .err()
+    .expect("unsupported pair");
+  assert!(err.to_string().contains("Order 1"));
+  Ok(())
+}
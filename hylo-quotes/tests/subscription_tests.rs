@@ -0,0 +1,218 @@
+//! Websocket subscription provider tests against a local mock pubsub server.
+
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use anchor_client::solana_account_decoder::{
+  encode_ui_account, UiAccountEncoding,
+};
+use anchor_client::solana_client::client_error;
+use anchor_client::solana_client::nonblocking::rpc_client::RpcClient;
+use anchor_client::solana_client::rpc_request::RpcRequest;
+use anchor_client::solana_client::rpc_sender::{RpcSender, RpcTransportStats};
+use anchor_client::solana_sdk::account::Account;
+use anchor_client::solana_sdk::clock::Clock;
+use anyhow::Result;
+use futures::{SinkExt, Stream, StreamExt};
+use hylo_core::solana_clock::SolanaClock;
+use hylo_quotes::prelude::*;
+use serde_json::{json, Value};
+use tokio::net::TcpListener;
+use tokio::sync::oneshot;
+use tokio_tungstenite::tungstenite::Message;
+
+mod common;
+
+use common::fixture_accounts;
+
+fn clock_at(clock: &Account, slot: u64) -> Result<Account> {
+  let mut sysvar = bincode::deserialize::<Clock>(&clock.data)?;
+  sysvar.slot = slot;
+  Ok(Account {
+    data: bincode::serialize(&sysvar)?,
+    ..clock.clone()
+  })
+}
+
+/// RPC sender serving the fixture accounts with a configurable clock slot.
+struct MockRpc {
+  accounts: ProtocolAccounts,
+  slot: Arc<AtomicU64>,
+}
+
+#[async_trait::async_trait]
+impl RpcSender for MockRpc {
+  async fn send(
+    &self,
+    request: RpcRequest,
+    params: Value,
+  ) -> client_error::Result<Value> {
+    assert_eq!(request, RpcRequest::GetMultipleAccounts);
+    let slot = self.slot.load(Ordering::SeqCst);
+    let mut accounts = self.accounts.clone();
+    accounts.clock = clock_at(&accounts.clock, slot).expect("clock");
+    let served = ProtocolAccounts::pubkeys()
+      .into_iter()
+      .zip([
+        accounts.hylo,
+        accounts.jitosol_header,
+        accounts.hylosol_header,
+        accounts.hyusd_mint,
+        accounts.shyusd_mint,
+        accounts.xsol_mint,
+        accounts.pool_config,
+        accounts.hyusd_pool,
+        accounts.xsol_pool,
+        accounts.sol_usd_pyth,
+        accounts.clock,
+      ])
+      .collect::<HashMap<_, _>>();
+    // Unknown keys, such as the LST registry, are reported as missing
+    let value = params[0]
+      .as_array()
+      .expect("pubkeys")
+      .iter()
+      .map(|key| {
+        let pubkey = key.as_str().expect("pubkey").parse().expect("pubkey");
+        served.get(&pubkey).map_or(Value::Null, |account| {
+          json!(encode_ui_account(
+            &pubkey,
+            account,
+            UiAccountEncoding::Base64,
+            None,
+            None,
+          ))
+        })
+      })
+      .collect::<Vec<_>>();
+    Ok(json!({ "context": { "slot": slot }, "value": value }))
+  }
+
+  fn get_transport_stats(&self) -> RpcTransportStats {
+    RpcTransportStats::default()
+  }
+
+  fn url(&self) -> String {
+    "mock".to_string()
+  }
+}
+
+/// Accepts one websocket connection, acknowledges every `accountSubscribe`
+/// and pushes a clock update at `slot` once `notify` fires, closing the
+/// connection once `close` fires.
+async fn mock_pubsub(
+  clock: Account,
+  slot: u64,
+  notify: oneshot::Receiver<()>,
+  close: oneshot::Receiver<()>,
+) -> Result<SocketAddr> {
+  let listener = TcpListener::bind("127.0.0.1:0").await?;
+  let addr = listener.local_addr()?;
+  tokio::spawn(async move {
+    let (stream, _) = listener.accept().await.expect("accept");
+    drop(listener);
+    let mut ws = tokio_tungstenite::accept_async(stream).await.expect("ws");
+    let clock_key = anchor_lang::solana_program::sysvar::clock::ID.to_string();
+    let mut clock_sid = None;
+    for sid in 0..ProtocolAccounts::expected_count() {
+      let Some(Ok(Message::Text(text))) = ws.next().await else {
+        panic!("expected subscribe request");
+      };
+      let request = serde_json::from_str::<Value>(&text).expect("json");
+      assert_eq!(request["method"], "accountSubscribe");
+      if request["params"][0] == clock_key {
+        clock_sid = Some(sid);
+      }
+      let response = json!({
+        "jsonrpc": "2.0",
+        "result": sid,
+        "id": request["id"],
+      });
+      ws.send(Message::Text(response.to_string()))
+        .await
+        .expect("send");
+    }
+    notify.await.expect("notify");
+    let account = clock_at(&clock, slot).expect("clock");
+    let notification = json!({
+      "jsonrpc": "2.0",
+      "method": "accountNotification",
+      "params": {
+        "result": {
+          "context": { "slot": slot },
+          "value": encode_ui_account(
+            &anchor_lang::solana_program::sysvar::clock::ID,
+            &account,
+            UiAccountEncoding::Base64,
+            None,
+            None,
+          ),
+        },
+        "subscription": clock_sid.expect("clock subscription"),
+      },
+    });
+    ws.send(Message::Text(notification.to_string()))
+      .await
+      .expect("send");
+    close.await.expect("close");
+    ws.close(None).await.expect("close");
+  });
+  Ok(addr)
+}
+
+async fn next_slot(
+  changes: &mut (impl Stream<Item = Result<ProtocolState<Clock>>> + Unpin),
+) -> Result<u64> {
+  let state = tokio::time::timeout(Duration::from_secs(10), changes.next())
+    .await?
+    .expect("change stream ended")?;
+  Ok(state.exchange_context.clock.slot())
+}
+
+#[tokio::test]
+async fn streams_updates_then_falls_back_to_polling() -> Result<()> {
+  let accounts = fixture_accounts()?;
+  let base = bincode::deserialize::<Clock>(&accounts.clock.data)?.slot;
+  let rpc_slot = Arc::new(AtomicU64::new(base + 1));
+  let rpc = RpcClient::new_sender(
+    MockRpc {
+      accounts: accounts.clone(),
+      slot: rpc_slot.clone(),
+    },
+    Default::default(),
+  );
+  let (notify, notified) = oneshot::channel();
+  let (close, closed) = oneshot::channel();
+  let addr =
+    mock_pubsub(accounts.clock.clone(), base + 2, notified, closed).await?;
+  let provider = SubscriptionStateProvider::connect(
+    Arc::new(rpc),
+    format!("ws://{addr}"),
+    Duration::from_millis(50),
+  )
+  .await?;
+  let state = provider.fetch_state().await?;
+  assert_eq!(state.exchange_context.clock.slot(), base + 1);
+
+  while !provider.is_connected() {
+    tokio::time::sleep(Duration::from_millis(10)).await;
+  }
+  let mut changes = Box::pin(provider.changes());
+  notify.send(()).expect("notify");
+  assert_eq!(next_slot(&mut changes).await?, base + 2);
+  assert_eq!(
+    provider.fetch_state().await?.exchange_context.clock.slot(),
+    base + 2
+  );
+
+  // Once the server closes the websocket and refuses reconnects, RPC
+  // polling should pick up the new slot
+  rpc_slot.store(base + 3, Ordering::SeqCst);
+  close.send(()).expect("close");
+  while next_slot(&mut changes).await? != base + 3 {}
+  assert!(!provider.is_connected());
+  Ok(())
+}